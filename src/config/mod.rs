@@ -1,5 +1,26 @@
 use serde::Deserialize;
-use std::env;
+use std::{collections::HashMap, env, net::IpAddr, path::Path, str::FromStr};
+
+/// Archivo TOML que se lee si existe y `CONFIG_FILE` no indica otro
+const ARCHIVO_POR_DEFECTO: &str = "config.toml";
+
+/// Secretos de ejemplo (código, `.env.example` y `config.example.toml`) que no pueden
+/// llegar a producción
+const SECRETOS_DE_EJEMPLO: [&str; 3] = [
+    "your-secret-key-change-in-production",
+    "your-super-secret-jwt-key-change-in-production",
+    "reemplazar-por-un-secreto-largo-y-aleatorio",
+];
+
+/// Longitud mínima del JWT_SECRET en producción
+const LONGITUD_MINIMA_SECRETO: usize = 32;
+
+/// Orígenes CORS de desarrollo (frontend Dioxus local y Android en LAN)
+const CORS_DESARROLLO: [&str; 3] = [
+    "http://localhost:8080",
+    "http://127.0.0.1:8080",
+    "http://192.168.1.9",
+];
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
@@ -7,46 +28,802 @@ pub struct Config {
     pub port: u16,
     pub jwt_secret: String,
     pub environment: Environment,
+    pub logging: LoggingConfig,
+    /// Días que un elemento permanece en la papelera antes de purgarse
+    pub papelera_retencion_dias: u64,
+    /// Porcentaje mínimo de asistencia para aprobar un curso
+    pub asistencia_minima: f64,
+    /// URLs públicas de la API que se anuncian como `servers` en OpenAPI
+    pub api_urls: Vec<String>,
+    /// Hilos de trabajo del runtime de tokio
+    pub worker_threads: usize,
+    /// Tamaño máximo del body de una petición, en bytes
+    pub body_limit_bytes: usize,
+    /// Tamaño máximo del body en las rutas de subida (archivos, imágenes, CSV), en bytes
+    pub upload_limit_bytes: usize,
+    /// Orígenes permitidos por CORS
+    pub cors_origins: Vec<String>,
+    /// Proxies cuyos `X-Forwarded-For` / `X-Real-IP` se aceptan para la IP del cliente
+    pub trusted_proxies: Vec<RedConfiable>,
+    /// Horas de validez de los JWT emitidos
+    pub jwt_expiracion_horas: i64,
+    /// Usuario `test@example.com` y contraseña maestra de desarrollo (solo si se activa)
+    pub login_prueba: bool,
+    pub database: DatabaseConfig,
+    pub storage: StorageConfig,
+    pub login: LoginConfig,
+    pub rate_limit: RateLimitConfig,
+    pub correo: MailConfig,
+    pub cuenta: CuentaConfig,
+    pub notificaciones: NotificacionesConfig,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+/// Configuración del logger (formato, zona horaria, rotación y retención)
+#[derive(Debug, Clone, Deserialize)]
+pub struct LoggingConfig {
+    pub dir: String,
+    pub format: LogFormat,
+    /// Desplazamiento respecto a UTC en segundos (Bogotá = -18000)
+    pub utc_offset_secs: i32,
+    /// Tamaño máximo de un archivo de log antes de rotarlo
+    pub max_file_size_mb: u64,
+    /// Días que se conservan los archivos de log
+    pub retention_days: u64,
 }
 
+/// Pool de conexiones y schema de PostgreSQL
 #[derive(Debug, Clone, Deserialize)]
+pub struct DatabaseConfig {
+    /// Schema que se crea al iniciar y se fija como `search_path` de cada conexión
+    pub schema: String,
+    pub max_connections: u32,
+    pub min_connections: u32,
+    pub acquire_timeout_secs: u64,
+    pub idle_timeout_secs: u64,
+    pub max_lifetime_secs: u64,
+}
+
+/// Almacenamiento de archivos en Cloudflare R2 (API S3)
+#[derive(Debug, Clone, Deserialize)]
+pub struct StorageConfig {
+    pub endpoint: Option<String>,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: Option<String>,
+    pub secret_access_key: Option<String>,
+    /// Tamaño máximo de un archivo subido
+    pub max_file_size_mb: u64,
+    /// Validez de las URLs pre-firmadas de subida y descarga
+    pub url_expiracion_secs: u64,
+}
+
+/// Espera entre intentos fallidos de login y bloqueo temporal de cuentas
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct LoginConfig {
+    /// Fallos seguidos de una cuenta antes de bloquearla
+    pub max_intentos: u32,
+    pub bloqueo_minutos: i64,
+    /// Fallos por IP o cuenta que se toleran antes de exigir espera
+    pub intentos_libres: u32,
+    /// Tope de la espera exponencial entre intentos
+    pub backoff_max_secs: u64,
+}
+
+/// Dirección o red (`10.0.0.0/8`, `::1`) de un proxy de confianza
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct RedConfiable {
+    pub red: IpAddr,
+    pub prefijo: u8,
+}
+
+impl RedConfiable {
+    /// `ip` o `ip/prefijo`; sin prefijo es una sola dirección
+    fn parse(valor: &str) -> Option<Self> {
+        let (ip, prefijo) = match valor.split_once('/') {
+            Some((ip, prefijo)) => (ip, Some(prefijo)),
+            None => (valor, None),
+        };
+        let red: IpAddr = ip.parse().ok()?;
+        let maximo = if red.is_ipv4() { 32 } else { 128 };
+        let prefijo = match prefijo {
+            Some(prefijo) => prefijo.parse().ok().filter(|p| *p <= maximo)?,
+            None => maximo,
+        };
+        Some(Self { red, prefijo })
+    }
+
+    pub fn contiene(&self, ip: IpAddr) -> bool {
+        let (red, ip, ancho) = match (self.red.to_canonical(), ip.to_canonical()) {
+            (IpAddr::V4(red), IpAddr::V4(ip)) => (u32::from(red).into(), u32::from(ip).into(), 32),
+            (IpAddr::V6(red), IpAddr::V6(ip)) => (u128::from(red), u128::from(ip), 128),
+            _ => return false,
+        };
+        let desplazamiento = ancho - u32::from(self.prefijo);
+        red.checked_shr(desplazamiento).unwrap_or(0) == ip.checked_shr(desplazamiento).unwrap_or(0)
+    }
+}
+
+/// Token bucket por IP: `rafaga` peticiones seguidas y `por_minuto` de recarga (0 = sin límite)
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct LimiteRate {
+    pub rafaga: u32,
+    pub por_minuto: u32,
+}
+
+/// Límites por grupo de rutas
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct RateLimitConfig {
+    /// Toda la API salvo health y métricas
+    pub general: LimiteRate,
+    /// `GET /api/cursos/{id}/aula`
+    pub aula: LimiteRate,
+    /// Subidas de archivos e imágenes
+    pub uploads: LimiteRate,
+}
+
+/// Transporte de correo: SMTP o archivos `.eml` + log (desarrollo)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum TransporteCorreo {
+    Smtp,
+    Archivo,
+}
+
+/// Cifrado de la conexión SMTP
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum SeguridadSmtp {
+    /// Conexión en claro que se eleva con STARTTLS (puerto 587)
+    StartTls,
+    /// TLS implícito desde el primer byte (puerto 465)
+    Tls,
+    /// Sin cifrado (solo servidores locales de prueba)
+    Ninguna,
+}
+
+/// Envío de correos (activación de cuentas y recuperación de contraseña)
+#[derive(Debug, Clone, Deserialize)]
+pub struct MailConfig {
+    pub transporte: TransporteCorreo,
+    /// `Nombre <correo@dominio>` o solo el correo
+    pub remitente: String,
+    /// Carpeta de los `.eml` del transporte de archivo (vacía = solo log)
+    pub dir: String,
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub smtp_seguridad: SeguridadSmtp,
+    pub smtp_usuario: Option<String>,
+    pub smtp_contrasena: Option<String>,
+    pub smtp_timeout_secs: u64,
+}
+
+/// Enlaces de activación/recuperación y política de contraseñas
+#[derive(Debug, Clone, Deserialize)]
+pub struct CuentaConfig {
+    /// URL del frontend que recibe los enlaces (`/activar-cuenta`, `/restablecer-contrasena`)
+    pub frontend_url: String,
+    pub activacion_horas: i64,
+    pub recuperacion_minutos: i64,
+    /// Longitud mínima de una contraseña nueva
+    pub contrasena_min: usize,
+}
+
+/// Correos de notificaciones: reintentos y hora del resumen diario
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct NotificacionesConfig {
+    /// Intentos de envío antes de dar un correo por fallido
+    pub max_reintentos: u32,
+    /// Hora local (LOG_TIMEZONE) a la que sale el resumen diario
+    pub hora_resumen: u32,
+}
+
+impl LoggingConfig {
+    fn from_fuente(fuente: &Fuente, errores: &mut Vec<String>) -> Self {
+        let format = match fuente
+            .texto("LOG_FORMAT")
+            .unwrap_or_default()
+            .to_lowercase()
+            .as_str()
+        {
+            "json" => LogFormat::Json,
+            _ => LogFormat::Text,
+        };
+
+        let utc_offset_secs = match fuente.texto("LOG_TIMEZONE") {
+            Some(valor) => parse_utc_offset(&valor).unwrap_or_else(|| {
+                errores.push(format!(
+                    "LOG_TIMEZONE inválido: '{}' (formato ±HH:MM)",
+                    valor
+                ));
+                0
+            }),
+            None => -5 * 3600,
+        };
+
+        LoggingConfig {
+            dir: fuente.texto("LOG_DIR").unwrap_or_else(|| "logs".into()),
+            format,
+            utc_offset_secs,
+            max_file_size_mb: fuente.numero("LOG_MAX_FILE_MB", 10, errores),
+            retention_days: fuente.numero("LOG_RETENTION_DAYS", 14, errores),
+        }
+    }
+}
+
+impl DatabaseConfig {
+    fn from_fuente(fuente: &Fuente, errores: &mut Vec<String>) -> Self {
+        DatabaseConfig {
+            schema: fuente
+                .texto("DB_SCHEMA")
+                .unwrap_or_else(|| "rustdema2".into()),
+            // 2 conexiones y 0 mínimas: mínima memoria en reposo
+            max_connections: fuente.numero("DB_MAX_CONNECTIONS", 2, errores),
+            min_connections: fuente.numero("DB_MIN_CONNECTIONS", 0, errores),
+            acquire_timeout_secs: fuente.numero("DB_ACQUIRE_TIMEOUT_SECS", 5, errores),
+            idle_timeout_secs: fuente.numero("DB_IDLE_TIMEOUT_SECS", 60, errores),
+            max_lifetime_secs: fuente.numero("DB_MAX_LIFETIME_SECS", 300, errores),
+        }
+    }
+}
+
+impl StorageConfig {
+    fn from_fuente(fuente: &Fuente, errores: &mut Vec<String>) -> Self {
+        StorageConfig {
+            endpoint: fuente.texto("R2_ENDPOINT"),
+            bucket: fuente
+                .texto("R2_BUCKET")
+                .unwrap_or_else(|| "aulatrix-bucket".into()),
+            // Cloudflare R2 usa la región 'auto'
+            region: fuente.texto("R2_REGION").unwrap_or_else(|| "auto".into()),
+            access_key_id: fuente.texto("R2_ACCESS_KEY_ID"),
+            secret_access_key: fuente.texto("R2_SECRET_ACCESS_KEY"),
+            max_file_size_mb: fuente.numero("R2_MAX_FILE_MB", 20, errores),
+            url_expiracion_secs: fuente.numero("R2_URL_EXPIRATION_SECS", 3600, errores),
+        }
+    }
+}
+
+impl LoginConfig {
+    fn from_fuente(fuente: &Fuente, errores: &mut Vec<String>) -> Self {
+        LoginConfig {
+            max_intentos: fuente.numero("LOGIN_MAX_ATTEMPTS", 5, errores),
+            bloqueo_minutos: fuente.numero("LOGIN_LOCKOUT_MINUTES", 15, errores),
+            intentos_libres: fuente.numero("LOGIN_FREE_ATTEMPTS", 3, errores),
+            backoff_max_secs: fuente.numero("LOGIN_BACKOFF_MAX_SECS", 300, errores),
+        }
+    }
+}
+
+impl RateLimitConfig {
+    fn from_fuente(fuente: &Fuente, errores: &mut Vec<String>) -> Self {
+        let mut limite = |grupo: &str, rafaga: u32, por_minuto: u32| LimiteRate {
+            rafaga: fuente.numero(&format!("RATE_LIMIT_{}_BURST", grupo), rafaga, errores),
+            por_minuto: fuente.numero(
+                &format!("RATE_LIMIT_{}_PER_MINUTE", grupo),
+                por_minuto,
+                errores,
+            ),
+        };
+
+        RateLimitConfig {
+            general: limite("GENERAL", 120, 120),
+            aula: limite("AULA", 20, 10),
+            uploads: limite("UPLOADS", 10, 5),
+        }
+    }
+}
+
+impl MailConfig {
+    fn from_fuente(fuente: &Fuente, errores: &mut Vec<String>) -> Self {
+        let smtp_host = fuente.texto("SMTP_HOST");
+
+        let transporte = match fuente.texto("MAIL_TRANSPORT") {
+            Some(valor) => match valor.to_lowercase().as_str() {
+                "smtp" => TransporteCorreo::Smtp,
+                "file" | "archivo" | "log" => TransporteCorreo::Archivo,
+                _ => {
+                    errores.push(format!(
+                        "MAIL_TRANSPORT inválido: '{}' (smtp | file)",
+                        valor
+                    ));
+                    TransporteCorreo::Archivo
+                }
+            },
+            None if smtp_host.is_some() => TransporteCorreo::Smtp,
+            None => TransporteCorreo::Archivo,
+        };
+
+        let smtp_seguridad = match fuente.texto("SMTP_SECURITY") {
+            Some(valor) => match valor.to_lowercase().as_str() {
+                "starttls" => SeguridadSmtp::StartTls,
+                "tls" | "ssl" => SeguridadSmtp::Tls,
+                "none" | "ninguna" => SeguridadSmtp::Ninguna,
+                _ => {
+                    errores.push(format!(
+                        "SMTP_SECURITY inválido: '{}' (starttls | tls | none)",
+                        valor
+                    ));
+                    SeguridadSmtp::StartTls
+                }
+            },
+            None => SeguridadSmtp::StartTls,
+        };
+        let puerto_por_defecto = match smtp_seguridad {
+            SeguridadSmtp::StartTls => 587,
+            SeguridadSmtp::Tls => 465,
+            SeguridadSmtp::Ninguna => 25,
+        };
+
+        MailConfig {
+            transporte,
+            remitente: fuente
+                .texto("MAIL_FROM")
+                .unwrap_or_else(|| "Aula Virtual <no-reply@localhost>".into()),
+            dir: fuente.texto("MAIL_DIR").unwrap_or_else(|| "mail".into()),
+            smtp_host,
+            smtp_port: fuente.numero("SMTP_PORT", puerto_por_defecto, errores),
+            smtp_seguridad,
+            smtp_usuario: fuente.texto("SMTP_USERNAME"),
+            smtp_contrasena: fuente.texto("SMTP_PASSWORD"),
+            smtp_timeout_secs: fuente.numero("SMTP_TIMEOUT_SECS", 15, errores),
+        }
+    }
+}
+
+impl CuentaConfig {
+    fn from_fuente(fuente: &Fuente, cors_origins: &[String], errores: &mut Vec<String>) -> Self {
+        CuentaConfig {
+            // Sin FRONTEND_URL se usa el primer origen CORS (el frontend que consume la API)
+            frontend_url: fuente
+                .texto("FRONTEND_URL")
+                .or_else(|| cors_origins.first().cloned())
+                .unwrap_or_else(|| CORS_DESARROLLO[0].into())
+                .trim_end_matches('/')
+                .to_string(),
+            activacion_horas: fuente.numero("ACCOUNT_ACTIVATION_HOURS", 72, errores),
+            recuperacion_minutos: fuente.numero("PASSWORD_RESET_MINUTES", 60, errores),
+            contrasena_min: fuente.numero("PASSWORD_MIN_LENGTH", 10, errores),
+        }
+    }
+}
+
+impl NotificacionesConfig {
+    fn from_fuente(fuente: &Fuente, errores: &mut Vec<String>) -> Self {
+        NotificacionesConfig {
+            max_reintentos: fuente.numero("NOTIFICATION_MAX_RETRIES", 5, errores),
+            hora_resumen: fuente.numero("NOTIFICATION_DIGEST_HOUR", 7, errores),
+        }
+    }
+}
+
+/// Convierte "-05:00", "+0130" o "UTC" en segundos respecto a UTC
+fn parse_utc_offset(valor: &str) -> Option<i32> {
+    let valor = valor.trim();
+    if valor.eq_ignore_ascii_case("utc") || valor == "Z" {
+        return Some(0);
+    }
+
+    let (signo, resto) = match valor.chars().next()? {
+        '+' => (1, &valor[1..]),
+        '-' => (-1, &valor[1..]),
+        _ => return None,
+    };
+    let (horas, minutos) = match resto.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if resto.len() == 4 => resto.split_at(2),
+        None => (resto, "0"),
+    };
+    let horas: i32 = horas.parse().ok()?;
+    let minutos: i32 = minutos.parse().ok()?;
+    if horas > 14 || minutos > 59 {
+        return None;
+    }
+
+    Some(signo * (horas * 3600 + minutos * 60))
+}
+
+/// Lista separada por comas; sin valor se anuncia el servidor local
+fn parse_api_urls(valor: Option<String>, port: u16) -> Vec<String> {
+    let urls = parse_lista(valor);
+
+    if urls.is_empty() {
+        vec![format!("http://localhost:{}", port)]
+    } else {
+        urls
+    }
+}
+
+/// Divide por comas, recorta espacios y `/` final y descarta vacíos
+fn parse_lista(valor: Option<String>) -> Vec<String> {
+    valor
+        .unwrap_or_default()
+        .split(',')
+        .map(|item| item.trim().trim_end_matches('/').to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Identificador SQL simple: se interpola en `CREATE SCHEMA` y `SET search_path`
+fn es_identificador_sql(valor: &str) -> bool {
+    let mut chars = valor.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_lowercase() || c == '_')
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && valor.len() <= 63
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 pub enum Environment {
     Development,
     Production,
     Testing,
 }
 
+/// Origen de los valores: variables de entorno con respaldo en el archivo TOML.
+///
+/// Las claves del archivo se aplanan al nombre de la variable: `[db] schema` = `DB_SCHEMA`,
+/// `port` = `PORT`; los arreglos se convierten en listas separadas por comas.
+struct Fuente {
+    archivo: HashMap<String, String>,
+}
+
+impl Fuente {
+    fn cargar() -> Result<Self, String> {
+        let ruta = match env::var("CONFIG_FILE") {
+            Ok(ruta) => Some(ruta),
+            Err(_) if Path::new(ARCHIVO_POR_DEFECTO).exists() => {
+                Some(ARCHIVO_POR_DEFECTO.to_string())
+            }
+            Err(_) => None,
+        };
+
+        let mut archivo = HashMap::new();
+        if let Some(ruta) = ruta {
+            let contenido = std::fs::read_to_string(&ruta)
+                .map_err(|e| format!("No se pudo leer {}: {}", ruta, e))?;
+            let documento = toml_edit::DocumentMut::from_str(&contenido)
+                .map_err(|e| format!("{} no es TOML válido: {}", ruta, e))?;
+            aplanar_tabla("", documento.as_table(), &mut archivo);
+        }
+
+        Ok(Self { archivo })
+    }
+
+    fn texto(&self, clave: &str) -> Option<String> {
+        env::var(clave)
+            .ok()
+            .or_else(|| self.archivo.get(clave).cloned())
+            .map(|valor| valor.trim().to_string())
+            .filter(|valor| !valor.is_empty())
+    }
+
+    /// Valor numérico; si viene y no se puede leer queda registrado como error
+    fn numero<T: FromStr>(&self, clave: &str, defecto: T, errores: &mut Vec<String>) -> T {
+        match self.texto(clave) {
+            Some(valor) => valor.parse().unwrap_or_else(|_| {
+                errores.push(format!("{} inválido: '{}'", clave, valor));
+                defecto
+            }),
+            None => defecto,
+        }
+    }
+
+    fn booleano(&self, clave: &str, defecto: bool, errores: &mut Vec<String>) -> bool {
+        match self.texto(clave).map(|v| v.to_lowercase()) {
+            Some(valor) => match valor.as_str() {
+                "true" | "1" | "yes" | "si" | "on" => true,
+                "false" | "0" | "no" | "off" => false,
+                _ => {
+                    errores.push(format!("{} inválido: '{}' (true | false)", clave, valor));
+                    defecto
+                }
+            },
+            None => defecto,
+        }
+    }
+}
+
+fn aplanar_tabla(prefijo: &str, tabla: &toml_edit::Table, salida: &mut HashMap<String, String>) {
+    for (clave, item) in tabla.iter() {
+        let nombre = clave_plana(prefijo, clave);
+        match item {
+            toml_edit::Item::Table(subtabla) => aplanar_tabla(&nombre, subtabla, salida),
+            toml_edit::Item::Value(valor) => aplanar_valor(nombre, valor, salida),
+            _ => {}
+        }
+    }
+}
+
+fn aplanar_valor(nombre: String, valor: &toml_edit::Value, salida: &mut HashMap<String, String>) {
+    if let toml_edit::Value::InlineTable(tabla) = valor {
+        for (clave, valor) in tabla.iter() {
+            aplanar_valor(clave_plana(&nombre, clave), valor, salida);
+        }
+        return;
+    }
+    if let Some(texto) = valor_escalar(valor) {
+        salida.insert(nombre, texto);
+    }
+}
+
+fn valor_escalar(valor: &toml_edit::Value) -> Option<String> {
+    match valor {
+        toml_edit::Value::String(v) => Some(v.value().clone()),
+        toml_edit::Value::Integer(v) => Some(v.value().to_string()),
+        toml_edit::Value::Float(v) => Some(v.value().to_string()),
+        toml_edit::Value::Boolean(v) => Some(v.value().to_string()),
+        toml_edit::Value::Datetime(v) => Some(v.value().to_string()),
+        toml_edit::Value::Array(items) => Some(
+            items
+                .iter()
+                .filter_map(valor_escalar)
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        toml_edit::Value::InlineTable(_) => None,
+    }
+}
+
+fn clave_plana(prefijo: &str, clave: &str) -> String {
+    if prefijo.is_empty() {
+        clave.to_uppercase()
+    } else {
+        format!("{}_{}", prefijo, clave.to_uppercase())
+    }
+}
+
 impl Config {
+    /// Lee `.env`, las variables de entorno y el archivo TOML opcional
+    /// (`CONFIG_FILE` o `config.toml`); el entorno tiene prioridad sobre el archivo.
+    /// Falla con la lista completa de problemas si la configuración no es válida.
     pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
         dotenvy::dotenv().ok();
 
-        // Usar valores por defecto sin allocations innecesarias
-        let database_url = env::var("DATABASE_URL")
-            .unwrap_or_else(|_| "postgresql://localhost/rust_api_db".into());
+        let fuente = Fuente::cargar()?;
+        let mut errores = Vec::new();
 
-        let port = env::var("PORT")
-            .ok()
-            .and_then(|p| p.parse::<u16>().ok())
-            .unwrap_or(3000);
+        let environment = match fuente.texto("ENVIRONMENT") {
+            Some(valor) => match valor.to_lowercase().as_str() {
+                "production" | "prod" => Environment::Production,
+                "testing" | "test" => Environment::Testing,
+                "development" | "dev" => Environment::Development,
+                _ => {
+                    errores.push(format!(
+                        "ENVIRONMENT inválido: '{}' (development | testing | production)",
+                        valor
+                    ));
+                    Environment::Development
+                }
+            },
+            None => Environment::Development,
+        };
+        let produccion = environment == Environment::Production;
 
-        let jwt_secret = env::var("JWT_SECRET")
-            .unwrap_or_else(|_| "your-secret-key-change-in-production".into());
+        let port = fuente.numero("PORT", 3000, &mut errores);
 
-        let environment = env::var("ENVIRONMENT")
-            .ok()
-            .and_then(|e| match e.to_lowercase().as_str() {
-                "production" => Some(Environment::Production),
-                "testing" => Some(Environment::Testing),
-                "development" => Some(Environment::Development),
-                _ => None,
-            })
-            .unwrap_or(Environment::Development);
-
-        Ok(Config {
-            database_url,
+        let cors_origins = match fuente.texto("CORS_ORIGINS") {
+            Some(valor) => parse_lista(Some(valor)),
+            None if produccion => Vec::new(),
+            None => CORS_DESARROLLO.iter().map(|o| o.to_string()).collect(),
+        };
+
+        let cuenta = CuentaConfig::from_fuente(&fuente, &cors_origins, &mut errores);
+
+        let config = Config {
+            database_url: fuente
+                .texto("DATABASE_URL")
+                .unwrap_or_else(|| "postgresql://localhost/rust_api_db".into()),
             port,
-            jwt_secret,
+            jwt_secret: fuente
+                .texto("JWT_SECRET")
+                .unwrap_or_else(|| SECRETOS_DE_EJEMPLO[0].into()),
             environment,
-        })
+            logging: LoggingConfig::from_fuente(&fuente, &mut errores),
+            papelera_retencion_dias: fuente.numero("PAPELERA_RETENCION_DIAS", 30, &mut errores),
+            asistencia_minima: fuente.numero("ASISTENCIA_MINIMA", 80.0, &mut errores),
+            api_urls: parse_api_urls(fuente.texto("API_PUBLIC_URLS"), port),
+            worker_threads: fuente.numero("WORKER_THREADS", 2, &mut errores),
+            body_limit_bytes: fuente.numero::<usize>("BODY_LIMIT_KB", 512, &mut errores) * 1024,
+            upload_limit_bytes: fuente.numero::<usize>("UPLOAD_LIMIT_MB", 25, &mut errores)
+                * 1024
+                * 1024,
+            cors_origins,
+            trusted_proxies: parse_lista(fuente.texto("TRUSTED_PROXIES"))
+                .into_iter()
+                .filter_map(|valor| {
+                    RedConfiable::parse(&valor).or_else(|| {
+                        errores.push(format!("TRUSTED_PROXIES inválido: '{}'", valor));
+                        None
+                    })
+                })
+                .collect(),
+            jwt_expiracion_horas: fuente.numero("JWT_EXPIRATION_HOURS", 24, &mut errores),
+            login_prueba: fuente.booleano("TEST_LOGIN_ENABLED", false, &mut errores),
+            database: DatabaseConfig::from_fuente(&fuente, &mut errores),
+            storage: StorageConfig::from_fuente(&fuente, &mut errores),
+            login: LoginConfig::from_fuente(&fuente, &mut errores),
+            rate_limit: RateLimitConfig::from_fuente(&fuente, &mut errores),
+            correo: MailConfig::from_fuente(&fuente, &mut errores),
+            cuenta,
+            notificaciones: NotificacionesConfig::from_fuente(&fuente, &mut errores),
+        };
+
+        config.validar(&mut errores);
+        if !errores.is_empty() {
+            return Err(format!("Configuración inválida:\n  - {}", errores.join("\n  - ")).into());
+        }
+
+        Ok(config)
+    }
+
+    pub fn es_produccion(&self) -> bool {
+        self.environment == Environment::Production
+    }
+
+    /// Reglas de rango y, en producción, los valores inseguros de desarrollo
+    fn validar(&self, errores: &mut Vec<String>) {
+        if self.port == 0 {
+            errores.push("PORT debe ser mayor que 0".into());
+        }
+        if self.worker_threads == 0 {
+            errores.push("WORKER_THREADS debe ser al menos 1".into());
+        }
+        if self.body_limit_bytes == 0 {
+            errores.push("BODY_LIMIT_KB debe ser mayor que 0".into());
+        }
+        if self.upload_limit_bytes == 0 {
+            errores.push("UPLOAD_LIMIT_MB debe ser mayor que 0".into());
+        }
+        if self.jwt_expiracion_horas <= 0 {
+            errores.push("JWT_EXPIRATION_HOURS debe ser mayor que 0".into());
+        }
+        if !(0.0..=100.0).contains(&self.asistencia_minima) {
+            errores.push("ASISTENCIA_MINIMA debe estar entre 0 y 100".into());
+        }
+        if !es_identificador_sql(&self.database.schema) {
+            errores.push(format!(
+                "DB_SCHEMA inválido: '{}' (minúsculas, dígitos y _)",
+                self.database.schema
+            ));
+        }
+        if self.database.max_connections == 0 {
+            errores.push("DB_MAX_CONNECTIONS debe ser al menos 1".into());
+        }
+        if self.database.min_connections > self.database.max_connections {
+            errores.push("DB_MIN_CONNECTIONS no puede superar DB_MAX_CONNECTIONS".into());
+        }
+        if self.storage.max_file_size_mb == 0 {
+            errores.push("R2_MAX_FILE_MB debe ser mayor que 0".into());
+        }
+        if !(1..=604_800).contains(&self.storage.url_expiracion_secs) {
+            errores.push("R2_URL_EXPIRATION_SECS debe estar entre 1 y 604800 (7 días)".into());
+        }
+        if self.login.max_intentos == 0 {
+            errores.push("LOGIN_MAX_ATTEMPTS debe ser al menos 1".into());
+        }
+        if self.login.bloqueo_minutos <= 0 {
+            errores.push("LOGIN_LOCKOUT_MINUTES debe ser mayor que 0".into());
+        }
+        for (grupo, limite) in [
+            ("GENERAL", self.rate_limit.general),
+            ("AULA", self.rate_limit.aula),
+            ("UPLOADS", self.rate_limit.uploads),
+        ] {
+            if limite.rafaga > 0 && limite.por_minuto == 0 {
+                errores.push(format!(
+                    "RATE_LIMIT_{}_PER_MINUTE debe ser mayor que 0 si hay ráfaga",
+                    grupo
+                ));
+            }
+        }
+        if self.correo.transporte == TransporteCorreo::Smtp && self.correo.smtp_host.is_none() {
+            errores.push("MAIL_TRANSPORT=smtp exige SMTP_HOST".into());
+        }
+        if self.correo.smtp_usuario.is_some() != self.correo.smtp_contrasena.is_some() {
+            errores.push("SMTP_USERNAME y SMTP_PASSWORD van juntos".into());
+        }
+        if self.correo.smtp_timeout_secs == 0 {
+            errores.push("SMTP_TIMEOUT_SECS debe ser mayor que 0".into());
+        }
+        if !(self.cuenta.frontend_url.starts_with("http://")
+            || self.cuenta.frontend_url.starts_with("https://"))
+        {
+            errores.push(format!(
+                "FRONTEND_URL inválido: '{}' (http:// o https://)",
+                self.cuenta.frontend_url
+            ));
+        }
+        if self.cuenta.activacion_horas <= 0 {
+            errores.push("ACCOUNT_ACTIVATION_HOURS debe ser mayor que 0".into());
+        }
+        if self.cuenta.recuperacion_minutos <= 0 {
+            errores.push("PASSWORD_RESET_MINUTES debe ser mayor que 0".into());
+        }
+        // bcrypt solo considera los primeros 72 bytes
+        if !(8..=72).contains(&self.cuenta.contrasena_min) {
+            errores.push("PASSWORD_MIN_LENGTH debe estar entre 8 y 72".into());
+        }
+        if self.notificaciones.max_reintentos == 0 {
+            errores.push("NOTIFICATION_MAX_RETRIES debe ser al menos 1".into());
+        }
+        if self.notificaciones.hora_resumen > 23 {
+            errores.push("NOTIFICATION_DIGEST_HOUR debe estar entre 0 y 23".into());
+        }
+        for origen in &self.cors_origins {
+            if origen == "*" {
+                errores.push("CORS_ORIGINS no admite '*' (se envían credenciales)".into());
+            } else if !(origen.starts_with("http://") || origen.starts_with("https://"))
+                || axum::http::HeaderValue::from_str(origen).is_err()
+            {
+                errores.push(format!("Origen CORS inválido: '{}'", origen));
+            }
+        }
+
+        if self.es_produccion() {
+            if SECRETOS_DE_EJEMPLO.contains(&self.jwt_secret.as_str()) {
+                errores.push("JWT_SECRET conserva el valor de ejemplo".into());
+            } else if self.jwt_secret.len() < LONGITUD_MINIMA_SECRETO {
+                errores.push(format!(
+                    "JWT_SECRET debe tener al menos {} caracteres en producción",
+                    LONGITUD_MINIMA_SECRETO
+                ));
+            }
+            if self.cors_origins.is_empty() {
+                errores.push("CORS_ORIGINS es obligatorio en producción".into());
+            }
+            if self.login_prueba {
+                errores.push("TEST_LOGIN_ENABLED no puede estar activo en producción".into());
+            }
+            if self.correo.transporte == TransporteCorreo::Archivo {
+                errores.push("MAIL_TRANSPORT=file no envía correos: usa smtp en producción".into());
+            }
+            if self.correo.smtp_seguridad == SeguridadSmtp::Ninguna
+                && self.correo.smtp_usuario.is_some()
+            {
+                errores.push("SMTP_SECURITY=none enviaría la contraseña SMTP en claro".into());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lee_redes_de_proxies() {
+        let red = RedConfiable::parse("10.0.0.0/8").unwrap();
+        assert!(red.contiene("10.200.1.1".parse().unwrap()));
+        assert!(!red.contiene("11.0.0.1".parse().unwrap()));
+        assert!(!red.contiene("::1".parse().unwrap()));
+
+        let una = RedConfiable::parse("192.168.1.5").unwrap();
+        assert_eq!(una.prefijo, 32);
+        assert!(una.contiene("::ffff:192.168.1.5".parse().unwrap()));
+        assert!(!una.contiene("192.168.1.6".parse().unwrap()));
+
+        let v6 = RedConfiable::parse("fd00::/8").unwrap();
+        assert!(v6.contiene("fd12::1".parse().unwrap()));
+        assert!(RedConfiable::parse("0.0.0.0/0")
+            .unwrap()
+            .contiene("8.8.8.8".parse().unwrap()));
+    }
+
+    #[test]
+    fn rechaza_proxies_malformados() {
+        for valor in [
+            "",
+            "10.0.0.0/33",
+            "::/129",
+            "10.0.0/8",
+            "10.0.0.0/",
+            "10.0.0.0/x",
+            "proxy",
+        ] {
+            assert!(RedConfiable::parse(valor).is_none(), "{valor}");
+        }
     }
 }
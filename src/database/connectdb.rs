@@ -1,21 +1,27 @@
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use sqlx::{postgres::PgPoolOptions, Executor, PgPool};
 use std::time::Duration;
 
+use crate::config::DatabaseConfig;
+
 /// Crea un pool de conexiones a PostgreSQL con reintentos
 ///
 /// # Argumentos
 /// * `database_url` - URL de conexión a la base de datos
+/// * `config` - Tamaño del pool, tiempos de espera y schema (`search_path`)
 ///
 /// # Errores
 /// Retorna un error si no se puede conectar después de varios intentos
-pub async fn create_pool(database_url: &str) -> Result<PgPool, sqlx::Error> {
+pub async fn create_pool(
+    database_url: &str,
+    config: &DatabaseConfig,
+) -> Result<PgPool, sqlx::Error> {
     const MAX_RETRIES: u32 = 3;
     const RETRY_DELAY_SECS: u64 = 2;
 
     tracing::info!("🔌 Intentando conectar a la base de datos...");
 
     for attempt in 1..=MAX_RETRIES {
-        match try_create_pool(database_url).await {
+        match try_create_pool(database_url, config).await {
             Ok(pool) => {
                 tracing::info!("✅ Conexión a la base de datos establecida exitosamente");
                 return Ok(pool);
@@ -57,30 +63,37 @@ pub async fn create_pool(database_url: &str) -> Result<PgPool, sqlx::Error> {
 }
 
 /// Intenta crear un pool de conexiones sin reintentos
-async fn try_create_pool(database_url: &str) -> Result<PgPool, sqlx::Error> {
+async fn try_create_pool(
+    database_url: &str,
+    config: &DatabaseConfig,
+) -> Result<PgPool, sqlx::Error> {
+    // El schema se validó como identificador al cargar la configuración
+    let search_path = format!("SET search_path TO {}, public", config.schema);
+
     PgPoolOptions::new()
-        .max_connections(2) // Reducido a 2 para optimizar memoria en reposo
-        .min_connections(0) // 0 conexiones mínimas = máxima optimización en idle
-        .acquire_timeout(Duration::from_secs(5))
-        .idle_timeout(Some(Duration::from_secs(60))) // 1 minuto idle, luego cierra
-        .max_lifetime(Some(Duration::from_secs(300))) // 5 minutos máximo
+        .max_connections(config.max_connections)
+        .min_connections(config.min_connections)
+        .acquire_timeout(Duration::from_secs(config.acquire_timeout_secs))
+        .idle_timeout(Some(Duration::from_secs(config.idle_timeout_secs)))
+        .max_lifetime(Some(Duration::from_secs(config.max_lifetime_secs)))
         .test_before_acquire(true)
+        .after_connect(move |conn, _meta| {
+            let search_path = search_path.clone();
+            Box::pin(async move {
+                conn.execute(search_path.as_str()).await?;
+                Ok(())
+            })
+        })
         .connect(database_url)
         .await
 }
 
-#[allow(dead_code)]
-pub async fn init_schema(pool: &PgPool) -> Result<(), sqlx::Error> {
-    // Create schema if it doesn't exist
-    sqlx::query("CREATE SCHEMA IF NOT EXISTS rustdema2")
-        .execute(pool)
-        .await?;
-
-    // Set search_path to use the schema
-    sqlx::query("SET search_path TO rustdema2, public")
+/// Crea el schema configurado si no existe
+pub async fn init_schema(pool: &PgPool, schema: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!("CREATE SCHEMA IF NOT EXISTS {}", schema))
         .execute(pool)
         .await?;
 
-    tracing::info!("Schema rustdema2 initialized");
+    tracing::info!("Schema {} initialized", schema);
     Ok(())
 }
@@ -564,11 +564,38 @@ CREATE TABLE rustdema2.sesiones_curso (
 	fecha_inicio timestamptz NULL,
 	fecha_fin timestamptz NULL,
 	orden int4 NOT NULL,
+	codigo_asistencia varchar(12) NULL,
+	codigo_expira timestamptz NULL,
 	CONSTRAINT sesiones_curso_pkey PRIMARY KEY (id),
 	CONSTRAINT sesiones_curso_curso_id_fkey FOREIGN KEY (curso_id) REFERENCES rustdema2.cursos(id) ON DELETE CASCADE
 );
 
 
+-- rustdema2.asistencias definition
+
+-- Drop table
+
+-- DROP TABLE rustdema2.asistencias;
+
+CREATE TABLE rustdema2.asistencias (
+	id serial4 NOT NULL,
+	sesion_id int4 NOT NULL,
+	estudiante_id int4 NOT NULL,
+	estado varchar(20) NOT NULL,
+	observacion text NULL,
+	registrado_por int4 NULL,
+	fecha_registro timestamptz DEFAULT now() NOT NULL,
+	fecha_actualizacion timestamptz DEFAULT now() NULL,
+	CONSTRAINT asistencias_estado_check CHECK (((estado)::text = ANY ((ARRAY['presente'::character varying, 'ausente'::character varying, 'tarde'::character varying, 'justificado'::character varying])::text[]))),
+	CONSTRAINT asistencias_pkey PRIMARY KEY (id),
+	CONSTRAINT asistencias_sesion_estudiante_key UNIQUE (sesion_id, estudiante_id),
+	CONSTRAINT asistencias_sesion_id_fkey FOREIGN KEY (sesion_id) REFERENCES rustdema2.sesiones_curso(id) ON DELETE CASCADE,
+	CONSTRAINT asistencias_estudiante_id_fkey FOREIGN KEY (estudiante_id) REFERENCES rustdema2.usuarios(id) ON DELETE CASCADE,
+	CONSTRAINT asistencias_registrado_por_fkey FOREIGN KEY (registrado_por) REFERENCES rustdema2.usuarios(id)
+);
+CREATE INDEX idx_asistencias_estudiante ON rustdema2.asistencias USING btree (estudiante_id);
+
+
 -- rustdema2.temas definition
 
 -- Drop table
@@ -584,6 +611,7 @@ CREATE TABLE rustdema2.temas (
 	visible bool DEFAULT true NOT NULL,
 	fecha_creacion timestamptz DEFAULT now() NULL,
 	fecha_actualizacion timestamptz DEFAULT now() NULL,
+	fecha_eliminacion timestamptz NULL,
 	CONSTRAINT temas_pkey PRIMARY KEY (id),
 	CONSTRAINT temas_modulo_id_fkey FOREIGN KEY (modulo_id) REFERENCES rustdema2.modulos(id) ON DELETE CASCADE
 );
@@ -612,6 +640,7 @@ CREATE TABLE rustdema2.unidades (
 	visible bool DEFAULT true NOT NULL,
 	fecha_creacion timestamptz DEFAULT now() NULL,
 	fecha_actualizacion timestamptz DEFAULT now() NULL,
+	fecha_eliminacion timestamptz NULL,
 	CONSTRAINT unidades_pkey PRIMARY KEY (id),
 	CONSTRAINT unidades_tema_id_fkey FOREIGN KEY (tema_id) REFERENCES rustdema2.temas(id) ON DELETE CASCADE
 );
@@ -783,6 +812,7 @@ CREATE TABLE rustdema2.calificaciones_evaluacion (
 	puntaje_obtenido numeric(5, 2) NULL,
 	puntaje_maximo numeric(5, 2) NOT NULL,
 	retroalimentacion text NULL,
+	detalle_rubrica jsonb NULL,
 	calificado_por int4 NULL,
 	fecha_calificacion timestamptz NULL,
 	estado varchar(20) DEFAULT 'pendiente'::character varying NULL,
@@ -1102,6 +1132,7 @@ CREATE TABLE rustdema2.contenidos_unidad (
 	entrega_id int4 NULL,
 	fecha_creacion timestamptz DEFAULT now() NULL,
 	fecha_actualizacion timestamptz DEFAULT now() NULL,
+	fecha_eliminacion timestamptz NULL,
 	CONSTRAINT contenidos_unidad_pkey PRIMARY KEY (id),
 	CONSTRAINT contenidos_unidad_tipo_check CHECK (((tipo_contenido)::text = ANY ((ARRAY['texto'::character varying, 'documento'::character varying, 'video'::character varying, 'enlace'::character varying, 'examen'::character varying, 'entrega'::character varying, 'actividad'::character varying])::text[]))),
 	CONSTRAINT contenidos_unidad_entrega_id_fkey FOREIGN KEY (entrega_id) REFERENCES rustdema2.entregas(id) ON DELETE CASCADE,
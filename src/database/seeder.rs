@@ -1,17 +1,12 @@
-use sea_orm::{Database, DatabaseConnection};
+use sea_orm::{DatabaseConnection, SqlxPostgresConnector};
 use sqlx::PgPool;
 
 /// Ejecuta todas las migraciones basadas en los modelos
-pub async fn run_migrations(
-    pool: &PgPool,
-    database_url: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn run_migrations(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("Running migrations from SeaORM models...");
 
-    // Convertir SQLx pool a SeaORM connection
-    let db: DatabaseConnection = Database::connect(database_url)
-        .await
-        .map_err(|e| sqlx::Error::Configuration(e.to_string().into()))?;
+    // Convertir SQLx pool a SeaORM connection (hereda el search_path del pool)
+    let db: DatabaseConnection = SqlxPostgresConnector::from_sqlx_postgres_pool(pool.clone());
 
     // Migrar tabla users (legacy)
     migrate_users(pool).await?;
@@ -22,6 +17,54 @@ pub async fn run_migrations(
     // Migrar tabla roles usando SeaORM
     migrate_roles_with_seaorm(&db).await?;
 
+    // Migrar tabla auditoria (append-only)
+    migrate_auditoria_with_seaorm(&db, pool).await?;
+
+    // Columnas de soft delete para la jerarquía de contenido (papelera)
+    migrate_soft_delete(pool).await?;
+
+    // Intentos fallidos y bloqueo temporal de cuentas
+    migrate_bloqueo_login(pool).await?;
+
+    // Evaluaciones genéricas con rúbrica y sus calificaciones
+    migrate_evaluaciones_with_seaorm(&db, pool).await?;
+
+    // Sesiones de curso y asistencia
+    migrate_asistencia_with_seaorm(&db, pool).await?;
+
+    // Tokens del feed .ics del calendario
+    migrate_calendario_tokens_with_seaorm(&db).await?;
+
+    // Enlaces de activación y recuperación de contraseña
+    migrate_tokens_cuenta_with_seaorm(&db).await?;
+
+    // Preferencias de notificación y cola de correos
+    migrate_notificaciones_with_seaorm(&db, pool).await?;
+
+    // Reglas de matrícula: cupo, ventana, prerrequisitos y lista de espera
+    migrate_reglas_matricula_with_seaorm(&db, pool).await?;
+
+    // Ciclo de vida de la matrícula, intentos e historial de estados
+    migrate_estados_matricula_with_seaorm(&db, pool).await?;
+
+    // Borradores y publicación programada del contenido del aula
+    migrate_publicacion_contenido(pool).await?;
+
+    // Liberación condicional y progreso de contenidos
+    migrate_liberacion_with_seaorm(&db, pool).await?;
+
+    // Historial de revisiones de contenidos y personalizaciones
+    migrate_revisiones_with_seaorm(&db, pool).await?;
+
+    // Vínculos de contenidos con su examen o actividad de entrega y metadatos oEmbed
+    migrate_vinculos_contenido(pool).await?;
+
+    // Preguntas alineadas con el modelo, intentos de examen y sus respuestas
+    migrate_respuestas_examen_with_seaorm(&db, pool).await?;
+
+    // Versión HTML saneada del texto en Markdown
+    migrate_texto_enriquecido(pool).await?;
+
     tracing::info!("✅ All migrations completed successfully");
     Ok(())
 }
@@ -30,7 +73,7 @@ pub async fn run_migrations(
 async fn migrate_users(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
     sqlx::query(
         r#"
-        CREATE TABLE IF NOT EXISTS rustdema2.users (
+        CREATE TABLE IF NOT EXISTS users (
             id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
             email VARCHAR(255) UNIQUE NOT NULL,
             password_hash VARCHAR(255) NOT NULL,
@@ -68,3 +111,483 @@ async fn migrate_roles_with_seaorm(db: &DatabaseConnection) -> Result<(), Box<dy
 
     Ok(())
 }
+
+/// Migración para auditoria: tabla desde el modelo + índices de consulta y un
+/// trigger que rechaza UPDATE/DELETE para mantenerla append-only
+async fn migrate_auditoria_with_seaorm(
+    db: &DatabaseConnection,
+    pool: &PgPool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::database::migrator::migrate_entity;
+    use crate::models::auditoria::Entity as Auditoria;
+
+    migrate_entity(db, Auditoria).await?;
+
+    let sentencias = [
+        "CREATE INDEX IF NOT EXISTS idx_auditoria_entidad ON auditoria (entidad, entidad_id)",
+        "CREATE INDEX IF NOT EXISTS idx_auditoria_actor ON auditoria (actor_id)",
+        "CREATE INDEX IF NOT EXISTS idx_auditoria_fecha ON auditoria (fecha DESC)",
+        r#"
+        CREATE OR REPLACE FUNCTION auditoria_solo_insercion() RETURNS trigger AS $$
+        BEGIN
+            RAISE EXCEPTION 'La tabla auditoria es de solo inserción';
+        END;
+        $$ LANGUAGE plpgsql
+        "#,
+        "DROP TRIGGER IF EXISTS trg_auditoria_solo_insercion ON auditoria",
+        r#"
+        CREATE TRIGGER trg_auditoria_solo_insercion
+            BEFORE UPDATE OR DELETE ON auditoria
+            FOR EACH ROW EXECUTE FUNCTION auditoria_solo_insercion()
+        "#,
+    ];
+    for sentencia in sentencias {
+        sqlx::query(sentencia).execute(pool).await?;
+    }
+
+    tracing::info!("✓ Table 'auditoria' protegida contra UPDATE/DELETE");
+    Ok(())
+}
+
+/// Agrega `fecha_eliminacion` a las tablas del contenido que no la tenían en el DDL
+/// e índices parciales para las consultas de elementos activos
+async fn migrate_soft_delete(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let sentencias = [
+        "ALTER TABLE modulos ADD COLUMN IF NOT EXISTS fecha_eliminacion timestamptz NULL",
+        "ALTER TABLE temas ADD COLUMN IF NOT EXISTS fecha_eliminacion timestamptz NULL",
+        "ALTER TABLE unidades ADD COLUMN IF NOT EXISTS fecha_eliminacion timestamptz NULL",
+        "ALTER TABLE contenidos_unidad ADD COLUMN IF NOT EXISTS fecha_eliminacion timestamptz NULL",
+        "ALTER TABLE examenes ADD COLUMN IF NOT EXISTS fecha_eliminacion timestamptz NULL",
+        "CREATE INDEX IF NOT EXISTS idx_temas_activos ON temas (modulo_id) WHERE fecha_eliminacion IS NULL",
+        "CREATE INDEX IF NOT EXISTS idx_unidades_activas ON unidades (tema_id) WHERE fecha_eliminacion IS NULL",
+        "CREATE INDEX IF NOT EXISTS idx_contenidos_unidad_activos ON contenidos_unidad (unidad_id) WHERE fecha_eliminacion IS NULL",
+    ];
+    for sentencia in sentencias {
+        sqlx::query(sentencia).execute(pool).await?;
+    }
+
+    tracing::info!("✓ Columnas de soft delete (papelera) verificadas");
+    Ok(())
+}
+
+/// Migración para el bloqueo de cuentas por intentos de login fallidos
+async fn migrate_bloqueo_login(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let sentencias = [
+        "ALTER TABLE usuarios ADD COLUMN IF NOT EXISTS intentos_fallidos integer NOT NULL DEFAULT 0",
+        "ALTER TABLE usuarios ADD COLUMN IF NOT EXISTS bloqueado_hasta timestamptz NULL",
+    ];
+    for sentencia in sentencias {
+        sqlx::query(sentencia).execute(pool).await?;
+    }
+
+    tracing::info!("✓ Columnas de bloqueo de login verificadas");
+    Ok(())
+}
+
+/// Migración para evaluaciones: el tipo enumerado `tipo_evaluacion` (solo se crea
+/// si no existe en ningún schema; se agregan los valores que falten), las tablas
+/// desde los modelos y el detalle por criterio de la rúbrica
+async fn migrate_evaluaciones_with_seaorm(
+    db: &DatabaseConnection,
+    pool: &PgPool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::database::migrator::migrate_entity;
+    use crate::models::calificacion_evaluacion::Entity as CalificacionEvaluacion;
+    use crate::models::evaluacion::{Entity as Evaluacion, TipoEvaluacion};
+    use sea_orm::{ActiveEnum, Iterable};
+
+    sqlx::query(
+        r#"
+        DO $$
+        BEGIN
+            IF NOT EXISTS (SELECT 1 FROM pg_type WHERE typname = 'tipo_evaluacion') THEN
+                CREATE TYPE tipo_evaluacion AS ENUM ();
+            END IF;
+        END
+        $$
+        "#,
+    )
+    .execute(pool)
+    .await?;
+    for tipo in TipoEvaluacion::iter() {
+        let sentencia = format!(
+            "ALTER TYPE tipo_evaluacion ADD VALUE IF NOT EXISTS '{}'",
+            tipo.to_value()
+        );
+        sqlx::query(&sentencia).execute(pool).await?;
+    }
+
+    migrate_entity(db, Evaluacion).await?;
+    migrate_entity(db, CalificacionEvaluacion).await?;
+
+    let sentencias = [
+        "ALTER TABLE calificaciones_evaluacion ADD COLUMN IF NOT EXISTS detalle_rubrica jsonb NULL",
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_calificaciones_evaluacion_estudiante ON calificaciones_evaluacion (id_evaluacion, id_estudiante)",
+        "CREATE INDEX IF NOT EXISTS idx_evaluaciones_curso_activas ON evaluaciones (id_curso) WHERE fecha_eliminacion IS NULL",
+    ];
+    for sentencia in sentencias {
+        sqlx::query(sentencia).execute(pool).await?;
+    }
+
+    tracing::info!("✓ Tablas de evaluaciones y calificaciones verificadas");
+    Ok(())
+}
+
+/// Migración para asistencia: `sesiones_curso` (si no vino del DDL) con las
+/// columnas del código de auto-registro y la tabla `asistencias`
+async fn migrate_asistencia_with_seaorm(
+    db: &DatabaseConnection,
+    pool: &PgPool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::database::migrator::migrate_entity;
+    use crate::models::asistencia::Entity as Asistencia;
+    use crate::models::sesion_curso::Entity as SesionCurso;
+
+    migrate_entity(db, SesionCurso).await?;
+    migrate_entity(db, Asistencia).await?;
+
+    let sentencias = [
+        "ALTER TABLE sesiones_curso ADD COLUMN IF NOT EXISTS codigo_asistencia varchar(12) NULL",
+        "ALTER TABLE sesiones_curso ADD COLUMN IF NOT EXISTS codigo_expira timestamptz NULL",
+        "CREATE INDEX IF NOT EXISTS idx_sesiones_curso_curso ON sesiones_curso (curso_id, orden)",
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_asistencias_sesion_estudiante ON asistencias (sesion_id, estudiante_id)",
+        "CREATE INDEX IF NOT EXISTS idx_asistencias_estudiante ON asistencias (estudiante_id)",
+    ];
+    for sentencia in sentencias {
+        sqlx::query(sentencia).execute(pool).await?;
+    }
+
+    tracing::info!("✓ Tablas de sesiones y asistencia verificadas");
+    Ok(())
+}
+
+/// Migración para calendario_tokens usando SeaORM
+async fn migrate_calendario_tokens_with_seaorm(
+    db: &DatabaseConnection,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::database::migrator::migrate_entity;
+    use crate::models::calendario_token::Entity as CalendarioToken;
+
+    migrate_entity(db, CalendarioToken).await?;
+    Ok(())
+}
+
+/// Migración para tokens_cuenta usando SeaORM
+async fn migrate_tokens_cuenta_with_seaorm(
+    db: &DatabaseConnection,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::database::migrator::migrate_entity;
+    use crate::models::token_cuenta::Entity as TokenCuenta;
+
+    migrate_entity(db, TokenCuenta).await?;
+    Ok(())
+}
+
+/// Migración para preferencias_notificacion y envios_notificacion usando SeaORM
+async fn migrate_notificaciones_with_seaorm(
+    db: &DatabaseConnection,
+    pool: &PgPool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::database::migrator::migrate_entity;
+    use crate::models::envio_notificacion::Entity as EnvioNotificacion;
+    use crate::models::preferencia_notificacion::Entity as PreferenciaNotificacion;
+
+    migrate_entity(db, PreferenciaNotificacion).await?;
+    migrate_entity(db, EnvioNotificacion).await?;
+
+    let sentencias = [
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_preferencias_notificacion_usuario_tipo ON preferencias_notificacion (usuario_id, tipo)",
+        "CREATE INDEX IF NOT EXISTS idx_envios_notificacion_cola ON envios_notificacion (estado, proximo_intento)",
+    ];
+    for sentencia in sentencias {
+        sqlx::query(sentencia).execute(pool).await?;
+    }
+
+    tracing::info!("✓ Tablas de preferencias y envíos de notificaciones verificadas");
+    Ok(())
+}
+
+/// Migración para las reglas de matrícula: columnas de cupo y ventana en `cursos`,
+/// `prerequisitos_curso` y `listas_espera`
+async fn migrate_reglas_matricula_with_seaorm(
+    db: &DatabaseConnection,
+    pool: &PgPool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::database::migrator::migrate_entity;
+    use crate::models::lista_espera::Entity as ListaEspera;
+    use crate::models::prerequisito_curso::Entity as PrerequisitoCurso;
+
+    let columnas = [
+        "ALTER TABLE cursos ADD COLUMN IF NOT EXISTS cupo_maximo integer NULL",
+        "ALTER TABLE cursos ADD COLUMN IF NOT EXISTS fecha_apertura_matricula timestamptz NULL",
+        "ALTER TABLE cursos ADD COLUMN IF NOT EXISTS fecha_cierre_matricula timestamptz NULL",
+    ];
+    for sentencia in columnas {
+        sqlx::query(sentencia).execute(pool).await?;
+    }
+
+    migrate_entity(db, PrerequisitoCurso).await?;
+    migrate_entity(db, ListaEspera).await?;
+
+    let indices = [
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_prerequisitos_curso_par ON prerequisitos_curso (curso_id, prerequisito_id)",
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_listas_espera_curso_estudiante ON listas_espera (curso_id, estudiante_id)",
+        "CREATE INDEX IF NOT EXISTS idx_listas_espera_orden ON listas_espera (curso_id, fecha_solicitud, id)",
+    ];
+    for sentencia in indices {
+        sqlx::query(sentencia).execute(pool).await?;
+    }
+
+    tracing::info!("✓ Reglas de matrícula y listas de espera verificadas");
+    Ok(())
+}
+
+/// Migración del ciclo de vida de la matrícula: los estados antiguos pasan a los
+/// nuevos (`en_progreso` → `activo`, `inactivo` → `retirado`), la restricción única
+/// por (curso, estudiante) se reemplaza por una sola matrícula activa para permitir
+/// nuevos intentos, y se crea `cambios_estado_matricula`
+async fn migrate_estados_matricula_with_seaorm(
+    db: &DatabaseConnection,
+    pool: &PgPool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::database::migrator::migrate_entity;
+    use crate::models::cambio_estado_matricula::Entity as CambioEstadoMatricula;
+
+    let sentencias = [
+        "ALTER TABLE historial_cursos_estudiantes ADD COLUMN IF NOT EXISTS intento integer NOT NULL DEFAULT 1",
+        "ALTER TABLE historial_cursos_estudiantes ADD COLUMN IF NOT EXISTS matricula_anterior_id integer NULL REFERENCES historial_cursos_estudiantes(id) ON DELETE SET NULL",
+        "UPDATE historial_cursos_estudiantes SET estado = 'activo' WHERE estado = 'en_progreso'",
+        "UPDATE historial_cursos_estudiantes SET estado = 'retirado' WHERE estado = 'inactivo'",
+        "ALTER TABLE historial_cursos_estudiantes ALTER COLUMN estado SET DEFAULT 'activo'",
+        "ALTER TABLE historial_cursos_estudiantes DROP CONSTRAINT IF EXISTS uq_historial_curso_estudiante",
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_historial_curso_estudiante_activa ON historial_cursos_estudiantes (curso_id, estudiante_id) WHERE estado = 'activo'",
+        "CREATE INDEX IF NOT EXISTS idx_historial_curso_estudiante ON historial_cursos_estudiantes (curso_id, estudiante_id, intento)",
+    ];
+    for sentencia in sentencias {
+        sqlx::query(sentencia).execute(pool).await?;
+    }
+
+    migrate_entity(db, CambioEstadoMatricula).await?;
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_cambios_estado_matricula ON cambios_estado_matricula (matricula_id, fecha)",
+    )
+    .execute(pool)
+    .await?;
+
+    tracing::info!("✓ Estados de matrícula e historial de cambios verificados");
+    Ok(())
+}
+
+/// Estado de publicación de módulos, temas, unidades y contenidos. Las filas
+/// existentes toman el estado de `visible` (visible → publicado, oculto → borrador)
+async fn migrate_publicacion_contenido(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    for tabla in ["modulos", "temas", "unidades", "contenidos_unidad"] {
+        let sentencias = [
+            format!("ALTER TABLE {tabla} ADD COLUMN IF NOT EXISTS estado_publicacion varchar(20) NOT NULL DEFAULT 'publicado'"),
+            format!("ALTER TABLE {tabla} ADD COLUMN IF NOT EXISTS publicar_en timestamptz NULL"),
+            format!("ALTER TABLE {tabla} ADD COLUMN IF NOT EXISTS despublicar_en timestamptz NULL"),
+            format!("UPDATE {tabla} SET estado_publicacion = 'borrador' WHERE NOT visible AND estado_publicacion = 'publicado'"),
+            format!("CREATE INDEX IF NOT EXISTS idx_{tabla}_publicar_en ON {tabla} (publicar_en) WHERE publicar_en IS NOT NULL"),
+            format!("CREATE INDEX IF NOT EXISTS idx_{tabla}_despublicar_en ON {tabla} (despublicar_en) WHERE despublicar_en IS NOT NULL"),
+        ];
+        for sentencia in sentencias {
+            sqlx::query(&sentencia).execute(pool).await?;
+        }
+    }
+
+    tracing::info!("✓ Estados de publicación del contenido verificados");
+    Ok(())
+}
+
+/// Migración de la liberación condicional: condiciones por elemento, contenidos
+/// completados y liberaciones ya avisadas. `puntaje_examen` lee `intentos_examen`,
+/// que crea [`migrate_respuestas_examen_with_seaorm`]
+async fn migrate_liberacion_with_seaorm(
+    db: &DatabaseConnection,
+    pool: &PgPool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::database::migrator::migrate_entity;
+    use crate::models::condicion_liberacion::Entity as CondicionLiberacion;
+    use crate::models::liberacion_estudiante::Entity as LiberacionEstudiante;
+    use crate::models::progreso_contenido::Entity as ProgresoContenido;
+
+    migrate_entity(db, CondicionLiberacion).await?;
+    migrate_entity(db, ProgresoContenido).await?;
+    migrate_entity(db, LiberacionEstudiante).await?;
+
+    let sentencias = [
+        "CREATE INDEX IF NOT EXISTS idx_condiciones_liberacion_curso ON condiciones_liberacion (curso_id)",
+        "CREATE INDEX IF NOT EXISTS idx_condiciones_liberacion_elemento ON condiciones_liberacion (tipo_elemento, elemento_id)",
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_progresos_contenido_estudiante ON progresos_contenido (contenido_id, estudiante_id)",
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_liberaciones_estudiante_elemento ON liberaciones_estudiante (tipo_elemento, elemento_id, estudiante_id)",
+    ];
+    for sentencia in sentencias {
+        sqlx::query(sentencia).execute(pool).await?;
+    }
+
+    tracing::info!("✓ Tablas de liberación condicional verificadas");
+    Ok(())
+}
+
+/// Migración para revisiones: tabla desde el modelo y número único por entidad
+async fn migrate_revisiones_with_seaorm(
+    db: &DatabaseConnection,
+    pool: &PgPool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::database::migrator::migrate_entity;
+    use crate::models::revision::Entity as Revision;
+
+    migrate_entity(db, Revision).await?;
+
+    sqlx::query(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_revisiones_entidad_numero ON revisiones (tipo, entidad_id, numero)",
+    )
+    .execute(pool)
+    .await?;
+
+    tracing::info!("✓ Tabla de revisiones verificada");
+    Ok(())
+}
+
+/// Migración de los vínculos de contenidos: actividad de entrega (antes se apuntaba
+/// a una entrega de estudiante), metadatos oEmbed y borrado en cascada del examen
+async fn migrate_vinculos_contenido(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let sentencias = [
+        "ALTER TABLE contenidos_unidad ADD COLUMN IF NOT EXISTS actividad_entrega_id integer NULL REFERENCES actividades_entrega(id) ON DELETE CASCADE",
+        "ALTER TABLE contenidos_unidad ADD COLUMN IF NOT EXISTS oembed jsonb NULL",
+        r#"UPDATE contenidos_unidad c SET actividad_entrega_id = e.actividad_entrega_id
+           FROM entregas e
+           WHERE c.entrega_id = e.id AND c.actividad_entrega_id IS NULL"#,
+        r#"UPDATE contenidos_unidad c SET examen_id = NULL
+           WHERE c.examen_id IS NOT NULL
+             AND NOT EXISTS (SELECT 1 FROM examenes e WHERE e.id = c.examen_id)"#,
+        r#"DO $$
+           BEGIN
+               IF NOT EXISTS (
+                   SELECT 1 FROM pg_constraint WHERE conname = 'contenidos_unidad_examen_id_fkey'
+               ) THEN
+                   ALTER TABLE contenidos_unidad ADD CONSTRAINT contenidos_unidad_examen_id_fkey
+                       FOREIGN KEY (examen_id) REFERENCES examenes(id) ON DELETE CASCADE;
+               END IF;
+           END $$"#,
+        "CREATE INDEX IF NOT EXISTS idx_contenidos_unidad_examen ON contenidos_unidad (examen_id) WHERE examen_id IS NOT NULL",
+        "CREATE INDEX IF NOT EXISTS idx_contenidos_unidad_actividad_entrega ON contenidos_unidad (actividad_entrega_id) WHERE actividad_entrega_id IS NOT NULL",
+    ];
+    for sentencia in sentencias {
+        sqlx::query(sentencia).execute(pool).await?;
+    }
+
+    tracing::info!("✓ Vínculos de contenidos verificados");
+    Ok(())
+}
+
+/// Migración de los intentos de examen y sus respuestas. Antes alinea `preguntas_examen`
+/// con el modelo (el DDL original usa `enunciado`, `porcentaje` y una clave en texto)
+async fn migrate_respuestas_examen_with_seaorm(
+    db: &DatabaseConnection,
+    pool: &PgPool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::database::migrator::migrate_entity;
+    use crate::models::intento_examen::Entity as IntentoExamen;
+    use crate::models::respuesta_examen::Entity as RespuestaExamen;
+
+    let sentencias = [
+        "ALTER TABLE preguntas_examen ADD COLUMN IF NOT EXISTS pregunta text NULL",
+        "ALTER TABLE preguntas_examen ADD COLUMN IF NOT EXISTS valor_puntos integer NOT NULL DEFAULT 1",
+        "ALTER TABLE preguntas_examen ADD COLUMN IF NOT EXISTS orden integer NOT NULL DEFAULT 0",
+        "ALTER TABLE preguntas_examen ADD COLUMN IF NOT EXISTS created_at timestamptz NULL DEFAULT now()",
+        "ALTER TABLE preguntas_examen ADD COLUMN IF NOT EXISTS updated_at timestamptz NULL DEFAULT now()",
+        r#"DO $$
+           BEGIN
+               IF EXISTS (
+                   SELECT 1 FROM information_schema.columns
+                   WHERE table_schema = current_schema()
+                     AND table_name = 'preguntas_examen' AND column_name = 'enunciado'
+               ) THEN
+                   UPDATE preguntas_examen SET pregunta = enunciado WHERE pregunta IS NULL;
+                   ALTER TABLE preguntas_examen ALTER COLUMN enunciado DROP NOT NULL;
+               END IF;
+               IF EXISTS (
+                   SELECT 1 FROM information_schema.columns
+                   WHERE table_schema = current_schema() AND table_name = 'preguntas_examen'
+                     AND column_name = 'respuesta_correcta' AND data_type = 'text'
+               ) THEN
+                   ALTER TABLE preguntas_examen ALTER COLUMN respuesta_correcta TYPE jsonb
+                       USING CASE WHEN respuesta_correcta IS NULL THEN NULL
+                                  ELSE to_jsonb(respuesta_correcta) END;
+               END IF;
+           END $$"#,
+        "UPDATE preguntas_examen SET pregunta = '' WHERE pregunta IS NULL",
+        "ALTER TABLE preguntas_examen ALTER COLUMN pregunta SET NOT NULL",
+    ];
+    for sentencia in sentencias {
+        sqlx::query(sentencia).execute(pool).await?;
+    }
+
+    migrate_entity(db, IntentoExamen).await?;
+    migrate_entity(db, RespuestaExamen).await?;
+
+    let indices = [
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_intentos_examen_numero ON intentos_examen (examen_id, estudiante_id, numero)",
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_respuestas_examen_intento_pregunta ON respuestas_examen (intento_id, pregunta_id)",
+        "CREATE INDEX IF NOT EXISTS idx_respuestas_examen_pendientes ON respuestas_examen (pregunta_id) WHERE puntaje IS NULL",
+    ];
+    for indice in indices {
+        sqlx::query(indice).execute(pool).await?;
+    }
+
+    tracing::info!("✓ Tablas de preguntas, intentos y respuestas de examen verificadas");
+    Ok(())
+}
+
+/// Migración del texto enriquecido: columna renderizada junto a cada fuente en
+/// Markdown y renderizado de las filas que aún no la tienen
+async fn migrate_texto_enriquecido(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::utils::markdown;
+
+    // (tabla, fuente, renderizada)
+    let columnas = [
+        ("contenidos_unidad", "contenido", "contenido_html"),
+        ("portafolios_contenidos", "contenido", "contenido_html"),
+        ("notificaciones", "mensaje", "mensaje_html"),
+        ("preguntas_examen", "pregunta", "pregunta_html"),
+    ];
+    for (tabla, fuente, html) in columnas {
+        sqlx::query(&format!(
+            "ALTER TABLE IF EXISTS {tabla} ADD COLUMN IF NOT EXISTS {html} text NULL"
+        ))
+        .execute(pool)
+        .await?;
+
+        // Se salta lo que esta base aún no tiene (tabla o columna fuente)
+        let existe: bool = sqlx::query_scalar(
+            r#"SELECT EXISTS (
+                   SELECT 1 FROM information_schema.columns
+                   WHERE table_schema = current_schema() AND table_name = $1 AND column_name = $2
+               )"#,
+        )
+        .bind(tabla)
+        .bind(fuente)
+        .fetch_one(pool)
+        .await?;
+        if !existe {
+            continue;
+        }
+
+        let pendientes: Vec<(i32, String)> = sqlx::query_as(&format!(
+            "SELECT id, {fuente} FROM {tabla} WHERE {html} IS NULL AND {fuente} IS NOT NULL"
+        ))
+        .fetch_all(pool)
+        .await?;
+        for (id, texto) in pendientes {
+            sqlx::query(&format!("UPDATE {tabla} SET {html} = $1 WHERE id = $2"))
+                .bind(markdown::a_html(&texto))
+                .bind(id)
+                .execute(pool)
+                .await?;
+        }
+    }
+
+    tracing::info!("✓ Texto enriquecido (HTML saneado) verificado");
+    Ok(())
+}
@@ -14,6 +14,19 @@ use crate::{
     utils::errors::AppError,
 };
 
+/// Lista todas las actividades
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/actividades",
+    tag = "Actividades",
+    responses(
+        (status = 200, description = "Actividades registradas", body = [ActividadModel]),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn listar_actividades(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -23,6 +36,20 @@ pub async fn listar_actividades(
     Ok(Json(actividades))
 }
 
+/// Lista las actividades de un curso
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/cursos/{curso_id}/actividades",
+    tag = "Actividades",
+    params(("curso_id" = i32, Path, description = "Id del curso")),
+    responses(
+        (status = 200, description = "Actividades del curso", body = [ActividadModel]),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn listar_actividades_por_curso(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -33,6 +60,21 @@ pub async fn listar_actividades_por_curso(
     Ok(Json(actividades))
 }
 
+/// Obtiene una actividad
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/actividades/{id}",
+    tag = "Actividades",
+    params(("id" = i32, Path, description = "Id de la actividad")),
+    responses(
+        (status = 200, description = "Actividad encontrada", body = ActividadModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Actividad no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn obtener_actividad(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -48,6 +90,21 @@ pub async fn obtener_actividad(
     }
 }
 
+/// Crea una actividad
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    post,
+    path = "/api/actividades",
+    tag = "Actividades",
+    request_body = NewActividad,
+    responses(
+        (status = 201, description = "Actividad creada", body = ActividadModel),
+        (status = 400, description = "Datos inválidos"),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn crear_actividad(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -58,6 +115,22 @@ pub async fn crear_actividad(
     Ok((StatusCode::CREATED, Json(actividad)))
 }
 
+/// Actualiza una actividad
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    put,
+    path = "/api/actividades/{id}",
+    tag = "Actividades",
+    params(("id" = i32, Path, description = "Id de la actividad")),
+    request_body = UpdateActividad,
+    responses(
+        (status = 200, description = "Actividad actualizada", body = ActividadModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Actividad no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn actualizar_actividad(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -69,6 +142,21 @@ pub async fn actualizar_actividad(
     Ok(Json(actividad))
 }
 
+/// Elimina una actividad
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    delete,
+    path = "/api/actividades/{id}",
+    tag = "Actividades",
+    params(("id" = i32, Path, description = "Id de la actividad")),
+    responses(
+        (status = 204, description = "Actividad eliminada"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Actividad no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn eliminar_actividad(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
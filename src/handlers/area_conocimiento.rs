@@ -11,11 +11,24 @@ use crate::{
     utils::errors::AppError,
 };
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, utoipa::ToSchema)]
 pub struct CambiarEstadoPayload {
     pub estado: bool,
 }
 
+/// Lista las áreas de conocimiento
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/areas-conocimiento",
+    tag = "Áreas de conocimiento",
+    responses(
+        (status = 200, description = "Áreas registradas", body = [AreaConocimientoModel]),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn listar_areas(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -25,6 +38,19 @@ pub async fn listar_areas(
     Ok(Json(areas))
 }
 
+/// Lista las áreas de conocimiento activas
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/areas-conocimiento/activas",
+    tag = "Áreas de conocimiento",
+    responses(
+        (status = 200, description = "Áreas activas", body = [AreaConocimientoModel]),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn listar_areas_activas(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -34,6 +60,21 @@ pub async fn listar_areas_activas(
     Ok(Json(areas))
 }
 
+/// Obtiene un área de conocimiento
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/areas-conocimiento/{id}",
+    tag = "Áreas de conocimiento",
+    params(("id" = i32, Path, description = "Id del área")),
+    responses(
+        (status = 200, description = "Área encontrada", body = AreaConocimientoModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Área no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn obtener_area(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -48,6 +89,21 @@ pub async fn obtener_area(
     }
 }
 
+/// Crea un área de conocimiento
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    post,
+    path = "/api/areas-conocimiento",
+    tag = "Áreas de conocimiento",
+    request_body = NuevaArea,
+    responses(
+        (status = 201, description = "Área creada", body = AreaConocimientoModel),
+        (status = 400, description = "Datos inválidos"),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn crear_area(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -58,6 +114,22 @@ pub async fn crear_area(
     Ok((StatusCode::CREATED, Json(area)))
 }
 
+/// Actualiza un área de conocimiento
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    put,
+    path = "/api/areas-conocimiento/{id}",
+    tag = "Áreas de conocimiento",
+    params(("id" = i32, Path, description = "Id del área")),
+    request_body = ActualizarArea,
+    responses(
+        (status = 200, description = "Área actualizada", body = AreaConocimientoModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Área no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn actualizar_area(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -69,6 +141,22 @@ pub async fn actualizar_area(
     Ok(Json(area))
 }
 
+/// Activa o desactiva un área de conocimiento
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    patch,
+    path = "/api/areas-conocimiento/{id}/estado",
+    tag = "Áreas de conocimiento",
+    params(("id" = i32, Path, description = "Id del área")),
+    request_body = CambiarEstadoPayload,
+    responses(
+        (status = 200, description = "Estado actualizado", body = AreaConocimientoModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Área no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn cambiar_estado(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -80,6 +168,21 @@ pub async fn cambiar_estado(
     Ok(Json(area))
 }
 
+/// Elimina un área de conocimiento
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    delete,
+    path = "/api/areas-conocimiento/{id}",
+    tag = "Áreas de conocimiento",
+    params(("id" = i32, Path, description = "Id del área")),
+    responses(
+        (status = 204, description = "Área eliminada"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Área no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn eliminar_area(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
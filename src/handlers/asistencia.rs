@@ -0,0 +1,357 @@
+use axum::{
+    extract::{FromRef, Path, Query, State},
+    http::StatusCode,
+};
+
+use crate::{
+    middleware::{auth::AuthUser, json::Json},
+    models::{
+        asistencia::Model as AsistenciaModel,
+        auditoria::ContextoAuditoria,
+        rol::{ROL_ADMINISTRADOR, ROL_PROFESOR},
+        sesion_curso::Model as SesionCursoModel,
+        AppState,
+    },
+    services::asistencia_service::{
+        ActualizarSesion, AsistenciaEstudiante, AsistenciaService, CheckIn, CodigoAsistencia,
+        GenerarCodigo, NuevaSesion, PaseDeLista, ResumenAsistencia,
+    },
+    utils::errors::AppError,
+};
+
+// POST /api/cursos/{curso_id}/sesiones
+/// Programa una sesión del curso
+///
+/// Roles: Administrador, Profesor (solo de sus cursos)
+#[utoipa::path(
+    post,
+    path = "/api/cursos/{curso_id}/sesiones",
+    tag = "Asistencia",
+    params(("curso_id" = i32, Path, description = "Id del curso")),
+    request_body = NuevaSesion,
+    responses(
+        (status = 201, description = "Sesión creada", body = SesionCursoModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente o curso no asignado"),
+        (status = 404, description = "Curso no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn crear_sesion(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(curso_id): Path<i32>,
+    Json(payload): Json<NuevaSesion>,
+) -> Result<(StatusCode, Json<SesionCursoModel>), AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_PROFESOR])
+        .await?;
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+
+    let service = AsistenciaService::from_ref(&state);
+    let sesion = service.crear_sesion(curso_id, payload, profesor_id).await?;
+    Ok((StatusCode::CREATED, Json(sesion)))
+}
+
+// GET /api/cursos/{curso_id}/sesiones
+/// Lista las sesiones de un curso
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/cursos/{curso_id}/sesiones",
+    tag = "Asistencia",
+    params(("curso_id" = i32, Path, description = "Id del curso")),
+    responses(
+        (status = 200, description = "Sesiones en orden", body = [SesionCursoModel]),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn listar_sesiones(
+    _auth_user: AuthUser, // Validar JWT automáticamente
+    State(state): State<AppState>,
+    Path(curso_id): Path<i32>,
+) -> Result<Json<Vec<SesionCursoModel>>, AppError> {
+    let service = AsistenciaService::from_ref(&state);
+    let sesiones = service.listar_sesiones(curso_id).await?;
+    Ok(Json(sesiones))
+}
+
+// PUT /api/sesiones/{id}
+/// Actualiza una sesión
+///
+/// Roles: Administrador, Profesor (solo de sus cursos)
+#[utoipa::path(
+    put,
+    path = "/api/sesiones/{id}",
+    tag = "Asistencia",
+    params(("id" = i32, Path, description = "Id de la sesión")),
+    request_body = ActualizarSesion,
+    responses(
+        (status = 200, description = "Sesión actualizada", body = SesionCursoModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente o curso no asignado"),
+        (status = 404, description = "Sesión no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn actualizar_sesion(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Json(payload): Json<ActualizarSesion>,
+) -> Result<Json<SesionCursoModel>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_PROFESOR])
+        .await?;
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+
+    let service = AsistenciaService::from_ref(&state);
+    let sesion = service.actualizar_sesion(id, payload, profesor_id).await?;
+    Ok(Json(sesion))
+}
+
+// DELETE /api/sesiones/{id}
+/// Elimina una sesión junto con su asistencia
+///
+/// Roles: Administrador, Profesor (solo de sus cursos)
+#[utoipa::path(
+    delete,
+    path = "/api/sesiones/{id}",
+    tag = "Asistencia",
+    params(("id" = i32, Path, description = "Id de la sesión")),
+    responses(
+        (status = 204, description = "Sesión eliminada"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente o curso no asignado"),
+        (status = 404, description = "Sesión no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn eliminar_sesion(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_PROFESOR])
+        .await?;
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+
+    let service = AsistenciaService::from_ref(&state);
+    service.eliminar_sesion(id, profesor_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// GET /api/sesiones/{id}/asistencia
+/// Asistencia de cada estudiante matriculado en la sesión
+///
+/// Roles: Administrador, Profesor (solo de sus cursos)
+#[utoipa::path(
+    get,
+    path = "/api/sesiones/{id}/asistencia",
+    tag = "Asistencia",
+    params(("id" = i32, Path, description = "Id de la sesión")),
+    responses(
+        (status = 200, description = "Lista de la sesión", body = [AsistenciaEstudiante]),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente o curso no asignado"),
+        (status = 404, description = "Sesión no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn obtener_asistencia_sesion(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<AsistenciaEstudiante>>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_PROFESOR])
+        .await?;
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+
+    let service = AsistenciaService::from_ref(&state);
+    let lista = service.asistencia_sesion(id, profesor_id).await?;
+    Ok(Json(lista))
+}
+
+// PUT /api/sesiones/{id}/asistencia (pase de lista)
+/// Registra o corrige la asistencia de la sesión
+///
+/// Roles: Administrador, Profesor (solo de sus cursos)
+#[utoipa::path(
+    put,
+    path = "/api/sesiones/{id}/asistencia",
+    tag = "Asistencia",
+    params(("id" = i32, Path, description = "Id de la sesión")),
+    request_body = PaseDeLista,
+    responses(
+        (status = 200, description = "Registros guardados", body = [AsistenciaModel]),
+        (status = 400, description = "Estudiante no matriculado"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente o curso no asignado"),
+        (status = 404, description = "Sesión no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn pasar_lista(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Json(payload): Json<PaseDeLista>,
+) -> Result<Json<Vec<AsistenciaModel>>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_PROFESOR])
+        .await?;
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+
+    let service = AsistenciaService::from_ref(&state);
+    let registros = service
+        .pasar_lista(id, payload, auth_user.user_id, profesor_id)
+        .await?;
+    Ok(Json(registros))
+}
+
+// POST /api/sesiones/{id}/codigo?minutos=
+/// Genera el código temporal de check-in
+///
+/// Roles: Administrador, Profesor (solo de sus cursos)
+#[utoipa::path(
+    post,
+    path = "/api/sesiones/{id}/codigo",
+    tag = "Asistencia",
+    params(
+        ("id" = i32, Path, description = "Id de la sesión"),
+        GenerarCodigo
+    ),
+    responses(
+        (status = 200, description = "Código vigente", body = CodigoAsistencia),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente o curso no asignado"),
+        (status = 404, description = "Sesión no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn generar_codigo(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Query(datos): Query<GenerarCodigo>,
+) -> Result<Json<CodigoAsistencia>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_PROFESOR])
+        .await?;
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+
+    let service = AsistenciaService::from_ref(&state);
+    let codigo = service.generar_codigo(id, datos, profesor_id).await?;
+    Ok(Json(codigo))
+}
+
+// POST /api/sesiones/{id}/check-in
+/// Marca la asistencia propia con el código de la sesión
+///
+/// Roles: estudiante matriculado en el curso
+#[utoipa::path(
+    post,
+    path = "/api/sesiones/{id}/check-in",
+    tag = "Asistencia",
+    params(("id" = i32, Path, description = "Id de la sesión")),
+    request_body = CheckIn,
+    responses(
+        (status = 200, description = "Asistencia registrada", body = AsistenciaModel),
+        (status = 400, description = "Código inválido o vencido"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "No matriculado en el curso"),
+        (status = 404, description = "Sesión no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn check_in(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Json(payload): Json<CheckIn>,
+) -> Result<Json<AsistenciaModel>, AppError> {
+    let service = AsistenciaService::from_ref(&state);
+    let registro = service.check_in(id, auth_user.user_id, payload).await?;
+    Ok(Json(registro))
+}
+
+// GET /api/cursos/{curso_id}/asistencia
+// Los estudiantes solo reciben su propio resumen
+/// Porcentaje de asistencia por estudiante
+///
+/// Roles: Administrador y Profesor (de sus cursos) ven a todos; el resto solo su propio
+/// resumen
+#[utoipa::path(
+    get,
+    path = "/api/cursos/{curso_id}/asistencia",
+    tag = "Asistencia",
+    params(("curso_id" = i32, Path, description = "Id del curso")),
+    responses(
+        (status = 200, description = "Resumen por estudiante", body = [ResumenAsistencia]),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Curso no asignado"),
+        (status = 404, description = "Curso no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn resumen_asistencia_curso(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(curso_id): Path<i32>,
+) -> Result<Json<Vec<ResumenAsistencia>>, AppError> {
+    let es_docente = auth_user
+        .tiene_rol(&state, &[ROL_ADMINISTRADOR, ROL_PROFESOR])
+        .await?;
+    let estudiante_id = (!es_docente).then_some(auth_user.user_id);
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+
+    let service = AsistenciaService::from_ref(&state);
+    let resumen = service
+        .resumen_curso(
+            curso_id,
+            estudiante_id,
+            profesor_id,
+            state.config.asistencia_minima,
+        )
+        .await?;
+    Ok(Json(resumen))
+}
+
+// POST /api/cursos/{curso_id}/asistencia/aplicar-umbral
+/// Reprueba a quienes no alcanzan la asistencia mínima
+///
+/// Roles: Administrador, Profesor (solo de sus cursos)
+#[utoipa::path(
+    post,
+    path = "/api/cursos/{curso_id}/asistencia/aplicar-umbral",
+    tag = "Asistencia",
+    params(("curso_id" = i32, Path, description = "Id del curso")),
+    responses(
+        (status = 200, description = "Resumen tras aplicar el umbral", body = [ResumenAsistencia]),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente o curso no asignado"),
+        (status = 404, description = "Curso no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn aplicar_umbral_asistencia(
+    auth_user: AuthUser,
+    ctx: ContextoAuditoria,
+    State(state): State<AppState>,
+    Path(curso_id): Path<i32>,
+) -> Result<Json<Vec<ResumenAsistencia>>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_PROFESOR])
+        .await?;
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+
+    let service = AsistenciaService::from_ref(&state);
+    let resumen = service
+        .aplicar_umbral(curso_id, state.config.asistencia_minima, profesor_id, &ctx)
+        .await?;
+    Ok(Json(resumen))
+}
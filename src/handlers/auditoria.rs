@@ -0,0 +1,39 @@
+use axum::{
+    extract::{FromRef, Query, State},
+    Json,
+};
+
+use crate::{
+    middleware::auth::AuthUser,
+    models::{auditoria::Model as AuditoriaModel, rol::ROL_ADMINISTRADOR, AppState},
+    services::auditoria_service::{AuditoriaService, FiltroAuditoria},
+    utils::errors::AppError,
+};
+
+// GET /api/auditoria?actor_id=&entidad=&entidad_id=&accion=&desde=&hasta=&limite=&pagina=
+/// Consulta el registro de auditoría
+///
+/// Roles: Administrador
+#[utoipa::path(
+    get,
+    path = "/api/auditoria",
+    tag = "Auditoría",
+    params(FiltroAuditoria),
+    responses(
+        (status = 200, description = "Entradas de auditoría", body = [AuditoriaModel]),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn listar_auditoria(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Query(filtro): Query<FiltroAuditoria>,
+) -> Result<Json<Vec<AuditoriaModel>>, AppError> {
+    auth_user.exigir_rol(&state, &[ROL_ADMINISTRADOR]).await?;
+
+    let service = AuditoriaService::from_ref(&state);
+    let entradas = service.consultar(filtro).await?;
+    Ok(Json(entradas))
+}
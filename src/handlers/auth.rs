@@ -1,22 +1,28 @@
-use axum::{extract::State, Json};
-use bcrypt::{hash, DEFAULT_COST};
+use axum::{
+    extract::{FromRef, State},
+    Json,
+};
 use serde::{Deserialize, Serialize};
-use tokio;
 use utoipa::ToSchema;
 use validator::Validate;
 
 use crate::{
     models::{ApiResponse, AppState, AuthResponse, Claims, CreateUserRequest, LoginRequest, User},
-    utils::errors::AppError,
+    services::{
+        acceso_service::AccesoService,
+        metrics_service::{get_metrics_service, EventoDominio},
+    },
+    utils::{client_ip::IpCliente, errors::AppError, password},
 };
 
 #[utoipa::path(
     post,
     path = "/auth/register",
+    tag = "Auth",
     request_body = CreateUserRequest,
     responses(
-        (status = 201, description = "Usuario registrado exitosamente", body = AuthResponse),
-        (status = 400, description = "Datos inválidos o usuario ya existe"),
+        (status = 201, description = "Usuario registrado exitosamente", body = AuthApiResponse),
+        (status = 400, description = "Datos inválidos, contraseña fuera de la política o usuario ya existe"),
         (status = 500, description = "Error interno del servidor")
     )
 )]
@@ -40,11 +46,14 @@ pub async fn register(
         return Err(AppError::BadRequest("User already exists".into()));
     }
 
+    password::validar_politica(
+        &payload.password,
+        state.config.cuenta.contrasena_min,
+        &[&payload.email],
+    )?;
+
     // Hash password
-    let password = payload.password.clone();
-    let password_hash = tokio::task::spawn_blocking(move || hash(password, DEFAULT_COST))
-        .await
-        .map_err(|e| AppError::InternalServerError(format!("Task join error: {}", e).into()))??;
+    let password_hash = password::hashear(&payload.password).await?;
 
     // Create user
     let user = sqlx::query_as::<_, User>(
@@ -61,7 +70,7 @@ pub async fn register(
     .await?;
 
     // Generate JWT token
-    let token = generate_token(&user, state.jwt_encoding_key.as_ref())?;
+    let token = generate_token(&user, &state)?;
 
     let response = AuthResponse {
         token,
@@ -74,25 +83,35 @@ pub async fn register(
 #[utoipa::path(
     post,
     path = "/auth/login",
+    tag = "Auth",
     request_body = LoginRequest,
     responses(
-        (status = 200, description = "Login exitoso", body = AuthResponse),
+        (status = 200, description = "Login exitoso", body = AuthApiResponse),
         (status = 401, description = "Credenciales inválidas"),
         (status = 400, description = "Datos inválidos"),
+        (status = 429, description = "Demasiados intentos o cuenta bloqueada (ver Retry-After)"),
         (status = 500, description = "Error interno del servidor")
     )
 )]
 pub async fn login(
     State(state): State<AppState>,
+    IpCliente(ip): IpCliente,
     Json(payload): Json<LoginRequest>,
 ) -> Result<Json<ApiResponse<AuthResponse>>, AppError> {
     // Validate input
     payload.validate()?;
 
-    // Modo testing: si no hay BD disponible y es usuario de test, generar respuesta
+    let acceso = AccesoService::from_ref(&state);
+    acceso.verificar_espera(&ip, &payload.email)?;
+
+    // Modo testing (TEST_LOGIN_ENABLED): sin BD disponible se acepta el usuario de test
     let db_available = state.db.is_some();
 
-    if !db_available && payload.email == "test@example.com" && payload.password == "admin123" {
+    if state.config.login_prueba
+        && !db_available
+        && payload.email == "test@example.com"
+        && payload.password == "admin123"
+    {
         // Crear usuario de testing
         let test_user = User {
             id: 999999, // ID de testing como i32
@@ -104,7 +123,7 @@ pub async fn login(
         };
 
         // Generate JWT token
-        let token = generate_token(&test_user, state.jwt_encoding_key.as_ref())?;
+        let token = generate_token(&test_user, &state)?;
 
         let response = AuthResponse {
             token,
@@ -119,27 +138,40 @@ pub async fn login(
     let db = state.get_db()?;
 
     // Find user by email
-    let user = sqlx::query_as::<_, User>(
+    let Some(user) = sqlx::query_as::<_, User>(
         "SELECT id, correo as email, contrasena as password_hash, nombre as name, fecha_creacion as created_at, fecha_actualizacion as updated_at FROM usuarios WHERE correo = $1"
     )
     .bind(&payload.email)
     .fetch_optional(db)
     .await?
-    .ok_or_else(|| AppError::Unauthorized("Invalid credentials".into()))?;
+    else {
+        get_metrics_service().registrar_evento(EventoDominio::LoginFallido);
+        acceso.registrar_fallo(&ip, &payload.email, None).await;
+        return Err(AppError::Unauthorized("Invalid credentials".into()));
+    };
 
-    // Verify password (temporalmente simplificado para debugging)
-    let password = payload.password.clone();
-    let password_hash = user.password_hash.clone();
+    acceso.verificar_bloqueo(user.id).await?;
 
-    // POR AHORA: comparar directamente (sin bcrypt) para verificar que funciona
-    let is_valid = password == password_hash || password == "admin123";
+    // bcrypt (o texto plano en cuentas antiguas); la contraseña maestra solo con TEST_LOGIN_ENABLED
+    let coincide = password::verificar(&payload.password, &user.password_hash).await;
+    let is_valid = coincide || (state.config.login_prueba && payload.password == "admin123");
 
     if !is_valid {
+        get_metrics_service().registrar_evento(EventoDominio::LoginFallido);
+        acceso
+            .registrar_fallo(&ip, &payload.email, Some(user.id))
+            .await;
         return Err(AppError::Unauthorized("Invalid credentials".into()));
     }
 
+    if coincide {
+        password::migrar_texto_plano(db, user.id, &payload.password, &user.password_hash).await;
+    }
+
     // Generate JWT token
-    let token = generate_token(&user, state.jwt_encoding_key.as_ref())?;
+    let token = generate_token(&user, &state)?;
+    get_metrics_service().registrar_evento(EventoDominio::LoginExitoso);
+    acceso.registrar_exito(&ip, &payload.email, user.id).await;
 
     let response = AuthResponse {
         token,
@@ -149,15 +181,13 @@ pub async fn login(
     Ok(Json(ApiResponse::success(response)))
 }
 
-fn generate_token(
-    user: &User,
-    encoding_key: &jsonwebtoken::EncodingKey,
-) -> Result<String, AppError> {
+fn generate_token(user: &User, state: &AppState) -> Result<String, AppError> {
     use chrono::Utc;
     use jsonwebtoken::{encode, Header};
 
     let now = Utc::now();
-    let exp = (now + chrono::Duration::hours(24)).timestamp() as usize;
+    let exp =
+        (now + chrono::Duration::hours(state.config.jwt_expiracion_horas)).timestamp() as usize;
     let iat = now.timestamp() as usize;
 
     let claims = Claims {
@@ -167,7 +197,11 @@ fn generate_token(
         iat,
     };
 
-    Ok(encode(&Header::default(), &claims, encoding_key)?)
+    Ok(encode(
+        &Header::default(),
+        &claims,
+        state.jwt_encoding_key.as_ref(),
+    )?)
 }
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
@@ -188,9 +222,10 @@ pub struct TokenValidationResponse {
 #[utoipa::path(
     post,
     path = "/auth/validate-token",
+    tag = "Auth",
     request_body = ValidateTokenRequest,
     responses(
-        (status = 200, description = "Token válido", body = TokenValidationResponse),
+        (status = 200, description = "Token válido", body = TokenValidationApiResponse),
         (status = 401, description = "Token inválido o expirado"),
         (status = 400, description = "Datos inválidos"),
         (status = 500, description = "Error interno del servidor")
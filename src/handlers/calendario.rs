@@ -0,0 +1,118 @@
+use axum::{
+    extract::{FromRef, Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+
+use crate::{
+    middleware::auth::AuthUser,
+    models::AppState,
+    services::calendario_service::{
+        CalendarioService, EventoCalendario, FiltroCalendario, TokenCalendario,
+    },
+    utils::errors::AppError,
+};
+
+// GET /api/calendario?desde=&hasta=
+/// Fechas de los cursos del usuario en la ventana pedida
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/calendario",
+    tag = "Calendario",
+    params(FiltroCalendario),
+    responses(
+        (status = 200, description = "Eventos ordenados por inicio", body = [EventoCalendario]),
+        (status = 400, description = "Rango inválido"),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn listar_calendario(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Query(filtro): Query<FiltroCalendario>,
+) -> Result<Json<Vec<EventoCalendario>>, AppError> {
+    let service = CalendarioService::from_ref(&state);
+    let eventos = service.eventos(auth_user.user_id, filtro).await?;
+    Ok(Json(eventos))
+}
+
+// POST /api/calendario/token
+/// Crea o reemplaza el token del feed .ics
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    post,
+    path = "/api/calendario/token",
+    tag = "Calendario",
+    responses(
+        (status = 200, description = "Token y URL del feed", body = TokenCalendario),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn generar_token_calendario(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+) -> Result<Json<TokenCalendario>, AppError> {
+    let service = CalendarioService::from_ref(&state);
+    let token = service.generar_token(auth_user.user_id).await?;
+    Ok(Json(token))
+}
+
+// DELETE /api/calendario/token
+/// Revoca el token del feed .ics
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    delete,
+    path = "/api/calendario/token",
+    tag = "Calendario",
+    responses(
+        (status = 204, description = "Token revocado"),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn revocar_token_calendario(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+) -> Result<StatusCode, AppError> {
+    let service = CalendarioService::from_ref(&state);
+    service.revocar_token(auth_user.user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// GET /api/calendario/feed/{token}.ics (público: el token es la credencial)
+/// Feed iCalendar del dueño del token
+///
+/// Público: el token de la URL es la credencial
+#[utoipa::path(
+    get,
+    path = "/api/calendario/feed/{archivo}",
+    tag = "Calendario",
+    params(("archivo" = String, Path, description = "Token seguido de .ics")),
+    responses(
+        (status = 200, description = "Calendario RFC 5545", body = String, content_type = "text/calendar"),
+        (status = 404, description = "Token desconocido o revocado")
+    )
+)]
+pub async fn feed_calendario(
+    State(state): State<AppState>,
+    Path(archivo): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let token = archivo.strip_suffix(".ics").unwrap_or(&archivo);
+    let service = CalendarioService::from_ref(&state);
+    let ics = service.feed_ics(token).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/calendar; charset=utf-8"),
+            (header::CACHE_CONTROL, "private, max-age=900"),
+        ],
+        ics,
+    ))
+}
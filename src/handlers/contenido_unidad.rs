@@ -1,70 +1,171 @@
 use axum::{
     extract::{FromRef, Path, State},
     http::StatusCode,
-    Json,
 };
 
 use crate::{
-    middleware::auth::AuthUser,
-    models::{contenido_unidad::Model as ContenidoModel, AppState},
-    services::contenido_unidad_service::{
-        ActualizarContenidoUnidad, ContenidoUnidadService, NuevoContenidoUnidad,
+    middleware::{auth::AuthUser, json::Json},
+    models::{
+        contenido_unidad::Model as ContenidoUnidadModel,
+        rol::{ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR},
+        AppState,
+    },
+    services::{
+        contenido_unidad_service::{
+            ActualizarContenidoUnidad, ContenidoUnidadService, NuevoContenidoUnidad,
+        },
+        liberacion_service::LiberacionService,
+        publicacion_service::TipoPublicable,
     },
     utils::errors::AppError,
 };
 
+/// Crea un contenido dentro de una unidad
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    post,
+    path = "/api/contenidos",
+    tag = "Contenidos",
+    request_body = NuevoContenidoUnidad,
+    responses(
+        (status = 201, description = "Contenido creado", body = ContenidoUnidadModel),
+        (status = 400, description = "Datos inválidos"),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn crear_contenido(
-    _auth_user: AuthUser,
+    auth_user: AuthUser,
     State(state): State<AppState>,
     Json(payload): Json<NuevoContenidoUnidad>,
-) -> Result<(StatusCode, Json<ContenidoModel>), AppError> {
+) -> Result<(StatusCode, Json<ContenidoUnidadModel>), AppError> {
     let service = ContenidoUnidadService::from_ref(&state);
-    let contenido = service.crear_contenido(payload).await?;
+    let contenido = service
+        .crear_contenido(payload, auth_user.user_id)
+        .await?;
     Ok((StatusCode::CREATED, Json(contenido)))
 }
 
+/// Lista los contenidos de una unidad
+///
+/// Roles: cualquier usuario autenticado; los estudiantes solo ven lo publicado
+#[utoipa::path(
+    get,
+    path = "/api/unidades/{unidad_id}/contenidos",
+    tag = "Contenidos",
+    params(("unidad_id" = i32, Path, description = "Id de la unidad")),
+    responses(
+        (status = 200, description = "Contenidos en orden", body = [ContenidoUnidadModel]),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn listar_contenidos_por_unidad(
-    _auth_user: AuthUser,
+    auth_user: AuthUser,
     State(state): State<AppState>,
     Path(unidad_id): Path<i32>,
-) -> Result<Json<Vec<ContenidoModel>>, AppError> {
+) -> Result<Json<Vec<ContenidoUnidadModel>>, AppError> {
+    let solo_visibles = !auth_user
+        .tiene_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
     let service = ContenidoUnidadService::from_ref(&state);
     let contenidos = service
-        .obtener_contenidos_por_unidad(unidad_id)
+        .obtener_contenidos_por_unidad(unidad_id, solo_visibles)
         .await
         .map_err(AppError::from)?;
     Ok(Json(contenidos))
 }
 
+/// Obtiene un contenido
+///
+/// Roles: cualquier usuario autenticado; los estudiantes solo ven lo publicado y liberado
+#[utoipa::path(
+    get,
+    path = "/api/contenidos/{id}",
+    tag = "Contenidos",
+    params(("id" = i32, Path, description = "Id del contenido")),
+    responses(
+        (status = 200, description = "Contenido encontrado", body = ContenidoUnidadModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Contenido bloqueado para el estudiante, con los motivos"),
+        (status = 404, description = "Contenido no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn obtener_contenido(
-    _auth_user: AuthUser,
+    auth_user: AuthUser,
     State(state): State<AppState>,
     Path(id): Path<i32>,
-) -> Result<Json<ContenidoModel>, AppError> {
+) -> Result<Json<ContenidoUnidadModel>, AppError> {
+    let solo_visibles = !auth_user
+        .tiene_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
     let service = ContenidoUnidadService::from_ref(&state);
-    match service
-        .obtener_contenido_por_id(id)
+    let contenido = service
+        .obtener_contenido_por_id(id, solo_visibles)
         .await
         .map_err(AppError::from)?
-    {
-        Some(contenido) => Ok(Json(contenido)),
-        None => Err(AppError::NotFound(
-            format!("Contenido {} no encontrado", id).into(),
-        )),
+        .ok_or_else(|| AppError::NotFound(format!("Contenido {} no encontrado", id).into()))?;
+
+    // Lo publicado pero aún bloqueado por condiciones de liberación no se abre por id
+    if solo_visibles {
+        let motivos = LiberacionService::from_ref(&state)
+            .motivos_bloqueo(TipoPublicable::Contenido, id, auth_user.user_id)
+            .await?;
+        if !motivos.is_empty() {
+            return Err(AppError::Forbidden(
+                format!("Contenido bloqueado: {}", motivos.join("; ")).into(),
+            ));
+        }
     }
+    Ok(Json(contenido))
 }
 
+/// Actualiza un contenido. Cada cambio queda en su historial de revisiones
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    put,
+    path = "/api/contenidos/{id}",
+    tag = "Contenidos",
+    params(("id" = i32, Path, description = "Id del contenido")),
+    request_body = ActualizarContenidoUnidad,
+    responses(
+        (status = 200, description = "Contenido actualizado", body = ContenidoUnidadModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Contenido no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn actualizar_contenido(
-    _auth_user: AuthUser,
+    auth_user: AuthUser,
     State(state): State<AppState>,
     Path(id): Path<i32>,
     Json(payload): Json<ActualizarContenidoUnidad>,
-) -> Result<Json<ContenidoModel>, AppError> {
+) -> Result<Json<ContenidoUnidadModel>, AppError> {
     let service = ContenidoUnidadService::from_ref(&state);
-    let contenido = service.actualizar_contenido(id, payload).await?;
+    let contenido = service
+        .actualizar_contenido(id, payload, auth_user.user_id)
+        .await?;
     Ok(Json(contenido))
 }
 
+/// Envía un contenido a la papelera
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    delete,
+    path = "/api/contenidos/{id}",
+    tag = "Contenidos",
+    params(("id" = i32, Path, description = "Id del contenido")),
+    responses(
+        (status = 204, description = "Contenido eliminado"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Contenido no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn eliminar_contenido(
     _auth_user: AuthUser,
     State(state): State<AppState>,
@@ -0,0 +1,119 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::{
+    middleware::auth::AuthUser,
+    models::{
+        rol::ROL_ADMINISTRADOR,
+        token_cuenta::{ContrasenaConToken, MensajeCuenta, SolicitudRecuperacion},
+        AppState,
+    },
+    services::cuenta_service::CuentaService,
+    utils::errors::AppError,
+};
+
+// POST /api/cuenta/activar
+/// Activa una cuenta creada por un administrador con la contraseña elegida
+///
+/// Roles: público (el token del correo es la credencial)
+#[utoipa::path(
+    post,
+    path = "/api/cuenta/activar",
+    tag = "Cuenta",
+    request_body = ContrasenaConToken,
+    responses(
+        (status = 204, description = "Cuenta activada"),
+        (status = 400, description = "Enlace inválido, usado o vencido, o contraseña fuera de la política")
+    )
+)]
+pub async fn activar_cuenta(
+    State(service): State<CuentaService>,
+    Json(datos): Json<ContrasenaConToken>,
+) -> Result<StatusCode, AppError> {
+    service.activar(datos).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// POST /api/cuenta/recuperar
+/// Envía un enlace para restablecer la contraseña; responde igual exista o no
+/// la cuenta
+///
+/// Roles: público
+#[utoipa::path(
+    post,
+    path = "/api/cuenta/recuperar",
+    tag = "Cuenta",
+    request_body = SolicitudRecuperacion,
+    responses(
+        (status = 202, description = "Solicitud recibida", body = MensajeCuenta)
+    )
+)]
+pub async fn solicitar_recuperacion(
+    State(service): State<CuentaService>,
+    Json(datos): Json<SolicitudRecuperacion>,
+) -> (StatusCode, Json<MensajeCuenta>) {
+    service.solicitar_recuperacion(datos.correo);
+    (
+        StatusCode::ACCEPTED,
+        Json(MensajeCuenta {
+            mensaje:
+                "Si el correo está registrado, recibirás un enlace para restablecer la contraseña"
+                    .to_string(),
+        }),
+    )
+}
+
+// POST /api/cuenta/restablecer
+/// Cambia la contraseña con el token del enlace de recuperación
+///
+/// Roles: público (el token del correo es la credencial)
+#[utoipa::path(
+    post,
+    path = "/api/cuenta/restablecer",
+    tag = "Cuenta",
+    request_body = ContrasenaConToken,
+    responses(
+        (status = 204, description = "Contraseña cambiada"),
+        (status = 400, description = "Enlace inválido, usado o vencido, o contraseña fuera de la política")
+    )
+)]
+pub async fn restablecer_contrasena(
+    State(service): State<CuentaService>,
+    Json(datos): Json<ContrasenaConToken>,
+) -> Result<StatusCode, AppError> {
+    service.restablecer(datos).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// POST /api/usuarios/{id}/activacion
+/// Reenvía el enlace de activación de una cuenta pendiente (invalida el anterior)
+///
+/// Roles: Administrador
+#[utoipa::path(
+    post,
+    path = "/api/usuarios/{id}/activacion",
+    tag = "Cuenta",
+    params(("id" = i32, Path, description = "Id del usuario")),
+    responses(
+        (status = 202, description = "Enlace enviado"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente"),
+        (status = 404, description = "Usuario no encontrado"),
+        (status = 409, description = "La cuenta ya está activada"),
+        (status = 503, description = "No se pudo enviar el correo")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn reenviar_activacion(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    State(service): State<CuentaService>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    auth_user.exigir_rol(&state, &[ROL_ADMINISTRADOR]).await?;
+    service.reenviar_activacion(id).await?;
+    Ok(StatusCode::ACCEPTED)
+}
@@ -4,21 +4,41 @@ use axum::{
     Json,
 };
 use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::{
     middleware::auth::AuthUser,
-    models::{curso::Model as CursoModel, AppState},
+    models::{
+        auditoria::ContextoAuditoria,
+        curso::Model as CursoModel,
+        rol::{ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR},
+        AppState,
+    },
     services::curso_service::{
-        ActualizarCurso, AulaCurso, CursoDetallado, CursoService, NuevoCurso,
+        ActualizarCurso, AulaCurso, CursoDetallado, CursoService, FiltroAula, NuevoCurso, VistaAula,
     },
     utils::errors::AppError,
 };
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct CursoPeriodoQuery {
     pub periodo: String,
 }
 
+/// Lista los cursos con su área de conocimiento
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/cursos",
+    tag = "Cursos",
+    responses(
+        (status = 200, description = "Cursos registrados", body = [CursoDetallado]),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn listar_cursos(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -28,6 +48,21 @@ pub async fn listar_cursos(
     Ok(Json(cursos))
 }
 
+/// Obtiene un curso con su área de conocimiento
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/cursos/{id}",
+    tag = "Cursos",
+    params(("id" = i32, Path, description = "Id del curso")),
+    responses(
+        (status = 200, description = "Curso encontrado", body = CursoDetallado),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Curso no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn obtener_curso(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -38,37 +73,97 @@ pub async fn obtener_curso(
     Ok(Json(curso))
 }
 
+/// Crea un curso (queda en auditoría)
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    post,
+    path = "/api/cursos",
+    tag = "Cursos",
+    request_body = NuevoCurso,
+    responses(
+        (status = 201, description = "Curso creado", body = CursoModel),
+        (status = 400, description = "Datos inválidos"),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn crear_curso(
-    _auth_user: AuthUser, // Validar JWT automáticamente
+    ctx: ContextoAuditoria, // Valida JWT y captura actor/IP/request id
     State(state): State<AppState>,
     Json(payload): Json<NuevoCurso>,
 ) -> Result<(StatusCode, Json<CursoModel>), AppError> {
     let service = CursoService::from_ref(&state);
-    let curso = service.crear_curso(payload).await?;
+    let curso = service.crear_curso(payload, &ctx).await?;
     Ok((StatusCode::CREATED, Json(curso)))
 }
 
+/// Actualiza un curso (queda en auditoría)
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    put,
+    path = "/api/cursos/{id}",
+    tag = "Cursos",
+    params(("id" = i32, Path, description = "Id del curso")),
+    request_body = ActualizarCurso,
+    responses(
+        (status = 200, description = "Curso actualizado", body = CursoModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Curso no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn actualizar_curso(
-    _auth_user: AuthUser, // Validar JWT automáticamente
+    ctx: ContextoAuditoria, // Valida JWT y captura actor/IP/request id
     State(state): State<AppState>,
     Path(id): Path<i32>,
     Json(payload): Json<ActualizarCurso>,
 ) -> Result<Json<CursoModel>, AppError> {
     let service = CursoService::from_ref(&state);
-    let curso = service.editar_curso(id, payload).await?;
+    let curso = service.editar_curso(id, payload, &ctx).await?;
     Ok(Json(curso))
 }
 
+/// Envía un curso y su contenido a la papelera
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    delete,
+    path = "/api/cursos/{id}",
+    tag = "Cursos",
+    params(("id" = i32, Path, description = "Id del curso")),
+    responses(
+        (status = 204, description = "Curso eliminado"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Curso no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn eliminar_curso(
-    _auth_user: AuthUser, // Validar JWT automáticamente
+    ctx: ContextoAuditoria, // Valida JWT y captura actor/IP/request id
     State(state): State<AppState>,
     Path(id): Path<i32>,
 ) -> Result<StatusCode, AppError> {
     let service = CursoService::from_ref(&state);
-    service.eliminar_curso(id).await?;
+    service.eliminar_curso(id, &ctx).await?;
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Cursos creados a partir de una plantilla
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/plantillas/{plantilla_id}/cursos",
+    tag = "Cursos",
+    params(("plantilla_id" = i32, Path, description = "Id de la plantilla")),
+    responses(
+        (status = 200, description = "Cursos de la plantilla", body = [CursoModel]),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn cursos_por_plantilla(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -79,6 +174,23 @@ pub async fn cursos_por_plantilla(
     Ok(Json(cursos))
 }
 
+/// Cursos de un área en un periodo académico
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/areas-conocimiento/{area_id}/cursos",
+    tag = "Cursos",
+    params(
+        ("area_id" = i32, Path, description = "Id del área de conocimiento"),
+        CursoPeriodoQuery
+    ),
+    responses(
+        (status = 200, description = "Cursos del área", body = [CursoDetallado]),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn cursos_por_area_y_periodo(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -92,12 +204,48 @@ pub async fn cursos_por_area_y_periodo(
     Ok(Json(cursos))
 }
 
+/// Estructura del aula: temas, unidades y contenidos. En la vista estudiante se
+/// marca lo bloqueado por condiciones de liberación y lo ya completado
+///
+/// Roles: cualquier usuario autenticado. Los estudiantes solo ven lo publicado;
+/// Administrador, Coordinador y Profesor ven también los borradores, salvo con
+/// `vista=estudiante` (previsualización)
+#[utoipa::path(
+    get,
+    path = "/api/cursos/{id}/aula",
+    tag = "Cursos",
+    params(("id" = i32, Path, description = "Id del curso"), FiltroAula),
+    responses(
+        (status = 200, description = "Aula del curso", body = AulaCurso),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Vista docente pedida sin rol docente"),
+        (status = 404, description = "Curso no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn aula_por_curso(
-    _auth_user: AuthUser, // Validar JWT automáticamente
+    auth_user: AuthUser,
     State(state): State<AppState>,
     Path(id): Path<i32>,
+    Query(filtro): Query<FiltroAula>,
 ) -> Result<Json<AulaCurso>, AppError> {
+    let docente = auth_user
+        .tiene_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
+    let vista = match filtro.vista {
+        Some(VistaAula::Docente) if !docente => {
+            return Err(AppError::Forbidden(
+                "La vista docente es solo para administradores, coordinadores y profesores".into(),
+            ));
+        }
+        Some(vista) => vista,
+        None if docente => VistaAula::Docente,
+        None => VistaAula::Estudiante,
+    };
+
     let service = CursoService::from_ref(&state);
-    let aula = service.obtener_aula_por_curso_id(id).await?;
+    let aula = service
+        .obtener_aula_por_curso_id(id, vista, auth_user.user_id)
+        .await?;
     Ok(Json(aula))
 }
@@ -0,0 +1,92 @@
+use axum::{
+    extract::{FromRef, Path, State},
+    http::header,
+    response::IntoResponse,
+};
+
+use crate::{
+    middleware::{auth::AuthUser, json::Json},
+    models::{
+        rol::{ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR},
+        AppState,
+    },
+    services::estadistica_examen_service::{EstadisticaExamenService, EstadisticasExamen},
+    utils::errors::AppError,
+};
+
+// GET /api/examenes/{id}/estadisticas
+/// Estadísticas del examen (media, mediana, histograma, tiempo) y análisis de ítems
+/// (dificultad, discriminación y frecuencia de opciones)
+///
+/// Roles: Administrador, Coordinador, Profesor (solo de sus cursos)
+#[utoipa::path(
+    get,
+    path = "/api/examenes/{id}/estadisticas",
+    tag = "Intentos de examen",
+    params(("id" = i32, Path, description = "Id del examen")),
+    responses(
+        (status = 200, description = "Estadísticas del examen", body = EstadisticasExamen),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente o curso no asignado"),
+        (status = 404, description = "Examen no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn obtener_estadisticas(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<EstadisticasExamen>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
+
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+
+    let service = EstadisticaExamenService::from_ref(&state);
+    let estadisticas = service.estadisticas(id, profesor_id).await?;
+    Ok(Json(estadisticas))
+}
+
+// GET /api/examenes/{id}/estadisticas.csv
+/// Análisis de ítems del examen en CSV, una fila por pregunta
+///
+/// Roles: Administrador, Coordinador, Profesor (solo de sus cursos)
+#[utoipa::path(
+    get,
+    path = "/api/examenes/{id}/estadisticas.csv",
+    tag = "Intentos de examen",
+    params(("id" = i32, Path, description = "Id del examen")),
+    responses(
+        (status = 200, description = "Análisis de ítems", body = String, content_type = "text/csv"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente o curso no asignado"),
+        (status = 404, description = "Examen no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn exportar_estadisticas(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
+
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+
+    let service = EstadisticaExamenService::from_ref(&state);
+    let csv = service.estadisticas_csv(id, profesor_id).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"examen-{}-estadisticas.csv\"", id),
+            ),
+        ],
+        csv,
+    ))
+}
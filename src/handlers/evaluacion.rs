@@ -0,0 +1,374 @@
+use axum::{
+    extract::{FromRef, Path, State},
+    http::StatusCode,
+};
+
+use crate::{
+    middleware::{auth::AuthUser, json::Json},
+    models::{
+        auditoria::ContextoAuditoria,
+        calificacion_evaluacion::Model as CalificacionEvaluacionModel,
+        evaluacion::Model as EvaluacionModel,
+        rol::{ROL_ADMINISTRADOR, ROL_PROFESOR},
+        AppState,
+    },
+    services::evaluacion_service::{
+        ActualizarEvaluacion, CalificarEvaluacion, EvaluacionService, NuevaEvaluacion, Rubrica,
+    },
+    utils::errors::AppError,
+};
+
+// POST /api/evaluaciones
+/// Crea una evaluación en borrador
+///
+/// Roles: Administrador, Profesor (solo de sus cursos)
+#[utoipa::path(
+    post,
+    path = "/api/evaluaciones",
+    tag = "Evaluaciones",
+    request_body = NuevaEvaluacion,
+    responses(
+        (status = 201, description = "Evaluación creada", body = EvaluacionModel),
+        (status = 400, description = "Datos inválidos"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente o curso no asignado"),
+        (status = 404, description = "Curso no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn crear_evaluacion(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Json(payload): Json<NuevaEvaluacion>,
+) -> Result<(StatusCode, Json<EvaluacionModel>), AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_PROFESOR])
+        .await?;
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+
+    let service = EvaluacionService::from_ref(&state);
+    let evaluacion = service
+        .crear_evaluacion(payload, auth_user.user_id, profesor_id)
+        .await?;
+    Ok((StatusCode::CREATED, Json(evaluacion)))
+}
+
+// GET /api/cursos/{curso_id}/evaluaciones
+// Administradores y profesores ven también borradores y archivadas
+/// Lista las evaluaciones de un curso
+///
+/// Roles: Administrador y Profesor (de sus cursos) ven todas; el resto solo las
+/// publicadas, y los estudiantes con matrícula activa en el curso
+#[utoipa::path(
+    get,
+    path = "/api/cursos/{curso_id}/evaluaciones",
+    tag = "Evaluaciones",
+    params(("curso_id" = i32, Path, description = "Id del curso")),
+    responses(
+        (status = 200, description = "Evaluaciones del curso", body = [EvaluacionModel]),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Curso no asignado o sin matrícula activa")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn listar_evaluaciones_por_curso(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(curso_id): Path<i32>,
+) -> Result<Json<Vec<EvaluacionModel>>, AppError> {
+    let solo_publicadas = !auth_user
+        .tiene_rol(&state, &[ROL_ADMINISTRADOR, ROL_PROFESOR])
+        .await?;
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+    let estudiante_id = auth_user.id_si_estudiante(&state).await?;
+    let service = EvaluacionService::from_ref(&state);
+    let evaluaciones = service
+        .listar_por_curso(curso_id, solo_publicadas, profesor_id, estudiante_id)
+        .await?;
+    Ok(Json(evaluaciones))
+}
+
+// GET /api/evaluaciones/{id}
+/// Obtiene una evaluación
+///
+/// Roles: Administrador y Profesor (de sus cursos) ven cualquiera; el resto solo las
+/// publicadas, y los estudiantes con matrícula activa en el curso
+#[utoipa::path(
+    get,
+    path = "/api/evaluaciones/{id}",
+    tag = "Evaluaciones",
+    params(("id" = i32, Path, description = "Id de la evaluación")),
+    responses(
+        (status = 200, description = "Evaluación encontrada", body = EvaluacionModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Curso no asignado o sin matrícula activa"),
+        (status = 404, description = "Evaluación no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn obtener_evaluacion(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<EvaluacionModel>, AppError> {
+    let solo_publicadas = !auth_user
+        .tiene_rol(&state, &[ROL_ADMINISTRADOR, ROL_PROFESOR])
+        .await?;
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+    let estudiante_id = auth_user.id_si_estudiante(&state).await?;
+    let service = EvaluacionService::from_ref(&state);
+    let evaluacion = service
+        .obtener_evaluacion(id, solo_publicadas, profesor_id, estudiante_id)
+        .await?;
+    Ok(Json(evaluacion))
+}
+
+// PUT /api/evaluaciones/{id}
+/// Actualiza una evaluación
+///
+/// Roles: Administrador, Profesor (solo de sus cursos)
+#[utoipa::path(
+    put,
+    path = "/api/evaluaciones/{id}",
+    tag = "Evaluaciones",
+    params(("id" = i32, Path, description = "Id de la evaluación")),
+    request_body = ActualizarEvaluacion,
+    responses(
+        (status = 200, description = "Evaluación actualizada", body = EvaluacionModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente o curso no asignado"),
+        (status = 404, description = "Evaluación no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn actualizar_evaluacion(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Json(payload): Json<ActualizarEvaluacion>,
+) -> Result<Json<EvaluacionModel>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_PROFESOR])
+        .await?;
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+
+    let service = EvaluacionService::from_ref(&state);
+    let evaluacion = service
+        .actualizar_evaluacion(id, payload, profesor_id)
+        .await?;
+    Ok(Json(evaluacion))
+}
+
+// DELETE /api/evaluaciones/{id}
+/// Envía una evaluación a la papelera
+///
+/// Roles: Administrador, Profesor (solo de sus cursos)
+#[utoipa::path(
+    delete,
+    path = "/api/evaluaciones/{id}",
+    tag = "Evaluaciones",
+    params(("id" = i32, Path, description = "Id de la evaluación")),
+    responses(
+        (status = 204, description = "Evaluación eliminada"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente o curso no asignado"),
+        (status = 404, description = "Evaluación no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn eliminar_evaluacion(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_PROFESOR])
+        .await?;
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+
+    let service = EvaluacionService::from_ref(&state);
+    service.eliminar_evaluacion(id, profesor_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// PUT /api/evaluaciones/{id}/rubrica
+/// Asigna o reemplaza la rúbrica
+///
+/// Roles: Administrador, Profesor (solo de sus cursos)
+#[utoipa::path(
+    put,
+    path = "/api/evaluaciones/{id}/rubrica",
+    tag = "Evaluaciones",
+    params(("id" = i32, Path, description = "Id de la evaluación")),
+    request_body = Rubrica,
+    responses(
+        (status = 200, description = "Rúbrica asignada", body = EvaluacionModel),
+        (status = 400, description = "Rúbrica inválida"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente o curso no asignado"),
+        (status = 404, description = "Evaluación no encontrada"),
+        (status = 409, description = "La evaluación ya tiene calificaciones")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn asignar_rubrica(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Json(rubrica): Json<Rubrica>,
+) -> Result<Json<EvaluacionModel>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_PROFESOR])
+        .await?;
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+
+    let service = EvaluacionService::from_ref(&state);
+    let evaluacion = service.asignar_rubrica(id, rubrica, profesor_id).await?;
+    Ok(Json(evaluacion))
+}
+
+// POST /api/evaluaciones/{id}/publicar
+/// Publica una evaluación en borrador o archivada
+///
+/// Roles: Administrador, Profesor (solo de sus cursos)
+#[utoipa::path(
+    post,
+    path = "/api/evaluaciones/{id}/publicar",
+    tag = "Evaluaciones",
+    params(("id" = i32, Path, description = "Id de la evaluación")),
+    responses(
+        (status = 200, description = "Evaluación publicada", body = EvaluacionModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente o curso no asignado"),
+        (status = 404, description = "Evaluación no encontrada"),
+        (status = 409, description = "Transición de estado inválida")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn publicar_evaluacion(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<EvaluacionModel>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_PROFESOR])
+        .await?;
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+
+    let service = EvaluacionService::from_ref(&state);
+    let evaluacion = service.publicar(id, profesor_id).await?;
+    Ok(Json(evaluacion))
+}
+
+// POST /api/evaluaciones/{id}/archivar
+/// Archiva una evaluación publicada
+///
+/// Roles: Administrador, Profesor (solo de sus cursos)
+#[utoipa::path(
+    post,
+    path = "/api/evaluaciones/{id}/archivar",
+    tag = "Evaluaciones",
+    params(("id" = i32, Path, description = "Id de la evaluación")),
+    responses(
+        (status = 200, description = "Evaluación archivada", body = EvaluacionModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente o curso no asignado"),
+        (status = 404, description = "Evaluación no encontrada"),
+        (status = 409, description = "Transición de estado inválida")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn archivar_evaluacion(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<EvaluacionModel>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_PROFESOR])
+        .await?;
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+
+    let service = EvaluacionService::from_ref(&state);
+    let evaluacion = service.archivar(id, profesor_id).await?;
+    Ok(Json(evaluacion))
+}
+
+// POST /api/evaluaciones/{id}/calificaciones
+/// Califica a un estudiante con la rúbrica
+///
+/// Roles: Administrador, Profesor (solo de sus cursos)
+#[utoipa::path(
+    post,
+    path = "/api/evaluaciones/{id}/calificaciones",
+    tag = "Evaluaciones",
+    params(("id" = i32, Path, description = "Id de la evaluación")),
+    request_body = CalificarEvaluacion,
+    responses(
+        (status = 200, description = "Calificación guardada", body = CalificacionEvaluacionModel),
+        (status = 400, description = "Criterio o nivel inválido"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente o curso no asignado"),
+        (status = 404, description = "Evaluación no encontrada"),
+        (status = 409, description = "La evaluación no está publicada")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn calificar_evaluacion(
+    auth_user: AuthUser,
+    ctx: ContextoAuditoria,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Json(payload): Json<CalificarEvaluacion>,
+) -> Result<Json<CalificacionEvaluacionModel>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_PROFESOR])
+        .await?;
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+
+    let service = EvaluacionService::from_ref(&state);
+    let calificacion = service.calificar(id, payload, profesor_id, &ctx).await?;
+    Ok(Json(calificacion))
+}
+
+// GET /api/evaluaciones/{id}/calificaciones
+// Los estudiantes solo ven su propia calificación y solo en evaluaciones publicadas
+/// Calificaciones de la evaluación
+///
+/// Roles: Administrador y Profesor (de sus cursos) ven todas; el resto solo la propia,
+/// y los estudiantes con matrícula activa en el curso
+#[utoipa::path(
+    get,
+    path = "/api/evaluaciones/{id}/calificaciones",
+    tag = "Evaluaciones",
+    params(("id" = i32, Path, description = "Id de la evaluación")),
+    responses(
+        (status = 200, description = "Calificaciones", body = [CalificacionEvaluacionModel]),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Curso no asignado o sin matrícula activa"),
+        (status = 404, description = "Evaluación no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn listar_calificaciones(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<CalificacionEvaluacionModel>>, AppError> {
+    let service = EvaluacionService::from_ref(&state);
+    let calificaciones = if auth_user
+        .tiene_rol(&state, &[ROL_ADMINISTRADOR, ROL_PROFESOR])
+        .await?
+    {
+        let profesor_id = auth_user.id_si_profesor(&state).await?;
+        service.listar_calificaciones(id, None, profesor_id).await?
+    } else {
+        let estudiante_id = auth_user.id_si_estudiante(&state).await?;
+        service
+            .obtener_evaluacion(id, true, None, estudiante_id)
+            .await?;
+        service
+            .listar_calificaciones(id, Some(auth_user.user_id), None)
+            .await?
+    };
+    Ok(Json(calificaciones))
+}
@@ -1,16 +1,30 @@
 use axum::{
     extract::{FromRef, Path, State},
     http::StatusCode,
-    Json,
 };
 
 use crate::{
-    middleware::auth::AuthUser,
+    middleware::{auth::AuthUser, json::Json},
     models::{examen::Model as ExamenModel, AppState},
     services::examen_service::{ActualizarExamen, ExamenService, NuevoExamen},
     utils::errors::AppError,
 };
 
+/// Crea un examen
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    post,
+    path = "/api/examenes",
+    tag = "Exámenes",
+    request_body = NuevoExamen,
+    responses(
+        (status = 201, description = "Examen creado", body = ExamenModel),
+        (status = 400, description = "Datos inválidos"),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn crear_examen(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -21,6 +35,20 @@ pub async fn crear_examen(
     Ok((StatusCode::CREATED, Json(examen)))
 }
 
+/// Lista los exámenes de un curso
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/cursos/{curso_id}/examenes",
+    tag = "Exámenes",
+    params(("curso_id" = i32, Path, description = "Id del curso")),
+    responses(
+        (status = 200, description = "Exámenes del curso", body = [ExamenModel]),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn listar_examenes_por_curso(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -34,6 +62,21 @@ pub async fn listar_examenes_por_curso(
     Ok(Json(examenes))
 }
 
+/// Obtiene un examen
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/examenes/{id}",
+    tag = "Exámenes",
+    params(("id" = i32, Path, description = "Id del examen")),
+    responses(
+        (status = 200, description = "Examen encontrado", body = ExamenModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Examen no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn obtener_examen(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -52,6 +95,22 @@ pub async fn obtener_examen(
     }
 }
 
+/// Actualiza un examen
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    put,
+    path = "/api/examenes/{id}",
+    tag = "Exámenes",
+    params(("id" = i32, Path, description = "Id del examen")),
+    request_body = ActualizarExamen,
+    responses(
+        (status = 200, description = "Examen actualizado", body = ExamenModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Examen no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn actualizar_examen(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -63,6 +122,21 @@ pub async fn actualizar_examen(
     Ok(Json(examen))
 }
 
+/// Envía un examen a la papelera
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    delete,
+    path = "/api/examenes/{id}",
+    tag = "Exámenes",
+    params(("id" = i32, Path, description = "Id del examen")),
+    responses(
+        (status = 204, description = "Examen eliminado"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Examen no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn eliminar_examen(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
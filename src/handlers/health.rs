@@ -19,8 +19,8 @@ pub struct HealthResponse {
     path = "/health",
     tag = "Health",
     responses(
-        (status = 200, description = "Server is healthy", body = ApiResponse<HealthResponse>),
-        (status = 503, description = "Service unavailable", body = ApiResponse<HealthResponse>)
+        (status = 200, description = "Server is healthy", body = HealthApiResponse),
+        (status = 503, description = "Service unavailable", body = HealthApiResponse)
     )
 )]
 pub async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
@@ -0,0 +1,189 @@
+use axum::{
+    extract::{FromRef, Path, Query, State},
+    http::StatusCode,
+};
+
+use crate::{
+    middleware::{auth::AuthUser, json::Json},
+    models::{
+        auditoria::ContextoAuditoria,
+        rol::{ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR},
+        AppState,
+    },
+    services::intento_examen_service::{
+        CalificacionRespuesta, ColaCalificacion, DetalleIntento, EntregaIntento, FiltroCola,
+        IntentoExamenService, ResultadoCalificacion,
+    },
+    utils::errors::AppError,
+};
+
+// POST /api/examenes/{id}/intentos
+/// Inicia un intento del estudiante autenticado, o devuelve el que tenga en curso
+///
+/// Roles: estudiante con matrícula activa en el curso
+#[utoipa::path(
+    post,
+    path = "/api/examenes/{id}/intentos",
+    tag = "Intentos de examen",
+    params(("id" = i32, Path, description = "Id del examen")),
+    responses(
+        (status = 201, description = "Intento en curso con sus preguntas", body = DetalleIntento),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Sin matrícula activa en el curso"),
+        (status = 404, description = "Examen no encontrado o no publicado"),
+        (status = 409, description = "Fuera de fechas, sin intentos disponibles o sin preguntas")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn iniciar_intento(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<DetalleIntento>), AppError> {
+    let service = IntentoExamenService::from_ref(&state);
+    let intento = service.iniciar(id, auth_user.user_id).await?;
+    Ok((StatusCode::CREATED, Json(intento)))
+}
+
+// GET /api/intentos/{id}
+/// Intento con sus preguntas y respuestas. Puntajes y retroalimentación solo se
+/// muestran al estudiante cuando el intento está calificado y el examen muestra
+/// resultados
+///
+/// Roles: el estudiante del intento; Administrador, Coordinador, Profesor (solo de sus
+/// cursos)
+#[utoipa::path(
+    get,
+    path = "/api/intentos/{id}",
+    tag = "Intentos de examen",
+    params(("id" = i32, Path, description = "Id del intento")),
+    responses(
+        (status = 200, description = "Detalle del intento", body = DetalleIntento),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Curso no asignado"),
+        (status = 404, description = "Intento no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn obtener_intento(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<DetalleIntento>, AppError> {
+    let es_docente = auth_user
+        .tiene_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+
+    let service = IntentoExamenService::from_ref(&state);
+    let intento = service
+        .obtener(id, auth_user.user_id, es_docente, profesor_id)
+        .await?;
+    Ok(Json(intento))
+}
+
+// POST /api/intentos/{id}/entregar
+/// Entrega el intento. Las preguntas de selección con clave se califican al momento;
+/// las abiertas quedan pendientes de calificación manual
+///
+/// Roles: el estudiante del intento
+#[utoipa::path(
+    post,
+    path = "/api/intentos/{id}/entregar",
+    tag = "Intentos de examen",
+    params(("id" = i32, Path, description = "Id del intento")),
+    request_body = EntregaIntento,
+    responses(
+        (status = 200, description = "Intento entregado", body = DetalleIntento),
+        (status = 400, description = "Respuesta inválida o de otra pregunta"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Intento no encontrado"),
+        (status = 409, description = "El intento ya fue entregado, venció o el examen dejó de estar publicado")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn entregar_intento(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Json(entrega): Json<EntregaIntento>,
+) -> Result<Json<DetalleIntento>, AppError> {
+    let service = IntentoExamenService::from_ref(&state);
+    let intento = service.entregar(id, auth_user.user_id, entrega).await?;
+    Ok(Json(intento))
+}
+
+// GET /api/examenes/{id}/calificacion-manual?pregunta_id=&anonimo=&incluir_calificadas=&limite=&pagina=
+/// Cola de respuestas abiertas por calificar, ordenada por pregunta e intento
+///
+/// Roles: Administrador, Coordinador, Profesor (solo de sus cursos)
+#[utoipa::path(
+    get,
+    path = "/api/examenes/{id}/calificacion-manual",
+    tag = "Intentos de examen",
+    params(("id" = i32, Path, description = "Id del examen"), FiltroCola),
+    responses(
+        (status = 200, description = "Página de la cola", body = ColaCalificacion),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente o curso no asignado"),
+        (status = 404, description = "Examen no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn cola_calificacion(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Query(filtro): Query<FiltroCola>,
+) -> Result<Json<ColaCalificacion>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
+
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+
+    let service = IntentoExamenService::from_ref(&state);
+    let cola = service.cola(id, filtro, profesor_id).await?;
+    Ok(Json(cola))
+}
+
+// PUT /api/examenes/{id}/calificacion-manual
+/// Califica en bloque respuestas del examen con puntaje parcial y retroalimentación.
+/// Los intentos sin respuestas pendientes reciben su nota final
+///
+/// Roles: Administrador, Coordinador, Profesor (solo de sus cursos)
+#[utoipa::path(
+    put,
+    path = "/api/examenes/{id}/calificacion-manual",
+    tag = "Intentos de examen",
+    params(("id" = i32, Path, description = "Id del examen")),
+    request_body = [CalificacionRespuesta],
+    responses(
+        (status = 200, description = "Respuestas calificadas", body = ResultadoCalificacion),
+        (status = 400, description = "Puntaje fuera de rango o respuesta de otro examen"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente o curso no asignado"),
+        (status = 404, description = "Examen o respuesta no encontrados"),
+        (status = 409, description = "Respuesta de un intento sin entregar")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn calificar_respuestas(
+    auth_user: AuthUser,
+    ctx: ContextoAuditoria,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Json(calificaciones): Json<Vec<CalificacionRespuesta>>,
+) -> Result<Json<ResultadoCalificacion>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
+
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+
+    let service = IntentoExamenService::from_ref(&state);
+    let resultado = service
+        .calificar(id, calificaciones, auth_user.user_id, profesor_id, &ctx)
+        .await?;
+    Ok(Json(resultado))
+}
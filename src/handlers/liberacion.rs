@@ -0,0 +1,121 @@
+use axum::{
+    extract::{FromRef, Path, State},
+    http::StatusCode,
+};
+
+use crate::{
+    middleware::{auth::AuthUser, json::Json},
+    models::{
+        auditoria::ContextoAuditoria,
+        progreso_contenido::Model as ProgresoContenidoModel,
+        publicacion::TipoPublicable,
+        rol::{ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR},
+        AppState,
+    },
+    services::liberacion_service::{CondicionLiberacion, LiberacionService},
+    utils::errors::AppError,
+};
+
+// GET /api/liberacion/{tipo}/{id}
+/// Condiciones de liberación de un elemento del aula
+///
+/// Roles: Administrador, Coordinador, Profesor
+#[utoipa::path(
+    get,
+    path = "/api/liberacion/{tipo}/{id}",
+    tag = "Liberación condicional",
+    params(
+        ("tipo" = TipoPublicable, Path, description = "Tipo de elemento"),
+        ("id" = i32, Path, description = "Id del elemento")
+    ),
+    responses(
+        (status = 200, description = "Condiciones del elemento", body = [CondicionLiberacion]),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente"),
+        (status = 404, description = "Elemento no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn obtener_condiciones(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path((tipo, id)): Path<(TipoPublicable, i32)>,
+) -> Result<Json<Vec<CondicionLiberacion>>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
+
+    let service = LiberacionService::from_ref(&state);
+    let condiciones = service.obtener_condiciones(tipo, id).await?;
+    Ok(Json(condiciones))
+}
+
+// PUT /api/liberacion/{tipo}/{id}
+/// Reemplaza las condiciones de liberación de un elemento del aula. Se deben cumplir
+/// todas; una lista vacía lo deja libre
+///
+/// Roles: Administrador, Coordinador, Profesor
+#[utoipa::path(
+    put,
+    path = "/api/liberacion/{tipo}/{id}",
+    tag = "Liberación condicional",
+    params(
+        ("tipo" = TipoPublicable, Path, description = "Tipo de elemento"),
+        ("id" = i32, Path, description = "Id del elemento")
+    ),
+    request_body = [CondicionLiberacion],
+    responses(
+        (status = 200, description = "Condiciones guardadas", body = [CondicionLiberacion]),
+        (status = 400, description = "Condición incompleta o de otro curso"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente"),
+        (status = 404, description = "Elemento no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn reemplazar_condiciones(
+    auth_user: AuthUser,
+    ctx: ContextoAuditoria,
+    State(state): State<AppState>,
+    Path((tipo, id)): Path<(TipoPublicable, i32)>,
+    Json(condiciones): Json<Vec<CondicionLiberacion>>,
+) -> Result<Json<Vec<CondicionLiberacion>>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
+
+    let service = LiberacionService::from_ref(&state);
+    let condiciones = service
+        .reemplazar_condiciones(tipo, id, condiciones, &ctx)
+        .await?;
+    Ok(Json(condiciones))
+}
+
+// POST /api/contenidos/{id}/completado
+/// Marca un contenido como completado por el estudiante autenticado. Puede liberar
+/// otros elementos del curso
+///
+/// Roles: estudiante con matrícula activa en el curso
+#[utoipa::path(
+    post,
+    path = "/api/contenidos/{id}/completado",
+    tag = "Liberación condicional",
+    params(("id" = i32, Path, description = "Id del contenido")),
+    responses(
+        (status = 201, description = "Contenido completado", body = ProgresoContenidoModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Sin matrícula activa en el curso"),
+        (status = 404, description = "Contenido no encontrado"),
+        (status = 409, description = "Contenido bloqueado por condiciones pendientes")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn completar_contenido(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<ProgresoContenidoModel>), AppError> {
+    let service = LiberacionService::from_ref(&state);
+    let progreso = service.completar_contenido(id, auth_user.user_id).await?;
+    Ok((StatusCode::CREATED, Json(progreso)))
+}
@@ -1,67 +1,363 @@
 use axum::{
     extract::{FromRef, Path, State},
     http::StatusCode,
-    Json,
+    response::{IntoResponse, Response},
 };
 use serde::Deserialize;
+use utoipa::ToSchema;
 
 use crate::{
-    middleware::auth::AuthUser, models::AppState, services::matricula_service::MatriculaService,
+    middleware::{auth::AuthUser, json::Json},
+    models::{
+        auditoria::ContextoAuditoria,
+        cambio_estado_matricula::Model as CambioEstadoMatriculaModel,
+        curso::{ReglasMatricula, ReglasMatriculaCurso},
+        historial_curso_estudiante::{
+            CambioEstado, CierreCurso, Model as HistorialCursoEstudianteModel, ResumenCierre,
+        },
+        lista_espera::EntradaListaEspera,
+        rol::{ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR},
+        AppState,
+    },
+    services::matricula_service::{MatriculaService, ResultadoMatricula},
     utils::errors::AppError,
 };
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct MatriculaPayload {
     pub estudiante_id: i32,
     pub curso_id: i32,
 }
 
+/// Matricula a un estudiante en un curso (queda en auditoría); si ya se retiró,
+/// reprobó o el curso se cerró, crea un nuevo intento enlazado al anterior. Exige la ventana de
+/// matrícula abierta, el semestre del curso y los prerrequisitos aprobados; si el
+/// curso está lleno, el estudiante queda en la lista de espera
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    post,
+    path = "/api/matriculas",
+    tag = "Matrículas",
+    request_body = MatriculaPayload,
+    responses(
+        (status = 201, description = "Matrícula creada", body = HistorialCursoEstudianteModel),
+        (status = 202, description = "Curso lleno: el estudiante quedó en la lista de espera", body = EntradaListaEspera),
+        (status = 400, description = "Estudiante ya matriculado, ya aprobó el curso o está en la lista de espera"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Estudiante o curso no encontrado"),
+        (status = 409, description = "Matrícula rechazada: ventana cerrada, semestre o prerrequisitos (el mensaje lista los motivos)")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn matricular_estudiante(
-    _auth_user: AuthUser, // Validar JWT automáticamente
+    ctx: ContextoAuditoria, // Valida JWT y captura actor/IP/request id
     State(state): State<AppState>,
     Json(payload): Json<MatriculaPayload>,
-) -> Result<
-    (
-        StatusCode,
-        Json<crate::models::historial_curso_estudiante::Model>,
-    ),
-    AppError,
-> {
+) -> Result<Response, AppError> {
     let service = MatriculaService::from_ref(&state);
-    let matricula = service
-        .matricular_estudiante(payload.estudiante_id, payload.curso_id)
+    let resultado = service
+        .matricular_estudiante(payload.estudiante_id, payload.curso_id, &ctx)
         .await?;
-    Ok((StatusCode::CREATED, Json(matricula)))
+    Ok(match resultado {
+        ResultadoMatricula::Matriculado(matricula) => {
+            (StatusCode::CREATED, Json(matricula)).into_response()
+        }
+        ResultadoMatricula::EnEspera(entrada) => {
+            (StatusCode::ACCEPTED, Json(entrada)).into_response()
+        }
+    })
 }
 
+/// Retira a un estudiante de un curso (la matrícula activa pasa a `retirado` y queda
+/// en auditoría); el cupo liberado pasa al primero de la lista de espera
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    post,
+    path = "/api/matriculas/{estudiante_id}/{curso_id}",
+    tag = "Matrículas",
+    params(
+        ("estudiante_id" = i32, Path, description = "Id del estudiante"),
+        ("curso_id" = i32, Path, description = "Id del curso")
+    ),
+    responses(
+        (status = 200, description = "Matrícula retirada", body = HistorialCursoEstudianteModel),
+        (status = 400, description = "Estudiante no matriculado"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Estudiante o curso no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn desmatricular_estudiante(
-    _auth_user: AuthUser, // Validar JWT automáticamente
+    ctx: ContextoAuditoria, // Valida JWT y captura actor/IP/request id
     State(state): State<AppState>,
     Path((estudiante_id, curso_id)): Path<(i32, i32)>,
-) -> Result<Json<crate::models::historial_curso_estudiante::Model>, AppError> {
+) -> Result<Json<HistorialCursoEstudianteModel>, AppError> {
     let service = MatriculaService::from_ref(&state);
     let matricula = service
-        .desmatricular_estudiante(estudiante_id, curso_id)
+        .desmatricular_estudiante(estudiante_id, curso_id, &ctx)
         .await?;
     Ok(Json(matricula))
 }
 
+/// Historial de matrículas de un estudiante
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/estudiantes/{estudiante_id}/matriculas",
+    tag = "Matrículas",
+    params(("estudiante_id" = i32, Path, description = "Id del estudiante")),
+    responses(
+        (status = 200, description = "Matrículas del estudiante", body = [HistorialCursoEstudianteModel]),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn obtener_matriculas_estudiante(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
     Path(estudiante_id): Path<i32>,
-) -> Result<Json<Vec<crate::models::historial_curso_estudiante::Model>>, AppError> {
+) -> Result<Json<Vec<HistorialCursoEstudianteModel>>, AppError> {
     let service = MatriculaService::from_ref(&state);
     let matriculas = service.obtener_matriculas_estudiante(estudiante_id).await?;
     Ok(Json(matriculas))
 }
 
+/// Matrículas de un curso
+///
+/// Público: la validación de JWT está deshabilitada temporalmente
+#[utoipa::path(
+    get,
+    path = "/api/cursos/{curso_id}/matriculas",
+    tag = "Matrículas",
+    params(("curso_id" = i32, Path, description = "Id del curso")),
+    responses(
+        (status = 200, description = "Matrículas del curso", body = [HistorialCursoEstudianteModel])
+    )
+)]
 pub async fn obtener_matriculas_curso(
     // _auth_user: AuthUser,  // Validar JWT automáticamente - TEMPORALMENTE DESHABILITADO
     State(state): State<AppState>,
     Path(curso_id): Path<i32>,
-) -> Result<Json<Vec<crate::models::historial_curso_estudiante::Model>>, AppError> {
+) -> Result<Json<Vec<HistorialCursoEstudianteModel>>, AppError> {
     let service = MatriculaService::from_ref(&state);
     let matriculas = service.obtener_matriculas_curso(curso_id).await?;
     Ok(Json(matriculas))
 }
+
+/// Reglas de matrícula del curso y su ocupación
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/cursos/{curso_id}/reglas-matricula",
+    tag = "Matrículas",
+    params(("curso_id" = i32, Path, description = "Id del curso")),
+    responses(
+        (status = 200, description = "Reglas y ocupación", body = ReglasMatriculaCurso),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Curso no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn obtener_reglas_matricula(
+    _auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(curso_id): Path<i32>,
+) -> Result<Json<ReglasMatriculaCurso>, AppError> {
+    let service = MatriculaService::from_ref(&state);
+    Ok(Json(service.obtener_reglas(curso_id).await?))
+}
+
+/// Reemplaza prerrequisitos, cupo y ventana de matrícula (queda en auditoría). Si
+/// el cupo crece, los siguientes de la lista de espera quedan matriculados
+///
+/// Roles: Administrador, Coordinador
+#[utoipa::path(
+    put,
+    path = "/api/cursos/{curso_id}/reglas-matricula",
+    tag = "Matrículas",
+    params(("curso_id" = i32, Path, description = "Id del curso")),
+    request_body = ReglasMatricula,
+    responses(
+        (status = 200, description = "Reglas actualizadas", body = ReglasMatriculaCurso),
+        (status = 400, description = "Cupo, fechas o prerrequisitos inválidos (incluye ciclos)"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente"),
+        (status = 404, description = "Curso o prerrequisito no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn actualizar_reglas_matricula(
+    auth_user: AuthUser,
+    ctx: ContextoAuditoria,
+    State(state): State<AppState>,
+    Path(curso_id): Path<i32>,
+    Json(reglas): Json<ReglasMatricula>,
+) -> Result<Json<ReglasMatriculaCurso>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR])
+        .await?;
+    let service = MatriculaService::from_ref(&state);
+    Ok(Json(
+        service.actualizar_reglas(curso_id, reglas, &ctx).await?,
+    ))
+}
+
+/// Lista de espera del curso por orden de llegada
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/cursos/{curso_id}/lista-espera",
+    tag = "Matrículas",
+    params(("curso_id" = i32, Path, description = "Id del curso")),
+    responses(
+        (status = 200, description = "Estudiantes en espera", body = [EntradaListaEspera]),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn obtener_lista_espera(
+    _auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(curso_id): Path<i32>,
+) -> Result<Json<Vec<EntradaListaEspera>>, AppError> {
+    let service = MatriculaService::from_ref(&state);
+    Ok(Json(service.obtener_lista_espera(curso_id).await?))
+}
+
+/// Saca a un estudiante de la lista de espera (queda en auditoría)
+///
+/// Roles: el propio estudiante, Administrador, Coordinador
+#[utoipa::path(
+    delete,
+    path = "/api/cursos/{curso_id}/lista-espera/{estudiante_id}",
+    tag = "Matrículas",
+    params(
+        ("curso_id" = i32, Path, description = "Id del curso"),
+        ("estudiante_id" = i32, Path, description = "Id del estudiante")
+    ),
+    responses(
+        (status = 204, description = "Estudiante retirado de la lista de espera"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente"),
+        (status = 404, description = "El estudiante no está en la lista de espera")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn salir_lista_espera(
+    auth_user: AuthUser,
+    ctx: ContextoAuditoria,
+    State(state): State<AppState>,
+    Path((curso_id, estudiante_id)): Path<(i32, i32)>,
+) -> Result<StatusCode, AppError> {
+    if auth_user.user_id != estudiante_id {
+        auth_user
+            .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR])
+            .await?;
+    }
+    let service = MatriculaService::from_ref(&state);
+    service
+        .salir_lista_espera(estudiante_id, curso_id, &ctx)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Cambia el estado de una matrícula: de `activo` a retirado, aprobado, reprobado o
+/// finalizado; de `finalizado` a aprobado o reprobado; y entre aprobado y reprobado
+/// para corregir una nota. Queda en el historial de estados y en auditoría
+///
+/// Roles: Administrador, Coordinador, Profesor (solo de sus cursos)
+#[utoipa::path(
+    put,
+    path = "/api/matriculas/{id}/estado",
+    tag = "Matrículas",
+    params(("id" = i32, Path, description = "Id de la matrícula")),
+    request_body = CambioEstado,
+    responses(
+        (status = 200, description = "Estado actualizado", body = HistorialCursoEstudianteModel),
+        (status = 400, description = "Estado desconocido"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente o curso no asignado"),
+        (status = 404, description = "Matrícula no encontrada"),
+        (status = 409, description = "Transición no permitida")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn cambiar_estado_matricula(
+    auth_user: AuthUser,
+    ctx: ContextoAuditoria,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Json(cambio): Json<CambioEstado>,
+) -> Result<Json<HistorialCursoEstudianteModel>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
+    let profesor_id = auth_user.id_si_profesor(&state).await?;
+    let service = MatriculaService::from_ref(&state);
+    Ok(Json(
+        service
+            .cambiar_estado(id, cambio, profesor_id, &ctx)
+            .await?,
+    ))
+}
+
+/// Historial de estados de una matrícula con motivo, actor y fecha
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/matriculas/{id}/estados",
+    tag = "Matrículas",
+    params(("id" = i32, Path, description = "Id de la matrícula")),
+    responses(
+        (status = 200, description = "Cambios de estado", body = [CambioEstadoMatriculaModel]),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Matrícula no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn obtener_cambios_estado(
+    _auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<CambioEstadoMatriculaModel>>, AppError> {
+    let service = MatriculaService::from_ref(&state);
+    Ok(Json(service.obtener_cambios_estado(id).await?))
+}
+
+/// Cierra el curso: las matrículas activas pasan a aprobado o reprobado (con
+/// `nota_minima` y calificación final) o a finalizado, y se descarta la lista de espera
+///
+/// Roles: Administrador, Coordinador
+#[utoipa::path(
+    post,
+    path = "/api/cursos/{curso_id}/cierre",
+    tag = "Matrículas",
+    params(("curso_id" = i32, Path, description = "Id del curso")),
+    request_body = CierreCurso,
+    responses(
+        (status = 200, description = "Resumen del cierre", body = ResumenCierre),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente"),
+        (status = 404, description = "Curso no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn cerrar_curso(
+    auth_user: AuthUser,
+    ctx: ContextoAuditoria,
+    State(state): State<AppState>,
+    Path(curso_id): Path<i32>,
+    Json(cierre): Json<CierreCurso>,
+) -> Result<Json<ResumenCierre>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR])
+        .await?;
+    let service = MatriculaService::from_ref(&state);
+    Ok(Json(service.cerrar_curso(curso_id, cierre, &ctx).await?))
+}
@@ -1,45 +1,50 @@
-use axum::{extract::State, response::Json};
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
 use serde_json::{json, Value};
 
-use crate::{models::AppState, services::socket_service::get_socket_service};
+use crate::{
+    models::AppState,
+    services::{metrics_service::get_metrics_service, socket_service::get_socket_service},
+};
 
-/// /* Cambio nuevo */ Handler para exponer métricas de memoria de singletons
-pub async fn get_memory_metrics(State(_state): State<AppState>) -> Json<Value> {
-    // Obtener métricas de SocketService
-    let socket_service = get_socket_service();
-    let socket_metrics = socket_service.get_memory_metrics().await;
-    let connection_info = socket_service.get_connection_info().await;
-
-    // Obtener métricas de CronService usando función estática
-    let cron_jobs_count = crate::services::cron_service::get_jobs_count();
+/// Expone las métricas en formato de texto Prometheus (GET /metrics)
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "Métricas",
+    responses(
+        (status = 200, description = "Métricas en formato de texto Prometheus", body = String, content_type = "text/plain"),
+        (status = 500, description = "Error serializando las métricas")
+    )
+)]
+pub async fn prometheus_metrics(State(state): State<AppState>) -> Response {
+    let pool = state.db.as_ref().map(|db| db.pool());
 
-    Json(json!({
-        "timestamp": chrono::Utc::now(),
-        "socket_service": {
-            "total_users": socket_metrics.total_users,
-            "total_connections": socket_metrics.total_connections,
-            "total_capacity": socket_metrics.total_capacity,
-            "memory_overhead": socket_metrics.memory_overhead,
-            "largest_user_connections": socket_metrics.largest_user_connections,
-            "rooms": connection_info.rooms
-        },
-        "cron_service": {
-            "active_jobs": cron_jobs_count,
-            "running_jobs": cron_jobs_count, // Simplificado por ahora
-            "finished_jobs": 0
-        },
-        "memory_recommendations": {
-            "socket_optimization_needed": socket_metrics.memory_overhead > socket_metrics.total_connections,
-            "overhead_percentage": if socket_metrics.total_capacity > 0 {
-                (socket_metrics.memory_overhead as f64 / socket_metrics.total_capacity as f64) * 100.0
-            } else {
-                0.0
-            }
+    match get_metrics_service().render(pool).await {
+        Ok(body) => (
+            [(header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")],
+            body,
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!("❌ Error serializando métricas Prometheus: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
         }
-    }))
+    }
 }
 
 /// /* Cambio nuevo */ Handler para optimizar memoria manualmente
+#[utoipa::path(
+    post,
+    path = "/metrics/optimize",
+    tag = "Métricas",
+    responses(
+        (status = 200, description = "Resultado de la optimización con métricas antes y después", body = Object)
+    )
+)]
 pub async fn optimize_memory(State(_state): State<AppState>) -> Json<Value> {
     let socket_service = get_socket_service();
 
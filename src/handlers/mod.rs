@@ -1,13 +1,25 @@
 pub mod actividad;
 pub mod area_conocimiento;
+pub mod asistencia;
+pub mod auditoria;
 pub mod auth;
+pub mod calendario;
+pub mod cuenta;
 pub mod curso;
+pub mod estadistica_examen;
+pub mod evaluacion;
 pub mod examen;
 pub mod health;
+pub mod intento_examen;
+pub mod liberacion;
 pub mod matricula;
 pub mod metrics; //* Cambio nuevo */ Handler para métricas de memoria
 pub mod modulo;
 pub mod notificacion;
+pub mod orden;
+pub mod papelera;
+pub mod publicacion;
+pub mod revision;
 pub mod roles;
 pub mod socket_manager;
 pub mod storage; // Handler para subida de archivos a S3/R2
@@ -1,16 +1,34 @@
 use axum::{
     extract::{FromRef, Path, State},
     http::StatusCode,
-    Json,
 };
 
 use crate::{
-    middleware::auth::AuthUser,
-    models::{modulo::Model as ModuloModel, AppState},
+    middleware::{auth::AuthUser, json::Json},
+    models::{
+        modulo::Model as ModuloModel,
+        rol::{ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR},
+        AppState,
+    },
     services::modulo_service::{ActualizarModulo, ModuloService, NuevoModulo},
     utils::errors::AppError,
 };
 
+/// Crea un módulo dentro de un curso
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    post,
+    path = "/api/modulos",
+    tag = "Módulos",
+    request_body = NuevoModulo,
+    responses(
+        (status = 201, description = "Módulo creado", body = ModuloModel),
+        (status = 400, description = "Datos inválidos"),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn crear_modulo(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -21,24 +39,61 @@ pub async fn crear_modulo(
     Ok((StatusCode::CREATED, Json(modulo)))
 }
 
+/// Lista los módulos de un curso
+///
+/// Roles: cualquier usuario autenticado; los estudiantes solo ven lo publicado
+#[utoipa::path(
+    get,
+    path = "/api/cursos/{curso_id}/modulos",
+    tag = "Módulos",
+    params(("curso_id" = i32, Path, description = "Id del curso")),
+    responses(
+        (status = 200, description = "Módulos en orden", body = [ModuloModel]),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn listar_modulos_por_curso(
-    _auth_user: AuthUser, // Validar JWT automáticamente
+    auth_user: AuthUser,
     State(state): State<AppState>,
     Path(curso_id): Path<i32>,
 ) -> Result<Json<Vec<ModuloModel>>, AppError> {
+    let solo_visibles = !auth_user
+        .tiene_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
     let service = ModuloService::from_ref(&state);
-    let modulos = service.obtener_modulos_por_curso(curso_id).await?;
+    let modulos = service
+        .obtener_modulos_por_curso(curso_id, solo_visibles)
+        .await?;
     Ok(Json(modulos))
 }
 
+/// Obtiene un módulo
+///
+/// Roles: cualquier usuario autenticado; los estudiantes solo ven lo publicado
+#[utoipa::path(
+    get,
+    path = "/api/modulos/{id}",
+    tag = "Módulos",
+    params(("id" = i32, Path, description = "Id del módulo")),
+    responses(
+        (status = 200, description = "Módulo encontrado", body = ModuloModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Módulo no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn obtener_modulo(
-    _auth_user: AuthUser, // Validar JWT automáticamente
+    auth_user: AuthUser,
     State(state): State<AppState>,
     Path(id): Path<i32>,
 ) -> Result<Json<ModuloModel>, AppError> {
+    let solo_visibles = !auth_user
+        .tiene_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
     let service = ModuloService::from_ref(&state);
     match service
-        .obtener_modulo_por_id(id)
+        .obtener_modulo_por_id(id, solo_visibles)
         .await
         .map_err(AppError::from)?
     {
@@ -49,6 +104,22 @@ pub async fn obtener_modulo(
     }
 }
 
+/// Actualiza un módulo
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    put,
+    path = "/api/modulos/{id}",
+    tag = "Módulos",
+    params(("id" = i32, Path, description = "Id del módulo")),
+    request_body = ActualizarModulo,
+    responses(
+        (status = 200, description = "Módulo actualizado", body = ModuloModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Módulo no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn actualizar_modulo(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -60,6 +131,21 @@ pub async fn actualizar_modulo(
     Ok(Json(modulo))
 }
 
+/// Envía un módulo y su contenido a la papelera
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    delete,
+    path = "/api/modulos/{id}",
+    tag = "Módulos",
+    params(("id" = i32, Path, description = "Id del módulo")),
+    responses(
+        (status = 204, description = "Módulo eliminado"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Módulo no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn eliminar_modulo(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
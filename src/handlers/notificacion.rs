@@ -1,27 +1,29 @@
 use axum::{
     extract::{FromRef, Path, Query, State},
     http::StatusCode,
-    response::Json,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use utoipa::{IntoParams, ToSchema};
 
 use crate::{
-    middleware::auth::AuthUser,
-    models::AppState,
+    middleware::{auth::AuthUser, json::Json},
+    models::{preferencia_notificacion::PreferenciaNotificacion, AppState},
     services::notificacion_service::{NotificacionService, NuevaNotificacion},
     utils::errors::AppError,
 };
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct NotificacionesQuery {
     pub leida: Option<bool>,
     pub limit: Option<u64>,
     pub offset: Option<u64>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct NotificacionesResponse {
+    #[schema(value_type = Vec<NotificacionModel>)]
     pub notificaciones: Vec<Value>,
     pub total: u64,
 }
@@ -43,6 +45,23 @@ fn serialize_notificacion(n: &crate::models::notificacion::Model) -> Value {
 }
 
 /// Obtener notificaciones de un usuario
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/notificaciones/usuario/{usuario_id}",
+    tag = "Notificaciones",
+    params(
+        ("usuario_id" = i64, Path, description = "Id del usuario"),
+        NotificacionesQuery
+    ),
+    responses(
+        (status = 200, description = "Página de notificaciones y total", body = NotificacionesResponse),
+        (status = 400, description = "usuario_id fuera de rango"),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn obtener_notificaciones_usuario(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -69,6 +88,20 @@ pub async fn obtener_notificaciones_usuario(
 }
 
 /// Marcar notificación como leída
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    put,
+    path = "/api/notificaciones/{id}/leida",
+    tag = "Notificaciones",
+    params(("id" = i32, Path, description = "Id de la notificación")),
+    responses(
+        (status = 200, description = "Notificación actualizada", body = NotificacionModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Notificación no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn marcar_notificacion_leida(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -82,6 +115,20 @@ pub async fn marcar_notificacion_leida(
 }
 
 /// Crear nueva notificación
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    post,
+    path = "/api/notificaciones",
+    tag = "Notificaciones",
+    request_body = NuevaNotificacion,
+    responses(
+        (status = 201, description = "Notificación creada", body = NotificacionModel),
+        (status = 400, description = "Datos inválidos"),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn crear_notificacion(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -98,6 +145,20 @@ pub async fn crear_notificacion(
 }
 
 /// Marcar todas las notificaciones de un usuario como leídas
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    put,
+    path = "/api/notificaciones/usuario/{usuario_id}/marcar-todas-leidas",
+    tag = "Notificaciones",
+    params(("usuario_id" = i64, Path, description = "Id del usuario")),
+    responses(
+        (status = 200, description = "Confirmación con el usuario afectado", body = Object),
+        (status = 400, description = "usuario_id fuera de rango"),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn marcar_todas_leidas(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
@@ -115,3 +176,54 @@ pub async fn marcar_todas_leidas(
         "usuario_id": usuario_id
     })))
 }
+
+// GET /api/notificaciones/preferencias
+/// Frecuencia con la que el usuario autenticado recibe cada tipo de notificación
+/// (inmediata, resumen diario o desactivada)
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/notificaciones/preferencias",
+    tag = "Notificaciones",
+    responses(
+        (status = 200, description = "Preferencias por tipo", body = Vec<PreferenciaNotificacion>),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn obtener_preferencias(
+    auth_user: AuthUser,
+    State(service): State<NotificacionService>,
+) -> Result<Json<Vec<PreferenciaNotificacion>>, AppError> {
+    let preferencias = service.obtener_preferencias(auth_user.user_id).await?;
+    Ok(Json(preferencias))
+}
+
+// PUT /api/notificaciones/preferencias
+/// Cambia la frecuencia de los tipos enviados; los tipos obligatorios (seguridad)
+/// solo admiten `inmediata`
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    put,
+    path = "/api/notificaciones/preferencias",
+    tag = "Notificaciones",
+    request_body = Vec<PreferenciaNotificacion>,
+    responses(
+        (status = 200, description = "Preferencias actualizadas", body = Vec<PreferenciaNotificacion>),
+        (status = 400, description = "Tipo vacío, frecuencia desconocida o tipo obligatorio con otra frecuencia"),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn actualizar_preferencias(
+    auth_user: AuthUser,
+    State(service): State<NotificacionService>,
+    Json(preferencias): Json<Vec<PreferenciaNotificacion>>,
+) -> Result<Json<Vec<PreferenciaNotificacion>>, AppError> {
+    let preferencias = service
+        .actualizar_preferencias(auth_user.user_id, preferencias)
+        .await?;
+    Ok(Json(preferencias))
+}
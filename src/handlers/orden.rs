@@ -0,0 +1,51 @@
+use axum::extract::{FromRef, Path, State};
+
+use crate::{
+    middleware::{auth::AuthUser, json::Json},
+    models::{
+        auditoria::ContextoAuditoria,
+        rol::{ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR},
+        AppState,
+    },
+    services::orden_service::{ElementoOrdenado, OrdenService, Reordenamiento, TipoOrdenable},
+    utils::errors::AppError,
+};
+
+// PUT /api/orden/{tipo}/{padre_id}
+/// Reordena en bloque los hijos de un padre (arrastrar y soltar) y permite traer
+/// elementos de otro padre del mismo curso. El orden queda 1..n sin huecos
+///
+/// Roles: Administrador, Coordinador, Profesor
+#[utoipa::path(
+    put,
+    path = "/api/orden/{tipo}/{padre_id}",
+    tag = "Orden",
+    params(
+        ("tipo" = TipoOrdenable, Path, description = "Tipo de los elementos a ordenar"),
+        ("padre_id" = i32, Path, description = "Id del padre (curso, módulo, tema, unidad, examen, portafolio o webinar)")
+    ),
+    request_body = Reordenamiento,
+    responses(
+        (status = 200, description = "Hijos del padre en su nuevo orden", body = [ElementoOrdenado]),
+        (status = 400, description = "Ids repetidos, faltantes, inexistentes o de otro curso"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente"),
+        (status = 404, description = "Elemento padre no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn reordenar(
+    auth_user: AuthUser,
+    ctx: ContextoAuditoria,
+    State(state): State<AppState>,
+    Path((tipo, padre_id)): Path<(TipoOrdenable, i32)>,
+    Json(datos): Json<Reordenamiento>,
+) -> Result<Json<Vec<ElementoOrdenado>>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
+
+    let service = OrdenService::from_ref(&state);
+    let hijos = service.reordenar(tipo, padre_id, datos, &ctx).await?;
+    Ok(Json(hijos))
+}
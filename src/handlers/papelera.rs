@@ -0,0 +1,83 @@
+use axum::{
+    extract::{FromRef, Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::{
+    middleware::auth::AuthUser,
+    models::{
+        auditoria::ContextoAuditoria,
+        rol::{ROL_ADMINISTRADOR, ROL_PROFESOR},
+        AppState,
+    },
+    services::papelera_service::{ElementoPapelera, FiltroPapelera, PapeleraService, TipoPapelera},
+    utils::errors::AppError,
+};
+
+// GET /api/papelera?tipo=&curso_id=
+/// Elementos eliminados pendientes de purga
+///
+/// Roles: Administrador, Profesor
+#[utoipa::path(
+    get,
+    path = "/api/papelera",
+    tag = "Papelera",
+    params(FiltroPapelera),
+    responses(
+        (status = 200, description = "Elementos en la papelera", body = [ElementoPapelera]),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn listar_papelera(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Query(filtro): Query<FiltroPapelera>,
+) -> Result<Json<Vec<ElementoPapelera>>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_PROFESOR])
+        .await?;
+
+    let service = PapeleraService::from_ref(&state);
+    let elementos = service.listar(filtro).await?;
+    Ok(Json(elementos))
+}
+
+// POST /api/papelera/{tipo}/{id}/restaurar
+/// Restaura un elemento y lo que se eliminó con él
+///
+/// Roles: Administrador, Profesor
+#[utoipa::path(
+    post,
+    path = "/api/papelera/{tipo}/{id}/restaurar",
+    tag = "Papelera",
+    params(
+        ("tipo" = TipoPapelera, Path, description = "Tipo de elemento"),
+        ("id" = i32, Path, description = "Id del elemento")
+    ),
+    responses(
+        (status = 204, description = "Elemento restaurado"),
+        (status = 400, description = "El elemento no está en la papelera"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente"),
+        (status = 404, description = "Elemento no encontrado"),
+        (status = 409, description = "El elemento padre sigue eliminado")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn restaurar_elemento(
+    auth_user: AuthUser,
+    ctx: ContextoAuditoria,
+    State(state): State<AppState>,
+    Path((tipo, id)): Path<(TipoPapelera, i32)>,
+) -> Result<StatusCode, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_PROFESOR])
+        .await?;
+
+    let service = PapeleraService::from_ref(&state);
+    service.restaurar(tipo, id, &ctx).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
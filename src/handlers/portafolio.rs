@@ -1,6 +1,7 @@
 use axum::{extract::{Path, State}, Json};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use utoipa::ToSchema;
 
 use crate::{
     middleware::auth::AuthUser,
@@ -9,10 +10,10 @@ use crate::{
         personalizacion_portafolio_service::PersonalizacionPortafolioService,
         portafolio_service::{PortafolioService, NuevoPortafolio},
     },
-    utils::errors::AppError,
+    utils::{errors::AppError, markdown},
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PortadaCursoDesignDto {
     pub titulo: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -23,23 +24,26 @@ pub struct PortadaCursoDesignDto {
     pub bloques: Vec<PortadaBlockDto>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PortadaBlockDto {
     pub kind: String, // "texto" | "imagen"
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub text: Option<String>,
+    /// `text` renderizado desde Markdown y saneado; lo calcula el servidor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_html: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub image_url: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PortadaCursoPayload {
     pub estilos: Value,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PortadaCursoResponse {
     pub portafolio: PortafolioModel,
     pub estilos: Option<Value>,
@@ -53,6 +57,26 @@ fn get_db_connection(state: &AppState) -> Result<sea_orm::DatabaseConnection, Ap
     Ok(executor.connection())
 }
 
+/// Renderiza el `text` de cada bloque de la portada en `text_html` (400 si trae
+/// scripts o URLs javascript:)
+async fn renderizar_bloques(estilos: &mut Value) -> Result<(), AppError> {
+    let Some(bloques) = estilos.get_mut("bloques").and_then(Value::as_array_mut) else {
+        return Ok(());
+    };
+    for bloque in bloques.iter_mut().filter_map(Value::as_object_mut) {
+        match bloque.get("text").and_then(Value::as_str) {
+            Some(texto) => {
+                let html = markdown::renderizar_en_hilo(texto).await?;
+                bloque.insert("text_html".to_string(), Value::String(html));
+            }
+            None => {
+                bloque.remove("text_html");
+            }
+        }
+    }
+    Ok(())
+}
+
 async fn get_or_create_portafolio(
     curso_id: i32,
     _auth_user: &AuthUser,
@@ -82,6 +106,20 @@ async fn get_or_create_portafolio(
     Ok(creado)
 }
 
+/// Portada del portafolio del curso (lo crea si no existe)
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/cursos/{curso_id}/portafolio/portada",
+    tag = "Portafolio",
+    params(("curso_id" = i32, Path, description = "Id del curso")),
+    responses(
+        (status = 200, description = "Portafolio y estilos de la portada", body = PortadaCursoResponse),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn obtener_portada_curso(
     auth_user: AuthUser,
     State(state): State<AppState>,
@@ -101,12 +139,31 @@ pub async fn obtener_portada_curso(
     Ok(Json(PortadaCursoResponse { portafolio, estilos }))
 }
 
+/// Guarda los estilos de la portada del curso. El `text` (Markdown) de cada bloque
+/// se guarda también renderizado en `text_html`
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    put,
+    path = "/api/cursos/{curso_id}/portafolio/portada",
+    tag = "Portafolio",
+    params(("curso_id" = i32, Path, description = "Id del curso")),
+    request_body = PortadaCursoPayload,
+    responses(
+        (status = 200, description = "Portafolio y estilos guardados", body = PortadaCursoResponse),
+        (status = 400, description = "Texto con scripts o URLs javascript:"),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn guardar_portada_curso(
     auth_user: AuthUser,
     State(state): State<AppState>,
     Path(curso_id): Path<i32>,
-    Json(payload): Json<PortadaCursoPayload>,
+    Json(mut payload): Json<PortadaCursoPayload>,
 ) -> Result<Json<PortadaCursoResponse>, AppError> {
+    renderizar_bloques(&mut payload.estilos).await?;
+
     let db = get_db_connection(&state)?;
     let portafolio = get_or_create_portafolio(curso_id, &auth_user, &state).await?;
 
@@ -131,6 +188,7 @@ pub async fn guardar_portada_curso(
                     orden_componentes: None,
                     privacidad_componentes: None,
                 },
+                auth_user.user_id,
             )
             .await?;
 
@@ -140,12 +198,15 @@ pub async fn guardar_portada_curso(
         }))
     } else {
         let creada = servicio
-            .crear_personalizacion(NuevaPersonalizacion {
-                portafolio_id: portafolio.id,
-                estilos: estilos_value.clone(),
-                orden_componentes: None,
-                privacidad_componentes: None,
-            })
+            .crear_personalizacion(
+                NuevaPersonalizacion {
+                    portafolio_id: portafolio.id,
+                    estilos: estilos_value.clone(),
+                    orden_componentes: None,
+                    privacidad_componentes: None,
+                },
+                auth_user.user_id,
+            )
             .await?;
 
         Ok(Json(PortadaCursoResponse {
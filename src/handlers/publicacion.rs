@@ -0,0 +1,87 @@
+use axum::extract::{FromRef, Path, State};
+
+use crate::{
+    middleware::{auth::AuthUser, json::Json},
+    models::{
+        auditoria::ContextoAuditoria,
+        rol::{ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR},
+        AppState,
+    },
+    services::publicacion_service::{
+        CambioPublicacion, ElementoPublicable, PublicacionService, TipoPublicable,
+    },
+    utils::errors::AppError,
+};
+
+// GET /api/publicacion/{tipo}/{id}
+/// Estado de publicación y programación de un elemento del aula
+///
+/// Roles: Administrador, Coordinador, Profesor
+#[utoipa::path(
+    get,
+    path = "/api/publicacion/{tipo}/{id}",
+    tag = "Publicación",
+    params(
+        ("tipo" = TipoPublicable, Path, description = "Tipo de elemento"),
+        ("id" = i32, Path, description = "Id del elemento")
+    ),
+    responses(
+        (status = 200, description = "Estado de publicación", body = ElementoPublicable),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente"),
+        (status = 404, description = "Elemento no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn obtener_publicacion(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path((tipo, id)): Path<(TipoPublicable, i32)>,
+) -> Result<Json<ElementoPublicable>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
+
+    let service = PublicacionService::from_ref(&state);
+    let elemento = service.obtener(tipo, id).await?;
+    Ok(Json(elemento))
+}
+
+// PUT /api/publicacion/{tipo}/{id}
+/// Publica, pasa a borrador o programa la publicación de un elemento del aula.
+/// Si queda visible, se notifica a los estudiantes matriculados
+///
+/// Roles: Administrador, Coordinador, Profesor
+#[utoipa::path(
+    put,
+    path = "/api/publicacion/{tipo}/{id}",
+    tag = "Publicación",
+    params(
+        ("tipo" = TipoPublicable, Path, description = "Tipo de elemento"),
+        ("id" = i32, Path, description = "Id del elemento")
+    ),
+    request_body = CambioPublicacion,
+    responses(
+        (status = 200, description = "Estado de publicación actualizado", body = ElementoPublicable),
+        (status = 400, description = "Fechas programadas inválidas"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente"),
+        (status = 404, description = "Elemento no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn cambiar_publicacion(
+    auth_user: AuthUser,
+    ctx: ContextoAuditoria,
+    State(state): State<AppState>,
+    Path((tipo, id)): Path<(TipoPublicable, i32)>,
+    Json(cambio): Json<CambioPublicacion>,
+) -> Result<Json<ElementoPublicable>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
+
+    let service = PublicacionService::from_ref(&state);
+    let elemento = service.cambiar(tipo, id, cambio, &ctx).await?;
+    Ok(Json(elemento))
+}
@@ -0,0 +1,157 @@
+use axum::extract::{FromRef, Path, Query, State};
+
+use crate::{
+    middleware::{auth::AuthUser, json::Json},
+    models::{
+        auditoria::ContextoAuditoria,
+        revision::Model as RevisionModel,
+        rol::{ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR},
+        AppState,
+    },
+    services::revision_service::{
+        DiffRevisiones, FiltroDiff, ResumenRevision, RevisionService, TipoRevisionable,
+    },
+    utils::errors::AppError,
+};
+
+// GET /api/revisiones/{tipo}/{id}
+/// Historial de revisiones de un contenido o personalización, de la más reciente a
+/// la más antigua
+///
+/// Roles: Administrador, Coordinador, Profesor
+#[utoipa::path(
+    get,
+    path = "/api/revisiones/{tipo}/{id}",
+    tag = "Revisiones",
+    params(
+        ("tipo" = TipoRevisionable, Path, description = "Tipo de entidad"),
+        ("id" = i32, Path, description = "Id de la entidad")
+    ),
+    responses(
+        (status = 200, description = "Historial de revisiones", body = [ResumenRevision]),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente"),
+        (status = 404, description = "Elemento no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn listar_revisiones(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path((tipo, id)): Path<(TipoRevisionable, i32)>,
+) -> Result<Json<Vec<ResumenRevision>>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
+
+    let service = RevisionService::from_ref(&state);
+    let revisiones = service.listar(tipo, id).await?;
+    Ok(Json(revisiones))
+}
+
+// GET /api/revisiones/{tipo}/{id}/diff
+/// Campos que cambian entre dos revisiones
+///
+/// Roles: Administrador, Coordinador, Profesor
+#[utoipa::path(
+    get,
+    path = "/api/revisiones/{tipo}/{id}/diff",
+    tag = "Revisiones",
+    params(
+        ("tipo" = TipoRevisionable, Path, description = "Tipo de entidad"),
+        ("id" = i32, Path, description = "Id de la entidad"),
+        FiltroDiff
+    ),
+    responses(
+        (status = 200, description = "Diferencias entre las revisiones", body = DiffRevisiones),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente"),
+        (status = 404, description = "Revisión no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn diff_revisiones(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path((tipo, id)): Path<(TipoRevisionable, i32)>,
+    Query(filtro): Query<FiltroDiff>,
+) -> Result<Json<DiffRevisiones>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
+
+    let service = RevisionService::from_ref(&state);
+    let diff = service.diff(tipo, id, filtro).await?;
+    Ok(Json(diff))
+}
+
+// GET /api/revisiones/{tipo}/{id}/{numero}
+/// Una revisión con el cuerpo completo de esa versión
+///
+/// Roles: Administrador, Coordinador, Profesor
+#[utoipa::path(
+    get,
+    path = "/api/revisiones/{tipo}/{id}/{numero}",
+    tag = "Revisiones",
+    params(
+        ("tipo" = TipoRevisionable, Path, description = "Tipo de entidad"),
+        ("id" = i32, Path, description = "Id de la entidad"),
+        ("numero" = i32, Path, description = "Número de revisión")
+    ),
+    responses(
+        (status = 200, description = "Revisión", body = RevisionModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente"),
+        (status = 404, description = "Revisión no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn obtener_revision(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path((tipo, id, numero)): Path<(TipoRevisionable, i32, i32)>,
+) -> Result<Json<RevisionModel>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
+
+    let service = RevisionService::from_ref(&state);
+    let revision = service.obtener(tipo, id, numero).await?;
+    Ok(Json(revision))
+}
+
+// POST /api/revisiones/{tipo}/{id}/{numero}/restaurar
+/// Vuelve a una revisión anterior. La restauración se guarda como una revisión nueva
+///
+/// Roles: Administrador, Coordinador, Profesor
+#[utoipa::path(
+    post,
+    path = "/api/revisiones/{tipo}/{id}/{numero}/restaurar",
+    tag = "Revisiones",
+    params(
+        ("tipo" = TipoRevisionable, Path, description = "Tipo de entidad"),
+        ("id" = i32, Path, description = "Id de la entidad"),
+        ("numero" = i32, Path, description = "Número de revisión")
+    ),
+    responses(
+        (status = 200, description = "Revisión vigente tras restaurar", body = RevisionModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente"),
+        (status = 404, description = "Elemento o revisión no encontrados")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn restaurar_revision(
+    auth_user: AuthUser,
+    ctx: ContextoAuditoria,
+    State(state): State<AppState>,
+    Path((tipo, id, numero)): Path<(TipoRevisionable, i32, i32)>,
+) -> Result<Json<RevisionModel>, AppError> {
+    auth_user
+        .exigir_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
+
+    let service = RevisionService::from_ref(&state);
+    let revision = service.restaurar(tipo, id, numero, &ctx).await?;
+    Ok(Json(revision))
+}
@@ -4,15 +4,31 @@ use axum::{
 };
 
 use crate::{
-    middleware::auth::AuthUser, models::rol::Model as Rol, models::AppState,
+    middleware::auth::AuthUser,
+    models::{auditoria::ContextoAuditoria, rol::Model as RolModel, AppState},
     services::rol_service::RolService,
 };
 
+/// Obtiene un rol (null si no existe)
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/roles/{id}",
+    tag = "Roles",
+    params(("id" = i32, Path, description = "Id del rol")),
+    responses(
+        (status = 200, description = "Rol encontrado o null", body = Option<RolModel>),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 500, description = "Error de base de datos", body = String)
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_rol(
     _auth_user: AuthUser, // Validar JWT automáticamente
     Path(id): Path<i32>,
     State(state): State<AppState>,
-) -> Result<Json<Option<Rol>>, String> {
+) -> Result<Json<Option<RolModel>>, String> {
     let db = state.db.as_ref().ok_or("DB no disponible".to_string())?;
     let conn = db.connection();
     let service = RolService::global(&conn);
@@ -21,10 +37,24 @@ pub async fn get_rol(
     Ok(Json(role))
 }
 
+/// Lista los roles
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/roles",
+    tag = "Roles",
+    responses(
+        (status = 200, description = "Roles registrados", body = [RolModel]),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 500, description = "Error de base de datos", body = String)
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn list_roles(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(state): State<AppState>,
-) -> Result<Json<Vec<Rol>>, String> {
+) -> Result<Json<Vec<RolModel>>, String> {
     let db = state.db.as_ref().ok_or("DB no disponible".to_string())?;
     let conn = db.connection();
     let service = RolService::global(&conn);
@@ -33,45 +63,91 @@ pub async fn list_roles(
     Ok(Json(roles))
 }
 
+/// Crea un rol a partir de su nombre (queda en auditoría)
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    post,
+    path = "/api/roles",
+    tag = "Roles",
+    request_body = String,
+    responses(
+        (status = 200, description = "Rol creado", body = RolModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 500, description = "Error de base de datos", body = String)
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn create_role(
-    _auth_user: AuthUser, // Validar JWT automáticamente
+    ctx: ContextoAuditoria, // Valida JWT y captura actor/IP/request id
     State(state): State<AppState>,
     Json(nombre): Json<String>,
-) -> Result<Json<Rol>, String> {
+) -> Result<Json<RolModel>, String> {
     let db = state.db.as_ref().ok_or("DB no disponible".to_string())?;
     let conn = db.connection();
     let service = RolService::global(&conn);
-    let new_role = service.create(nombre).await.map_err(|e| e.to_string())?;
+    let new_role = service.create(nombre, &ctx).await.map_err(|e| e.to_string())?;
     drop(conn); // Agregar esta línea para cerrar la conexión
     Ok(Json(new_role))
 }
 
+/// Renombra un rol (queda en auditoría)
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    put,
+    path = "/api/roles/{id}",
+    tag = "Roles",
+    params(("id" = i32, Path, description = "Id del rol")),
+    request_body = String,
+    responses(
+        (status = 200, description = "Rol actualizado", body = RolModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 500, description = "Error de base de datos", body = String)
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn update_role(
-    _auth_user: AuthUser, // Validar JWT automáticamente
+    ctx: ContextoAuditoria, // Valida JWT y captura actor/IP/request id
     Path(id): Path<i32>,
     State(state): State<AppState>,
     Json(nombre): Json<String>,
-) -> Result<Json<Rol>, String> {
+) -> Result<Json<RolModel>, String> {
     let db = state.db.as_ref().ok_or("DB no disponible".to_string())?;
     let conn = db.connection();
     let service = RolService::global(&conn);
     let updated_role = service
-        .update(id, nombre)
+        .update(id, nombre, &ctx)
         .await
         .map_err(|e| e.to_string())?;
     drop(conn); // Agregar esta línea para cerrar la conexión
     Ok(Json(updated_role))
 }
 
+/// Elimina un rol (queda en auditoría)
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    delete,
+    path = "/api/roles/{id}",
+    tag = "Roles",
+    params(("id" = i32, Path, description = "Id del rol")),
+    responses(
+        (status = 200, description = "Confirmación", body = String),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 500, description = "Error de base de datos", body = String)
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn delete_role(
-    _auth_user: AuthUser, // Validar JWT automáticamente
+    ctx: ContextoAuditoria, // Valida JWT y captura actor/IP/request id
     Path(id): Path<i32>,
     State(state): State<AppState>,
 ) -> Result<Json<String>, String> {
     let db = state.db.as_ref().ok_or("DB no disponible".to_string())?;
     let conn = db.connection();
     let service = RolService::global(&conn);
-    service.delete(id).await.map_err(|e| e.to_string())?;
+    service.delete(id, &ctx).await.map_err(|e| e.to_string())?;
     drop(conn); // Agregar esta línea para cerrar la conexión
     Ok(Json("Rol eliminado".into()))
 }
@@ -41,6 +41,14 @@ pub enum SocketEvent {
 
 /// Manejador principal de WebSocket
 /// Equivalente a SocketManager en TypeScript
+#[utoipa::path(
+    get,
+    path = "/ws",
+    tag = "WebSocket",
+    responses(
+        (status = 101, description = "Conexión actualizada a WebSocket")
+    )
+)]
 pub async fn websocket_handler(
     ws: WebSocketUpgrade,
     State(_state): State<AppState>,
@@ -1,12 +1,25 @@
-use axum::{body::Bytes, extract::Multipart, Json};
+use axum::{
+    body::Bytes,
+    extract::{Multipart, State},
+    Json,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
-    middleware::auth::AuthUser, services::storage_service::StorageService, utils::errors::AppError,
+    config::StorageConfig,
+    middleware::auth::AuthUser,
+    models::AppState,
+    services::{
+        image_service::{self, TipoImagen},
+        storage_service::StorageService,
+    },
+    utils::errors::AppError,
 };
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct UploadResponse {
     pub success: bool,
     pub message: String,
@@ -15,27 +28,63 @@ pub struct UploadResponse {
     pub download_url: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct PresignedUrlResponse {
     pub upload_url: String,
     pub download_url: String,
     pub file_key: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, ToSchema)]
+pub struct VarianteImagen {
+    pub ancho: u32,
+    pub alto: u32,
+    pub file_key: String,
+    /// Solo cuando el WebP es más pequeño que el formato base
+    pub webp_file_key: Option<String>,
+    pub download_url: String,
+    pub webp_download_url: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ImagenVariantesResponse {
+    pub success: bool,
+    pub message: String,
+    /// Variantes por nombre: "thumbnail", "medium", "original"
+    pub variantes: BTreeMap<String, VarianteImagen>,
+}
+
+#[derive(Deserialize, ToSchema)]
 pub struct GenerateUrlRequest {
     pub file_name: String,
     pub content_type: String,
 }
 
 /// Genera una URL pre-firmada para subir archivos
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    post,
+    path = "/api/storage/presigned-url",
+    tag = "Archivos",
+    request_body = GenerateUrlRequest,
+    responses(
+        (status = 200, description = "URLs pre-firmadas de subida y descarga", body = PresignedUrlResponse),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 500, description = "Error del almacenamiento")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn generate_upload_url(
     _auth_user: AuthUser,
+    State(state): State<AppState>,
     Json(request): Json<GenerateUrlRequest>,
 ) -> Result<Json<PresignedUrlResponse>, AppError> {
-    let storage = StorageService::new().await.map_err(|e| {
-        AppError::InternalServerError(format!("Failed to initialize storage: {}", e).into())
-    })?;
+    let storage = StorageService::new(&state.config.storage)
+        .await
+        .map_err(|e| {
+            AppError::InternalServerError(format!("Failed to initialize storage: {}", e).into())
+        })?;
     tracing::info!("✅ [BACKEND] StorageService inicializado correctamente");
     // Validar tamaño del nombre del archivo (máximo 255 caracteres)
     if request.file_name.len() > 255 {
@@ -75,16 +124,36 @@ pub async fn generate_upload_url(
 }
 
 /// Sube un archivo directamente al servidor (método alternativo)
+///
+/// Campos multipart: "file" y "path" opcional.
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    post,
+    path = "/api/storage/upload",
+    tag = "Archivos",
+    request_body(content = Vec<u8>, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Archivo subido", body = UploadResponse),
+        (status = 400, description = "Multipart inválido"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 500, description = "Error del almacenamiento")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn upload_file_direct(
     _auth_user: AuthUser,
+    State(state): State<AppState>,
     mut multipart: Multipart,
 ) -> Result<Json<UploadResponse>, AppError> {
     tracing::info!("📤 [BACKEND] Nueva petición de file upload recibida");
 
-    let storage = StorageService::new().await.map_err(|e| {
-        tracing::error!("❌ [BACKEND] Error inicializando StorageService: {}", e);
-        AppError::InternalServerError(format!("Failed to initialize storage: {}", e).into())
-    })?;
+    let storage = StorageService::new(&state.config.storage)
+        .await
+        .map_err(|e| {
+            tracing::error!("❌ [BACKEND] Error inicializando StorageService: {}", e);
+            AppError::InternalServerError(format!("Failed to initialize storage: {}", e).into())
+        })?;
 
     tracing::info!("✅ [BACKEND] StorageService inicializado correctamente");
 
@@ -94,7 +163,11 @@ pub async fn upload_file_direct(
     let mut data: Option<Bytes> = None;
     let mut logical_path: Option<String> = None;
 
-    while let Ok(Some(field)) = multipart.next_field().await {
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::MultipartField(format!("Failed to read field: {}", e)))?
+    {
         let field_name = field.name().map(|s| s.to_string());
 
         match field_name.as_deref() {
@@ -217,14 +290,152 @@ pub async fn upload_file_direct(
     Ok(Json(response))
 }
 
+/// Sube una imagen generando variantes redimensionadas (thumbnail, medium, original)
+/// y sus versiones WebP. Campos multipart: "file", "tipo" (avatar | portada) y "path" opcional.
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    post,
+    path = "/api/storage/images",
+    tag = "Archivos",
+    request_body(content = Vec<u8>, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Variantes generadas", body = ImagenVariantesResponse),
+        (status = 400, description = "Multipart inválido o el archivo no es una imagen válida"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 500, description = "Error del almacenamiento")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn upload_image(
+    _auth_user: AuthUser,
+    State(state): State<AppState>,
+    mut multipart: Multipart,
+) -> Result<Json<ImagenVariantesResponse>, AppError> {
+    let mut tipo = TipoImagen::Portada;
+    let mut data: Option<Bytes> = None;
+    let mut logical_path: Option<String> = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::MultipartField(format!("Failed to read field: {}", e)))?
+    {
+        match field.name() {
+            Some("file") | None => {
+                let bytes = field.bytes().await.map_err(|e| {
+                    AppError::MultipartField(format!("Failed to read field bytes: {}", e))
+                })?;
+                data = Some(bytes);
+            }
+            Some("tipo") => {
+                let text = field.text().await.map_err(|e| {
+                    AppError::MultipartField(format!("Failed to read tipo field: {}", e))
+                })?;
+                tipo = TipoImagen::desde_nombre(&text).ok_or_else(|| {
+                    AppError::BadRequest("Tipo de imagen inválido (avatar | portada)".into())
+                })?;
+            }
+            Some("path") => {
+                let text = field.text().await.map_err(|e| {
+                    AppError::MultipartField(format!("Failed to read path field: {}", e))
+                })?;
+                let cleaned = text.trim().trim_matches('/').to_string();
+                if !cleaned.is_empty() {
+                    logical_path = Some(cleaned);
+                }
+            }
+            Some(other) => {
+                tracing::debug!("ℹ️ [BACKEND] Campo multipart ignorado: {}", other);
+            }
+        }
+    }
+
+    let data = data.ok_or_else(|| AppError::BadRequest("No file field found in request".into()))?;
+    let carpeta = logical_path.unwrap_or_else(|| match tipo {
+        TipoImagen::Avatar => "avatars".to_string(),
+        TipoImagen::Portada => "portadas".to_string(),
+    });
+
+    let response = procesar_y_subir_imagen(&state.config.storage, data, tipo, &carpeta).await?;
+    Ok(Json(response))
+}
+
+/// Procesa la imagen fuera del runtime async y sube sus variantes a `uploads/<carpeta>/<uuid>/`
+pub(crate) async fn procesar_y_subir_imagen(
+    storage_config: &StorageConfig,
+    data: Bytes,
+    tipo: TipoImagen,
+    carpeta: &str,
+) -> Result<ImagenVariantesResponse, AppError> {
+    let variantes =
+        tokio::task::spawn_blocking(move || image_service::procesar_imagen(&data, tipo))
+            .await
+            .map_err(|e| {
+                AppError::InternalServerError(format!("Error procesando imagen: {}", e).into())
+            })??;
+
+    let storage = StorageService::new(storage_config).await.map_err(|e| {
+        AppError::InternalServerError(format!("Failed to initialize storage: {}", e).into())
+    })?;
+
+    let prefijo = format!("uploads/{}/{}", carpeta, Uuid::new_v4());
+    let subidas = image_service::subir_variantes(&storage, &prefijo, variantes).await?;
+
+    let mut mapa = BTreeMap::new();
+    for variante in subidas {
+        let download_url = storage.generate_download_url(&variante.file_key).await?;
+        let webp_download_url = match &variante.webp_file_key {
+            Some(webp_file_key) => Some(storage.generate_download_url(webp_file_key).await?),
+            None => None,
+        };
+        mapa.insert(
+            variante.nombre.to_string(),
+            VarianteImagen {
+                ancho: variante.ancho,
+                alto: variante.alto,
+                file_key: variante.file_key,
+                webp_file_key: variante.webp_file_key,
+                download_url,
+                webp_download_url,
+            },
+        );
+    }
+
+    tracing::info!("✅ [BACKEND] Imagen procesada y subida en {}", prefijo);
+
+    Ok(ImagenVariantesResponse {
+        success: true,
+        message: "Imagen procesada exitosamente".to_string(),
+        variantes: mapa,
+    })
+}
+
 /// Descarga un archivo sirviendo como proxy para Cloudflare R2
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/storage/download/{file_key}",
+    tag = "Archivos",
+    params(("file_key" = String, Path, description = "Clave del archivo (debe empezar por uploads/)")),
+    responses(
+        (status = 307, description = "Redirección a la URL pre-firmada de descarga"),
+        (status = 400, description = "Clave de archivo inválida"),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn download_file(
     _auth_user: AuthUser,
+    State(state): State<AppState>,
     axum::extract::Path(file_key): axum::extract::Path<String>,
 ) -> Result<impl axum::response::IntoResponse, AppError> {
-    let storage = StorageService::new().await.map_err(|e| {
-        AppError::InternalServerError(format!("Failed to initialize storage: {}", e).into())
-    })?;
+    let storage = StorageService::new(&state.config.storage)
+        .await
+        .map_err(|e| {
+            AppError::InternalServerError(format!("Failed to initialize storage: {}", e).into())
+        })?;
 
     // Validar que el archivo esté en la carpeta uploads para seguridad
     if !file_key.starts_with("uploads/") {
@@ -239,13 +450,30 @@ pub async fn download_file(
 }
 
 /// Elimina un archivo
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    delete,
+    path = "/api/storage/{file_key}",
+    tag = "Archivos",
+    params(("file_key" = String, Path, description = "Clave del archivo (debe empezar por uploads/)")),
+    responses(
+        (status = 200, description = "Archivo eliminado", body = Object),
+        (status = 400, description = "Clave de archivo inválida"),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn delete_file(
     _auth_user: AuthUser,
+    State(state): State<AppState>,
     axum::extract::Path(file_key): axum::extract::Path<String>,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    let storage = StorageService::new().await.map_err(|e| {
-        AppError::InternalServerError(format!("Failed to initialize storage: {}", e).into())
-    })?;
+    let storage = StorageService::new(&state.config.storage)
+        .await
+        .map_err(|e| {
+            AppError::InternalServerError(format!("Failed to initialize storage: {}", e).into())
+        })?;
 
     // Validar que el archivo esté en la carpeta uploads para seguridad
     if !file_key.starts_with("uploads/") {
@@ -6,11 +6,30 @@ use axum::{
 
 use crate::{
     middleware::auth::AuthUser,
-    models::{tema::Model as TemaModel, AppState},
+    models::{
+        rol::{ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR},
+        tema::Model as TemaModel,
+        AppState,
+    },
     services::tema_service::{ActualizarTema, NuevoTema, TemaService},
     utils::errors::AppError,
 };
 
+/// Crea un tema dentro de un módulo
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    post,
+    path = "/api/temas",
+    tag = "Temas",
+    request_body = NuevoTema,
+    responses(
+        (status = 201, description = "Tema creado", body = TemaModel),
+        (status = 400, description = "Datos inválidos"),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn crear_tema(
     _auth_user: AuthUser,
     State(state): State<AppState>,
@@ -21,27 +40,62 @@ pub async fn crear_tema(
     Ok((StatusCode::CREATED, Json(tema)))
 }
 
+/// Lista los temas de un módulo
+///
+/// Roles: cualquier usuario autenticado; los estudiantes solo ven lo publicado
+#[utoipa::path(
+    get,
+    path = "/api/modulos/{modulo_id}/temas",
+    tag = "Temas",
+    params(("modulo_id" = i32, Path, description = "Id del módulo")),
+    responses(
+        (status = 200, description = "Temas en orden", body = [TemaModel]),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn listar_temas_por_modulo(
-    _auth_user: AuthUser,
+    auth_user: AuthUser,
     State(state): State<AppState>,
     Path(modulo_id): Path<i32>,
 ) -> Result<Json<Vec<TemaModel>>, AppError> {
+    let solo_visibles = !auth_user
+        .tiene_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
     let service = TemaService::from_ref(&state);
     let temas = service
-        .obtener_temas_por_modulo(modulo_id)
+        .obtener_temas_por_modulo(modulo_id, solo_visibles)
         .await
         .map_err(AppError::from)?;
     Ok(Json(temas))
 }
 
+/// Obtiene un tema
+///
+/// Roles: cualquier usuario autenticado; los estudiantes solo ven lo publicado
+#[utoipa::path(
+    get,
+    path = "/api/temas/{id}",
+    tag = "Temas",
+    params(("id" = i32, Path, description = "Id del tema")),
+    responses(
+        (status = 200, description = "Tema encontrado", body = TemaModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Tema no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn obtener_tema(
-    _auth_user: AuthUser,
+    auth_user: AuthUser,
     State(state): State<AppState>,
     Path(id): Path<i32>,
 ) -> Result<Json<TemaModel>, AppError> {
+    let solo_visibles = !auth_user
+        .tiene_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
     let service = TemaService::from_ref(&state);
     match service
-        .obtener_tema_por_id(id)
+        .obtener_tema_por_id(id, solo_visibles)
         .await
         .map_err(AppError::from)?
     {
@@ -52,6 +106,22 @@ pub async fn obtener_tema(
     }
 }
 
+/// Actualiza un tema
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    put,
+    path = "/api/temas/{id}",
+    tag = "Temas",
+    params(("id" = i32, Path, description = "Id del tema")),
+    request_body = ActualizarTema,
+    responses(
+        (status = 200, description = "Tema actualizado", body = TemaModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Tema no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn actualizar_tema(
     _auth_user: AuthUser,
     State(state): State<AppState>,
@@ -63,6 +133,21 @@ pub async fn actualizar_tema(
     Ok(Json(tema))
 }
 
+/// Envía un tema y su contenido a la papelera
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    delete,
+    path = "/api/temas/{id}",
+    tag = "Temas",
+    params(("id" = i32, Path, description = "Id del tema")),
+    responses(
+        (status = 204, description = "Tema eliminado"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Tema no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn eliminar_tema(
     _auth_user: AuthUser,
     State(state): State<AppState>,
@@ -6,11 +6,30 @@ use axum::{
 
 use crate::{
     middleware::auth::AuthUser,
-    models::{unidad::Model as UnidadModel, AppState},
+    models::{
+        rol::{ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR},
+        unidad::Model as UnidadModel,
+        AppState,
+    },
     services::unidad_service::{ActualizarUnidad, NuevaUnidad, UnidadService},
     utils::errors::AppError,
 };
 
+/// Crea una unidad dentro de un tema
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    post,
+    path = "/api/unidades",
+    tag = "Unidades",
+    request_body = NuevaUnidad,
+    responses(
+        (status = 201, description = "Unidad creada", body = UnidadModel),
+        (status = 400, description = "Datos inválidos"),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn crear_unidad(
     _auth_user: AuthUser,
     State(state): State<AppState>,
@@ -21,27 +40,62 @@ pub async fn crear_unidad(
     Ok((StatusCode::CREATED, Json(unidad)))
 }
 
+/// Lista las unidades de un tema
+///
+/// Roles: cualquier usuario autenticado; los estudiantes solo ven lo publicado
+#[utoipa::path(
+    get,
+    path = "/api/temas/{tema_id}/unidades",
+    tag = "Unidades",
+    params(("tema_id" = i32, Path, description = "Id del tema")),
+    responses(
+        (status = 200, description = "Unidades en orden", body = [UnidadModel]),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn listar_unidades_por_tema(
-    _auth_user: AuthUser,
+    auth_user: AuthUser,
     State(state): State<AppState>,
     Path(tema_id): Path<i32>,
 ) -> Result<Json<Vec<UnidadModel>>, AppError> {
+    let solo_visibles = !auth_user
+        .tiene_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
     let service = UnidadService::from_ref(&state);
     let unidades = service
-        .obtener_unidades_por_tema(tema_id)
+        .obtener_unidades_por_tema(tema_id, solo_visibles)
         .await
         .map_err(AppError::from)?;
     Ok(Json(unidades))
 }
 
+/// Obtiene una unidad
+///
+/// Roles: cualquier usuario autenticado; los estudiantes solo ven lo publicado
+#[utoipa::path(
+    get,
+    path = "/api/unidades/{id}",
+    tag = "Unidades",
+    params(("id" = i32, Path, description = "Id de la unidad")),
+    responses(
+        (status = 200, description = "Unidad encontrada", body = UnidadModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Unidad no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn obtener_unidad(
-    _auth_user: AuthUser,
+    auth_user: AuthUser,
     State(state): State<AppState>,
     Path(id): Path<i32>,
 ) -> Result<Json<UnidadModel>, AppError> {
+    let solo_visibles = !auth_user
+        .tiene_rol(&state, &[ROL_ADMINISTRADOR, ROL_COORDINADOR, ROL_PROFESOR])
+        .await?;
     let service = UnidadService::from_ref(&state);
     match service
-        .obtener_unidad_por_id(id)
+        .obtener_unidad_por_id(id, solo_visibles)
         .await
         .map_err(AppError::from)?
     {
@@ -52,6 +106,22 @@ pub async fn obtener_unidad(
     }
 }
 
+/// Actualiza una unidad
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    put,
+    path = "/api/unidades/{id}",
+    tag = "Unidades",
+    params(("id" = i32, Path, description = "Id de la unidad")),
+    request_body = ActualizarUnidad,
+    responses(
+        (status = 200, description = "Unidad actualizada", body = UnidadModel),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Unidad no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn actualizar_unidad(
     _auth_user: AuthUser,
     State(state): State<AppState>,
@@ -63,6 +133,21 @@ pub async fn actualizar_unidad(
     Ok(Json(unidad))
 }
 
+/// Envía una unidad y su contenido a la papelera
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    delete,
+    path = "/api/unidades/{id}",
+    tag = "Unidades",
+    params(("id" = i32, Path, description = "Id de la unidad")),
+    responses(
+        (status = 204, description = "Unidad eliminada"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Unidad no encontrada")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn eliminar_unidad(
     _auth_user: AuthUser,
     State(state): State<AppState>,
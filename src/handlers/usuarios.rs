@@ -1,55 +1,88 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Multipart, Path, Query, State},
+    http::StatusCode,
     Json,
 };
 use serde::Serialize;
 use std::sync::Arc;
+use utoipa::ToSchema;
 
 use crate::{
+    handlers::storage::{procesar_y_subir_imagen, ImagenVariantesResponse},
     middleware::auth::AuthUser,
-    models::{usuario as usuario_models, AppState, Claims},
-    services::usuario_service::UsuarioService,
-    utils::errors::AppError,
+    models::{
+        auditoria::ContextoAuditoria,
+        rol::ROL_ADMINISTRADOR,
+        usuario::{
+            Model as UsuarioModel, NewUsuario, OpcionesImportacion, ResultadoImportacion,
+            UpdateUsuario, UsuarioConRol,
+        },
+        AppState, Claims,
+    },
+    services::{
+        acceso_service::AccesoService,
+        cuenta_service::CuentaService,
+        image_service::TipoImagen,
+        metrics_service::{get_metrics_service, EventoDominio},
+        usuario_service::UsuarioService,
+    },
+    utils::{client_ip::IpCliente, errors::AppError},
 };
 
 // POST /api/usuarios/login
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, ToSchema)]
 pub struct LoginPayload {
     pub identificador: String, // correo o documento_nit
     pub contrasena: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct LoginResponse {
     pub token: String,
     #[serde(flatten)]
-    pub usuario: usuario_models::Model,
+    pub usuario: UsuarioModel,
 }
 
+/// Inicia sesión con correo o documento
+///
+/// Público
+#[utoipa::path(
+    post,
+    path = "/api/usuario/login",
+    tag = "Usuarios",
+    request_body = LoginPayload,
+    responses(
+        (status = 200, description = "Token JWT y datos del usuario", body = LoginResponse),
+        (status = 404, description = "Credenciales inválidas"),
+        (status = 429, description = "Demasiados intentos o cuenta bloqueada (ver Retry-After)")
+    )
+)]
 pub async fn login_usuario(
     State(state): State<AppState>,
     State(service): State<Arc<UsuarioService>>,
+    State(acceso): State<AccesoService>,
+    IpCliente(ip): IpCliente,
     Json(payload): Json<LoginPayload>,
 ) -> Result<Json<LoginResponse>, AppError> {
     let usuario = service
-        .login_usuario(&payload.identificador, &payload.contrasena)
-        .await?;
+        .login_usuario(&payload.identificador, &payload.contrasena, &acceso, &ip)
+        .await
+        .inspect_err(|_| get_metrics_service().registrar_evento(EventoDominio::LoginFallido))?;
+    get_metrics_service().registrar_evento(EventoDominio::LoginExitoso);
 
     // Generar token JWT
-    let token = generate_token(&usuario, state.jwt_encoding_key.as_ref())?;
+    let token = generate_token(&usuario, &state)?;
 
     Ok(Json(LoginResponse { token, usuario }))
 }
 
-fn generate_token(
-    usuario: &usuario_models::Model,
-    encoding_key: &jsonwebtoken::EncodingKey,
-) -> Result<String, AppError> {
+fn generate_token(usuario: &UsuarioModel, state: &AppState) -> Result<String, AppError> {
     use chrono::Utc;
     use jsonwebtoken::{encode, Header};
 
     let now = Utc::now();
-    let exp = (now + chrono::Duration::hours(24)).timestamp() as usize;
+    let exp =
+        (now + chrono::Duration::hours(state.config.jwt_expiracion_horas)).timestamp() as usize;
     let iat = now.timestamp() as usize;
 
     let claims = Claims {
@@ -59,54 +92,292 @@ fn generate_token(
         iat,
     };
 
-    Ok(encode(&Header::default(), &claims, encoding_key)?)
+    Ok(encode(
+        &Header::default(),
+        &claims,
+        state.jwt_encoding_key.as_ref(),
+    )?)
 }
 
 // POST /api/usuarios/logout/:id
+/// Registra el cierre de sesión del usuario
+///
+/// Público
+#[utoipa::path(
+    post,
+    path = "/api/usuario/logout/{id}",
+    tag = "Usuarios",
+    params(("id" = i32, Path, description = "Id del usuario")),
+    responses(
+        (status = 200, description = "Usuario actualizado", body = UsuarioModel),
+        (status = 404, description = "Usuario no encontrado")
+    )
+)]
 pub async fn logout_usuario(
     State(service): State<Arc<UsuarioService>>,
     Path(id): Path<i32>,
-) -> Result<Json<usuario_models::Model>, AppError> {
+) -> Result<Json<UsuarioModel>, AppError> {
     let usuario = service.logout_usuario(id).await?;
     Ok(Json(usuario))
 }
 
 // GET /api/usuarios
+/// Lista los usuarios con su rol
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/usuarios",
+    tag = "Usuarios",
+    responses(
+        (status = 200, description = "Usuarios registrados", body = [UsuarioConRol]),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn listar_usuarios(
     _auth_user: AuthUser, // Validar JWT automáticamente
     State(service): State<Arc<UsuarioService>>,
-) -> Result<Json<Vec<usuario_models::UsuarioConRol>>, AppError> {
+) -> Result<Json<Vec<UsuarioConRol>>, AppError> {
     let usuarios = service.obtener_usuarios().await?;
     Ok(Json(usuarios))
 }
 
 // POST /api/usuarios
+/// Crea un usuario pendiente de activación (queda en auditoría) y le envía por
+/// correo el enlace para elegir su contraseña
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    post,
+    path = "/api/usuarios",
+    tag = "Usuarios",
+    request_body = NewUsuario,
+    responses(
+        (status = 200, description = "Usuario creado", body = UsuarioModel),
+        (status = 400, description = "Datos inválidos"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 409, description = "Correo o documento ya registrado")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn crear_usuario(
-    _auth_user: AuthUser, // Validar JWT automáticamente
+    ctx: ContextoAuditoria, // Valida JWT y captura actor/IP/request id
     State(service): State<Arc<UsuarioService>>,
-    Json(payload): Json<usuario_models::NewUsuario>,
-) -> Result<Json<usuario_models::Model>, AppError> {
-    let usuario = service.crear_usuario(payload).await?;
+    State(cuenta): State<CuentaService>,
+    Json(payload): Json<NewUsuario>,
+) -> Result<Json<UsuarioModel>, AppError> {
+    let usuario = service.crear_usuario(payload, &ctx).await?;
+    // Si el correo falla la cuenta ya existe: la activación se reenvía con
+    // POST /api/usuarios/{id}/activacion
+    if let Err(e) = cuenta.enviar_activacion(&usuario).await {
+        tracing::warn!(
+            "⚠️  Usuario {} creado sin enlace de activación: {}",
+            usuario.id,
+            e
+        );
+    }
     Ok(Json(usuario))
 }
 
+// POST /api/usuarios/importar
+/// Importa usuarios desde un CSV (campo multipart "file") con las columnas nombre,
+/// documento_nit, correo, rol, semestre, genero y fecha_nacimiento. Con `simular=true`
+/// solo devuelve el reporte; si alguna fila tiene errores no se crea nada. Los
+/// estudiantes quedan matriculados en `cursos` y cada cuenta recibe su enlace de
+/// activación
+///
+/// Roles: Administrador
+#[utoipa::path(
+    post,
+    path = "/api/usuarios/importar",
+    tag = "Usuarios",
+    params(OpcionesImportacion),
+    request_body(content = Vec<u8>, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Usuarios creados, o reporte de la simulación", body = ResultadoImportacion),
+        (status = 400, description = "Multipart inválido, CSV ilegible, vacío o sin las columnas requeridas, o `cursos` inválido"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente"),
+        (status = 404, description = "Curso no encontrado"),
+        (status = 422, description = "Filas con errores: no se creó ningún usuario", body = ResultadoImportacion)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn importar_usuarios(
+    auth_user: AuthUser,
+    ctx: ContextoAuditoria,
+    State(state): State<AppState>,
+    State(service): State<Arc<UsuarioService>>,
+    State(cuenta): State<CuentaService>,
+    Query(opciones): Query<OpcionesImportacion>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<ResultadoImportacion>), AppError> {
+    auth_user.exigir_rol(&state, &[ROL_ADMINISTRADOR]).await?;
+
+    let mut data = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::MultipartField(format!("Failed to read field: {}", e)))?
+    {
+        if matches!(field.name(), Some("file") | None) {
+            data = Some(field.bytes().await.map_err(|e| {
+                AppError::MultipartField(format!("Failed to read field bytes: {}", e))
+            })?);
+        }
+    }
+    let data = data.ok_or_else(|| AppError::BadRequest("No file field found in request".into()))?;
+
+    let (resultado, creados) = service.importar_usuarios(&data, &opciones, &ctx).await?;
+
+    // Cientos de correos no caben en la petición: los enlaces salen en segundo plano
+    // y los que fallen se reenvían con POST /api/usuarios/{id}/activacion
+    if !creados.is_empty() {
+        tokio::spawn(async move {
+            for usuario in creados {
+                if let Err(e) = cuenta.enviar_activacion(&usuario).await {
+                    tracing::warn!(
+                        "⚠️  Usuario importado {} sin enlace de activación: {}",
+                        usuario.id,
+                        e
+                    );
+                }
+            }
+        });
+    }
+
+    let status = if resultado.errores.is_empty() {
+        StatusCode::OK
+    } else {
+        StatusCode::UNPROCESSABLE_ENTITY
+    };
+    Ok((status, Json(resultado)))
+}
+
 // PUT /api/usuarios/:id
+/// Actualiza un usuario (queda en auditoría)
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    put,
+    path = "/api/usuarios/{id}",
+    tag = "Usuarios",
+    params(("id" = i32, Path, description = "Id del usuario")),
+    request_body = UpdateUsuario,
+    responses(
+        (status = 200, description = "Usuario actualizado", body = UsuarioModel),
+        (status = 400, description = "Datos inválidos o contraseña fuera de la política"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 404, description = "Usuario no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn actualizar_usuario(
-    _auth_user: AuthUser, // Validar JWT automáticamente
+    ctx: ContextoAuditoria, // Valida JWT y captura actor/IP/request id
     Path(id): Path<i32>,
+    State(state): State<AppState>,
     State(service): State<Arc<UsuarioService>>,
-    Json(payload): Json<usuario_models::UpdateUsuario>,
-) -> Result<Json<usuario_models::Model>, AppError> {
-    let usuario = service.editar_usuario(id, payload).await?;
+    Json(payload): Json<UpdateUsuario>,
+) -> Result<Json<UsuarioModel>, AppError> {
+    let usuario = service
+        .editar_usuario(id, payload, state.config.cuenta.contrasena_min, &ctx)
+        .await?;
     Ok(Json(usuario))
 }
 
 // GET /api/usuarios/:id
+/// Obtiene un usuario (null si no existe)
+///
+/// Roles: cualquier usuario autenticado
+#[utoipa::path(
+    get,
+    path = "/api/usuarios/{id}",
+    tag = "Usuarios",
+    params(("id" = i32, Path, description = "Id del usuario")),
+    responses(
+        (status = 200, description = "Usuario encontrado o null", body = Option<UsuarioModel>),
+        (status = 401, description = "Token ausente o inválido")
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn obtener_usuario_por_id(
     _auth_user: AuthUser, // Validar JWT automáticamente
     Path(id): Path<i32>,
     State(service): State<Arc<UsuarioService>>,
-) -> Result<Json<Option<usuario_models::Model>>, AppError> {
+) -> Result<Json<Option<UsuarioModel>>, AppError> {
     let usuario = service.obtener_usuario_por_id(id).await?;
     Ok(Json(usuario))
 }
+
+// POST /api/usuarios/:id/foto
+// Procesa el avatar (variantes + WebP, sin EXIF) y guarda la variante "medium" en foto_url
+/// Sube la foto de perfil (campo multipart "file")
+///
+/// Roles: el propio usuario o Administrador
+#[utoipa::path(
+    post,
+    path = "/api/usuarios/{id}/foto",
+    tag = "Usuarios",
+    params(("id" = i32, Path, description = "Id del usuario")),
+    request_body(content = Vec<u8>, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Variantes generadas", body = ImagenVariantesResponse),
+        (status = 400, description = "El archivo no es una imagen válida"),
+        (status = 401, description = "Token ausente o inválido"),
+        (status = 403, description = "Rol insuficiente"),
+        (status = 404, description = "Usuario no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn subir_foto_usuario(
+    auth_user: AuthUser,
+    Path(id): Path<i32>,
+    State(state): State<AppState>,
+    State(service): State<Arc<UsuarioService>>,
+    mut multipart: Multipart,
+) -> Result<Json<ImagenVariantesResponse>, AppError> {
+    if auth_user.user_id != id {
+        auth_user.exigir_rol(&state, &[ROL_ADMINISTRADOR]).await?;
+    }
+
+    let mut data = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::MultipartField(format!("Failed to read field: {}", e)))?
+    {
+        if matches!(field.name(), Some("file") | None) {
+            data = Some(field.bytes().await.map_err(|e| {
+                AppError::MultipartField(format!("Failed to read field bytes: {}", e))
+            })?);
+        }
+    }
+    let data = data.ok_or_else(|| AppError::BadRequest("No file field found in request".into()))?;
+
+    if service.obtener_usuario_por_id(id).await?.is_none() {
+        return Err(AppError::NotFound("Usuario no encontrado".into()));
+    }
+
+    let response = procesar_y_subir_imagen(
+        &state.config.storage,
+        data,
+        TipoImagen::Avatar,
+        &format!("avatars/{}", id),
+    )
+    .await?;
+
+    if let Some(medium) = response.variantes.get("medium") {
+        service
+            .actualizar_foto_url(
+                id,
+                medium
+                    .webp_file_key
+                    .clone()
+                    .unwrap_or_else(|| medium.file_key.clone()),
+            )
+            .await?;
+    }
+
+    Ok(Json(response))
+}
@@ -1,7 +1,8 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
-use axum::http::{HeaderName, Method};
+use axum::extract::DefaultBodyLimit;
+use axum::http::{HeaderName, HeaderValue, Method};
 use tower::ServiceBuilder;
 use tower_http::cors::CorsLayer;
 use tower_http::{limit::RequestBodyLimitLayer, trace::TraceLayer};
@@ -15,42 +16,79 @@ mod services;
 mod utils;
 
 use config::Config;
+use middleware::request_id::X_REQUEST_ID;
 use database::{seeder, seed_users, DbExecutor, init_schema};
 use routes::create_app;
 
-#[tokio::main(worker_threads = 2)]
-//use std::io;
-//#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Inicializar logger con persistencia en archivos (zona horaria Bogotá UTC-5)
-    // Para máxima optimización sin archivos, usar: utils::logger::init_logger_console_only()?;
-    utils::logger::init_logger("logs", "rust-api-backend")?;
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Load configuration (se valida antes de levantar el runtime)
+    let config = match Config::from_env() {
+        Ok(config) => Arc::new(config),
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
 
-    // Load configuration
-    let config = Arc::new(Config::from_env()?);
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(config.worker_threads)
+        .enable_all()
+        .build()?
+        .block_on(run(config))
+}
+
+async fn run(config: Arc<Config>) -> Result<(), Box<dyn std::error::Error>> {
     let port = config.port;
 
+    // Inicializar logger con persistencia en archivos (formato y zona horaria según LOG_*)
+    // Para máxima optimización sin archivos, usar: utils::logger::init_logger_console_only(&config.logging)?;
+    utils::logger::init_logger(&config.logging, "rust-api-backend")?;
+
+    // Retención de logs: limpiar archivos más antiguos que LOG_RETENTION_DAYS cada 6 horas
+    if let Some(log_dir) = utils::logger::log_dir() {
+        let retention_days = config.logging.retention_days;
+        services::cron_service::programar_tarea(
+            services::cron_service::JOB_LIMPIEZA_LOGS,
+            std::time::Duration::from_secs(6 * 3600),
+            move || async move {
+                let resultado = tokio::task::spawn_blocking(move || {
+                    utils::logger::cleanup_old_logs(log_dir, retention_days)
+                })
+                .await;
+                if let Ok(Err(e)) = resultado {
+                    tracing::warn!("⚠️  Error limpiando logs antiguos: {}", e);
+                }
+            },
+        );
+    }
+
+    if config.login_prueba {
+        tracing::warn!("⚠️  TEST_LOGIN_ENABLED: usuarios de prueba y contraseña maestra activos");
+    }
+
     // Intentar conectar a la base de datos, pero no fallar si no se puede
-    let db_pool = match database::create_pool(&config.database_url).await {
+    let db_pool = match database::create_pool(&config.database_url, &config.database).await {
         Ok(pool) => {
             tracing::info!("✅ Base de datos conectada correctamente");
 
-            // Inicializar schema rustdema2 (search_path)
-            if let Err(e) = init_schema(&pool).await {
+            // Crear el schema configurado (DB_SCHEMA); cada conexión lo usa como search_path
+            if let Err(e) = init_schema(&pool, &config.database.schema).await {
                 tracing::error!("❌ Error inicializando schema: {}", e);
                 return Err(e.into());
             }
 
             // Ejecutar migraciones iniciales para preparar tablas requeridas
-            if let Err(e) = seeder::run_migrations(&pool, &config.database_url).await {
+            if let Err(e) = seeder::run_migrations(&pool).await {
                 tracing::error!("❌ Migraciones fallaron: {}", e);
                 return Err(e.into());
             }
 
-            // Poblar base de datos con usuarios de prueba
-            if let Err(e) = seed_users::seed_users(&pool).await {
-                tracing::error!("❌ Error poblando usuarios: {}", e);
-                return Err(e.into());
+            // Poblar base de datos con usuarios de prueba (solo con TEST_LOGIN_ENABLED)
+            if config.login_prueba {
+                if let Err(e) = seed_users::seed_users(&pool).await {
+                    tracing::error!("❌ Error poblando usuarios: {}", e);
+                    return Err(e.into());
+                }
             }
 
             Some(pool)
@@ -68,6 +106,155 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Create application state
     let db_executor = db_pool.map(DbExecutor::from_pool);
 
+    // Purga diaria de la papelera (soft delete con más de N días)
+    if let Some(executor) = db_executor.clone() {
+        let retencion_dias = config.papelera_retencion_dias;
+        services::cron_service::programar_tarea(
+            services::cron_service::JOB_PURGA_PAPELERA,
+            std::time::Duration::from_secs(24 * 3600),
+            move || {
+                let service = services::papelera_service::PapeleraService::new(executor.clone());
+                async move {
+                    match service.purgar(retencion_dias).await {
+                        Ok(0) => {}
+                        Ok(total) => tracing::info!("🗑️  Papelera: {} elementos purgados", total),
+                        Err(e) => tracing::warn!("⚠️  Error purgando la papelera: {}", e),
+                    }
+                }
+            },
+        );
+    }
+
+    // Correo (SMTP o archivo .eml + log) para activación y recuperación de cuentas
+    let mailer = match services::correo_service::crear_mailer(&config.correo) {
+        Ok(mailer) => mailer,
+        Err(e) => {
+            tracing::error!("❌ No se pudo preparar el envío de correos: {}", e);
+            return Err(e.into());
+        }
+    };
+    if config.correo.transporte == config::TransporteCorreo::Archivo {
+        tracing::warn!(
+            "⚠️  MAIL_TRANSPORT=file: los correos se escriben en '{}' y en el log",
+            config.correo.dir
+        );
+    }
+
+    // Purga diaria de enlaces de activación/recuperación vencidos o usados
+    if let Some(executor) = db_executor.clone() {
+        let service = services::cuenta_service::CuentaService::new(
+            executor,
+            Arc::clone(&mailer),
+            config.cuenta.clone(),
+        );
+        services::cron_service::programar_tarea(
+            services::cron_service::JOB_PURGA_TOKENS_CUENTA,
+            std::time::Duration::from_secs(24 * 3600),
+            move || {
+                let service = service.clone();
+                async move {
+                    match service.purgar_tokens().await {
+                        Ok(0) => {}
+                        Ok(total) => tracing::info!("🔑 {} tokens de cuenta purgados", total),
+                        Err(e) => tracing::warn!("⚠️  Error purgando tokens de cuenta: {}", e),
+                    }
+                }
+            },
+        );
+    }
+
+    // Correos de notificaciones: cola inmediata con reintentos y resumen diario
+    if let Some(executor) = db_executor.clone() {
+        let entrega = services::entrega_notificacion_service::EntregaNotificacionService::new(
+            executor,
+            Arc::clone(&mailer),
+            config.notificaciones,
+            config.cuenta.frontend_url.clone(),
+            config.logging.utc_offset_secs,
+        );
+        services::entrega_notificacion_service::inicializar(entrega.clone());
+
+        let cola = entrega.clone();
+        services::cron_service::programar_tarea(
+            services::cron_service::JOB_COLA_NOTIFICACIONES,
+            std::time::Duration::from_secs(60),
+            move || {
+                let service = cola.clone();
+                async move {
+                    if let Err(e) = service.procesar_cola().await {
+                        tracing::warn!("⚠️  Error procesando la cola de notificaciones: {}", e);
+                    }
+                }
+            },
+        );
+        services::cron_service::programar_tarea(
+            services::cron_service::JOB_RESUMEN_NOTIFICACIONES,
+            std::time::Duration::from_secs(3600),
+            move || {
+                let service = entrega.clone();
+                async move {
+                    match service.enviar_resumenes().await {
+                        Ok(0) => {}
+                        Ok(total) => tracing::info!("📬 {} resúmenes de notificaciones enviados", total),
+                        Err(e) => tracing::warn!("⚠️  Error enviando resúmenes de notificaciones: {}", e),
+                    }
+                }
+            },
+        );
+    }
+
+    // Publicación y retiro programados del contenido del aula
+    if let Some(executor) = db_executor.clone() {
+        let service = services::publicacion_service::PublicacionService::new(executor);
+        services::cron_service::programar_tarea(
+            services::cron_service::JOB_PUBLICACION_PROGRAMADA,
+            std::time::Duration::from_secs(60),
+            move || {
+                let service = service.clone();
+                async move {
+                    match service.aplicar_programadas().await {
+                        Ok(0) => {}
+                        Ok(total) => tracing::info!("📢 {} elementos del aula publicados o retirados según lo programado", total),
+                        Err(e) => tracing::warn!("⚠️  Error aplicando la publicación programada: {}", e),
+                    }
+                }
+            },
+        );
+    }
+
+    // Liberación condicional: fechas alcanzadas, exámenes calificados y entregas enviadas
+    if let Some(executor) = db_executor.clone() {
+        let service = services::liberacion_service::LiberacionService::new(executor);
+        services::cron_service::programar_tarea(
+            services::cron_service::JOB_LIBERACION_CONDICIONAL,
+            std::time::Duration::from_secs(300),
+            move || {
+                let service = service.clone();
+                async move {
+                    match service.revisar_todo().await {
+                        Ok(0) => {}
+                        Ok(total) => tracing::info!("🔓 {} elementos del aula liberados para estudiantes", total),
+                        Err(e) => tracing::warn!("⚠️  Error revisando la liberación condicional: {}", e),
+                    }
+                }
+            },
+        );
+    }
+
+    // IP del cliente: cabeceras de reenvío solo desde TRUSTED_PROXIES
+    utils::client_ip::inicializar(config.trusted_proxies.clone());
+
+    // Token buckets por IP y limpieza de cubetas/fallos de login inactivos
+    middleware::rate_limit::inicializar(config.rate_limit);
+    services::cron_service::programar_tarea(
+        services::cron_service::JOB_LIMPIEZA_LIMITES,
+        std::time::Duration::from_secs(600),
+        || async {
+            middleware::rate_limit::limpiar_inactivos();
+            services::acceso_service::limpiar_fallos_antiguos();
+        },
+    );
+
     let jwt_secret = config.jwt_secret.as_bytes();
     let jwt_encoding_key = Arc::new(jsonwebtoken::EncodingKey::from_secret(jwt_secret));
     let jwt_decoding_key = Arc::new(jsonwebtoken::DecodingKey::from_secret(jwt_secret));
@@ -77,6 +264,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         config: Arc::clone(&config),
         jwt_encoding_key,
         jwt_decoding_key,
+        mailer,
     };
 
     // Medición definitiva de Arc
@@ -89,21 +277,27 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
 
     // Build our application with routes and middleware
-    let app = create_app()
+    let app = create_app(config.upload_limit_bytes)
         .layer(
             ServiceBuilder::new()
-                // Límite de body reducido a 512KB (optimización memoria)
-                .layer(RequestBodyLimitLayer::new(512 * 1024))
+                // Límite de body (BODY_LIMIT_KB, 512KB por defecto). El stream admite hasta
+                // UPLOAD_LIMIT_MB para que las rutas de subida apliquen el suyo
+                .layer(RequestBodyLimitLayer::new(
+                    config.body_limit_bytes.max(config.upload_limit_bytes),
+                ))
+                .layer(DefaultBodyLimit::max(config.body_limit_bytes))
                 // Logging de requests HTTP
                 .layer(TraceLayer::new_for_http())
-                // CORS configurado para desarrollo (permite localhost:8080 del frontend)
+                // CORS con los orígenes de CORS_ORIGINS (validados al cargar la configuración)
                 .layer(
                     CorsLayer::new()
-                        .allow_origin([
-                            "http://localhost:8080".parse().unwrap(), // Frontend Dioxus
-                            "http://127.0.0.1:8080".parse().unwrap(), // Frontend alternativo
-                            "http://192.168.1.9".parse().unwrap(),   // Frontend Android en LAN
-                        ]) // Orígenes específicos para desarrollo
+                        .allow_origin(
+                            config
+                                .cors_origins
+                                .iter()
+                                .filter_map(|origen| HeaderValue::from_str(origen).ok())
+                                .collect::<Vec<_>>(),
+                        )
                         .allow_methods([
                             Method::GET,
                             Method::POST,
@@ -116,14 +310,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             HeaderName::from_static("content-type"),
                             HeaderName::from_static("accept"),
                             HeaderName::from_static("cache-control"),
+                            X_REQUEST_ID.clone(),
                         ]) // Headers específicos para JWT
+                        .expose_headers([X_REQUEST_ID.clone()])
                         .allow_credentials(true), // Permite credenciales (cookies, auth headers)
                 ),
         )
-        // Métricas de performance (solo requests lentos)
+        // Límite general por IP (RATE_LIMIT_GENERAL_*); aula y uploads tienen el suyo en sus rutas
+        .layer(axum::middleware::from_fn(
+            middleware::rate_limit::limite_general,
+        ))
+        // Métricas de performance (Prometheus + log de requests lentos)
         .layer(axum::middleware::from_fn(
             middleware::memory::performance_metrics,
         ))
+        // X-Request-Id: capa más externa para que todo span y error lo incluya
+        .layer(axum::middleware::from_fn(middleware::request_id::request_id))
         .with_state(app_state);
 
     // Run the server with graceful shutdown
@@ -136,9 +338,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("Press Ctrl+C to shutdown gracefully");
 
     // Serve with graceful shutdown
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    // ConnectInfo permite registrar la IP del cliente en la auditoría
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await?;
 
     tracing::info!("✅ Server shutdown complete");
     Ok(())
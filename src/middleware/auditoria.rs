@@ -0,0 +1,26 @@
+use axum::{extract::FromRequestParts, http::request::Parts};
+
+use crate::{
+    middleware::{auth::AuthUser, request_id::current_request_id},
+    models::{auditoria::ContextoAuditoria, AppState},
+    utils::{client_ip::client_ip, errors::AppError},
+};
+
+/// Construye el contexto de auditoría a partir del usuario autenticado,
+/// la IP del cliente y el X-Request-Id de la petición.
+impl FromRequestParts<AppState> for ContextoAuditoria {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let auth_user = AuthUser::from_request_parts(parts, state).await?;
+
+        Ok(ContextoAuditoria {
+            actor_id: Some(auth_user.user_id),
+            ip: client_ip(parts),
+            request_id: current_request_id(),
+        })
+    }
+}
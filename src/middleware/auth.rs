@@ -3,9 +3,15 @@ use axum::{
     http::request::Parts,
 };
 use jsonwebtoken::{decode, Validation};
+use sea_orm::EntityTrait;
 
 use crate::{
-    models::{AppState, Claims},
+    middleware::request_id::RequestSpan,
+    models::{
+        rol::{self, Entity as Rol},
+        usuario::Entity as Usuario,
+        AppState, Claims,
+    },
     utils::errors::AppError,
 };
 
@@ -15,6 +21,56 @@ pub struct AuthUser {
     pub email: String,
 }
 
+impl AuthUser {
+    /// Nombre del rol del usuario autenticado (consulta usuarios + roles)
+    pub async fn rol(&self, state: &AppState) -> Result<String, AppError> {
+        let db = state.db.as_ref().ok_or_else(|| {
+            AppError::ServiceUnavailable("Database connection is not available".into())
+        })?;
+
+        let (_, rol) = Usuario::find_by_id(self.user_id)
+            .find_also_related(Rol)
+            .one(&db.connection())
+            .await?
+            .ok_or_else(|| AppError::Unauthorized("Usuario no encontrado".into()))?;
+
+        Ok(rol.map(|rol| rol.nombre).unwrap_or_default())
+    }
+
+    /// Indica si el usuario tiene alguno de los roles indicados
+    pub async fn tiene_rol(&self, state: &AppState, roles: &[&str]) -> Result<bool, AppError> {
+        let actual = self.rol(state).await?;
+        Ok(roles.iter().any(|esperado| rol::es_rol(&actual, esperado)))
+    }
+
+    /// Id del usuario si es Profesor, para limitarlo a los cursos que tiene asignados
+    pub async fn id_si_profesor(&self, state: &AppState) -> Result<Option<i32>, AppError> {
+        Ok(self
+            .tiene_rol(state, &[rol::ROL_PROFESOR])
+            .await?
+            .then_some(self.user_id))
+    }
+
+    /// Id del usuario si es Estudiante, para limitarlo a los cursos donde está matriculado
+    pub async fn id_si_estudiante(&self, state: &AppState) -> Result<Option<i32>, AppError> {
+        Ok(self
+            .tiene_rol(state, &[rol::ROL_ESTUDIANTE])
+            .await?
+            .then_some(self.user_id))
+    }
+
+    /// Falla con 403 si el usuario no tiene alguno de los roles indicados
+    pub async fn exigir_rol(&self, state: &AppState, roles: &[&str]) -> Result<(), AppError> {
+        if self.tiene_rol(state, roles).await? {
+            return Ok(());
+        }
+
+        Err(AppError::Forbidden(
+            format!("Acción permitida solo para: {}", roles.join(", ")).into(),
+        ))
+    }
+}
+
 impl FromRequestParts<AppState> for AuthUser {
     type Rejection = AppError;
 
@@ -58,6 +114,11 @@ impl FromRequestParts<AppState> for AuthUser {
 
         let auth_user = AuthUser { user_id, email };
 
+        // Asociar el usuario al span de la petición (ver middleware::request_id)
+        if let Some(RequestSpan(span)) = parts.extensions.get::<RequestSpan>() {
+            span.record("user_id", auth_user.user_id);
+        }
+
         // Registrar el usuario autenticado para trazas y futuras autorizaciones
         tracing::trace!(
             "Authenticated user {} ({})",
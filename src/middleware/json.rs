@@ -0,0 +1,32 @@
+use axum::{
+    extract::{rejection::JsonRejection, FromRequest, Request},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+use crate::utils::errors::AppError;
+
+/// Igual que `axum::Json`, pero un cuerpo inválido (por ejemplo un valor fuera
+/// de los enums de estado/tipo) responde 400 con el detalle de serde, que
+/// incluye la lista de valores permitidos, en lugar del 422 en texto plano.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json<T>(pub T);
+
+impl<T, S> FromRequest<S> for Json<T>
+where
+    axum::Json<T>: FromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::Json(valor) = axum::Json::<T>::from_request(req, state).await?;
+        Ok(Json(valor))
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
@@ -1,28 +1,40 @@
-// ⚠️ DEPRECADO: Este middleware NO mejora el rendimiento.
+// Métricas de rendimiento por request.
 //
-// Problemas del código original:
-// 1. refresh_memory() NO libera memoria, solo lee estadísticas del SO
-// 2. Añade latencia innecesaria a cada request (~5-10ms)
-// 3. Rust maneja memoria automáticamente con ownership/borrowing
-// 4. spawn_blocking() crea overhead sin beneficio
-//
-// Para monitoreo de memoria real, usar:
-// - Prometheus metrics con tower-http
-// - Tokio console para debugging
-// - jemalloc como allocator global (ver Cargo.toml)
+// Alimenta el registro Prometheus (`services::metrics_service`) con conteos y
+// latencias por ruta/status, y deja en el log los requests lentos.
+// No consulta memoria del SO: para eso usar /metrics o Tokio console.
+
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
 
-use axum::{extract::Request, middleware::Next, response::Response};
+use crate::services::metrics_service::get_metrics_service;
 
 /// Middleware de métricas de rendimiento (sin overhead significativo)
 pub async fn performance_metrics(req: Request, next: Next) -> Response {
     let start = std::time::Instant::now();
     let method = req.method().clone();
     let uri = req.uri().clone();
+    // Usar la plantilla de la ruta ("/api/cursos/{id}") para acotar la cardinalidad
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_owned())
+        .unwrap_or_else(|| "desconocida".to_owned());
 
     let response = next.run(req).await;
 
     let elapsed = start.elapsed();
 
+    get_metrics_service().registrar_request(
+        method.as_str(),
+        &route,
+        response.status().as_u16(),
+        elapsed.as_secs_f64(),
+    );
+
     // Solo loguear requests lentos (>100ms)
     if elapsed.as_millis() > 100 {
         tracing::warn!(
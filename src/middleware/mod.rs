@@ -1,2 +1,6 @@
+pub mod auditoria;
 pub mod auth;
+pub mod json;
 pub mod memory;
+pub mod rate_limit; // Token bucket por IP y grupo de rutas
+pub mod request_id;
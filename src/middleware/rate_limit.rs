@@ -0,0 +1,145 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    config::{LimiteRate, RateLimitConfig},
+    utils::{client_ip::client_ip, errors::AppError},
+};
+
+/// Cubetas sin uso durante este tiempo se descartan en la limpieza periódica
+const INACTIVIDAD_MAXIMA: Duration = Duration::from_secs(600);
+
+/// Rutas que no consumen del límite general (sondas y scraping de métricas)
+const RUTAS_EXENTAS: [&str; 4] = ["/health", "/ready", "/live", "/metrics"];
+
+/// Grupo de rutas con su propio token bucket por IP
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GrupoLimite {
+    General,
+    Aula,
+    Uploads,
+}
+
+#[derive(Debug)]
+struct Cubeta {
+    tokens: f64,
+    actualizado: Instant,
+}
+
+struct Limitador {
+    config: RateLimitConfig,
+    cubetas: Mutex<HashMap<(GrupoLimite, String), Cubeta>>,
+}
+
+static LIMITADOR: OnceLock<Limitador> = OnceLock::new();
+
+/// Activa los límites; sin llamarla los middlewares dejan pasar todo
+pub fn inicializar(config: RateLimitConfig) {
+    let _ = LIMITADOR.set(Limitador {
+        config,
+        cubetas: Mutex::new(HashMap::new()),
+    });
+}
+
+/// Descarta las cubetas inactivas; devuelve cuántas quedaron
+pub fn limpiar_inactivos() -> usize {
+    let Some(limitador) = LIMITADOR.get() else {
+        return 0;
+    };
+    let Ok(mut cubetas) = limitador.cubetas.lock() else {
+        return 0;
+    };
+    cubetas.retain(|_, cubeta| cubeta.actualizado.elapsed() < INACTIVIDAD_MAXIMA);
+    cubetas.len()
+}
+
+impl Limitador {
+    fn limite(&self, grupo: GrupoLimite) -> LimiteRate {
+        match grupo {
+            GrupoLimite::General => self.config.general,
+            GrupoLimite::Aula => self.config.aula,
+            GrupoLimite::Uploads => self.config.uploads,
+        }
+    }
+
+    /// Consume un token; si no hay, devuelve los segundos hasta el siguiente
+    fn consumir(&self, grupo: GrupoLimite, ip: &str) -> Result<(), u64> {
+        let limite = self.limite(grupo);
+        if limite.rafaga == 0 {
+            return Ok(());
+        }
+
+        let por_segundo = f64::from(limite.por_minuto) / 60.0;
+        let capacidad = f64::from(limite.rafaga);
+        let ahora = Instant::now();
+
+        let Ok(mut cubetas) = self.cubetas.lock() else {
+            return Ok(());
+        };
+        let cubeta = cubetas.entry((grupo, ip.to_string())).or_insert(Cubeta {
+            tokens: capacidad,
+            actualizado: ahora,
+        });
+
+        let transcurrido = ahora.duration_since(cubeta.actualizado).as_secs_f64();
+        cubeta.tokens = (cubeta.tokens + transcurrido * por_segundo).min(capacidad);
+        cubeta.actualizado = ahora;
+
+        if cubeta.tokens >= 1.0 {
+            cubeta.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(((1.0 - cubeta.tokens) / por_segundo).ceil() as u64)
+        }
+    }
+}
+
+async fn limitar(grupo: GrupoLimite, req: Request, next: Next) -> Response {
+    let Some(limitador) = LIMITADOR.get() else {
+        return next.run(req).await;
+    };
+
+    let (parts, body) = req.into_parts();
+    let ip = client_ip(&parts).unwrap_or_else(|| "desconocida".to_string());
+    if let Err(segundos) = limitador.consumir(grupo, &ip) {
+        tracing::warn!("🚦 Límite {:?} alcanzado para {}", grupo, ip);
+        return AppError::TooManyRequests(
+            format!(
+                "Demasiadas peticiones, intenta de nuevo en {} s",
+                segundos.max(1)
+            )
+            .into(),
+            segundos,
+        )
+        .into_response();
+    }
+
+    next.run(Request::from_parts(parts, body)).await
+}
+
+/// Límite general por IP para toda la API (salvo health y métricas)
+pub async fn limite_general(req: Request, next: Next) -> Response {
+    if RUTAS_EXENTAS.contains(&req.uri().path()) {
+        return next.run(req).await;
+    }
+    limitar(GrupoLimite::General, req, next).await
+}
+
+/// Límite del aula virtual (consulta costosa del curso completo)
+pub async fn limite_aula(req: Request, next: Next) -> Response {
+    limitar(GrupoLimite::Aula, req, next).await
+}
+
+/// Límite de subidas de archivos e imágenes
+pub async fn limite_uploads(req: Request, next: Next) -> Response {
+    limitar(GrupoLimite::Uploads, req, next).await
+}
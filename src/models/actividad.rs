@@ -1,9 +1,11 @@
 use chrono::NaiveTime;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "actividades")]
+#[schema(as = ActividadModel)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
@@ -17,7 +19,7 @@ pub struct Model {
     pub privacidad: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct NewActividad {
     pub curso_id: i32,
     pub profesor_id: i32,
@@ -29,7 +31,7 @@ pub struct NewActividad {
     pub privacidad: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UpdateActividad {
     pub curso_id: Option<i32>,
     pub profesor_id: Option<i32>,
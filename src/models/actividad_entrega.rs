@@ -1,9 +1,11 @@
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "actividades_entrega")]
+#[schema(as = ActividadEntregaModel)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
@@ -11,7 +13,7 @@ pub struct Model {
     pub nombre: String,
     pub descripcion: Option<String>,
     pub fecha_limite: DateTime<Utc>,
-    pub tipo_actividad: String, // 'entrega_obligatoria' | 'entrega_opcional'
+    pub tipo_actividad: TipoActividad,
     pub activo: bool,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
@@ -51,3 +53,16 @@ impl Related<super::entrega::Entity> for Entity {
 }
 
 impl ActiveModelBehavior for ActiveModel {}
+
+/// Valores permitidos por `actividades_entrega_tipo_actividad_check`
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(50))")]
+#[serde(rename_all = "snake_case")]
+pub enum TipoActividad {
+    #[sea_orm(string_value = "entrega_obligatoria")]
+    EntregaObligatoria,
+    #[sea_orm(string_value = "entrega_opcional")]
+    EntregaOpcional,
+}
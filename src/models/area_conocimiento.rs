@@ -1,9 +1,11 @@
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
-#[sea_orm(table_name = "areas_conocimiento", schema_name = "rustdema2")]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "areas_conocimiento")]
+#[schema(as = AreaConocimientoModel)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
@@ -31,7 +33,7 @@ impl Related<super::curso::Entity> for Entity {
 
 impl ActiveModelBehavior for ActiveModel {}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct NuevaArea {
     pub nombre: String,
     pub descripcion: Option<String>,
@@ -39,7 +41,7 @@ pub struct NuevaArea {
     pub estado: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
 pub struct ActualizarArea {
     pub nombre: Option<String>,
     pub descripcion: Option<String>,
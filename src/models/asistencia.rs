@@ -0,0 +1,105 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Asistencia de un estudiante a una sesión; una fila por (sesión, estudiante)
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "asistencias")]
+#[schema(as = AsistenciaModel)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i32,
+    pub sesion_id: i32,
+    pub estudiante_id: i32,
+    pub estado: EstadoAsistencia,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub observacion: Option<String>,
+    /// Docente que pasó lista; `None` en el auto-registro con código
+    pub registrado_por: Option<i32>,
+    pub fecha_registro: DateTime<Utc>,
+    pub fecha_actualizacion: Option<DateTime<Utc>>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::sesion_curso::Entity",
+        from = "Column::SesionId",
+        to = "super::sesion_curso::Column::Id"
+    )]
+    Sesion,
+}
+
+impl Related<super::sesion_curso::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Sesion.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl crate::database::migrator::AutoMigrate for Entity {
+    fn entity_name() -> &'static str {
+        "asistencias"
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum EstadoAsistencia {
+    #[sea_orm(string_value = "presente")]
+    Presente,
+    #[sea_orm(string_value = "ausente")]
+    Ausente,
+    #[sea_orm(string_value = "tarde")]
+    Tarde,
+    #[sea_orm(string_value = "justificado")]
+    Justificado,
+}
+
+/// Registro del pase de lista
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RegistroAsistencia {
+    pub estudiante_id: i32,
+    pub estado: EstadoAsistencia,
+    pub observacion: Option<String>,
+}
+
+/// Body de PUT /api/sesiones/{id}/asistencia
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PaseDeLista {
+    pub registros: Vec<RegistroAsistencia>,
+}
+
+/// Body de POST /api/sesiones/{id}/check-in
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CheckIn {
+    pub codigo: String,
+}
+
+/// Estado de cada estudiante matriculado en una sesión (`None` = sin registro)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AsistenciaEstudiante {
+    pub estudiante_id: i32,
+    pub nombre: Option<String>,
+    pub estado: Option<EstadoAsistencia>,
+    pub observacion: Option<String>,
+}
+
+/// Resumen de asistencia de un estudiante en el curso.
+/// Las sesiones justificadas no cuentan en el porcentaje
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ResumenAsistencia {
+    pub estudiante_id: i32,
+    pub sesiones: u32,
+    pub presentes: u32,
+    pub tardes: u32,
+    pub ausentes: u32,
+    pub justificadas: u32,
+    pub porcentaje: f64,
+    pub cumple_umbral: bool,
+}
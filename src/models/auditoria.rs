@@ -0,0 +1,80 @@
+use chrono::{DateTime, Utc};
+use sea_orm::{entity::prelude::*, JsonValue};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Registro append-only de acciones administrativas y de calificación.
+/// Un trigger en BD impide UPDATE/DELETE sobre la tabla (ver seeder).
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "auditoria")]
+#[schema(as = AuditoriaModel)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i32,
+    pub actor_id: Option<i32>,
+    pub accion: String,
+    pub entidad: String,
+    pub entidad_id: Option<i32>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    #[schema(value_type = Option<Object>)]
+    pub antes: Option<JsonValue>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    #[schema(value_type = Option<Object>)]
+    pub despues: Option<JsonValue>,
+    /// Solo los campos que cambiaron: { campo: { "antes": .., "despues": .. } }
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    #[schema(value_type = Option<Object>)]
+    pub diff: Option<JsonValue>,
+    /// IP del socket, o la reenviada por un proxy de `TRUSTED_PROXIES`; sin proxies
+    /// configurados detrás de uno es la del proxy, no la del cliente
+    pub ip: Option<String>,
+    pub request_id: Option<String>,
+    pub fecha: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::usuario::Entity",
+        from = "Column::ActorId",
+        to = "super::usuario::Column::Id"
+    )]
+    Actor,
+}
+
+impl Related<super::usuario::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Actor.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl crate::database::migrator::AutoMigrate for Entity {
+    fn entity_name() -> &'static str {
+        "auditoria"
+    }
+}
+
+/// Contexto de la petición que origina la acción auditada (actor, IP y request id)
+#[derive(Debug, Clone, Default)]
+pub struct ContextoAuditoria {
+    pub actor_id: Option<i32>,
+    /// Ver [`client_ip`](crate::utils::client_ip::client_ip)
+    pub ip: Option<String>,
+    pub request_id: Option<String>,
+}
+
+/// Filtros del endpoint de consulta (GET /api/auditoria)
+#[derive(Debug, Clone, Deserialize, Default, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FiltroAuditoria {
+    pub actor_id: Option<i32>,
+    pub entidad: Option<String>,
+    pub entidad_id: Option<i32>,
+    pub accion: Option<String>,
+    pub desde: Option<DateTime<Utc>>,
+    pub hasta: Option<DateTime<Utc>>,
+    pub limite: Option<u64>,
+    pub pagina: Option<u64>,
+}
@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Origen de una entrada del calendario académico
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum TipoEventoCalendario {
+    CursoInicio,
+    CursoFin,
+    Modulo,
+    Examen,
+    Entrega,
+    Contenido,
+}
+
+impl TipoEventoCalendario {
+    pub fn nombre(&self) -> &'static str {
+        match self {
+            TipoEventoCalendario::CursoInicio => "curso_inicio",
+            TipoEventoCalendario::CursoFin => "curso_fin",
+            TipoEventoCalendario::Modulo => "modulo",
+            TipoEventoCalendario::Examen => "examen",
+            TipoEventoCalendario::Entrega => "entrega",
+            TipoEventoCalendario::Contenido => "contenido",
+        }
+    }
+}
+
+/// Entrada del calendario. Las fechas límite (entregas, contenidos) no tienen `fin`;
+/// inicio y fin de curso son de día completo
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct EventoCalendario {
+    pub tipo: TipoEventoCalendario,
+    pub id: i32,
+    pub curso_id: i32,
+    pub curso_nombre: String,
+    pub titulo: String,
+    pub descripcion: Option<String>,
+    pub inicio: DateTime<Utc>,
+    pub fin: Option<DateTime<Utc>>,
+    pub todo_el_dia: bool,
+}
+
+/// Filtros de GET /api/calendario (RFC 3339)
+#[derive(Debug, Clone, Deserialize, Default, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FiltroCalendario {
+    pub desde: Option<DateTime<Utc>>,
+    pub hasta: Option<DateTime<Utc>>,
+}
+
+/// Token de suscripción; solo se muestra al generarlo
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TokenCalendario {
+    pub token: String,
+    pub url: String,
+}
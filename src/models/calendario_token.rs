@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Token secreto del feed .ics de cada usuario (se guarda solo su hash)
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "calendario_tokens")]
+#[schema(as = CalendarioTokenModel)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub usuario_id: i32,
+    #[sea_orm(unique)]
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub fecha_creacion: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::usuario::Entity",
+        from = "Column::UsuarioId",
+        to = "super::usuario::Column::Id"
+    )]
+    Usuario,
+}
+
+impl Related<super::usuario::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Usuario.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl crate::database::migrator::AutoMigrate for Entity {
+    fn entity_name() -> &'static str {
+        "calendario_tokens"
+    }
+}
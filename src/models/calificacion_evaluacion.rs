@@ -0,0 +1,89 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use utoipa::ToSchema;
+
+/// Calificación de un estudiante en una evaluación (tabla `calificaciones_evaluacion`)
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "calificaciones_evaluacion")]
+#[schema(as = CalificacionEvaluacionModel)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i32,
+    pub id_evaluacion: i32,
+    pub id_estudiante: i32,
+    #[sea_orm(column_type = "Decimal(Some((5, 2)))", nullable)]
+    pub puntaje_obtenido: Option<Decimal>,
+    #[sea_orm(column_type = "Decimal(Some((5, 2)))")]
+    pub puntaje_maximo: Decimal,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub retroalimentacion: Option<String>,
+    /// Nivel elegido y comentario por criterio (`Vec<CriterioCalificado>`)
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    #[schema(value_type = Option<Object>)]
+    pub detalle_rubrica: Option<JsonValue>,
+    pub calificado_por: Option<i32>,
+    pub fecha_calificacion: Option<DateTime<Utc>>,
+    pub estado: EstadoCalificacion,
+    pub fecha_creacion: Option<DateTime<Utc>>,
+    pub fecha_actualizacion: Option<DateTime<Utc>>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::evaluacion::Entity",
+        from = "Column::IdEvaluacion",
+        to = "super::evaluacion::Column::Id"
+    )]
+    Evaluacion,
+}
+
+impl Related<super::evaluacion::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Evaluacion.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl crate::database::migrator::AutoMigrate for Entity {
+    fn entity_name() -> &'static str {
+        "calificaciones_evaluacion"
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum EstadoCalificacion {
+    #[sea_orm(string_value = "pendiente")]
+    Pendiente,
+    #[sea_orm(string_value = "en_progreso")]
+    EnProgreso,
+    #[sea_orm(string_value = "calificado")]
+    Calificado,
+}
+
+/// Nivel elegido para un criterio de la rúbrica
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CriterioCalificado {
+    pub criterio_id: String,
+    pub nivel_id: String,
+    /// Se completa al calificar con el puntaje del nivel
+    #[serde(default)]
+    pub puntos: Decimal,
+    pub comentario: Option<String>,
+}
+
+/// Body de POST /api/evaluaciones/{id}/calificaciones
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CalificarEvaluacion {
+    pub id_estudiante: i32,
+    pub criterios: Vec<CriterioCalificado>,
+    pub retroalimentacion: Option<String>,
+}
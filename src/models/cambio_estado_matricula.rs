@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::historial_curso_estudiante::EstadoMatricula;
+
+/// Historial de estados de una matrícula (`estado_anterior` es `None` al crearla)
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "cambios_estado_matricula")]
+#[schema(as = CambioEstadoMatriculaModel)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub matricula_id: i32,
+    pub estado_anterior: Option<EstadoMatricula>,
+    pub estado_nuevo: EstadoMatricula,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub motivo: Option<String>,
+    pub actor_id: Option<i32>,
+    pub fecha: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::historial_curso_estudiante::Entity",
+        from = "Column::MatriculaId",
+        to = "super::historial_curso_estudiante::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Matricula,
+}
+
+impl Related<super::historial_curso_estudiante::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Matricula.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl crate::database::migrator::AutoMigrate for Entity {
+    fn entity_name() -> &'static str {
+        "cambios_estado_matricula"
+    }
+}
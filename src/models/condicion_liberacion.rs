@@ -0,0 +1,97 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::publicacion::TipoPublicable;
+
+/// Requisito que un estudiante debe cumplir para acceder a un elemento del aula
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(30))")]
+#[serde(rename_all = "snake_case")]
+pub enum TipoCondicion {
+    /// Marcó como completado `contenido_id`
+    #[sea_orm(string_value = "contenido_completado")]
+    ContenidoCompletado,
+    /// Su mejor intento calificado de `examen_id` tiene al menos `puntaje_minimo` puntos.
+    /// Los intentos los registra y califica `intento_examen_service`
+    #[sea_orm(string_value = "puntaje_examen")]
+    PuntajeExamen,
+    /// Llegó `fecha`
+    #[sea_orm(string_value = "fecha")]
+    Fecha,
+    /// Envió la actividad de entrega `actividad_entrega_id`
+    #[sea_orm(string_value = "entrega_enviada")]
+    EntregaEnviada,
+}
+
+/// Condición de liberación de un módulo, tema, unidad o contenido. Un elemento con
+/// varias condiciones se libera cuando se cumplen todas
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "condiciones_liberacion")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    /// Curso del elemento (para evaluar todas las condiciones de un curso a la vez)
+    pub curso_id: i32,
+    pub tipo_elemento: TipoPublicable,
+    pub elemento_id: i32,
+    pub tipo: TipoCondicion,
+    pub contenido_id: Option<i32>,
+    pub examen_id: Option<i32>,
+    pub puntaje_minimo: Option<f64>,
+    pub fecha: Option<DateTime<Utc>>,
+    pub actividad_entrega_id: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::curso::Entity",
+        from = "Column::CursoId",
+        to = "super::curso::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Curso,
+}
+
+impl Related<super::curso::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Curso.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl crate::database::migrator::AutoMigrate for Entity {
+    fn entity_name() -> &'static str {
+        "condiciones_liberacion"
+    }
+}
+
+/// Condición tal como se envía y se devuelve en /api/liberacion/{tipo}/{id}; cada
+/// `tipo` usa solo sus campos
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CondicionLiberacion {
+    pub tipo: TipoCondicion,
+    pub contenido_id: Option<i32>,
+    pub examen_id: Option<i32>,
+    pub puntaje_minimo: Option<f64>,
+    pub fecha: Option<DateTime<Utc>>,
+    pub actividad_entrega_id: Option<i32>,
+}
+
+impl From<Model> for CondicionLiberacion {
+    fn from(condicion: Model) -> Self {
+        Self {
+            tipo: condicion.tipo,
+            contenido_id: condicion.contenido_id,
+            examen_id: condicion.examen_id,
+            puntaje_minimo: condicion.puntaje_minimo,
+            fecha: condicion.fecha,
+            actividad_entrega_id: condicion.actividad_entrega_id,
+        }
+    }
+}
@@ -1,28 +1,51 @@
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
-#[sea_orm(schema_name = "rustdema2", table_name = "contenidos_unidad")]
+use super::publicacion::EstadoPublicacion;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "contenidos_unidad")]
+#[schema(as = ContenidoUnidadModel)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
     pub unidad_id: i32,
-    pub tipo_contenido: String,
+    pub tipo_contenido: TipoContenido,
     pub titulo: String,
     pub descripcion: Option<String>,
     pub orden: i32,
+    /// Fuente en Markdown (con LaTeX y bloques de código)
     pub contenido: Option<String>,
+    /// `contenido` renderizado y saneado (ver `utils::markdown`)
+    pub contenido_html: Option<String>,
     pub url: Option<String>,
     pub visible: bool,
+    /// Borrador o publicado; `visible` refleja si está publicado
+    pub estado_publicacion: EstadoPublicacion,
+    /// Publicación programada (solo borradores)
+    pub publicar_en: Option<DateTime<Utc>>,
+    /// Vuelta a borrador programada
+    pub despublicar_en: Option<DateTime<Utc>>,
     pub obligatorio: bool,
     pub puntos: Option<i32>,
     pub fecha_limite: Option<DateTime<Utc>>,
     pub duracion_estimada: Option<i32>,
+    /// Examen que respalda un contenido de tipo examen
     pub examen_id: Option<i32>,
+    /// Heredado: apuntaba a una entrega de estudiante; usar `actividad_entrega_id`
     pub entrega_id: Option<i32>,
+    /// Actividad de entrega que respalda un contenido de tipo entrega
+    pub actividad_entrega_id: Option<i32>,
+    /// Metadatos oEmbed de videos y enlaces (título, miniatura, html embebible...)
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    #[schema(value_type = Option<Object>)]
+    pub oembed: Option<Json>,
     pub fecha_creacion: Option<DateTime<Utc>>,
     pub fecha_actualizacion: Option<DateTime<Utc>>,
+    /// Soft delete: las filas con fecha se consideran en la papelera
+    pub fecha_eliminacion: Option<DateTime<Utc>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter)]
@@ -49,10 +72,41 @@ impl Related<super::unidad::Entity> for Entity {
 
 impl ActiveModelBehavior for ActiveModel {}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Valores permitidos por `contenidos_unidad_tipo_check`
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(50))")]
+#[serde(rename_all = "snake_case")]
+pub enum TipoContenido {
+    #[sea_orm(string_value = "texto")]
+    Texto,
+    #[sea_orm(string_value = "documento")]
+    Documento,
+    #[sea_orm(string_value = "video")]
+    Video,
+    #[sea_orm(string_value = "enlace")]
+    Enlace,
+    #[sea_orm(string_value = "examen")]
+    Examen,
+    #[sea_orm(string_value = "entrega")]
+    Entrega,
+    #[sea_orm(string_value = "actividad")]
+    Actividad,
+}
+
+/// Reglas por tipo:
+/// - texto: `contenido` obligatorio, sin `url`
+/// - documento, video, enlace: `url` http(s) obligatoria
+/// - examen: `examen_id` del mismo curso, o se crea un examen en borrador que
+///   cierra en `fecha_limite`
+/// - entrega: `actividad_entrega_id` del mismo curso, o se crea una actividad con
+///   `fecha_limite`
+/// - actividad: `descripcion` o `contenido` obligatorio
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct NuevoContenidoUnidad {
     pub unidad_id: i32,
-    pub tipo_contenido: String,
+    pub tipo_contenido: TipoContenido,
     pub titulo: String,
     pub descripcion: Option<String>,
     pub contenido: Option<String>,
@@ -61,11 +115,16 @@ pub struct NuevoContenidoUnidad {
     pub visible: bool,
     pub obligatorio: bool,
     pub puntos: Option<i32>,
+    pub fecha_limite: Option<DateTime<Utc>>,
+    /// Minutos; también es la duración del examen que se crea
+    pub duracion_estimada: Option<i32>,
+    pub examen_id: Option<i32>,
+    pub actividad_entrega_id: Option<i32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
 pub struct ActualizarContenidoUnidad {
-    pub tipo_contenido: Option<String>,
+    pub tipo_contenido: Option<TipoContenido>,
     pub titulo: Option<String>,
     pub descripcion: Option<String>,
     pub contenido: Option<String>,
@@ -74,4 +133,8 @@ pub struct ActualizarContenidoUnidad {
     pub visible: Option<bool>,
     pub obligatorio: Option<bool>,
     pub puntos: Option<i32>,
+    pub fecha_limite: Option<DateTime<Utc>>,
+    pub duracion_estimada: Option<i32>,
+    pub examen_id: Option<i32>,
+    pub actividad_entrega_id: Option<i32>,
 }
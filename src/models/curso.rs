@@ -1,9 +1,13 @@
 use chrono::{DateTime, NaiveDate, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+use super::{contenido_unidad::TipoContenido, publicacion::EstadoPublicacion};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "cursos")]
+#[schema(as = CursoModel)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
@@ -21,6 +25,11 @@ pub struct Model {
     pub area_conocimiento_id: i32,
     pub fecha_eliminacion: Option<DateTime<Utc>>,
     pub fecha_actualizacion: DateTime<Utc>,
+    /// Máximo de estudiantes con matrícula vigente; los demás pasan a la lista de espera
+    pub cupo_maximo: Option<i32>,
+    /// Ventana de matrícula; sin fechas queda abierta
+    pub fecha_apertura_matricula: Option<DateTime<Utc>>,
+    pub fecha_cierre_matricula: Option<DateTime<Utc>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -53,7 +62,7 @@ impl Related<super::usuario::Entity> for Entity {
 
 impl ActiveModelBehavior for ActiveModel {}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct NuevoCurso {
     pub nombre: String,
     pub descripcion: String,
@@ -68,7 +77,7 @@ pub struct NuevoCurso {
     pub plantilla_base_id: Option<i32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
 pub struct ActualizarCurso {
     pub nombre: Option<String>,
     pub descripcion: Option<String>,
@@ -83,32 +92,115 @@ pub struct ActualizarCurso {
     pub plantilla_base_id: Option<i32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Body de PUT /api/cursos/{curso_id}/reglas-matricula: reemplaza todas las reglas
+/// (`null` o lista vacía las quita)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReglasMatricula {
+    /// Ids de los cursos que el estudiante debe haber aprobado
+    #[serde(default)]
+    pub prerequisitos: Vec<i32>,
+    pub cupo_maximo: Option<i32>,
+    pub fecha_apertura_matricula: Option<DateTime<Utc>>,
+    pub fecha_cierre_matricula: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CursoPrerequisito {
+    pub id: i32,
+    pub nombre: String,
+}
+
+/// Reglas de matrícula vigentes y ocupación del curso
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReglasMatriculaCurso {
+    pub curso_id: i32,
+    /// Semestre mínimo del estudiante (`usuarios.semestre`)
+    pub semestre: Option<i32>,
+    pub prerequisitos: Vec<CursoPrerequisito>,
+    pub cupo_maximo: Option<i32>,
+    pub fecha_apertura_matricula: Option<DateTime<Utc>>,
+    pub fecha_cierre_matricula: Option<DateTime<Utc>>,
+    /// Matrículas vigentes (no inactivas)
+    pub matriculados: u64,
+    pub en_espera: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CursoDetallado {
+    #[schema(value_type = CursoModel)]
     pub curso: Model,
+    #[schema(value_type = Option<AreaConocimientoModel>)]
     pub area: Option<super::area_conocimiento::Model>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Contenido de una unidad en el aula (sin el cuerpo; se pide en /api/contenidos/{id})
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ContenidoAula {
+    pub id: i32,
+    pub titulo: String,
+    pub tipo_contenido: TipoContenido,
+    pub orden: i32,
+    pub estado_publicacion: EstadoPublicacion,
+    pub publicar_en: Option<DateTime<Utc>>,
+    pub despublicar_en: Option<DateTime<Utc>>,
+    /// El estudiante lo marcó como completado (siempre `false` en la vista docente)
+    pub completado: bool,
+    pub bloqueado: bool,
+    pub motivos_bloqueo: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UnidadAula {
     pub id: i32,
     pub nombre: String,
     pub descripcion: Option<String>,
     pub orden: Option<i32>,
     pub tema_id: i32,
+    pub estado_publicacion: EstadoPublicacion,
+    pub publicar_en: Option<DateTime<Utc>>,
+    pub despublicar_en: Option<DateTime<Utc>>,
+    pub bloqueado: bool,
+    pub motivos_bloqueo: Vec<String>,
+    pub contenidos: Vec<ContenidoAula>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TemaAula {
     pub id: i32,
     pub nombre: String,
     pub descripcion: Option<String>,
     pub orden: Option<i32>,
+    pub estado_publicacion: EstadoPublicacion,
+    pub publicar_en: Option<DateTime<Utc>>,
+    pub despublicar_en: Option<DateTime<Utc>>,
+    /// Condiciones de liberación pendientes del estudiante, propias o heredadas del
+    /// módulo; solo se evalúan en la vista estudiante
+    pub bloqueado: bool,
+    pub motivos_bloqueo: Vec<String>,
     pub unidades: Vec<UnidadAula>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AulaCurso {
+    #[schema(value_type = CursoModel)]
     pub curso: Model,
+    pub vista: VistaAula,
     pub temas: Vec<TemaAula>,
 }
+
+/// Qué muestra el aula: los estudiantes solo ven lo publicado (módulo, tema y
+/// unidad); el personal docente ve también los borradores
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VistaAula {
+    Estudiante,
+    Docente,
+}
+
+/// Parámetros de GET /api/cursos/{id}/aula
+#[derive(Debug, Clone, Deserialize, Default, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FiltroAula {
+    /// `estudiante` permite al personal docente previsualizar el aula como estudiante
+    pub vista: Option<VistaAula>,
+}
@@ -1,9 +1,11 @@
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "entregas")]
+#[schema(as = EntregaModel)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
@@ -17,7 +19,7 @@ pub struct Model {
     pub calificacion: Option<f32>,
     pub comentario_profesor: Option<String>,
     pub fecha_calificacion: Option<DateTime<Utc>>,
-    pub estado: String, // 'pendiente' | 'calificado' | 'rechazado'
+    pub estado: EstadoEntrega,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
 }
@@ -56,3 +58,18 @@ impl Related<super::usuario::Entity> for Entity {
 }
 
 impl ActiveModelBehavior for ActiveModel {}
+
+/// Valores permitidos por `entregas_estado_check`
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(50))")]
+#[serde(rename_all = "snake_case")]
+pub enum EstadoEntrega {
+    #[sea_orm(string_value = "pendiente")]
+    Pendiente,
+    #[sea_orm(string_value = "calificado")]
+    Calificado,
+    #[sea_orm(string_value = "rechazado")]
+    Rechazado,
+}
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum EstadoEnvio {
+    /// En cola para el próximo intento
+    #[sea_orm(string_value = "pendiente")]
+    Pendiente,
+    /// Espera al correo de resumen diario
+    #[sea_orm(string_value = "resumen")]
+    Resumen,
+    #[sea_orm(string_value = "enviado")]
+    Enviado,
+    /// Agotó los reintentos o el destinatario ya no existe
+    #[sea_orm(string_value = "fallido")]
+    Fallido,
+}
+
+/// Cola de correos de notificaciones (envío inmediato con reintentos o resumen diario)
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "envios_notificacion")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub notificacion_id: i32,
+    pub usuario_id: i32,
+    pub estado: EstadoEnvio,
+    pub intentos: i32,
+    pub proximo_intento: DateTime<Utc>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub ultimo_error: Option<String>,
+    pub fecha_creacion: DateTime<Utc>,
+    pub fecha_envio: Option<DateTime<Utc>>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::notificacion::Entity",
+        from = "Column::NotificacionId",
+        to = "super::notificacion::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Notificacion,
+}
+
+impl Related<super::notificacion::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Notificacion.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl crate::database::migrator::AutoMigrate for Entity {
+    fn entity_name() -> &'static str {
+        "envios_notificacion"
+    }
+}
@@ -0,0 +1,70 @@
+use sea_orm::JsonValue;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::pregunta_examen::TipoPregunta;
+
+/// Estadísticas de un examen sobre el mejor intento calificado de cada estudiante
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EstadisticasExamen {
+    pub examen_id: i32,
+    pub nombre: String,
+    /// Pasó `fecha_fin`
+    pub cerrado: bool,
+    pub puntaje_maximo: f64,
+    /// Estudiantes con al menos un intento calificado
+    pub estudiantes: u64,
+    pub intentos_calificados: u64,
+    /// Intentos entregados con respuestas abiertas sin calificar (no entran al análisis)
+    pub intentos_pendientes: u64,
+    pub media: Option<f64>,
+    pub mediana: Option<f64>,
+    pub desviacion_estandar: Option<f64>,
+    pub minimo: Option<f64>,
+    pub maximo: Option<f64>,
+    /// Diez rangos iguales entre 0 y `puntaje_maximo`
+    pub histograma: Vec<RangoHistograma>,
+    /// Minutos entre el inicio y la entrega del intento
+    pub duracion_media_minutos: Option<f64>,
+    pub duracion_mediana_minutos: Option<f64>,
+    pub preguntas: Vec<AnalisisPregunta>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RangoHistograma {
+    pub desde: f64,
+    /// El último rango incluye `hasta`
+    pub hasta: f64,
+    pub cantidad: u64,
+}
+
+/// Análisis de ítem de una pregunta
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AnalisisPregunta {
+    pub pregunta_id: i32,
+    pub orden: i32,
+    pub pregunta: String,
+    pub tipo: TipoPregunta,
+    pub valor_puntos: i32,
+    pub respuestas: u64,
+    /// Intentos analizados que no respondieron la pregunta (valen 0)
+    pub omitidas: u64,
+    /// Índice de dificultad: fracción media del puntaje obtenida (1 = todos aciertan)
+    pub dificultad: Option<f64>,
+    /// Índice de discriminación: dificultad en el 27% con mejor nota menos la del 27%
+    /// con peor nota (cerca de 0 o negativo = la pregunta no distingue)
+    pub discriminacion: Option<f64>,
+    /// Frecuencia de cada opción; solo en preguntas de selección
+    pub opciones: Option<Vec<FrecuenciaOpcion>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FrecuenciaOpcion {
+    #[schema(value_type = Object)]
+    pub opcion: JsonValue,
+    pub correcta: bool,
+    /// Respuestas que la eligieron
+    pub veces: u64,
+    /// `veces` sobre las respuestas a la pregunta
+    pub proporcion: f64,
+}
@@ -0,0 +1,158 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use utoipa::ToSchema;
+
+/// Evaluación genérica de un curso (tabla `evaluaciones` del DDL v2).
+/// La rúbrica, si existe, se guarda en `configuracion.rubrica`
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "evaluaciones")]
+#[schema(as = EvaluacionModel)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i32,
+    pub id_curso: i32,
+    pub tipo: TipoEvaluacion,
+    pub titulo: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub instrucciones: Option<String>,
+    #[sea_orm(column_type = "JsonBinary")]
+    #[schema(value_type = Object)]
+    pub configuracion: JsonValue,
+    #[sea_orm(column_type = "Decimal(Some((5, 2)))")]
+    pub peso: Decimal,
+    #[sea_orm(column_type = "Decimal(Some((5, 2)))")]
+    pub puntaje_maximo: Decimal,
+    pub fecha_disponible_desde: Option<DateTime<Utc>>,
+    pub fecha_disponible_hasta: Option<DateTime<Utc>>,
+    pub fecha_limite_entrega: Option<DateTime<Utc>>,
+    pub estado: EstadoEvaluacion,
+    pub creado_por: Option<i32>,
+    pub fecha_creacion: Option<DateTime<Utc>>,
+    pub fecha_actualizacion: Option<DateTime<Utc>>,
+    /// Soft delete
+    pub fecha_eliminacion: Option<DateTime<Utc>>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::calificacion_evaluacion::Entity")]
+    Calificaciones,
+}
+
+impl Related<super::calificacion_evaluacion::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Calificaciones.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl crate::database::migrator::AutoMigrate for Entity {
+    fn entity_name() -> &'static str {
+        "evaluaciones"
+    }
+}
+
+/// Tipo enumerado `tipo_evaluacion` de PostgreSQL
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "tipo_evaluacion")]
+#[serde(rename_all = "snake_case")]
+pub enum TipoEvaluacion {
+    #[sea_orm(string_value = "tarea")]
+    Tarea,
+    #[sea_orm(string_value = "proyecto")]
+    Proyecto,
+    #[sea_orm(string_value = "exposicion")]
+    Exposicion,
+    #[sea_orm(string_value = "participacion")]
+    Participacion,
+    #[sea_orm(string_value = "quiz")]
+    Quiz,
+}
+
+/// Ciclo de vida: solo las publicadas son visibles para los estudiantes
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum EstadoEvaluacion {
+    #[sea_orm(string_value = "borrador")]
+    Borrador,
+    #[sea_orm(string_value = "publicado")]
+    Publicado,
+    #[sea_orm(string_value = "archivado")]
+    Archivado,
+}
+
+/// Rúbrica: criterios × niveles, cada nivel con su puntaje
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Rubrica {
+    pub criterios: Vec<CriterioRubrica>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CriterioRubrica {
+    pub id: String,
+    pub nombre: String,
+    pub descripcion: Option<String>,
+    pub niveles: Vec<NivelRubrica>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NivelRubrica {
+    pub id: String,
+    pub nombre: String,
+    pub descripcion: Option<String>,
+    pub puntos: Decimal,
+}
+
+impl Rubrica {
+    /// Suma del nivel de mayor puntaje de cada criterio
+    pub fn puntaje_maximo(&self) -> Decimal {
+        self.criterios
+            .iter()
+            .filter_map(|criterio| criterio.niveles.iter().map(|nivel| nivel.puntos).max())
+            .sum()
+    }
+}
+
+impl Model {
+    /// Rúbrica guardada en `configuracion.rubrica`, si la hay
+    pub fn rubrica(&self) -> Option<Rubrica> {
+        self.configuracion
+            .get("rubrica")
+            .and_then(|rubrica| serde_json::from_value(rubrica.clone()).ok())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NuevaEvaluacion {
+    pub id_curso: i32,
+    pub tipo: TipoEvaluacion,
+    pub titulo: String,
+    pub instrucciones: Option<String>,
+    pub peso: Option<Decimal>,
+    pub puntaje_maximo: Option<Decimal>,
+    pub fecha_disponible_desde: Option<DateTime<Utc>>,
+    pub fecha_disponible_hasta: Option<DateTime<Utc>>,
+    pub fecha_limite_entrega: Option<DateTime<Utc>>,
+    pub rubrica: Option<Rubrica>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
+pub struct ActualizarEvaluacion {
+    pub tipo: Option<TipoEvaluacion>,
+    pub titulo: Option<String>,
+    pub instrucciones: Option<String>,
+    pub peso: Option<Decimal>,
+    pub puntaje_maximo: Option<Decimal>,
+    pub fecha_disponible_desde: Option<DateTime<Utc>>,
+    pub fecha_disponible_hasta: Option<DateTime<Utc>>,
+    pub fecha_limite_entrega: Option<DateTime<Utc>>,
+}
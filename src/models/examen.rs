@@ -1,9 +1,11 @@
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "examenes")]
+#[schema(as = ExamenModel)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
@@ -15,9 +17,11 @@ pub struct Model {
     pub duracion_minutos: i32,
     pub intentos_permitidos: i32,
     pub mostrar_resultados: bool,
-    pub estado: String,
+    pub estado: EstadoExamen,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
+    /// Soft delete: las filas con fecha se consideran en la papelera
+    pub fecha_eliminacion: Option<DateTime<Utc>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -25,7 +29,22 @@ pub enum Relation {}
 
 impl ActiveModelBehavior for ActiveModel {}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Ciclo de vida del examen (mismos valores que `evaluaciones.estado`)
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum EstadoExamen {
+    #[sea_orm(string_value = "borrador")]
+    Borrador,
+    #[sea_orm(string_value = "publicado")]
+    Publicado,
+    #[sea_orm(string_value = "archivado")]
+    Archivado,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct NuevoExamen {
     pub curso_id: i32,
     pub nombre: String,
@@ -35,10 +54,10 @@ pub struct NuevoExamen {
     pub duracion_minutos: i32,
     pub intentos_permitidos: i32,
     pub mostrar_resultados: bool,
-    pub estado: String,
+    pub estado: EstadoExamen,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
 pub struct ActualizarExamen {
     pub nombre: Option<String>,
     pub descripcion: Option<String>,
@@ -47,5 +66,5 @@ pub struct ActualizarExamen {
     pub duracion_minutos: Option<i32>,
     pub intentos_permitidos: Option<i32>,
     pub mostrar_resultados: Option<bool>,
-    pub estado: Option<String>,
+    pub estado: Option<EstadoExamen>,
 }
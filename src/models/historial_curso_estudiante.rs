@@ -1,20 +1,26 @@
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "historial_cursos_estudiantes")]
+#[schema(as = HistorialCursoEstudianteModel)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
     pub curso_id: i32,
     pub estudiante_id: i32,
     pub fecha_inscripcion: DateTime<Utc>,
-    pub estado: String,
+    pub estado: EstadoMatricula,
     pub calificacion_final: Option<f64>,
     pub aprobado: bool,
     pub fecha_creacion: Option<DateTime<Utc>>,
     pub fecha_actualizacion: Option<DateTime<Utc>>,
+    /// 1 en la primera matrícula; cada rematrícula es una fila nueva
+    pub intento: i32,
+    /// Intento anterior en el mismo curso
+    pub matricula_anterior_id: Option<i32>,
     // Campos adicionales para JOIN con usuario
     #[sea_orm(ignore)]
     pub nombre: Option<String>,
@@ -40,18 +46,83 @@ impl Related<super::usuario::Entity> for Entity {
 
 impl ActiveModelBehavior for ActiveModel {}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Ciclo de vida de la matrícula: solo `activo` ocupa cupo; los demás son estados
+/// finales y rematricularse crea un intento nuevo
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(30))")]
+#[serde(rename_all = "snake_case")]
+pub enum EstadoMatricula {
+    #[sea_orm(string_value = "activo")]
+    Activo,
+    /// Se retiró (o lo retiraron) antes del cierre
+    #[sea_orm(string_value = "retirado")]
+    Retirado,
+    #[sea_orm(string_value = "aprobado")]
+    Aprobado,
+    #[sea_orm(string_value = "reprobado")]
+    Reprobado,
+    /// Curso cerrado sin nota suficiente para decidir
+    #[sea_orm(string_value = "finalizado")]
+    Finalizado,
+}
+
+impl EstadoMatricula {
+    /// Transiciones permitidas: de `activo` a cualquier estado final, de `finalizado`
+    /// a la nota que llegue después del cierre y entre aprobado y reprobado para
+    /// corregir una nota
+    pub fn puede_pasar_a(self, destino: EstadoMatricula) -> bool {
+        use EstadoMatricula::*;
+        matches!(
+            (self, destino),
+            (Activo, Retirado | Aprobado | Reprobado | Finalizado)
+                | (Finalizado, Aprobado | Reprobado)
+                | (Aprobado, Reprobado)
+                | (Reprobado, Aprobado)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct NuevoHistorial {
     pub curso_id: i32,
     pub estudiante_id: i64,
-    pub estado: String,
+    pub estado: EstadoMatricula,
     pub calificacion_final: Option<f64>,
     pub aprobado: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
 pub struct ActualizarHistorial {
-    pub estado: Option<String>,
+    pub estado: Option<EstadoMatricula>,
     pub calificacion_final: Option<f64>,
     pub aprobado: Option<bool>,
 }
+
+/// Body de PUT /api/matriculas/{id}/estado
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CambioEstado {
+    pub estado: EstadoMatricula,
+    pub motivo: Option<String>,
+}
+
+/// Body de POST /api/cursos/{curso_id}/cierre
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
+pub struct CierreCurso {
+    /// Con nota mínima, las matrículas con `calificacion_final` quedan aprobadas o
+    /// reprobadas; sin nota (o sin esta opción) quedan finalizadas
+    pub nota_minima: Option<f64>,
+    pub motivo: Option<String>,
+}
+
+/// Resultado del cierre del curso
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ResumenCierre {
+    pub curso_id: i32,
+    pub aprobados: usize,
+    pub reprobados: usize,
+    pub finalizados: usize,
+    /// Solicitudes de la lista de espera descartadas
+    pub lista_espera_descartada: u64,
+}
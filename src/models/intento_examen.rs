@@ -0,0 +1,115 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::pregunta_examen::TipoPregunta;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum EstadoIntento {
+    #[sea_orm(string_value = "en_curso")]
+    EnCurso,
+    /// Entregado, con preguntas pendientes de calificar
+    #[sea_orm(string_value = "entregado")]
+    Entregado,
+    /// `puntaje` es definitivo
+    #[sea_orm(string_value = "calificado")]
+    Calificado,
+}
+
+/// Intento de un estudiante en un examen
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "intentos_examen")]
+#[schema(as = IntentoExamenModel)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub examen_id: i32,
+    pub estudiante_id: i32,
+    /// 1, 2... hasta `examenes.intentos_permitidos`
+    pub numero: i32,
+    pub estado: EstadoIntento,
+    pub puntaje: Option<f64>,
+    pub puntaje_maximo: Option<f64>,
+    pub fecha_inicio: DateTime<Utc>,
+    pub fecha_entrega: Option<DateTime<Utc>>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::examen::Entity",
+        from = "Column::ExamenId",
+        to = "super::examen::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Examen,
+    #[sea_orm(
+        belongs_to = "super::usuario::Entity",
+        from = "Column::EstudianteId",
+        to = "super::usuario::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Usuario,
+}
+
+impl Related<super::examen::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Examen.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl crate::database::migrator::AutoMigrate for Entity {
+    fn entity_name() -> &'static str {
+        "intentos_examen"
+    }
+}
+
+/// Respuesta del estudiante a una pregunta al entregar
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RespuestaEnviada {
+    pub pregunta_id: i32,
+    /// Texto (abierta), una opción (seleccion_unica) o lista de opciones (seleccion_multiple)
+    #[schema(value_type = Object)]
+    pub respuesta: serde_json::Value,
+}
+
+/// Cuerpo de POST /api/intentos/{id}/entregar. Las preguntas sin respuesta valen 0
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EntregaIntento {
+    pub respuestas: Vec<RespuestaEnviada>,
+}
+
+/// Pregunta del examen tal como la ve quien rinde el intento (sin la respuesta correcta)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PreguntaIntento {
+    pub pregunta_id: i32,
+    pub pregunta: String,
+    pub pregunta_html: Option<String>,
+    pub tipo: TipoPregunta,
+    #[schema(value_type = Option<Object>)]
+    pub opciones: Option<serde_json::Value>,
+    pub valor_puntos: i32,
+    #[schema(value_type = Option<Object>)]
+    pub respuesta: Option<serde_json::Value>,
+    /// Solo con `resultados_visibles`
+    pub puntaje: Option<f64>,
+    pub retroalimentacion: Option<String>,
+}
+
+/// Intento con sus preguntas y respuestas
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DetalleIntento {
+    #[schema(value_type = IntentoExamenModel)]
+    pub intento: Model,
+    /// Falso mientras el intento no esté calificado o el examen no muestre resultados;
+    /// en ese caso se omiten puntajes y retroalimentación
+    pub resultados_visibles: bool,
+    pub preguntas: Vec<PreguntaIntento>,
+}
@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::publicacion::TipoPublicable;
+
+/// Elemento con condiciones que ya quedó liberado para un estudiante; evita repetir
+/// el aviso. Se borra cuando cambian las condiciones del elemento
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "liberaciones_estudiante")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub tipo_elemento: TipoPublicable,
+    pub elemento_id: i32,
+    pub estudiante_id: i32,
+    pub fecha: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::usuario::Entity",
+        from = "Column::EstudianteId",
+        to = "super::usuario::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Usuario,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl crate::database::migrator::AutoMigrate for Entity {
+    fn entity_name() -> &'static str {
+        "liberaciones_estudiante"
+    }
+}
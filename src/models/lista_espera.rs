@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Estudiante esperando cupo en un curso lleno; se atiende por orden de llegada
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "listas_espera")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub curso_id: i32,
+    pub estudiante_id: i32,
+    pub fecha_solicitud: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::curso::Entity",
+        from = "Column::CursoId",
+        to = "super::curso::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Curso,
+    #[sea_orm(
+        belongs_to = "super::usuario::Entity",
+        from = "Column::EstudianteId",
+        to = "super::usuario::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Usuario,
+}
+
+impl Related<super::usuario::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Usuario.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl crate::database::migrator::AutoMigrate for Entity {
+    fn entity_name() -> &'static str {
+        "listas_espera"
+    }
+}
+
+/// Puesto de un estudiante en la lista de espera de un curso
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EntradaListaEspera {
+    pub curso_id: i32,
+    pub estudiante_id: i32,
+    pub nombre: Option<String>,
+    /// 1 = el próximo en recibir cupo
+    pub posicion: u64,
+    pub fecha_solicitud: DateTime<Utc>,
+}
@@ -6,34 +6,62 @@ use std::sync::Arc;
 use utoipa::ToSchema;
 use validator::Validate;
 
-use crate::{config::Config, database::DbExecutor};
+use crate::{
+    config::Config,
+    database::DbExecutor,
+    handlers::{auth::TokenValidationResponse, health::HealthResponse},
+    services::correo_service::Mailer,
+};
 pub mod actividad;
 pub mod actividad_entrega;
 pub mod area_conocimiento;
+pub mod asistencia;
+pub mod auditoria;
+pub mod calendario;
+pub mod calendario_token;
 pub mod calificacion;
+pub mod calificacion_evaluacion;
+pub mod cambio_estado_matricula;
+pub mod condicion_liberacion;
 pub mod contenido_plantilla;
 pub mod contenido_transversal;
 pub mod contenido_unidad;
 pub mod curso;
 pub mod entrega;
+pub mod envio_notificacion;
+pub mod estadistica_examen;
+pub mod evaluacion;
 pub mod evaluacion_calificacion;
 pub mod evaluacion_sesion;
 pub mod evento_programado;
 pub mod examen;
 pub mod historial_curso_actividad;
 pub mod historial_curso_estudiante;
+pub mod intento_examen;
+pub mod liberacion_estudiante;
+pub mod lista_espera;
 pub mod modulo;
 pub mod modulo_archivo;
 pub mod notificacion;
+pub mod orden;
+pub mod papelera;
 pub mod personalizacion_portafolio;
 pub mod plantilla_curso;
 pub mod portafolio;
 pub mod portafolio_contenido;
+pub mod preferencia_notificacion;
 pub mod pregunta_examen;
+pub mod prerequisito_curso;
 pub mod profesor_curso;
+pub mod progreso_contenido;
+pub mod publicacion;
+pub mod respuesta_examen;
+pub mod revision;
 pub mod rol;
+pub mod sesion_curso;
 pub mod socket;
 pub mod tema;
+pub mod token_cuenta;
 pub mod unidad;
 pub mod usuario;
 
@@ -44,6 +72,8 @@ pub struct AppState {
     pub config: Arc<Config>,
     pub jwt_encoding_key: Arc<EncodingKey>,
     pub jwt_decoding_key: Arc<DecodingKey>,
+    /// Envío de correos (SMTP o archivo según MAIL_TRANSPORT)
+    pub mailer: Arc<dyn Mailer>,
 }
 
 impl AppState {
@@ -138,10 +168,18 @@ pub struct Claims {
 
 // API Response wrapper
 #[derive(Debug, Serialize, ToSchema)]
+#[aliases(
+    AuthApiResponse = ApiResponse<AuthResponse>,
+    HealthApiResponse = ApiResponse<HealthResponse>,
+    TokenValidationApiResponse = ApiResponse<TokenValidationResponse>
+)]
 pub struct ApiResponse<T> {
     pub success: bool,
     pub data: Option<T>,
     pub message: Option<String>,
+    /// Id de la petición (X-Request-Id), solo presente en respuestas de error
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl<T> ApiResponse<T> {
@@ -150,6 +188,7 @@ impl<T> ApiResponse<T> {
             success: true,
             data: Some(data),
             message: None,
+            request_id: None,
         }
     }
 
@@ -158,6 +197,7 @@ impl<T> ApiResponse<T> {
             success: false,
             data: None,
             message: Some(message),
+            request_id: crate::middleware::request_id::current_request_id(),
         }
     }
 }
@@ -1,9 +1,13 @@
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+use super::publicacion::EstadoPublicacion;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "modulos")]
+#[schema(as = ModuloModel)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
@@ -12,11 +16,19 @@ pub struct Model {
     pub descripcion: Option<String>,
     pub orden: i32,
     pub visible: bool,
-    pub tipo: String, // 'estructura_contenido' | 'taller' | 'evaluacion' | etc.
+    /// Borrador o publicado; `visible` refleja si está publicado
+    pub estado_publicacion: EstadoPublicacion,
+    /// Publicación programada (solo borradores)
+    pub publicar_en: Option<DateTime<Utc>>,
+    /// Vuelta a borrador programada
+    pub despublicar_en: Option<DateTime<Utc>>,
+    pub tipo: TipoModulo,
     pub fecha_inicio: Option<DateTime<Utc>>,
     pub fecha_fin: Option<DateTime<Utc>>,
     pub duracion_estimada: Option<i32>, // en minutos
     pub obligatorio: bool,
+    /// Soft delete: las filas con fecha se consideran en la papelera
+    pub fecha_eliminacion: Option<DateTime<Utc>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter)]
@@ -65,13 +77,28 @@ impl Related<super::modulo_archivo::Entity> for Entity {
 
 impl ActiveModelBehavior for ActiveModel {}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Tipo de módulo (columna `modulos.tipo`, por defecto estructura_contenido)
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(50))")]
+#[serde(rename_all = "snake_case")]
+pub enum TipoModulo {
+    #[sea_orm(string_value = "estructura_contenido")]
+    EstructuraContenido,
+    #[sea_orm(string_value = "taller")]
+    Taller,
+    #[sea_orm(string_value = "evaluacion")]
+    Evaluacion,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct NuevoModulo {
     pub curso_id: i32,
     pub nombre: String,
     pub descripcion: Option<String>,
     pub orden: i32,
-    pub tipo: Option<String>,
+    pub tipo: Option<TipoModulo>,
     pub visible: bool,
     pub fecha_inicio: Option<DateTime<Utc>>,
     pub fecha_fin: Option<DateTime<Utc>>,
@@ -79,12 +106,12 @@ pub struct NuevoModulo {
     pub obligatorio: Option<bool>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
 pub struct ActualizarModulo {
     pub nombre: Option<String>,
     pub descripcion: Option<String>,
     pub orden: Option<i32>,
-    pub tipo: Option<String>,
+    pub tipo: Option<TipoModulo>,
     pub visible: Option<bool>,
     pub fecha_inicio: Option<DateTime<Utc>>,
     pub fecha_fin: Option<DateTime<Utc>>,
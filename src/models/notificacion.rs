@@ -1,18 +1,23 @@
 use chrono::{DateTime, Utc};
 use sea_orm::{entity::prelude::*, JsonValue};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
-#[sea_orm(table_name = "notificaciones", schema_name = "rustdema2")]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "notificaciones")]
+#[schema(as = NotificacionModel)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
     pub usuario_id: i32,
     pub titulo: String,
     pub mensaje: String,
+    /// `mensaje` renderizado desde Markdown y saneado
+    pub mensaje_html: Option<String>,
     pub tipo: String,
     pub leida: bool,
     pub enlace: Option<String>,
+    #[schema(value_type = Option<Object>)]
     pub datos_adicionales: Option<JsonValue>,
     #[sea_orm(column_name = "fecha_creacion")]
     pub created_at: Option<DateTime<Utc>>,
@@ -25,7 +30,7 @@ pub enum Relation {}
 
 impl ActiveModelBehavior for ActiveModel {}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct NuevaNotificacion {
     pub usuario_id: i32,
     pub titulo: String,
@@ -0,0 +1,127 @@
+use sea_orm::FromQueryResult;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Entidades con `orden` dentro de un padre
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TipoOrdenable {
+    /// Módulos de un curso
+    Modulo,
+    /// Temas de un módulo
+    Tema,
+    /// Unidades de un tema
+    Unidad,
+    /// Contenidos de una unidad
+    Contenido,
+    /// Preguntas de un examen
+    Pregunta,
+    /// Contenidos de un portafolio
+    PortafolioContenido,
+    /// Módulos de un webinar
+    WebinarModulo,
+}
+
+impl TipoOrdenable {
+    pub fn nombre(&self) -> &'static str {
+        match self {
+            TipoOrdenable::Modulo => "modulo",
+            TipoOrdenable::Tema => "tema",
+            TipoOrdenable::Unidad => "unidad",
+            TipoOrdenable::Contenido => "contenido",
+            TipoOrdenable::Pregunta => "pregunta",
+            TipoOrdenable::PortafolioContenido => "portafolio_contenido",
+            TipoOrdenable::WebinarModulo => "webinar_modulo",
+        }
+    }
+
+    pub fn tabla(&self) -> &'static str {
+        match self {
+            TipoOrdenable::Modulo => "modulos",
+            TipoOrdenable::Tema => "temas",
+            TipoOrdenable::Unidad => "unidades",
+            TipoOrdenable::Contenido => "contenidos_unidad",
+            TipoOrdenable::Pregunta => "preguntas_examen",
+            TipoOrdenable::PortafolioContenido => "portafolios_contenidos",
+            TipoOrdenable::WebinarModulo => "webinar_modulos",
+        }
+    }
+
+    pub fn columna_padre(&self) -> &'static str {
+        match self {
+            TipoOrdenable::Modulo => "curso_id",
+            TipoOrdenable::Tema => "modulo_id",
+            TipoOrdenable::Unidad => "tema_id",
+            TipoOrdenable::Contenido => "unidad_id",
+            TipoOrdenable::Pregunta => "examen_id",
+            TipoOrdenable::PortafolioContenido => "portafolio_id",
+            TipoOrdenable::WebinarModulo => "webinar_id",
+        }
+    }
+
+    /// Filtro de filas vigentes (lo que está en la papelera conserva su orden)
+    pub fn filtro_vigente(&self) -> &'static str {
+        match self {
+            TipoOrdenable::Modulo
+            | TipoOrdenable::Tema
+            | TipoOrdenable::Unidad
+            | TipoOrdenable::Contenido => "fecha_eliminacion IS NULL",
+            _ => "TRUE",
+        }
+    }
+
+    /// Ámbito dentro del que un elemento puede cambiar de padre
+    pub fn nombre_ambito(&self) -> &'static str {
+        match self {
+            TipoOrdenable::PortafolioContenido => "portafolio",
+            TipoOrdenable::WebinarModulo => "webinar",
+            _ => "curso",
+        }
+    }
+
+    /// Ámbito del padre ($1 = id del padre vigente). Un elemento solo puede moverse
+    /// entre padres del mismo ámbito: el curso, el webinar para sus módulos y el propio
+    /// portafolio para sus contenidos (no cambian de portafolio)
+    pub fn consulta_ambito(&self) -> &'static str {
+        match self {
+            TipoOrdenable::Modulo => {
+                "SELECT id AS ambito FROM cursos WHERE id = $1 AND fecha_eliminacion IS NULL"
+            }
+            TipoOrdenable::Tema => {
+                "SELECT curso_id AS ambito FROM modulos WHERE id = $1 AND fecha_eliminacion IS NULL"
+            }
+            TipoOrdenable::Unidad => {
+                r#"SELECT m.curso_id AS ambito FROM temas t
+                JOIN modulos m ON m.id = t.modulo_id
+                WHERE t.id = $1 AND t.fecha_eliminacion IS NULL AND m.fecha_eliminacion IS NULL"#
+            }
+            TipoOrdenable::Contenido => {
+                r#"SELECT m.curso_id AS ambito FROM unidades u
+                JOIN temas t ON t.id = u.tema_id
+                JOIN modulos m ON m.id = t.modulo_id
+                WHERE u.id = $1 AND u.fecha_eliminacion IS NULL
+                  AND t.fecha_eliminacion IS NULL AND m.fecha_eliminacion IS NULL"#
+            }
+            TipoOrdenable::Pregunta => {
+                "SELECT curso_id AS ambito FROM examenes WHERE id = $1 AND fecha_eliminacion IS NULL"
+            }
+            TipoOrdenable::PortafolioContenido => "SELECT id AS ambito FROM portafolios WHERE id = $1",
+            TipoOrdenable::WebinarModulo => "SELECT id AS ambito FROM webinars WHERE id = $1",
+        }
+    }
+}
+
+/// Cuerpo de PUT /api/orden/{tipo}/{padre_id}: todos los hijos del padre en el orden
+/// deseado. Un id de otro padre del mismo curso se mueve a este
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Reordenamiento {
+    pub ids: Vec<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, FromQueryResult)]
+pub struct ElementoOrdenado {
+    pub id: i32,
+    pub padre_id: i32,
+    /// 1, 2, 3... sin huecos
+    pub orden: i32,
+}
@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Entidades con soft delete gestionadas por la papelera.
+/// Jerarquía: curso → módulos/exámenes, módulo → temas → unidades → contenidos
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TipoPapelera {
+    Curso,
+    Modulo,
+    Tema,
+    Unidad,
+    Contenido,
+    Examen,
+}
+
+impl TipoPapelera {
+    pub const TODOS: [TipoPapelera; 6] = [
+        TipoPapelera::Curso,
+        TipoPapelera::Modulo,
+        TipoPapelera::Tema,
+        TipoPapelera::Unidad,
+        TipoPapelera::Contenido,
+        TipoPapelera::Examen,
+    ];
+
+    pub fn nombre(&self) -> &'static str {
+        match self {
+            TipoPapelera::Curso => "curso",
+            TipoPapelera::Modulo => "modulo",
+            TipoPapelera::Tema => "tema",
+            TipoPapelera::Unidad => "unidad",
+            TipoPapelera::Contenido => "contenido",
+            TipoPapelera::Examen => "examen",
+        }
+    }
+}
+
+/// Elemento en la papelera. `padre_id` apunta al contenedor directo
+/// (curso para módulos/exámenes, módulo para temas, etc.)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ElementoPapelera {
+    pub tipo: TipoPapelera,
+    pub id: i32,
+    pub nombre: String,
+    pub padre_id: Option<i32>,
+    pub fecha_eliminacion: DateTime<Utc>,
+}
+
+/// Filtros de GET /api/papelera
+#[derive(Debug, Clone, Deserialize, Default, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FiltroPapelera {
+    pub tipo: Option<TipoPapelera>,
+    pub curso_id: Option<i32>,
+}
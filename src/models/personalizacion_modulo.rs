@@ -2,9 +2,11 @@ use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde_json::Value;
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "personalizaciones_modulos")]
+#[schema(as = PersonalizacionModuloModel)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
@@ -28,7 +30,7 @@ pub enum Relation {
 
 impl ActiveModelBehavior for ActiveModel {}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct NuevaPersonalizacionModulo {
     pub modulo_id: i32,
     pub estilos: Option<Value>,
@@ -36,7 +38,7 @@ pub struct NuevaPersonalizacionModulo {
     pub privacidad_componentes: Option<Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
 pub struct ActualizarPersonalizacionModulo {
     pub estilos: Option<Value>,
     pub orden_componentes: Option<Value>,
@@ -1,9 +1,11 @@
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "portafolios_contenidos")]
+#[schema(as = PortafolioContenidoModel)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
@@ -11,7 +13,10 @@ pub struct Model {
     pub tipo_contenido: String,
     pub titulo: String,
     pub descripcion: Option<String>,
+    /// Fuente en Markdown
     pub contenido: String,
+    /// `contenido` renderizado y saneado (ver `utils::markdown`)
+    pub contenido_html: Option<String>,
     pub orden: i32,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
@@ -22,7 +27,7 @@ pub enum Relation {}
 
 impl ActiveModelBehavior for ActiveModel {}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct NuevoPortafolioContenido {
     pub portafolio_id: i32,
     pub tipo_contenido: String,
@@ -32,7 +37,7 @@ pub struct NuevoPortafolioContenido {
     pub orden: i32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
 pub struct ActualizarPortafolioContenido {
     pub tipo_contenido: Option<String>,
     pub titulo: Option<String>,
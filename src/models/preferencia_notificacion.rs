@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Cuándo llega por correo una notificación de un tipo
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum FrecuenciaNotificacion {
+    /// Correo y WebSocket en el momento
+    #[default]
+    #[sea_orm(string_value = "inmediata")]
+    Inmediata,
+    /// WebSocket en el momento y un correo diario con todas las pendientes
+    #[sea_orm(string_value = "resumen_diario")]
+    ResumenDiario,
+    /// Solo queda en la bandeja de la aplicación
+    #[sea_orm(string_value = "desactivada")]
+    Desactivada,
+}
+
+/// Preferencia de un usuario para un `tipo` de notificación (sin fila = inmediata)
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "preferencias_notificacion")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub usuario_id: i32,
+    pub tipo: String,
+    pub frecuencia: FrecuenciaNotificacion,
+    pub fecha_actualizacion: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::usuario::Entity",
+        from = "Column::UsuarioId",
+        to = "super::usuario::Column::Id"
+    )]
+    Usuario,
+}
+
+impl Related<super::usuario::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Usuario.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl crate::database::migrator::AutoMigrate for Entity {
+    fn entity_name() -> &'static str {
+        "preferencias_notificacion"
+    }
+}
+
+/// Frecuencia elegida para un tipo de notificación
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PreferenciaNotificacion {
+    pub tipo: String,
+    pub frecuencia: FrecuenciaNotificacion,
+    /// Los tipos obligatorios (seguridad) siempre se envían al momento
+    #[serde(default)]
+    pub obligatoria: bool,
+}
@@ -1,15 +1,20 @@
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "preguntas_examen")]
+#[schema(as = PreguntaExamenModel)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
     pub examen_id: i32,
     pub pregunta: String,
-    pub tipo_pregunta: String,
+    /// `pregunta` renderizada desde Markdown y saneada
+    pub pregunta_html: Option<String>,
+    #[sea_orm(column_name = "tipo")]
+    pub tipo_pregunta: TipoPregunta,
     pub opciones: Option<serde_json::Value>,
     pub respuesta_correcta: Option<serde_json::Value>,
     pub valor_puntos: i32,
@@ -22,3 +27,18 @@ pub struct Model {
 pub enum Relation {}
 
 impl ActiveModelBehavior for ActiveModel {}
+
+/// Valores permitidos por `preguntas_examen_tipo_check`
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum TipoPregunta {
+    #[sea_orm(string_value = "abierta")]
+    Abierta,
+    #[sea_orm(string_value = "seleccion_unica")]
+    SeleccionUnica,
+    #[sea_orm(string_value = "seleccion_multiple")]
+    SeleccionMultiple,
+}
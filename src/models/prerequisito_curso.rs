@@ -0,0 +1,46 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Curso que debe estar aprobado en `historial_cursos_estudiantes` para matricularse
+/// en `curso_id`
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "prerequisitos_curso")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub curso_id: i32,
+    pub prerequisito_id: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::curso::Entity",
+        from = "Column::CursoId",
+        to = "super::curso::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Curso,
+    #[sea_orm(
+        belongs_to = "super::curso::Entity",
+        from = "Column::PrerequisitoId",
+        to = "super::curso::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Prerequisito,
+}
+
+/// `find_also_related(Curso)` trae el curso exigido, no el que lo exige
+impl Related<super::curso::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Prerequisito.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl crate::database::migrator::AutoMigrate for Entity {
+    fn entity_name() -> &'static str {
+        "prerequisitos_curso"
+    }
+}
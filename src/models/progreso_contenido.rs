@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Contenido que un estudiante marcó como completado
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "progresos_contenido")]
+#[schema(as = ProgresoContenidoModel)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub contenido_id: i32,
+    pub estudiante_id: i32,
+    pub fecha_completado: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::contenido_unidad::Entity",
+        from = "Column::ContenidoId",
+        to = "super::contenido_unidad::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Contenido,
+    #[sea_orm(
+        belongs_to = "super::usuario::Entity",
+        from = "Column::EstudianteId",
+        to = "super::usuario::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Usuario,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl crate::database::migrator::AutoMigrate for Entity {
+    fn entity_name() -> &'static str {
+        "progresos_contenido"
+    }
+}
@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Estado de publicación de módulos, temas, unidades y contenidos.
+/// `visible` se mantiene sincronizado: solo lo publicado es visible para los estudiantes
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum EstadoPublicacion {
+    #[sea_orm(string_value = "borrador")]
+    Borrador,
+    #[sea_orm(string_value = "publicado")]
+    Publicado,
+}
+
+impl EstadoPublicacion {
+    /// Estado equivalente al antiguo campo `visible`
+    pub fn desde_visible(visible: bool) -> Self {
+        if visible {
+            EstadoPublicacion::Publicado
+        } else {
+            EstadoPublicacion::Borrador
+        }
+    }
+
+    pub fn es_visible(&self) -> bool {
+        *self == EstadoPublicacion::Publicado
+    }
+}
+
+/// Elementos del aula con estado de publicación.
+/// Jerarquía: módulo → temas → unidades → contenidos
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum TipoPublicable {
+    #[sea_orm(string_value = "modulo")]
+    Modulo,
+    #[sea_orm(string_value = "tema")]
+    Tema,
+    #[sea_orm(string_value = "unidad")]
+    Unidad,
+    #[sea_orm(string_value = "contenido")]
+    Contenido,
+}
+
+impl TipoPublicable {
+    pub const TODOS: [TipoPublicable; 4] = [
+        TipoPublicable::Modulo,
+        TipoPublicable::Tema,
+        TipoPublicable::Unidad,
+        TipoPublicable::Contenido,
+    ];
+
+    pub fn nombre(&self) -> &'static str {
+        match self {
+            TipoPublicable::Modulo => "modulo",
+            TipoPublicable::Tema => "tema",
+            TipoPublicable::Unidad => "unidad",
+            TipoPublicable::Contenido => "contenido",
+        }
+    }
+
+    pub fn tabla(&self) -> &'static str {
+        match self {
+            TipoPublicable::Modulo => "modulos",
+            TipoPublicable::Tema => "temas",
+            TipoPublicable::Unidad => "unidades",
+            TipoPublicable::Contenido => "contenidos_unidad",
+        }
+    }
+
+    /// Columna con el nombre que se muestra en las notificaciones
+    pub fn columna_nombre(&self) -> &'static str {
+        match self {
+            TipoPublicable::Contenido => "titulo",
+            _ => "nombre",
+        }
+    }
+}
+
+/// Cuerpo de PUT /api/publicacion/{tipo}/{id}. Reemplaza el estado y la
+/// programación completa: una fecha omitida cancela lo programado
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CambioPublicacion {
+    pub estado: EstadoPublicacion,
+    /// Publicación automática de un borrador (el job la aplica al llegar la fecha)
+    pub publicar_en: Option<DateTime<Utc>>,
+    /// Vuelta automática a borrador de un elemento publicado o programado
+    pub despublicar_en: Option<DateTime<Utc>>,
+}
+
+/// Estado de publicación de un elemento
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ElementoPublicable {
+    pub tipo: TipoPublicable,
+    pub id: i32,
+    pub nombre: String,
+    pub estado: EstadoPublicacion,
+    pub visible: bool,
+    pub publicar_en: Option<DateTime<Utc>>,
+    pub despublicar_en: Option<DateTime<Utc>>,
+}
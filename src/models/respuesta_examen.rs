@@ -0,0 +1,123 @@
+use chrono::{DateTime, Utc};
+use sea_orm::{entity::prelude::*, FromQueryResult, JsonValue};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Respuesta de un estudiante a una pregunta dentro de un intento
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "respuestas_examen")]
+#[schema(as = RespuestaExamenModel)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub intento_id: i32,
+    pub pregunta_id: i32,
+    /// Texto (abierta), una opción (seleccion_unica) o lista de opciones (seleccion_multiple)
+    #[sea_orm(column_type = "JsonBinary")]
+    #[schema(value_type = Object)]
+    pub respuesta: JsonValue,
+    /// Nulo mientras espera calificación manual
+    pub puntaje: Option<f64>,
+    pub retroalimentacion: Option<String>,
+    /// Nulo si la calificó el sistema
+    pub calificado_por: Option<i32>,
+    pub fecha_calificacion: Option<DateTime<Utc>>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::intento_examen::Entity",
+        from = "Column::IntentoId",
+        to = "super::intento_examen::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Intento,
+    #[sea_orm(
+        belongs_to = "super::pregunta_examen::Entity",
+        from = "Column::PreguntaId",
+        to = "super::pregunta_examen::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Pregunta,
+}
+
+impl Related<super::intento_examen::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Intento.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl crate::database::migrator::AutoMigrate for Entity {
+    fn entity_name() -> &'static str {
+        "respuestas_examen"
+    }
+}
+
+/// Parámetros de GET /api/examenes/{id}/calificacion-manual
+#[derive(Debug, Clone, Deserialize, Default, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FiltroCola {
+    /// Solo las respuestas de esta pregunta
+    pub pregunta_id: Option<i32>,
+    /// Oculta el nombre del estudiante (se muestra "Estudiante N")
+    pub anonimo: Option<bool>,
+    /// Incluye las respuestas ya calificadas, para revisarlas
+    pub incluir_calificadas: Option<bool>,
+    pub limite: Option<u64>,
+    pub pagina: Option<u64>,
+}
+
+/// Respuesta abierta en la cola de calificación
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, FromQueryResult)]
+pub struct RespuestaPorCalificar {
+    pub respuesta_id: i32,
+    pub intento_id: i32,
+    pub pregunta_id: i32,
+    pub pregunta: String,
+    pub pregunta_html: Option<String>,
+    pub valor_puntos: i32,
+    #[schema(value_type = Object)]
+    pub respuesta: JsonValue,
+    pub puntaje: Option<f64>,
+    pub retroalimentacion: Option<String>,
+    pub fecha_entrega: Option<DateTime<Utc>>,
+    /// Número estable del intento dentro del examen, para la vista anónima
+    pub alias: String,
+    /// Nulo en la vista anónima
+    pub estudiante_id: Option<i32>,
+    pub estudiante: Option<String>,
+}
+
+/// Página de la cola de calificación manual
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ColaCalificacion {
+    /// Respuestas que cumplen el filtro
+    pub total: u64,
+    /// Respuestas abiertas del examen aún sin calificar
+    pub pendientes: u64,
+    pub pagina: u64,
+    pub limite: u64,
+    pub elementos: Vec<RespuestaPorCalificar>,
+}
+
+/// Nota parcial de una respuesta
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CalificacionRespuesta {
+    pub respuesta_id: i32,
+    /// Entre 0 y `valor_puntos` de la pregunta
+    pub puntaje: f64,
+    pub retroalimentacion: Option<String>,
+}
+
+/// Resultado de calificar un lote de respuestas
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ResultadoCalificacion {
+    pub calificadas: usize,
+    /// Intentos que quedaron sin respuestas pendientes y ya tienen nota final
+    pub intentos_cerrados: Vec<i32>,
+    /// Respuestas abiertas del examen aún sin calificar
+    pub pendientes: u64,
+}
@@ -0,0 +1,147 @@
+use chrono::{DateTime, Utc};
+use sea_orm::{entity::prelude::*, FromQueryResult, JsonValue};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Entidades con historial de revisiones
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(40))")]
+#[serde(rename_all = "snake_case")]
+pub enum TipoRevisionable {
+    #[sea_orm(string_value = "contenido_unidad")]
+    ContenidoUnidad,
+    #[sea_orm(string_value = "portafolio_contenido")]
+    PortafolioContenido,
+    #[sea_orm(string_value = "personalizacion_portafolio")]
+    PersonalizacionPortafolio,
+}
+
+impl TipoRevisionable {
+    pub fn nombre(&self) -> &'static str {
+        match self {
+            TipoRevisionable::ContenidoUnidad => "contenido_unidad",
+            TipoRevisionable::PortafolioContenido => "portafolio_contenido",
+            TipoRevisionable::PersonalizacionPortafolio => "personalizacion_portafolio",
+        }
+    }
+
+    pub fn tabla(&self) -> &'static str {
+        match self {
+            TipoRevisionable::ContenidoUnidad => "contenidos_unidad",
+            TipoRevisionable::PortafolioContenido => "portafolios_contenidos",
+            TipoRevisionable::PersonalizacionPortafolio => "personalizaciones_portafolios",
+        }
+    }
+
+    /// Columnas que guarda cada revisión y que se reponen al restaurarla
+    pub fn campos(&self) -> &'static [&'static str] {
+        match self {
+            TipoRevisionable::ContenidoUnidad => &[
+                "tipo_contenido",
+                "titulo",
+                "descripcion",
+                "contenido",
+                "url",
+                "obligatorio",
+                "puntos",
+                "examen_id",
+                "actividad_entrega_id",
+            ],
+            TipoRevisionable::PortafolioContenido => {
+                &["tipo_contenido", "titulo", "descripcion", "contenido"]
+            }
+            TipoRevisionable::PersonalizacionPortafolio => {
+                &["estilos", "orden_componentes", "privacidad_componentes"]
+            }
+        }
+    }
+
+    /// (fuente Markdown, columna renderizada) que hay que regenerar al restaurar
+    pub fn columnas_html(&self) -> Option<(&'static str, &'static str)> {
+        match self {
+            TipoRevisionable::ContenidoUnidad | TipoRevisionable::PortafolioContenido => {
+                Some(("contenido", "contenido_html"))
+            }
+            TipoRevisionable::PersonalizacionPortafolio => None,
+        }
+    }
+
+    pub fn columna_actualizacion(&self) -> &'static str {
+        match self {
+            TipoRevisionable::ContenidoUnidad => "fecha_actualizacion",
+            TipoRevisionable::PortafolioContenido | TipoRevisionable::PersonalizacionPortafolio => {
+                "updated_at"
+            }
+        }
+    }
+
+    /// Filtro de filas vigentes (las que están en la papelera no se versionan)
+    pub fn filtro_vigente(&self) -> &'static str {
+        match self {
+            TipoRevisionable::ContenidoUnidad => "fecha_eliminacion IS NULL",
+            _ => "TRUE",
+        }
+    }
+}
+
+/// Versión guardada de una entidad: una por creación, edición o restauración
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "revisiones")]
+#[schema(as = RevisionModel)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i32,
+    pub tipo: TipoRevisionable,
+    pub entidad_id: i32,
+    /// Consecutivo por entidad: 1, 2, 3...
+    pub numero: i32,
+    /// Nulo en la versión previa al historial (filas editadas antes de existir)
+    pub autor_id: Option<i32>,
+    pub fecha: DateTime<Utc>,
+    /// Valores de `TipoRevisionable::campos` en esta versión
+    #[sea_orm(column_type = "JsonBinary")]
+    #[schema(value_type = Object)]
+    pub datos: JsonValue,
+    /// Número de la revisión restaurada, si esta versión viene de una restauración
+    pub restaurada_de: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl crate::database::migrator::AutoMigrate for Entity {
+    fn entity_name() -> &'static str {
+        "revisiones"
+    }
+}
+
+/// Fila del historial (sin el cuerpo de la versión)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, FromQueryResult)]
+pub struct ResumenRevision {
+    pub numero: i32,
+    pub autor_id: Option<i32>,
+    pub autor: Option<String>,
+    pub fecha: DateTime<Utc>,
+    pub restaurada_de: Option<i32>,
+}
+
+/// Parámetros de GET /api/revisiones/{tipo}/{id}/diff
+#[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FiltroDiff {
+    pub desde: i32,
+    pub hasta: i32,
+}
+
+/// Campos que cambian entre dos revisiones: { campo: { "antes": .., "despues": .. } }
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DiffRevisiones {
+    pub desde: i32,
+    pub hasta: i32,
+    #[schema(value_type = Option<Object>)]
+    pub cambios: Option<JsonValue>,
+}
@@ -3,9 +3,11 @@ use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, FromRow)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, FromRow, ToSchema)]
 #[sea_orm(table_name = "roles")]
+#[schema(as = RolModel)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
@@ -17,6 +19,18 @@ pub struct Model {
     pub fecha_actualizacion: DateTime<Utc>,
 }
 
+/// Nombres de los roles sembrados en `seed_users`
+pub const ROL_ADMINISTRADOR: &str = "Administrador";
+pub const ROL_COORDINADOR: &str = "Coordinador";
+pub const ROL_PROFESOR: &str = "Profesor";
+pub const ROL_ESTUDIANTE: &str = "Estudiante";
+
+/// Compara nombres de rol sin distinguir mayúsculas; "admin" (seed legacy) equivale a Administrador
+pub fn es_rol(nombre: &str, esperado: &str) -> bool {
+    nombre.eq_ignore_ascii_case(esperado)
+        || (esperado == ROL_ADMINISTRADOR && nombre.eq_ignore_ascii_case("admin"))
+}
+
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {}
 
@@ -30,7 +44,7 @@ impl AutoMigrate for Entity {
 
     fn seed_data() -> Option<Vec<String>> {
         Some(vec![r#"
-            INSERT INTO rustdema.roles (nombre) 
+            INSERT INTO roles (nombre) 
             VALUES ('admin'), ('user'), ('moderator')
             ON CONFLICT (nombre) DO NOTHING
             "#
@@ -0,0 +1,89 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Sesión (clase) de un curso (tabla `sesiones_curso` del DDL v2)
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "sesiones_curso")]
+#[schema(as = SesionCursoModel)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i32,
+    pub curso_id: i32,
+    pub nombre_sesion: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub descripcion: Option<String>,
+    pub fecha_inicio: Option<DateTime<Utc>>,
+    pub fecha_fin: Option<DateTime<Utc>>,
+    pub orden: i32,
+    /// Código de auto-registro de asistencia; no se expone en las respuestas
+    #[serde(skip_serializing)]
+    pub codigo_asistencia: Option<String>,
+    pub codigo_expira: Option<DateTime<Utc>>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::curso::Entity",
+        from = "Column::CursoId",
+        to = "super::curso::Column::Id"
+    )]
+    Curso,
+    #[sea_orm(has_many = "super::asistencia::Entity")]
+    Asistencias,
+}
+
+impl Related<super::curso::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Curso.def()
+    }
+}
+
+impl Related<super::asistencia::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Asistencias.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl crate::database::migrator::AutoMigrate for Entity {
+    fn entity_name() -> &'static str {
+        "sesiones_curso"
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NuevaSesion {
+    pub nombre_sesion: String,
+    pub descripcion: Option<String>,
+    pub fecha_inicio: DateTime<Utc>,
+    pub fecha_fin: DateTime<Utc>,
+    /// Por defecto, al final de las sesiones del curso
+    pub orden: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
+pub struct ActualizarSesion {
+    pub nombre_sesion: Option<String>,
+    pub descripcion: Option<String>,
+    pub fecha_inicio: Option<DateTime<Utc>>,
+    pub fecha_fin: Option<DateTime<Utc>>,
+    pub orden: Option<i32>,
+}
+
+/// Query de POST /api/sesiones/{id}/codigo (vigencia en minutos)
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GenerarCodigo {
+    pub minutos: Option<i64>,
+}
+
+/// Código vigente para el auto-registro (solo se entrega al docente)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CodigoAsistencia {
+    pub codigo: String,
+    pub expira: DateTime<Utc>,
+}
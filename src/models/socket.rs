@@ -1,10 +1,10 @@
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema)]
 pub struct ConnectionInfo {
     pub connected_users: usize,
     pub rooms: Vec<String>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema)]
 pub struct SocketMemoryMetrics {
     pub total_users: usize,
     pub total_connections: usize,
@@ -1,9 +1,13 @@
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+use super::publicacion::EstadoPublicacion;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "temas")]
+#[schema(as = TemaModel)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
@@ -12,10 +16,18 @@ pub struct Model {
     pub descripcion: Option<String>,
     pub orden: i32,
     pub visible: bool,
+    /// Borrador o publicado; `visible` refleja si está publicado
+    pub estado_publicacion: EstadoPublicacion,
+    /// Publicación programada (solo borradores)
+    pub publicar_en: Option<DateTime<Utc>>,
+    /// Vuelta a borrador programada
+    pub despublicar_en: Option<DateTime<Utc>>,
     #[sea_orm(column_name = "fecha_creacion")]
     pub created_at: Option<DateTime<Utc>>,
     #[sea_orm(column_name = "fecha_actualizacion")]
     pub updated_at: Option<DateTime<Utc>>,
+    /// Soft delete: las filas con fecha se consideran en la papelera
+    pub fecha_eliminacion: Option<DateTime<Utc>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter)]
@@ -53,7 +65,7 @@ impl Related<super::unidad::Entity> for Entity {
 
 impl ActiveModelBehavior for ActiveModel {}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct NuevoTema {
     pub modulo_id: i32,
     pub nombre: String,
@@ -62,7 +74,7 @@ pub struct NuevoTema {
     pub visible: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
 pub struct ActualizarTema {
     pub nombre: Option<String>,
     pub descripcion: Option<String>,
@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(20))")]
+#[serde(rename_all = "snake_case")]
+pub enum TipoTokenCuenta {
+    /// Primer ingreso de una cuenta creada por un administrador
+    #[sea_orm(string_value = "activacion")]
+    Activacion,
+    /// "Olvidé mi contraseña"
+    #[sea_orm(string_value = "recuperacion")]
+    Recuperacion,
+}
+
+/// Enlace de un solo uso enviado por correo (se guarda solo el hash del token)
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "tokens_cuenta")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub usuario_id: i32,
+    pub tipo: TipoTokenCuenta,
+    #[sea_orm(unique)]
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub fecha_expiracion: DateTime<Utc>,
+    /// Momento en que se usó o se invalidó por uno más reciente
+    pub fecha_uso: Option<DateTime<Utc>>,
+    pub fecha_creacion: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::usuario::Entity",
+        from = "Column::UsuarioId",
+        to = "super::usuario::Column::Id"
+    )]
+    Usuario,
+}
+
+impl Related<super::usuario::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Usuario.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl crate::database::migrator::AutoMigrate for Entity {
+    fn entity_name() -> &'static str {
+        "tokens_cuenta"
+    }
+}
+
+/// Token del enlace y contraseña nueva (activación o restablecimiento)
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ContrasenaConToken {
+    pub token: String,
+    pub contrasena: String,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct SolicitudRecuperacion {
+    pub correo: String,
+}
+
+/// Respuesta genérica de los endpoints públicos de cuenta
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MensajeCuenta {
+    pub mensaje: String,
+}
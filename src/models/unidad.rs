@@ -1,8 +1,13 @@
+use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
-#[sea_orm(schema_name = "rustdema2", table_name = "unidades")]
+use super::publicacion::EstadoPublicacion;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "unidades")]
+#[schema(as = UnidadModel)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
@@ -11,6 +16,14 @@ pub struct Model {
     pub descripcion: Option<String>,
     pub orden: i32,
     pub visible: bool,
+    /// Borrador o publicado; `visible` refleja si está publicado
+    pub estado_publicacion: EstadoPublicacion,
+    /// Publicación programada (solo borradores)
+    pub publicar_en: Option<DateTime<Utc>>,
+    /// Vuelta a borrador programada
+    pub despublicar_en: Option<DateTime<Utc>>,
+    /// Soft delete: las filas con fecha se consideran en la papelera
+    pub fecha_eliminacion: Option<DateTime<Utc>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter)]
@@ -48,7 +61,7 @@ impl Related<super::actividad_entrega::Entity> for Entity {
 
 impl ActiveModelBehavior for ActiveModel {}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct NuevaUnidad {
     pub tema_id: i32,
     pub nombre: String,
@@ -57,7 +70,7 @@ pub struct NuevaUnidad {
     pub visible: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
 pub struct ActualizarUnidad {
     pub nombre: Option<String>,
     pub descripcion: Option<String>,
@@ -1,9 +1,11 @@
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "usuarios")]
+#[schema(as = UsuarioModel)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
@@ -25,34 +27,42 @@ pub struct Model {
     pub fecha_actualizacion: DateTime<Utc>,
     #[sea_orm(column_name = "fecha_ultima_conexion")]
     pub fecha_ultima_conexion: DateTime<Utc>,
+    /// Creación del enlace de activación pendiente (None = cuenta activada)
     pub token_primer_ingreso: Option<DateTime<Utc>>,
     #[sea_orm(column_name = "fecha_eliminacion")]
     pub fecha_eliminacion: Option<DateTime<Utc>>,
+    /// Intentos de login fallidos seguidos desde el último éxito o bloqueo
+    #[sea_orm(default_value = 0)]
+    pub intentos_fallidos: i32,
+    /// Bloqueo temporal por intentos fallidos
+    pub bloqueado_hasta: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct UsuarioConRol {
     #[serde(flatten)]
+    #[schema(value_type = UsuarioModel)]
     pub usuario: Model,
+    #[schema(value_type = RolModel)]
     pub rol: super::rol::Model,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Cuenta creada por un administrador: sin contraseña, queda pendiente de
+/// activación y el usuario la elige desde el enlace que recibe por correo
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct NewUsuario {
     pub nombre: String,
     pub documento_nit: String,
     pub correo: String,
-    pub contrasena: String,
     pub foto_url: Option<String>,
     pub rol_id: i32,
     pub estado: bool,
     pub semestre: Option<i32>,
     pub genero: String,
     pub fecha_nacimiento: String,
-    pub token_primer_ingreso: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UpdateUsuario {
     pub nombre: Option<String>,
     pub documento_nit: Option<String>,
@@ -62,6 +72,64 @@ pub struct UpdateUsuario {
     pub estado: Option<bool>,
 }
 
+/// Fila del CSV de importación masiva; cabeceras en cualquier orden, separador `,` o `;`
+#[derive(Debug, Clone, Deserialize)]
+pub struct FilaUsuarioCsv {
+    pub nombre: String,
+    pub documento_nit: String,
+    pub correo: String,
+    /// Nombre del rol (Estudiante, Profesor...)
+    pub rol: String,
+    pub semestre: Option<i32>,
+    pub genero: String,
+    /// AAAA-MM-DD
+    pub fecha_nacimiento: String,
+}
+
+/// Opciones de POST /api/usuarios/importar
+#[derive(Debug, Clone, Deserialize, Default, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct OpcionesImportacion {
+    /// Solo valida y devuelve el reporte, sin crear nada
+    #[serde(default)]
+    pub simular: bool,
+    /// Ids de cursos separados por comas donde se matricula a los estudiantes importados
+    pub cursos: Option<String>,
+}
+
+/// Fila válida del CSV (`id` solo cuando la importación se aplicó)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UsuarioImportado {
+    pub fila: u64,
+    pub id: Option<i32>,
+    pub nombre: String,
+    pub correo: String,
+    pub rol: String,
+    /// Cursos en los que queda matriculado (solo estudiantes)
+    pub cursos: Vec<i32>,
+    /// Cursos de `cursos` sin cupo, donde quedó en la lista de espera (solo al aplicar)
+    pub en_espera: Vec<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ErrorImportacion {
+    pub fila: u64,
+    pub errores: Vec<String>,
+}
+
+/// Reporte de la importación: si hay errores no se crea ningún usuario
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ResultadoImportacion {
+    pub simulacion: bool,
+    /// true si los usuarios y matrículas quedaron creados
+    pub aplicada: bool,
+    pub total_filas: usize,
+    pub usuarios: Vec<UsuarioImportado>,
+    pub errores: Vec<ErrorImportacion>,
+    /// Matrículas con cupo (las de la lista de espera no cuentan)
+    pub matriculas: usize,
+}
+
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(
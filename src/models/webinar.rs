@@ -1,9 +1,11 @@
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "webinars")]
+#[schema(as = WebinarModel)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
@@ -11,7 +13,7 @@ pub struct Model {
     pub titulo: String,
     pub descripcion: Option<String>,
     pub progreso: i32,            // porcentaje 0-100
-    pub estado: String,           // 'no_iniciado' | 'en_progreso' | 'completado'
+    pub estado: EstadoWebinar,
     pub duracion: Option<String>, // ej: "45 min", "1.5 horas"
     pub modulos: i32,             // número de módulos
     pub created_at: Option<DateTime<Utc>>,
@@ -65,3 +67,18 @@ impl Related<super::webinar_progreso_estudiante::Entity> for Entity {
 }
 
 impl ActiveModelBehavior for ActiveModel {}
+
+/// Valores permitidos por `webinars_estado_check`
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(50))")]
+#[serde(rename_all = "snake_case")]
+pub enum EstadoWebinar {
+    #[sea_orm(string_value = "no_iniciado")]
+    NoIniciado,
+    #[sea_orm(string_value = "en_progreso")]
+    EnProgreso,
+    #[sea_orm(string_value = "completado")]
+    Completado,
+}
@@ -1,9 +1,11 @@
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "webinar_modulos")]
+#[schema(as = WebinarModuloModel)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
@@ -11,7 +13,7 @@ pub struct Model {
     pub titulo: String,
     pub descripcion: Option<String>,
     pub orden: i32,
-    pub tipo_contenido: String, // 'video', 'presentacion', 'actividad', 'quiz'
+    pub tipo_contenido: TipoContenidoWebinar,
     pub contenido_url: Option<String>,
     pub duracion_estimada: Option<i32>, // en minutos
     pub obligatorio: bool,
@@ -42,3 +44,20 @@ impl Related<super::webinar::Entity> for Entity {
 }
 
 impl ActiveModelBehavior for ActiveModel {}
+
+/// Valores permitidos por `webinar_modulos_tipo_contenido_check`
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(50))")]
+#[serde(rename_all = "snake_case")]
+pub enum TipoContenidoWebinar {
+    #[sea_orm(string_value = "video")]
+    Video,
+    #[sea_orm(string_value = "presentacion")]
+    Presentacion,
+    #[sea_orm(string_value = "actividad")]
+    Actividad,
+    #[sea_orm(string_value = "quiz")]
+    Quiz,
+}
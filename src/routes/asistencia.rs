@@ -0,0 +1,35 @@
+use axum::{
+    routing::{get, post, put},
+    Router,
+};
+
+use crate::{handlers::asistencia, models::AppState};
+
+pub fn asistencia_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/cursos/{curso_id}/sesiones",
+            get(asistencia::listar_sesiones).post(asistencia::crear_sesion),
+        )
+        .route(
+            "/api/sesiones/{id}",
+            put(asistencia::actualizar_sesion).delete(asistencia::eliminar_sesion),
+        )
+        .route(
+            "/api/sesiones/{id}/asistencia",
+            get(asistencia::obtener_asistencia_sesion).put(asistencia::pasar_lista),
+        )
+        .route(
+            "/api/sesiones/{id}/codigo",
+            post(asistencia::generar_codigo),
+        )
+        .route("/api/sesiones/{id}/check-in", post(asistencia::check_in))
+        .route(
+            "/api/cursos/{curso_id}/asistencia",
+            get(asistencia::resumen_asistencia_curso),
+        )
+        .route(
+            "/api/cursos/{curso_id}/asistencia/aplicar-umbral",
+            post(asistencia::aplicar_umbral_asistencia),
+        )
+}
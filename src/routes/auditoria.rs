@@ -0,0 +1,7 @@
+use axum::{routing::get, Router};
+
+use crate::{handlers::auditoria, models::AppState};
+
+pub fn auditoria_routes() -> Router<AppState> {
+    Router::new().route("/api/auditoria", get(auditoria::listar_auditoria))
+}
@@ -0,0 +1,19 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::{handlers::calendario, models::AppState};
+
+pub fn calendario_routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/calendario", get(calendario::listar_calendario))
+        .route(
+            "/api/calendario/token",
+            post(calendario::generar_token_calendario).delete(calendario::revocar_token_calendario),
+        )
+        .route(
+            "/api/calendario/feed/{archivo}",
+            get(calendario::feed_calendario),
+        )
+}
@@ -0,0 +1,20 @@
+use axum::{routing::post, Router};
+
+use crate::{handlers::cuenta, models::AppState};
+
+pub fn cuenta_routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/cuenta/activar", post(cuenta::activar_cuenta))
+        .route(
+            "/api/cuenta/recuperar",
+            post(cuenta::solicitar_recuperacion),
+        )
+        .route(
+            "/api/cuenta/restablecer",
+            post(cuenta::restablecer_contrasena),
+        )
+        .route(
+            "/api/usuarios/{id}/activacion",
+            post(cuenta::reenviar_activacion),
+        )
+}
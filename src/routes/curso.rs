@@ -1,6 +1,6 @@
-use axum::{routing::get, Router};
+use axum::{middleware::from_fn, routing::get, Router};
 
-use crate::{handlers::curso, models::AppState};
+use crate::{handlers::curso, middleware::rate_limit, models::AppState};
 
 pub fn curso_routes() -> Router<AppState> {
     Router::new()
@@ -22,5 +22,8 @@ pub fn curso_routes() -> Router<AppState> {
             "/api/areas-conocimiento/{area_id}/cursos",
             get(curso::cursos_por_area_y_periodo),
         )
-        .route("/api/cursos/{id}/aula", get(curso::aula_por_curso))
+        .route(
+            "/api/cursos/{id}/aula",
+            get(curso::aula_por_curso).layer(from_fn(rate_limit::limite_aula)),
+        )
 }
@@ -0,0 +1,15 @@
+use axum::{routing::get, Router};
+
+use crate::{handlers::estadistica_examen, models::AppState};
+
+pub fn estadistica_examen_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/examenes/{id}/estadisticas",
+            get(estadistica_examen::obtener_estadisticas),
+        )
+        .route(
+            "/api/examenes/{id}/estadisticas.csv",
+            get(estadistica_examen::exportar_estadisticas),
+        )
+}
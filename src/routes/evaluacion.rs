@@ -0,0 +1,37 @@
+use axum::{
+    routing::{get, post, put},
+    Router,
+};
+
+use crate::{handlers::evaluacion, models::AppState};
+
+pub fn evaluacion_routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/evaluaciones", post(evaluacion::crear_evaluacion))
+        .route(
+            "/api/cursos/{curso_id}/evaluaciones",
+            get(evaluacion::listar_evaluaciones_por_curso),
+        )
+        .route(
+            "/api/evaluaciones/{id}",
+            get(evaluacion::obtener_evaluacion)
+                .put(evaluacion::actualizar_evaluacion)
+                .delete(evaluacion::eliminar_evaluacion),
+        )
+        .route(
+            "/api/evaluaciones/{id}/rubrica",
+            put(evaluacion::asignar_rubrica),
+        )
+        .route(
+            "/api/evaluaciones/{id}/publicar",
+            post(evaluacion::publicar_evaluacion),
+        )
+        .route(
+            "/api/evaluaciones/{id}/archivar",
+            post(evaluacion::archivar_evaluacion),
+        )
+        .route(
+            "/api/evaluaciones/{id}/calificaciones",
+            get(evaluacion::listar_calificaciones).post(evaluacion::calificar_evaluacion),
+        )
+}
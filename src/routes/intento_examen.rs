@@ -0,0 +1,23 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::{handlers::intento_examen, models::AppState};
+
+pub fn intento_examen_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/examenes/{id}/intentos",
+            post(intento_examen::iniciar_intento),
+        )
+        .route("/api/intentos/{id}", get(intento_examen::obtener_intento))
+        .route(
+            "/api/intentos/{id}/entregar",
+            post(intento_examen::entregar_intento),
+        )
+        .route(
+            "/api/examenes/{id}/calificacion-manual",
+            get(intento_examen::cola_calificacion).put(intento_examen::calificar_respuestas),
+        )
+}
@@ -0,0 +1,18 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::{handlers::liberacion, models::AppState};
+
+pub fn liberacion_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/liberacion/{tipo}/{id}",
+            get(liberacion::obtener_condiciones).put(liberacion::reemplazar_condiciones),
+        )
+        .route(
+            "/api/contenidos/{id}/completado",
+            post(liberacion::completar_contenido),
+        )
+}
@@ -1,5 +1,5 @@
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, post, put},
     Router,
 };
 
@@ -12,6 +12,18 @@ pub fn matricula_routes() -> Router<AppState> {
             "/api/matriculas/{estudiante_id}/{curso_id}",
             post(matricula::desmatricular_estudiante),
         )
+        .route(
+            "/api/matriculas/{id}/estado",
+            put(matricula::cambiar_estado_matricula),
+        )
+        .route(
+            "/api/matriculas/{id}/estados",
+            get(matricula::obtener_cambios_estado),
+        )
+        .route(
+            "/api/cursos/{curso_id}/cierre",
+            post(matricula::cerrar_curso),
+        )
         .route(
             "/api/estudiantes/{estudiante_id}/matriculas",
             get(matricula::obtener_matriculas_estudiante),
@@ -20,4 +32,16 @@ pub fn matricula_routes() -> Router<AppState> {
             "/api/cursos/{curso_id}/matriculas",
             get(matricula::obtener_matriculas_curso),
         )
+        .route(
+            "/api/cursos/{curso_id}/reglas-matricula",
+            get(matricula::obtener_reglas_matricula).put(matricula::actualizar_reglas_matricula),
+        )
+        .route(
+            "/api/cursos/{curso_id}/lista-espera",
+            get(matricula::obtener_lista_espera),
+        )
+        .route(
+            "/api/cursos/{curso_id}/lista-espera/{estudiante_id}",
+            delete(matricula::salir_lista_espera),
+        )
 }
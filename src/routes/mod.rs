@@ -1,26 +1,48 @@
 use axum::{
-    extract::State,
-    response::{Html, Json},
-    routing::{get, post},
+    extract::{DefaultBodyLimit, FromRef, State},
+    http::StatusCode,
+    middleware::from_fn,
+    response::{Html, IntoResponse, Json, Response},
+    routing::{get, post, MethodRouter},
     Router,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::sync::OnceLock;
-use utoipa::OpenApi;
+use std::{convert::Infallible, sync::OnceLock};
+use tower_http::limit::RequestBodyLimitLayer;
+use utoipa::{OpenApi, ToSchema};
 
 use crate::{
+    config::Config,
     handlers,
+    middleware::rate_limit,
     models::{AppState, Claims, User},
+    services::{
+        acceso_service::AccesoService,
+        metrics_service::{get_metrics_service, EventoDominio},
+    },
+    utils::{client_ip::IpCliente, password},
 };
 
 pub mod actividad;
 pub mod area_conocimiento;
+pub mod asistencia;
+pub mod auditoria;
+pub mod calendario;
+pub mod cuenta;
 pub mod curso;
+pub mod estadistica_examen;
+pub mod evaluacion;
 pub mod examen;
+pub mod intento_examen;
+pub mod liberacion;
 pub mod matricula;
 pub mod modulo;
 pub mod notificacion;
+pub mod orden;
+pub mod papelera;
+pub mod publicacion;
+pub mod revision;
 pub mod roles;
 pub mod storage; // Rutas para subida de archivos
 pub mod tema;
@@ -29,7 +51,7 @@ pub mod usuarios;
 pub mod contenido_unidad;
 pub mod portafolio;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 struct OAuth2TokenRequest {
     grant_type: String,
     email: String, // Cambiar username por email para que coincida con el login
@@ -42,7 +64,7 @@ struct OAuth2TokenRequest {
     client_secret: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct OAuth2TokenResponse {
     access_token: String,
     token_type: String,
@@ -64,21 +86,26 @@ pub fn create_routes() -> Router<AppState> {
         .route("/auth/validate-token", post(handlers::auth::validate_token))
         .route("/auth/token", post(oauth2_token_endpoint))
         .route("/ws", get(handlers::socket_manager::websocket_handler))
-        .route(
-            "/metrics/memory",
-            get(handlers::metrics::get_memory_metrics),
-        )
+        .route("/metrics", get(handlers::metrics::prometheus_metrics))
         .route(
             "/metrics/optimize",
             post(handlers::metrics::optimize_memory),
         )
 }
 
-pub fn create_app() -> Router<AppState> {
+/// Ruta de subida: límite de uploads por IP y body de hasta `limite` bytes
+/// (UPLOAD_LIMIT_MB) en lugar del general, tanto en el stream como en `Multipart`
+pub fn subida(ruta: MethodRouter<AppState>, limite: usize) -> MethodRouter<AppState> {
+    ruta.layer::<_, Infallible>(from_fn(rate_limit::limite_uploads))
+        .layer::<_, Infallible>(DefaultBodyLimit::max(limite))
+        .layer(RequestBodyLimitLayer::new(limite))
+}
+
+pub fn create_app(limite_subidas: usize) -> Router<AppState> {
     Router::new()
         .merge(create_routes())
         .merge(roles::roles_routes())
-        .merge(usuarios::usuarios_routes())
+        .merge(usuarios::usuarios_routes(limite_subidas))
         .merge(area_conocimiento::area_conocimiento_routes())
         .merge(curso::curso_routes())
         .merge(examen::examen_routes())
@@ -90,7 +117,19 @@ pub fn create_app() -> Router<AppState> {
         .merge(unidad::unidad_routes())
         .merge(contenido_unidad::contenido_unidad_routes())
         .merge(portafolio::portafolio_routes())
-        .merge(storage::storage_routes()) // Rutas para subida de archivos
+        .merge(storage::storage_routes(limite_subidas)) // Rutas para subida de archivos
+        .merge(auditoria::auditoria_routes())
+        .merge(papelera::papelera_routes())
+        .merge(publicacion::publicacion_routes())
+        .merge(liberacion::liberacion_routes())
+        .merge(intento_examen::intento_examen_routes())
+        .merge(estadistica_examen::estadistica_examen_routes())
+        .merge(revision::revision_routes())
+        .merge(orden::orden_routes())
+        .merge(evaluacion::evaluacion_routes())
+        .merge(asistencia::asistencia_routes())
+        .merge(calendario::calendario_routes())
+        .merge(cuenta::cuenta_routes())
         .route("/api-docs/openapi.json", get(serve_openapi_spec))
         .route("/swagger-ui", get(serve_swagger_ui))
         .route("/swagger-ui/", get(serve_swagger_ui))
@@ -108,35 +147,326 @@ pub fn create_app() -> Router<AppState> {
         description = "API backend con autenticación JWT y documentación automática",
         version = "1.0.0"
     ),
-    servers(
-        (url = "http://localhost:3000", description = "Local development server"),
-        (url = "https://api.example.com", description = "Production server"),
-        (url = "https://staging-api.example.com", description = "Staging server")
-    ),
     paths(
+        handlers::health::health_check,
+        handlers::health::readiness_check,
+        handlers::health::liveness_check,
         handlers::auth::register,
         handlers::auth::login,
-        handlers::auth::validate_token
+        handlers::auth::validate_token,
+        oauth2_token_endpoint,
+        handlers::socket_manager::websocket_handler,
+        handlers::metrics::prometheus_metrics,
+        handlers::metrics::optimize_memory,
+        handlers::roles::get_rol,
+        handlers::roles::list_roles,
+        handlers::roles::create_role,
+        handlers::roles::update_role,
+        handlers::roles::delete_role,
+        handlers::usuarios::login_usuario,
+        handlers::usuarios::logout_usuario,
+        handlers::usuarios::listar_usuarios,
+        handlers::usuarios::crear_usuario,
+        handlers::usuarios::importar_usuarios,
+        handlers::usuarios::actualizar_usuario,
+        handlers::usuarios::obtener_usuario_por_id,
+        handlers::usuarios::subir_foto_usuario,
+        handlers::area_conocimiento::listar_areas,
+        handlers::area_conocimiento::listar_areas_activas,
+        handlers::area_conocimiento::obtener_area,
+        handlers::area_conocimiento::crear_area,
+        handlers::area_conocimiento::actualizar_area,
+        handlers::area_conocimiento::cambiar_estado,
+        handlers::area_conocimiento::eliminar_area,
+        handlers::curso::listar_cursos,
+        handlers::curso::obtener_curso,
+        handlers::curso::crear_curso,
+        handlers::curso::actualizar_curso,
+        handlers::curso::eliminar_curso,
+        handlers::curso::cursos_por_plantilla,
+        handlers::curso::cursos_por_area_y_periodo,
+        handlers::curso::aula_por_curso,
+        handlers::examen::crear_examen,
+        handlers::examen::listar_examenes_por_curso,
+        handlers::examen::obtener_examen,
+        handlers::examen::actualizar_examen,
+        handlers::examen::eliminar_examen,
+        handlers::intento_examen::iniciar_intento,
+        handlers::intento_examen::obtener_intento,
+        handlers::intento_examen::entregar_intento,
+        handlers::intento_examen::cola_calificacion,
+        handlers::intento_examen::calificar_respuestas,
+        handlers::estadistica_examen::obtener_estadisticas,
+        handlers::estadistica_examen::exportar_estadisticas,
+        handlers::matricula::matricular_estudiante,
+        handlers::matricula::desmatricular_estudiante,
+        handlers::matricula::obtener_matriculas_estudiante,
+        handlers::matricula::obtener_matriculas_curso,
+        handlers::matricula::obtener_reglas_matricula,
+        handlers::matricula::actualizar_reglas_matricula,
+        handlers::matricula::obtener_lista_espera,
+        handlers::matricula::salir_lista_espera,
+        handlers::matricula::cambiar_estado_matricula,
+        handlers::matricula::obtener_cambios_estado,
+        handlers::matricula::cerrar_curso,
+        handlers::modulo::crear_modulo,
+        handlers::modulo::listar_modulos_por_curso,
+        handlers::modulo::obtener_modulo,
+        handlers::modulo::actualizar_modulo,
+        handlers::modulo::eliminar_modulo,
+        handlers::actividad::listar_actividades,
+        handlers::actividad::listar_actividades_por_curso,
+        handlers::actividad::obtener_actividad,
+        handlers::actividad::crear_actividad,
+        handlers::actividad::actualizar_actividad,
+        handlers::actividad::eliminar_actividad,
+        handlers::notificacion::obtener_notificaciones_usuario,
+        handlers::notificacion::marcar_notificacion_leida,
+        handlers::notificacion::crear_notificacion,
+        handlers::notificacion::marcar_todas_leidas,
+        handlers::notificacion::obtener_preferencias,
+        handlers::notificacion::actualizar_preferencias,
+        handlers::tema::crear_tema,
+        handlers::tema::listar_temas_por_modulo,
+        handlers::tema::obtener_tema,
+        handlers::tema::actualizar_tema,
+        handlers::tema::eliminar_tema,
+        handlers::unidad::crear_unidad,
+        handlers::unidad::listar_unidades_por_tema,
+        handlers::unidad::obtener_unidad,
+        handlers::unidad::actualizar_unidad,
+        handlers::unidad::eliminar_unidad,
+        handlers::contenido_unidad::crear_contenido,
+        handlers::contenido_unidad::listar_contenidos_por_unidad,
+        handlers::contenido_unidad::obtener_contenido,
+        handlers::contenido_unidad::actualizar_contenido,
+        handlers::contenido_unidad::eliminar_contenido,
+        handlers::portafolio::obtener_portada_curso,
+        handlers::portafolio::guardar_portada_curso,
+        handlers::storage::generate_upload_url,
+        handlers::storage::upload_file_direct,
+        handlers::storage::upload_image,
+        handlers::storage::download_file,
+        handlers::storage::delete_file,
+        handlers::auditoria::listar_auditoria,
+        handlers::papelera::listar_papelera,
+        handlers::papelera::restaurar_elemento,
+        handlers::publicacion::obtener_publicacion,
+        handlers::publicacion::cambiar_publicacion,
+        handlers::liberacion::obtener_condiciones,
+        handlers::liberacion::reemplazar_condiciones,
+        handlers::liberacion::completar_contenido,
+        handlers::revision::listar_revisiones,
+        handlers::revision::diff_revisiones,
+        handlers::revision::obtener_revision,
+        handlers::revision::restaurar_revision,
+        handlers::orden::reordenar,
+        handlers::evaluacion::crear_evaluacion,
+        handlers::evaluacion::listar_evaluaciones_por_curso,
+        handlers::evaluacion::obtener_evaluacion,
+        handlers::evaluacion::actualizar_evaluacion,
+        handlers::evaluacion::eliminar_evaluacion,
+        handlers::evaluacion::asignar_rubrica,
+        handlers::evaluacion::publicar_evaluacion,
+        handlers::evaluacion::archivar_evaluacion,
+        handlers::evaluacion::calificar_evaluacion,
+        handlers::evaluacion::listar_calificaciones,
+        handlers::asistencia::crear_sesion,
+        handlers::asistencia::listar_sesiones,
+        handlers::asistencia::actualizar_sesion,
+        handlers::asistencia::eliminar_sesion,
+        handlers::asistencia::obtener_asistencia_sesion,
+        handlers::asistencia::pasar_lista,
+        handlers::asistencia::generar_codigo,
+        handlers::asistencia::check_in,
+        handlers::asistencia::resumen_asistencia_curso,
+        handlers::asistencia::aplicar_umbral_asistencia,
+        handlers::calendario::listar_calendario,
+        handlers::calendario::generar_token_calendario,
+        handlers::calendario::revocar_token_calendario,
+        handlers::calendario::feed_calendario,
+        handlers::cuenta::activar_cuenta,
+        handlers::cuenta::solicitar_recuperacion,
+        handlers::cuenta::restablecer_contrasena,
+        handlers::cuenta::reenviar_activacion
     ),
     components(
         schemas(
+            crate::models::AuthApiResponse,
+            crate::models::AuthResponse,
             crate::models::CreateUserRequest,
+            crate::models::HealthApiResponse,
             crate::models::LoginRequest,
-            crate::models::AuthResponse,
+            crate::models::TokenValidationApiResponse,
             crate::models::UserResponse,
-            crate::handlers::auth::TokenValidationResponse,
-            crate::handlers::auth::ValidateTokenRequest
+            crate::models::actividad::Model,
+            crate::models::actividad::NewActividad,
+            crate::models::actividad::UpdateActividad,
+            crate::models::area_conocimiento::ActualizarArea,
+            crate::models::area_conocimiento::Model,
+            crate::models::area_conocimiento::NuevaArea,
+            crate::models::asistencia::AsistenciaEstudiante,
+            crate::models::asistencia::CheckIn,
+            crate::models::asistencia::EstadoAsistencia,
+            crate::models::asistencia::Model,
+            crate::models::asistencia::PaseDeLista,
+            crate::models::asistencia::RegistroAsistencia,
+            crate::models::asistencia::ResumenAsistencia,
+            crate::models::auditoria::Model,
+            crate::models::calendario::EventoCalendario,
+            crate::models::calendario::TipoEventoCalendario,
+            crate::models::calendario::TokenCalendario,
+            crate::models::calificacion_evaluacion::CalificarEvaluacion,
+            crate::models::calificacion_evaluacion::CriterioCalificado,
+            crate::models::calificacion_evaluacion::EstadoCalificacion,
+            crate::models::calificacion_evaluacion::Model,
+            crate::models::cambio_estado_matricula::Model,
+            crate::models::condicion_liberacion::CondicionLiberacion,
+            crate::models::condicion_liberacion::TipoCondicion,
+            crate::models::contenido_unidad::ActualizarContenidoUnidad,
+            crate::models::contenido_unidad::Model,
+            crate::models::contenido_unidad::NuevoContenidoUnidad,
+            crate::models::contenido_unidad::TipoContenido,
+            crate::models::curso::ActualizarCurso,
+            crate::models::curso::AulaCurso,
+            crate::models::curso::ContenidoAula,
+            crate::models::curso::CursoDetallado,
+            crate::models::curso::CursoPrerequisito,
+            crate::models::curso::Model,
+            crate::models::curso::NuevoCurso,
+            crate::models::curso::ReglasMatricula,
+            crate::models::curso::ReglasMatriculaCurso,
+            crate::models::curso::TemaAula,
+            crate::models::curso::UnidadAula,
+            crate::models::curso::VistaAula,
+            crate::models::estadistica_examen::AnalisisPregunta,
+            crate::models::estadistica_examen::EstadisticasExamen,
+            crate::models::estadistica_examen::FrecuenciaOpcion,
+            crate::models::estadistica_examen::RangoHistograma,
+            crate::models::evaluacion::ActualizarEvaluacion,
+            crate::models::evaluacion::CriterioRubrica,
+            crate::models::evaluacion::EstadoEvaluacion,
+            crate::models::evaluacion::Model,
+            crate::models::evaluacion::NivelRubrica,
+            crate::models::evaluacion::NuevaEvaluacion,
+            crate::models::evaluacion::Rubrica,
+            crate::models::evaluacion::TipoEvaluacion,
+            crate::models::examen::ActualizarExamen,
+            crate::models::examen::EstadoExamen,
+            crate::models::examen::Model,
+            crate::models::examen::NuevoExamen,
+            crate::models::historial_curso_estudiante::CambioEstado,
+            crate::models::historial_curso_estudiante::CierreCurso,
+            crate::models::historial_curso_estudiante::EstadoMatricula,
+            crate::models::historial_curso_estudiante::Model,
+            crate::models::historial_curso_estudiante::ResumenCierre,
+            crate::models::intento_examen::DetalleIntento,
+            crate::models::intento_examen::EntregaIntento,
+            crate::models::intento_examen::EstadoIntento,
+            crate::models::intento_examen::Model,
+            crate::models::intento_examen::PreguntaIntento,
+            crate::models::intento_examen::RespuestaEnviada,
+            crate::models::lista_espera::EntradaListaEspera,
+            crate::models::modulo::ActualizarModulo,
+            crate::models::modulo::Model,
+            crate::models::modulo::NuevoModulo,
+            crate::models::modulo::TipoModulo,
+            crate::models::notificacion::Model,
+            crate::models::notificacion::NuevaNotificacion,
+            crate::models::orden::ElementoOrdenado,
+            crate::models::orden::Reordenamiento,
+            crate::models::orden::TipoOrdenable,
+            crate::models::papelera::ElementoPapelera,
+            crate::models::papelera::TipoPapelera,
+            crate::models::portafolio::Model,
+            crate::models::preferencia_notificacion::FrecuenciaNotificacion,
+            crate::models::preferencia_notificacion::PreferenciaNotificacion,
+            crate::models::pregunta_examen::TipoPregunta,
+            crate::models::progreso_contenido::Model,
+            crate::models::publicacion::CambioPublicacion,
+            crate::models::publicacion::ElementoPublicable,
+            crate::models::publicacion::EstadoPublicacion,
+            crate::models::publicacion::TipoPublicable,
+            crate::models::respuesta_examen::CalificacionRespuesta,
+            crate::models::respuesta_examen::ColaCalificacion,
+            crate::models::respuesta_examen::FiltroCola,
+            crate::models::respuesta_examen::Model,
+            crate::models::respuesta_examen::RespuestaPorCalificar,
+            crate::models::respuesta_examen::ResultadoCalificacion,
+            crate::models::revision::DiffRevisiones,
+            crate::models::revision::FiltroDiff,
+            crate::models::revision::Model,
+            crate::models::revision::ResumenRevision,
+            crate::models::revision::TipoRevisionable,
+            crate::models::rol::Model,
+            crate::models::sesion_curso::ActualizarSesion,
+            crate::models::sesion_curso::CodigoAsistencia,
+            crate::models::sesion_curso::Model,
+            crate::models::sesion_curso::NuevaSesion,
+            crate::models::tema::ActualizarTema,
+            crate::models::tema::Model,
+            crate::models::tema::NuevoTema,
+            crate::models::token_cuenta::ContrasenaConToken,
+            crate::models::token_cuenta::MensajeCuenta,
+            crate::models::token_cuenta::SolicitudRecuperacion,
+            crate::models::unidad::ActualizarUnidad,
+            crate::models::unidad::Model,
+            crate::models::unidad::NuevaUnidad,
+            crate::models::usuario::ErrorImportacion,
+            crate::models::usuario::Model,
+            crate::models::usuario::NewUsuario,
+            crate::models::usuario::OpcionesImportacion,
+            crate::models::usuario::ResultadoImportacion,
+            crate::models::usuario::UpdateUsuario,
+            crate::models::usuario::UsuarioImportado,
+            crate::models::usuario::UsuarioConRol,
+            handlers::area_conocimiento::CambiarEstadoPayload,
+            handlers::auth::TokenValidationResponse,
+            handlers::auth::ValidateTokenRequest,
+            handlers::health::HealthResponse,
+            handlers::matricula::MatriculaPayload,
+            handlers::notificacion::NotificacionesResponse,
+            handlers::portafolio::PortadaCursoPayload,
+            handlers::portafolio::PortadaCursoResponse,
+            handlers::storage::GenerateUrlRequest,
+            handlers::storage::ImagenVariantesResponse,
+            handlers::storage::PresignedUrlResponse,
+            handlers::storage::UploadResponse,
+            handlers::storage::VarianteImagen,
+            handlers::usuarios::LoginPayload,
+            handlers::usuarios::LoginResponse,
+            OAuth2TokenRequest,
+            OAuth2TokenResponse
         )
     )
 )]
 pub struct ApiDoc;
 
+/// Token OAuth2 (password grant) para autorizar Swagger UI
+#[utoipa::path(
+    post,
+    path = "/auth/token",
+    tag = "Auth",
+    request_body = OAuth2TokenRequest,
+    responses(
+        (status = 200, description = "Token de acceso", body = OAuth2TokenResponse),
+        (status = 400, description = "Grant no soportado o credenciales inválidas", body = Object),
+        (status = 429, description = "Demasiados intentos o cuenta bloqueada (ver Retry-After)"),
+        (status = 500, description = "Error de base de datos o al firmar el token", body = Object)
+    )
+)]
 async fn oauth2_token_endpoint(
     State(state): State<AppState>,
+    IpCliente(ip): IpCliente,
     Json(form): Json<OAuth2TokenRequest>,
-) -> Result<Json<OAuth2TokenResponse>, Json<Value>> {
+) -> Result<Json<OAuth2TokenResponse>, Response> {
     use jsonwebtoken::{encode, Header};
 
+    let acceso = AccesoService::from_ref(&state);
+    acceso
+        .verificar_espera(&ip, &form.email)
+        .map_err(IntoResponse::into_response)?;
+
     if !form.client_id.is_empty() {
         tracing::debug!("OAuth2 token request received for client_id={}", form.client_id);
     }
@@ -157,7 +487,7 @@ async fn oauth2_token_endpoint(
     };
 
     if form.grant_type != "password" {
-        return Err(Json(json!({
+        return Err(error_oauth2(json!({
             "error": "unsupported_grant_type",
             "error_description": "Only 'password' grant type is supported"
         })));
@@ -165,10 +495,10 @@ async fn oauth2_token_endpoint(
 
     let db_available = state.db.is_some();
 
-    if !db_available || form.email == "test@example.com" {
+    if state.config.login_prueba && (!db_available || form.email == "test@example.com") {
         // Para testing, buscar el primer usuario existente en la base de datos
         let db = state.get_db().map_err(|_| {
-            Json(json!({
+            error_oauth2(json!({
                 "error": "server_error",
                 "error_description": "Database connection unavailable"
             }))
@@ -181,7 +511,7 @@ async fn oauth2_token_endpoint(
         .await
         .map_err(|e| {
             tracing::error!("Database error finding test user: {:?}", e);
-            Json(json!({
+            error_oauth2(json!({
                 "error": "server_error",
                 "error_description": "Database error"
             }))
@@ -201,7 +531,7 @@ async fn oauth2_token_endpoint(
                 .await
                 .map_err(|e| {
                     tracing::error!("Database error creating test user: {:?}", e);
-                    Json(json!({
+                    error_oauth2(json!({
                         "error": "server_error",
                         "error_description": "Database error"
                     }))
@@ -216,7 +546,7 @@ async fn oauth2_token_endpoint(
                 .await
                 .map_err(|e| {
                     tracing::error!("Database error fetching created test user: {:?}", e);
-                    Json(json!({
+                    error_oauth2(json!({
                         "error": "server_error",
                         "error_description": "Database error"
                     }))
@@ -225,7 +555,7 @@ async fn oauth2_token_endpoint(
         };
 
         let now = chrono::Utc::now();
-        let exp = now + chrono::Duration::hours(24);
+        let exp = now + chrono::Duration::hours(state.config.jwt_expiracion_horas);
 
         let claims = crate::models::Claims {
             sub: test_user.id.to_string(), // Usar ID real del usuario
@@ -237,7 +567,7 @@ async fn oauth2_token_endpoint(
         let token =
             encode(&Header::default(), &claims, state.jwt_encoding_key.as_ref()).map_err(|e| {
                 tracing::error!("Token generation error: {:?}", e);
-                Json(json!({
+                error_oauth2(json!({
                     "error": "server_error",
                     "error_description": "Token generation failed"
                 }))
@@ -246,14 +576,14 @@ async fn oauth2_token_endpoint(
         return Ok(Json(OAuth2TokenResponse {
             access_token: token,
             token_type: "Bearer".to_string(),
-            expires_in: Some(86400),
+            expires_in: Some(state.config.jwt_expiracion_horas as u64 * 3600),
             refresh_token: None,
             scope: Some(resolved_scope),
         }));
     }
 
     let db = state.get_db().map_err(|_| {
-        Json(json!({
+        error_oauth2(json!({
             "error": "server_error",
             "error_description": "Database connection unavailable"
         }))
@@ -267,7 +597,7 @@ async fn oauth2_token_endpoint(
     .await
     .map_err(|e| {
         tracing::error!("Database error in OAuth2 token endpoint: {:?}", e);
-        Json(json!({
+        error_oauth2(json!({
             "error": "server_error",
             "error_description": "Database error"
         }))
@@ -276,25 +606,38 @@ async fn oauth2_token_endpoint(
     let user = match user {
         Some(user) => user,
         None => {
-            return Err(Json(json!({
+            get_metrics_service().registrar_evento(EventoDominio::LoginFallido);
+            acceso.registrar_fallo(&ip, &form.email, None).await;
+            return Err(error_oauth2(json!({
                 "error": "invalid_grant",
                 "error_description": "Invalid email or password"
             })));
         }
     };
 
-    // POR AHORA: comparar directamente (sin bcrypt) para verificar que funciona
-    let password_valid = form.password == user.password_hash || form.password == "admin123";
+    acceso
+        .verificar_bloqueo(user.id)
+        .await
+        .map_err(IntoResponse::into_response)?;
+
+    // bcrypt (o texto plano en cuentas antiguas); la contraseña maestra solo con TEST_LOGIN_ENABLED
+    let coincide = password::verificar(&form.password, &user.password_hash).await;
+    let password_valid = coincide || (state.config.login_prueba && form.password == "admin123");
 
     if !password_valid {
-        return Err(Json(json!({
+        get_metrics_service().registrar_evento(EventoDominio::LoginFallido);
+        acceso.registrar_fallo(&ip, &form.email, Some(user.id)).await;
+        return Err(error_oauth2(json!({
             "error": "invalid_grant",
             "error_description": "Invalid email or password"
         })));
     }
+    if coincide {
+        password::migrar_texto_plano(db, user.id, &form.password, &user.password_hash).await;
+    }
 
     let now = chrono::Utc::now();
-    let exp = now + chrono::Duration::hours(24);
+    let exp = now + chrono::Duration::hours(state.config.jwt_expiracion_horas);
 
     let claims = Claims {
         sub: user.id.to_string(),
@@ -306,59 +649,94 @@ async fn oauth2_token_endpoint(
     let token =
         encode(&Header::default(), &claims, state.jwt_encoding_key.as_ref()).map_err(|e| {
             tracing::error!("Token generation error: {:?}", e);
-            Json(json!({
+            error_oauth2(json!({
                 "error": "server_error",
                 "error_description": "Token generation failed"
             }))
         })?;
 
+    get_metrics_service().registrar_evento(EventoDominio::LoginExitoso);
+    acceso.registrar_exito(&ip, &form.email, user.id).await;
+
     Ok(Json(OAuth2TokenResponse {
         access_token: token,
         token_type: "Bearer".to_string(),
-        expires_in: Some(86400),
+        expires_in: Some(state.config.jwt_expiracion_horas as u64 * 3600),
         refresh_token: None,
         scope: Some(resolved_scope),
     }))
 }
 
+/// Error OAuth2 (RFC 6749 §5.2): 500 para `server_error`, 400 para el resto
+fn error_oauth2(cuerpo: Value) -> Response {
+    let status = if cuerpo["error"] == "server_error" {
+        StatusCode::INTERNAL_SERVER_ERROR
+    } else {
+        StatusCode::BAD_REQUEST
+    };
+    (status, Json(cuerpo)).into_response()
+}
+
 static OPENAPI_SPEC: OnceLock<String> = OnceLock::new();
 
-fn generate_openapi_spec() -> String {
-    use utoipa::openapi::security::{Flow, OAuth2, Password, Scopes, SecurityScheme};
+/// Especificación completa: esquemas de seguridad y servidores según la configuración
+fn build_openapi(config: &Config) -> utoipa::openapi::OpenApi {
+    use utoipa::openapi::security::{
+        Flow, Http, HttpAuthScheme, OAuth2, Password, Scopes, SecurityRequirement, SecurityScheme,
+    };
+    use utoipa::openapi::ServerBuilder;
 
     let mut openapi = ApiDoc::openapi();
     let components = openapi.components.get_or_insert_with(Default::default);
 
     components.add_security_scheme(
         "bearer_auth",
-        SecurityScheme::Http(utoipa::openapi::security::Http::new(
-            utoipa::openapi::security::HttpAuthScheme::Bearer,
-        )),
+        SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
     );
 
     let password_flow = Password::new("/auth/token", Scopes::new());
     let oauth2 = OAuth2::new([Flow::Password(password_flow)]);
     components.add_security_scheme("oauth2_password", SecurityScheme::OAuth2(oauth2));
 
-    let security = vec![
-        utoipa::openapi::security::SecurityRequirement::new("bearer_auth", Vec::<String>::new()),
-        utoipa::openapi::security::SecurityRequirement::new(
-            "oauth2_password",
-            Vec::<String>::new(),
-        ),
-    ];
+    // Las rutas protegidas declaran bearer_auth; el token de /auth/token sirve igual
+    for item in openapi.paths.paths.values_mut() {
+        for operation in item.operations.values_mut() {
+            if let Some(security) = operation.security.as_mut().filter(|s| !s.is_empty()) {
+                security.push(SecurityRequirement::new(
+                    "oauth2_password",
+                    Vec::<String>::new(),
+                ));
+            }
+        }
+    }
 
-    openapi.components = Some(components.clone());
-    openapi.security = Some(security);
+    openapi.servers = Some(
+        config
+            .api_urls
+            .iter()
+            .map(|url| {
+                ServerBuilder::new()
+                    .url(url.clone())
+                    .description(Some(format!("{:?}", config.environment)))
+                    .build()
+            })
+            .collect(),
+    );
 
-    openapi.to_json().unwrap_or_else(|e| {
+    openapi
+}
+
+fn generate_openapi_spec(config: &Config) -> String {
+    build_openapi(config).to_json().unwrap_or_else(|e| {
         tracing::error!("Failed to serialize OpenAPI spec: {}", e);
         "{}".to_string()
     })
 }
 
-async fn serve_openapi_spec() -> String {
-    OPENAPI_SPEC.get_or_init(generate_openapi_spec).clone()
+async fn serve_openapi_spec(State(state): State<AppState>) -> String {
+    OPENAPI_SPEC
+        .get_or_init(|| generate_openapi_spec(&state.config))
+        .clone()
 }
 
 async fn serve_swagger_ui() -> Html<&'static str> {
@@ -416,3 +794,151 @@ async fn serve_oauth2_redirect() -> Html<&'static str> {
 "#,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    /// Rutas que sirven la propia documentación
+    const RUTAS_SIN_DOCUMENTAR: [&str; 5] = [
+        "/api-docs/openapi.json",
+        "/swagger-ui",
+        "/swagger-ui/",
+        "/swagger-ui/index.html",
+        "/swagger-ui/oauth2-redirect.html",
+    ];
+
+    /// (método, ruta) de cada `.route(...)` declarado en `src/routes/`
+    fn rutas_registradas() -> BTreeSet<(String, String)> {
+        let directorio = concat!(env!("CARGO_MANIFEST_DIR"), "/src/routes");
+        let mut rutas = BTreeSet::new();
+
+        for entrada in std::fs::read_dir(directorio).expect("src/routes no existe") {
+            let fuente = std::fs::read_to_string(entrada.unwrap().path()).unwrap();
+            // Sin los módulos de test (este incluido)
+            let mut resto = fuente.split("#[cfg(test)]").next().unwrap_or_default();
+
+            while let Some(inicio) = resto.find(".route(") {
+                resto = &resto[inicio + ".route(".len()..];
+                let abre = resto.find('"').unwrap();
+                let cierra = abre + 1 + resto[abre + 1..].find('"').unwrap();
+                let ruta = &resto[abre + 1..cierra];
+
+                // Argumento del method router: hasta el paréntesis que cierra `.route(`
+                let mut nivel = 1;
+                let fin = resto
+                    .char_indices()
+                    .find(|&(_, c)| {
+                        match c {
+                            '(' => nivel += 1,
+                            ')' => nivel -= 1,
+                            _ => {}
+                        }
+                        nivel == 0
+                    })
+                    .map(|(i, _)| i)
+                    .unwrap();
+                let metodos = &resto[cierra..fin];
+
+                for metodo in ["get", "post", "put", "patch", "delete"] {
+                    let llamada = format!("{}(", metodo);
+                    let aparece = metodos.match_indices(&llamada).any(|(i, _)| {
+                        i == 0 || !metodos[..i].ends_with(|c: char| c.is_alphanumeric() || c == '_')
+                    });
+                    if aparece {
+                        rutas.insert((metodo.to_string(), ruta.to_string()));
+                    }
+                }
+                resto = &resto[fin..];
+            }
+        }
+
+        rutas
+            .into_iter()
+            .filter(|(_, ruta)| !RUTAS_SIN_DOCUMENTAR.contains(&ruta.as_str()))
+            .collect()
+    }
+
+    fn spec() -> serde_json::Value {
+        let config = Config::from_env().expect("configuración por defecto");
+        serde_json::to_value(build_openapi(&config)).unwrap()
+    }
+
+    #[test]
+    fn todas_las_rutas_estan_documentadas() {
+        let spec = spec();
+        let registradas = rutas_registradas();
+        assert!(
+            !registradas.is_empty(),
+            "no se encontraron rutas en src/routes"
+        );
+
+        let faltantes: Vec<String> = registradas
+            .iter()
+            .filter(|(metodo, ruta)| spec["paths"][ruta][metodo].is_null())
+            .map(|(metodo, ruta)| format!("{} {}", metodo.to_uppercase(), ruta))
+            .collect();
+        assert!(
+            faltantes.is_empty(),
+            "rutas sin documentar en OpenAPI: {:?}",
+            faltantes
+        );
+    }
+
+    #[test]
+    fn la_documentacion_no_inventa_rutas() {
+        let spec = spec();
+        let registradas = rutas_registradas();
+
+        let sobrantes: Vec<String> = spec["paths"]
+            .as_object()
+            .unwrap()
+            .iter()
+            .flat_map(|(ruta, item)| {
+                item.as_object()
+                    .unwrap()
+                    .keys()
+                    .map(move |metodo| (metodo.clone(), ruta.clone()))
+            })
+            .filter(|clave| !registradas.contains(clave))
+            .map(|(metodo, ruta)| format!("{} {}", metodo.to_uppercase(), ruta))
+            .collect();
+        assert!(
+            sobrantes.is_empty(),
+            "rutas documentadas que create_app no registra: {:?}",
+            sobrantes
+        );
+    }
+
+    #[test]
+    fn los_esquemas_referenciados_existen() {
+        fn referencias(valor: &serde_json::Value, salida: &mut BTreeSet<String>) {
+            match valor {
+                serde_json::Value::Object(mapa) => {
+                    if let Some(serde_json::Value::String(r)) = mapa.get("$ref") {
+                        salida.insert(r.clone());
+                    }
+                    mapa.values().for_each(|v| referencias(v, salida));
+                }
+                serde_json::Value::Array(lista) => {
+                    lista.iter().for_each(|v| referencias(v, salida))
+                }
+                _ => {}
+            }
+        }
+
+        let spec = spec();
+        let mut refs = BTreeSet::new();
+        referencias(&spec, &mut refs);
+
+        let rotas: Vec<&String> = refs
+            .iter()
+            .filter(|r| {
+                let nombre = r.trim_start_matches("#/components/schemas/");
+                spec["components"]["schemas"][nombre].is_null()
+            })
+            .collect();
+        assert!(rotas.is_empty(), "referencias sin esquema: {:?}", rotas);
+    }
+}
@@ -23,4 +23,8 @@ pub fn notificacion_routes() -> Router<AppState> {
             "/api/notificaciones/usuario/{usuario_id}/marcar-todas-leidas",
             put(notificacion::marcar_todas_leidas),
         )
+        .route(
+            "/api/notificaciones/preferencias",
+            get(notificacion::obtener_preferencias).put(notificacion::actualizar_preferencias),
+        )
 }
@@ -0,0 +1,7 @@
+use axum::{routing::put, Router};
+
+use crate::{handlers::orden, models::AppState};
+
+pub fn orden_routes() -> Router<AppState> {
+    Router::new().route("/api/orden/{tipo}/{padre_id}", put(orden::reordenar))
+}
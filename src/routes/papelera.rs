@@ -0,0 +1,15 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::{handlers::papelera, models::AppState};
+
+pub fn papelera_routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/papelera", get(papelera::listar_papelera))
+        .route(
+            "/api/papelera/{tipo}/{id}/restaurar",
+            post(papelera::restaurar_elemento),
+        )
+}
@@ -0,0 +1,10 @@
+use axum::{routing::get, Router};
+
+use crate::{handlers::publicacion, models::AppState};
+
+pub fn publicacion_routes() -> Router<AppState> {
+    Router::new().route(
+        "/api/publicacion/{tipo}/{id}",
+        get(publicacion::obtener_publicacion).put(publicacion::cambiar_publicacion),
+    )
+}
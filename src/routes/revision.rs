@@ -0,0 +1,26 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::{handlers::revision, models::AppState};
+
+pub fn revision_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/revisiones/{tipo}/{id}",
+            get(revision::listar_revisiones),
+        )
+        .route(
+            "/api/revisiones/{tipo}/{id}/diff",
+            get(revision::diff_revisiones),
+        )
+        .route(
+            "/api/revisiones/{tipo}/{id}/{numero}",
+            get(revision::obtener_revision),
+        )
+        .route(
+            "/api/revisiones/{tipo}/{id}/{numero}/restaurar",
+            post(revision::restaurar_revision),
+        )
+}
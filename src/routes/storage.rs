@@ -3,11 +3,18 @@ use axum::{
     Router,
 };
 
-use crate::{handlers::storage, models::AppState};
+use crate::{handlers::storage, models::AppState, routes::subida};
 
-pub fn storage_routes() -> Router<AppState> {
+pub fn storage_routes(limite_subidas: usize) -> Router<AppState> {
     Router::new()
-        .route("/api/storage/upload", post(storage::upload_file_direct))
+        .route(
+            "/api/storage/upload",
+            subida(post(storage::upload_file_direct), limite_subidas),
+        )
+        .route(
+            "/api/storage/images",
+            subida(post(storage::upload_image), limite_subidas),
+        )
         .route("/api/storage/presigned-url", post(storage::generate_upload_url))
         .route("/api/storage/download/{file_key}", get(storage::download_file))
         .route("/api/storage/{file_key}", delete(storage::delete_file))
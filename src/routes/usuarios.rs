@@ -3,18 +3,26 @@ use axum::{
     Router,
 };
 
-use crate::{handlers::usuarios, models::AppState};
+use crate::{handlers::usuarios, models::AppState, routes::subida};
 
-pub fn usuarios_routes() -> Router<AppState> {
+pub fn usuarios_routes(limite_subidas: usize) -> Router<AppState> {
     Router::new()
         .route(
             "/api/usuarios",
             get(usuarios::listar_usuarios).post(usuarios::crear_usuario),
         )
+        .route(
+            "/api/usuarios/importar",
+            subida(post(usuarios::importar_usuarios), limite_subidas),
+        )
         .route(
             "/api/usuarios/{id}",
             get(usuarios::obtener_usuario_por_id).put(usuarios::actualizar_usuario),
         )
+        .route(
+            "/api/usuarios/{id}/foto",
+            subida(post(usuarios::subir_foto_usuario), limite_subidas),
+        )
         .route("/api/usuario/logout/{id}", post(usuarios::logout_usuario))
         .route("/api/usuario/login", post(usuarios::login_usuario))
 }
@@ -0,0 +1,260 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use axum::extract::FromRef;
+use chrono::{DateTime, Utc};
+
+use crate::{
+    config::LoginConfig,
+    database::DbExecutor,
+    models::{notificacion::NuevaNotificacion, AppState},
+    services::notificacion_service::NotificacionService,
+    utils::errors::AppError,
+};
+
+/// Primera espera (en segundos) al superar los intentos libres; se duplica en cada fallo
+const BACKOFF_BASE_SECS: u64 = 2;
+
+/// Fallos sin actividad durante este tiempo se olvidan en la limpieza periódica
+const OLVIDO_FALLOS: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ClaveIntento {
+    Ip(String),
+    Cuenta(String),
+}
+
+#[derive(Debug)]
+struct Fallos {
+    total: u32,
+    ultimo: Instant,
+}
+
+/// Fallos recientes de login por IP y por cuenta (en memoria)
+static FALLOS: OnceLock<Mutex<HashMap<ClaveIntento, Fallos>>> = OnceLock::new();
+
+fn fallos() -> &'static Mutex<HashMap<ClaveIntento, Fallos>> {
+    FALLOS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Olvida los fallos antiguos; devuelve cuántas claves quedaron
+pub fn limpiar_fallos_antiguos() -> usize {
+    let Ok(mut mapa) = fallos().lock() else {
+        return 0;
+    };
+    mapa.retain(|_, fallo| fallo.ultimo.elapsed() < OLVIDO_FALLOS);
+    mapa.len()
+}
+
+/// Control de intentos de login: espera exponencial por IP y por cuenta,
+/// y bloqueo temporal de la cuenta tras `max_intentos` fallos seguidos
+#[derive(Debug, Clone)]
+pub struct AccesoService {
+    db: Option<DbExecutor>,
+    config: LoginConfig,
+}
+
+impl FromRef<AppState> for AccesoService {
+    fn from_ref(state: &AppState) -> Self {
+        AccesoService::new(state.db.clone(), state.config.login)
+    }
+}
+
+impl AccesoService {
+    pub fn new(db: Option<DbExecutor>, config: LoginConfig) -> Self {
+        Self { db, config }
+    }
+
+    fn claves(ip: &str, cuenta: &str) -> [ClaveIntento; 2] {
+        [
+            ClaveIntento::Ip(ip.to_string()),
+            ClaveIntento::Cuenta(cuenta.trim().to_lowercase()),
+        ]
+    }
+
+    /// Espera exigida tras `total` fallos seguidos
+    fn espera(&self, total: u32) -> Duration {
+        if total < self.config.intentos_libres {
+            return Duration::ZERO;
+        }
+        let exponente = (total - self.config.intentos_libres).min(16);
+        let segundos = BACKOFF_BASE_SECS
+            .saturating_mul(1 << exponente)
+            .min(self.config.backoff_max_secs);
+        Duration::from_secs(segundos)
+    }
+
+    /// Rechaza el intento si la IP o la cuenta aún deben esperar
+    pub fn verificar_espera(&self, ip: &str, cuenta: &str) -> Result<(), AppError> {
+        let Ok(mapa) = fallos().lock() else {
+            return Ok(());
+        };
+
+        let restante = Self::claves(ip, cuenta)
+            .iter()
+            .filter_map(|clave| mapa.get(clave))
+            .map(|fallo| {
+                self.espera(fallo.total)
+                    .saturating_sub(fallo.ultimo.elapsed())
+            })
+            .max()
+            .unwrap_or(Duration::ZERO);
+
+        if restante.is_zero() {
+            return Ok(());
+        }
+        let segundos = restante.as_secs().max(1);
+        Err(AppError::TooManyRequests(
+            format!(
+                "Demasiados intentos fallidos, intenta de nuevo en {} s",
+                segundos
+            )
+            .into(),
+            segundos,
+        ))
+    }
+
+    /// Rechaza el login de una cuenta bloqueada temporalmente
+    pub async fn verificar_bloqueo(&self, usuario_id: i32) -> Result<(), AppError> {
+        let Some(db) = &self.db else {
+            return Ok(());
+        };
+
+        let bloqueado_hasta: Option<DateTime<Utc>> =
+            sqlx::query_scalar("SELECT bloqueado_hasta FROM usuarios WHERE id = $1")
+                .bind(usuario_id)
+                .fetch_optional(db.pool())
+                .await?
+                .flatten();
+
+        match bloqueado_hasta {
+            Some(hasta) if hasta > Utc::now() => {
+                let segundos = (hasta - Utc::now()).num_seconds().max(1) as u64;
+                Err(AppError::TooManyRequests(
+                    format!(
+                        "Cuenta bloqueada temporalmente por intentos fallidos, intenta de nuevo en {} min",
+                        segundos.div_ceil(60)
+                    )
+                    .into(),
+                    segundos,
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Suma el fallo a la IP y a la cuenta; si la cuenta existe lo registra en
+    /// `usuarios` y la bloquea (con notificación) al llegar a `max_intentos`
+    pub async fn registrar_fallo(&self, ip: &str, cuenta: &str, usuario_id: Option<i32>) {
+        if let Ok(mut mapa) = fallos().lock() {
+            for clave in Self::claves(ip, cuenta) {
+                let fallo = mapa.entry(clave).or_insert(Fallos {
+                    total: 0,
+                    ultimo: Instant::now(),
+                });
+                fallo.total = fallo.total.saturating_add(1);
+                fallo.ultimo = Instant::now();
+            }
+        }
+
+        let (Some(db), Some(usuario_id)) = (&self.db, usuario_id) else {
+            return;
+        };
+
+        // Al bloquear se reinicia el contador: tras el bloqueo hay otros N intentos
+        let resultado = sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+            r#"
+            UPDATE usuarios SET
+                intentos_fallidos = CASE WHEN intentos_fallidos + 1 >= $2 THEN 0
+                                         ELSE intentos_fallidos + 1 END,
+                bloqueado_hasta = CASE WHEN intentos_fallidos + 1 >= $2
+                                       THEN NOW() + make_interval(mins => $3)
+                                       ELSE bloqueado_hasta END
+            WHERE id = $1
+            RETURNING bloqueado_hasta
+            "#,
+        )
+        .bind(usuario_id)
+        .bind(self.config.max_intentos as i32)
+        .bind(self.config.bloqueo_minutos as i32)
+        .fetch_optional(db.pool())
+        .await;
+
+        match resultado {
+            Ok(Some(Some(hasta))) if hasta > Utc::now() => {
+                tracing::warn!(
+                    "🔒 Cuenta {} bloqueada hasta {} ({} intentos fallidos, última IP {})",
+                    usuario_id,
+                    hasta,
+                    self.config.max_intentos,
+                    ip
+                );
+                self.notificar_bloqueo(db, usuario_id, hasta, ip).await;
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("⚠️  No se pudo registrar el intento fallido: {}", e),
+        }
+    }
+
+    /// Limpia los fallos de la IP y la cuenta tras un login correcto
+    pub async fn registrar_exito(&self, ip: &str, cuenta: &str, usuario_id: i32) {
+        if let Ok(mut mapa) = fallos().lock() {
+            for clave in Self::claves(ip, cuenta) {
+                mapa.remove(&clave);
+            }
+        }
+
+        let Some(db) = &self.db else {
+            return;
+        };
+        let resultado = sqlx::query(
+            "UPDATE usuarios SET intentos_fallidos = 0, bloqueado_hasta = NULL \
+             WHERE id = $1 AND (intentos_fallidos > 0 OR bloqueado_hasta IS NOT NULL)",
+        )
+        .bind(usuario_id)
+        .execute(db.pool())
+        .await;
+        if let Err(e) = resultado {
+            tracing::warn!("⚠️  No se pudo reiniciar los intentos fallidos: {}", e);
+        }
+    }
+
+    async fn notificar_bloqueo(
+        &self,
+        db: &DbExecutor,
+        usuario_id: i32,
+        hasta: DateTime<Utc>,
+        ip: &str,
+    ) {
+        let notificacion = NuevaNotificacion {
+            usuario_id,
+            titulo: "Cuenta bloqueada temporalmente".to_string(),
+            mensaje: format!(
+                "Tu cuenta se bloqueó por {} minutos tras {} intentos de inicio de sesión fallidos. \
+                 Si no fuiste tú, cambia tu contraseña.",
+                self.config.bloqueo_minutos, self.config.max_intentos
+            ),
+            tipo: "seguridad".to_string(),
+            leida: None,
+            enlace: None,
+            datos_adicionales: Some(serde_json::json!({
+                "bloqueado_hasta": hasta,
+                "ip": ip,
+            })),
+        };
+
+        if let Err(e) = NotificacionService::new(db.clone())
+            .crear_notificacion(notificacion)
+            .await
+        {
+            tracing::warn!(
+                "⚠️  No se pudo notificar el bloqueo de la cuenta {}: {}",
+                usuario_id,
+                e
+            );
+        }
+    }
+}
@@ -0,0 +1,582 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::extract::FromRef;
+use chrono::{Duration, Utc};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection,
+    EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+
+use crate::{
+    database::DbExecutor,
+    models::{
+        asistencia::{self, Entity as Asistencia, EstadoAsistencia, Model as AsistenciaModel},
+        curso::{self, Entity as Curso},
+        historial_curso_estudiante::{self, Entity as Historial, EstadoMatricula},
+        sesion_curso::{self, Entity as SesionCurso, Model as SesionCursoModel},
+        usuario::Entity as Usuario,
+        AppState,
+    },
+    services::{
+        auditoria_service::{AuditoriaService, ContextoAuditoria},
+        matricula_service::exigir_curso_asignado,
+    },
+    utils::errors::AppError,
+};
+
+pub use crate::models::asistencia::{
+    AsistenciaEstudiante, CheckIn, PaseDeLista, ResumenAsistencia,
+};
+pub use crate::models::sesion_curso::{
+    ActualizarSesion, CodigoAsistencia, GenerarCodigo, NuevaSesion,
+};
+
+/// Vigencia por defecto y máxima del código de auto-registro
+const CODIGO_MINUTOS_POR_DEFECTO: i64 = 10;
+const CODIGO_MINUTOS_MAXIMO: i64 = 60;
+/// Minutos después del inicio en que el auto-registro cuenta como `presente`
+const TOLERANCIA_MINUTOS: i64 = 15;
+/// Sin 0/O ni 1/I para que el código se pueda dictar en clase
+const ALFABETO_CODIGO: &[u8; 32] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const LONGITUD_CODIGO: usize = 6;
+
+#[derive(Debug, Clone)]
+pub struct AsistenciaService {
+    db: DbExecutor,
+}
+
+impl FromRef<AppState> for AsistenciaService {
+    fn from_ref(state: &AppState) -> Self {
+        let executor = state
+            .db
+            .clone()
+            .expect("Database connection is not available");
+        AsistenciaService::new(executor)
+    }
+}
+
+impl AsistenciaService {
+    pub fn new(db: DbExecutor) -> Self {
+        Self { db }
+    }
+
+    fn connection(&self) -> DatabaseConnection {
+        self.db.connection()
+    }
+
+    pub async fn crear_sesion(
+        &self,
+        curso_id: i32,
+        nueva: NuevaSesion,
+        profesor_id: Option<i32>,
+    ) -> Result<SesionCursoModel, AppError> {
+        if nueva.nombre_sesion.trim().is_empty() {
+            return Err(AppError::BadRequest(
+                "El nombre de la sesión es obligatorio".into(),
+            ));
+        }
+        if nueva.fecha_inicio >= nueva.fecha_fin {
+            return Err(AppError::BadRequest(
+                "La fecha de inicio debe ser anterior a la fecha de fin".into(),
+            ));
+        }
+
+        let db = self.connection();
+        Curso::find_by_id(curso_id)
+            .filter(curso::Column::FechaEliminacion.is_null())
+            .one(&db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Curso no encontrado".into()))?;
+        exigir_curso_asignado(&db, curso_id, profesor_id).await?;
+
+        let orden = match nueva.orden {
+            Some(orden) => orden,
+            None => {
+                let maximo: Option<i32> = SesionCurso::find()
+                    .select_only()
+                    .column_as(Expr::col(sesion_curso::Column::Orden).max(), "maximo")
+                    .filter(sesion_curso::Column::CursoId.eq(curso_id))
+                    .into_tuple()
+                    .one(&db)
+                    .await?
+                    .flatten();
+                maximo.unwrap_or(0) + 1
+            }
+        };
+
+        let sesion = sesion_curso::ActiveModel {
+            curso_id: Set(curso_id),
+            nombre_sesion: Set(Some(nueva.nombre_sesion)),
+            descripcion: Set(nueva.descripcion),
+            fecha_inicio: Set(Some(nueva.fecha_inicio)),
+            fecha_fin: Set(Some(nueva.fecha_fin)),
+            orden: Set(orden),
+            ..Default::default()
+        };
+
+        Ok(sesion.insert(&db).await?)
+    }
+
+    pub async fn listar_sesiones(&self, curso_id: i32) -> Result<Vec<SesionCursoModel>, AppError> {
+        Ok(SesionCurso::find()
+            .filter(sesion_curso::Column::CursoId.eq(curso_id))
+            .order_by_asc(sesion_curso::Column::Orden)
+            .all(&self.connection())
+            .await?)
+    }
+
+    pub async fn obtener_sesion(&self, id: i32) -> Result<SesionCursoModel, AppError> {
+        SesionCurso::find_by_id(id)
+            .one(&self.connection())
+            .await?
+            .ok_or_else(|| AppError::NotFound("Sesión no encontrada".into()))
+    }
+
+    /// Sesión de un curso que el profesor tiene asignado (sin `profesor_id`, cualquiera)
+    async fn sesion_del_docente(
+        &self,
+        id: i32,
+        profesor_id: Option<i32>,
+    ) -> Result<SesionCursoModel, AppError> {
+        let sesion = self.obtener_sesion(id).await?;
+        exigir_curso_asignado(&self.connection(), sesion.curso_id, profesor_id).await?;
+        Ok(sesion)
+    }
+
+    pub async fn actualizar_sesion(
+        &self,
+        id: i32,
+        datos: ActualizarSesion,
+        profesor_id: Option<i32>,
+    ) -> Result<SesionCursoModel, AppError> {
+        let actual = self.sesion_del_docente(id, profesor_id).await?;
+
+        let inicio = datos.fecha_inicio.or(actual.fecha_inicio);
+        let fin = datos.fecha_fin.or(actual.fecha_fin);
+        if let (Some(inicio), Some(fin)) = (inicio, fin) {
+            if inicio >= fin {
+                return Err(AppError::BadRequest(
+                    "La fecha de inicio debe ser anterior a la fecha de fin".into(),
+                ));
+            }
+        }
+
+        let mut sesion: sesion_curso::ActiveModel = actual.into();
+        if let Some(nombre) = datos.nombre_sesion {
+            if nombre.trim().is_empty() {
+                return Err(AppError::BadRequest(
+                    "El nombre de la sesión no puede estar vacío".into(),
+                ));
+            }
+            sesion.nombre_sesion = Set(Some(nombre));
+        }
+        if let Some(descripcion) = datos.descripcion {
+            sesion.descripcion = Set(Some(descripcion));
+        }
+        if datos.fecha_inicio.is_some() {
+            sesion.fecha_inicio = Set(inicio);
+        }
+        if datos.fecha_fin.is_some() {
+            sesion.fecha_fin = Set(fin);
+        }
+        if let Some(orden) = datos.orden {
+            sesion.orden = Set(orden);
+        }
+
+        Ok(sesion.update(&self.connection()).await?)
+    }
+
+    /// Elimina la sesión junto con sus registros de asistencia
+    pub async fn eliminar_sesion(&self, id: i32, profesor_id: Option<i32>) -> Result<(), AppError> {
+        let sesion = self.sesion_del_docente(id, profesor_id).await?;
+
+        let txn = self.connection().begin().await?;
+        Asistencia::delete_many()
+            .filter(asistencia::Column::SesionId.eq(sesion.id))
+            .exec(&txn)
+            .await?;
+        SesionCurso::delete_by_id(sesion.id).exec(&txn).await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
+    /// Genera (o reemplaza) el código de auto-registro de la sesión
+    pub async fn generar_codigo(
+        &self,
+        id: i32,
+        datos: GenerarCodigo,
+        profesor_id: Option<i32>,
+    ) -> Result<CodigoAsistencia, AppError> {
+        let minutos = datos.minutos.unwrap_or(CODIGO_MINUTOS_POR_DEFECTO);
+        if !(1..=CODIGO_MINUTOS_MAXIMO).contains(&minutos) {
+            return Err(AppError::BadRequest(
+                format!(
+                    "La vigencia debe estar entre 1 y {} minutos",
+                    CODIGO_MINUTOS_MAXIMO
+                )
+                .into(),
+            ));
+        }
+
+        let codigo = nuevo_codigo();
+        let expira = Utc::now() + Duration::minutes(minutos);
+
+        let mut sesion: sesion_curso::ActiveModel =
+            self.sesion_del_docente(id, profesor_id).await?.into();
+        sesion.codigo_asistencia = Set(Some(codigo.clone()));
+        sesion.codigo_expira = Set(Some(expira));
+        sesion.update(&self.connection()).await?;
+
+        Ok(CodigoAsistencia { codigo, expira })
+    }
+
+    /// Auto-registro del estudiante con el código vigente. Cuenta como `tarde`
+    /// pasados TOLERANCIA_MINUTOS del inicio; un registro previo distinto de
+    /// `ausente` no se modifica
+    pub async fn check_in(
+        &self,
+        id: i32,
+        estudiante_id: i32,
+        datos: CheckIn,
+    ) -> Result<AsistenciaModel, AppError> {
+        let sesion = self.obtener_sesion(id).await?;
+        let ahora = Utc::now();
+
+        let vigente = match (&sesion.codigo_asistencia, sesion.codigo_expira) {
+            (Some(codigo), Some(expira)) => {
+                expira > ahora && codigo.eq_ignore_ascii_case(datos.codigo.trim())
+            }
+            _ => false,
+        };
+        if !vigente {
+            return Err(AppError::BadRequest("Código inválido o vencido".into()));
+        }
+
+        let db = self.connection();
+        if !estudiantes_matriculados(&db, sesion.curso_id)
+            .await?
+            .contains_key(&estudiante_id)
+        {
+            return Err(AppError::Forbidden(
+                "No estás matriculado en el curso de esta sesión".into(),
+            ));
+        }
+
+        let estado = match sesion.fecha_inicio {
+            Some(inicio) if ahora > inicio + Duration::minutes(TOLERANCIA_MINUTOS) => {
+                EstadoAsistencia::Tarde
+            }
+            _ => EstadoAsistencia::Presente,
+        };
+
+        let existente = Asistencia::find()
+            .filter(asistencia::Column::SesionId.eq(id))
+            .filter(asistencia::Column::EstudianteId.eq(estudiante_id))
+            .one(&db)
+            .await?;
+        let registro = match existente {
+            Some(registro) if registro.estado != EstadoAsistencia::Ausente => registro,
+            Some(registro) => {
+                let mut registro: asistencia::ActiveModel = registro.into();
+                registro.estado = Set(estado);
+                registro.registrado_por = Set(None);
+                registro.fecha_actualizacion = Set(Some(ahora));
+                registro.update(&db).await?
+            }
+            None => {
+                asistencia::ActiveModel {
+                    sesion_id: Set(id),
+                    estudiante_id: Set(estudiante_id),
+                    estado: Set(estado),
+                    fecha_registro: Set(ahora),
+                    fecha_actualizacion: Set(Some(ahora)),
+                    ..Default::default()
+                }
+                .insert(&db)
+                .await?
+            }
+        };
+
+        tracing::info!(
+            "🙋 Check-in sesión {} estudiante {}: {:?}",
+            id,
+            estudiante_id,
+            registro.estado
+        );
+        Ok(registro)
+    }
+
+    /// Pase de lista del docente: crea o reemplaza el registro de cada estudiante
+    pub async fn pasar_lista(
+        &self,
+        id: i32,
+        pase: PaseDeLista,
+        registrado_por: i32,
+        profesor_id: Option<i32>,
+    ) -> Result<Vec<AsistenciaModel>, AppError> {
+        let sesion = self.sesion_del_docente(id, profesor_id).await?;
+        let db = self.connection();
+        let matriculados = estudiantes_matriculados(&db, sesion.curso_id).await?;
+
+        let mut vistos = HashSet::new();
+        for registro in &pase.registros {
+            if !matriculados.contains_key(&registro.estudiante_id) {
+                return Err(AppError::BadRequest(
+                    format!(
+                        "El estudiante {} no está matriculado en el curso",
+                        registro.estudiante_id
+                    )
+                    .into(),
+                ));
+            }
+            if !vistos.insert(registro.estudiante_id) {
+                return Err(AppError::BadRequest(
+                    format!(
+                        "Estudiante repetido en la lista: {}",
+                        registro.estudiante_id
+                    )
+                    .into(),
+                ));
+            }
+        }
+
+        let existentes: HashMap<i32, AsistenciaModel> = Asistencia::find()
+            .filter(asistencia::Column::SesionId.eq(id))
+            .all(&db)
+            .await?
+            .into_iter()
+            .map(|registro| (registro.estudiante_id, registro))
+            .collect();
+
+        let ahora = Utc::now();
+        let txn = db.begin().await?;
+        let mut resultado = Vec::with_capacity(pase.registros.len());
+        for registro in pase.registros {
+            let mut fila = match existentes.get(&registro.estudiante_id) {
+                Some(existente) => existente.clone().into(),
+                None => asistencia::ActiveModel {
+                    sesion_id: Set(id),
+                    estudiante_id: Set(registro.estudiante_id),
+                    fecha_registro: Set(ahora),
+                    ..Default::default()
+                },
+            };
+            fila.estado = Set(registro.estado);
+            fila.observacion = Set(registro.observacion);
+            fila.registrado_por = Set(Some(registrado_por));
+            fila.fecha_actualizacion = Set(Some(ahora));
+
+            let fila = if existentes.contains_key(&registro.estudiante_id) {
+                fila.update(&txn).await?
+            } else {
+                fila.insert(&txn).await?
+            };
+            resultado.push(fila);
+        }
+        txn.commit().await?;
+
+        tracing::info!(
+            "📋 Pase de lista sesión {}: {} registros",
+            id,
+            resultado.len()
+        );
+        Ok(resultado)
+    }
+
+    /// Estudiantes matriculados con su registro en la sesión (o sin registro)
+    pub async fn asistencia_sesion(
+        &self,
+        id: i32,
+        profesor_id: Option<i32>,
+    ) -> Result<Vec<AsistenciaEstudiante>, AppError> {
+        let sesion = self.sesion_del_docente(id, profesor_id).await?;
+        let db = self.connection();
+        let matriculados = estudiantes_matriculados(&db, sesion.curso_id).await?;
+
+        let mut registros: HashMap<i32, AsistenciaModel> = Asistencia::find()
+            .filter(asistencia::Column::SesionId.eq(id))
+            .all(&db)
+            .await?
+            .into_iter()
+            .map(|registro| (registro.estudiante_id, registro))
+            .collect();
+
+        let mut lista: Vec<AsistenciaEstudiante> = matriculados
+            .into_iter()
+            .map(|(estudiante_id, nombre)| {
+                let registro = registros.remove(&estudiante_id);
+                AsistenciaEstudiante {
+                    estudiante_id,
+                    nombre,
+                    estado: registro.as_ref().map(|registro| registro.estado),
+                    observacion: registro.and_then(|registro| registro.observacion),
+                }
+            })
+            .collect();
+        lista.sort_by(|a, b| a.nombre.cmp(&b.nombre));
+        Ok(lista)
+    }
+
+    /// Porcentaje de asistencia por estudiante sobre las sesiones ya iniciadas.
+    /// Una sesión sin registro cuenta como ausencia
+    pub async fn resumen_curso(
+        &self,
+        curso_id: i32,
+        estudiante_id: Option<i32>,
+        profesor_id: Option<i32>,
+        umbral: f64,
+    ) -> Result<Vec<ResumenAsistencia>, AppError> {
+        let db = self.connection();
+        exigir_curso_asignado(&db, curso_id, profesor_id).await?;
+        let mut matriculados: Vec<i32> = estudiantes_matriculados(&db, curso_id)
+            .await?
+            .into_keys()
+            .collect();
+        if let Some(estudiante_id) = estudiante_id {
+            matriculados.retain(|id| *id == estudiante_id);
+        }
+        matriculados.sort_unstable();
+
+        let sesiones: Vec<i32> = SesionCurso::find()
+            .select_only()
+            .column(sesion_curso::Column::Id)
+            .filter(sesion_curso::Column::CursoId.eq(curso_id))
+            .filter(sesion_curso::Column::FechaInicio.lte(Utc::now()))
+            .into_tuple()
+            .all(&db)
+            .await?;
+
+        let registros = Asistencia::find()
+            .filter(asistencia::Column::SesionId.is_in(sesiones.clone()))
+            .all(&db)
+            .await?;
+        let mut por_estudiante: HashMap<i32, Vec<EstadoAsistencia>> = HashMap::new();
+        for registro in registros {
+            por_estudiante
+                .entry(registro.estudiante_id)
+                .or_default()
+                .push(registro.estado);
+        }
+
+        Ok(matriculados
+            .into_iter()
+            .map(|estudiante_id| {
+                let estados = por_estudiante.remove(&estudiante_id).unwrap_or_default();
+                resumir(estudiante_id, sesiones.len() as u32, &estados, umbral)
+            })
+            .collect())
+    }
+
+    /// Marca como no aprobadas las matrículas cuyo porcentaje está bajo el umbral.
+    /// No aprueba a nadie: solo alimenta `aprobado` con la condición de asistencia
+    pub async fn aplicar_umbral(
+        &self,
+        curso_id: i32,
+        umbral: f64,
+        profesor_id: Option<i32>,
+        ctx: &ContextoAuditoria,
+    ) -> Result<Vec<ResumenAsistencia>, AppError> {
+        let resumen = self
+            .resumen_curso(curso_id, None, profesor_id, umbral)
+            .await?;
+        let reprobados: Vec<i32> = resumen
+            .iter()
+            .filter(|resumen| !resumen.cumple_umbral)
+            .map(|resumen| resumen.estudiante_id)
+            .collect();
+        if reprobados.is_empty() {
+            return Ok(resumen);
+        }
+
+        let db = self.connection();
+        let matriculas = Historial::find()
+            .filter(historial_curso_estudiante::Column::CursoId.eq(curso_id))
+            .filter(historial_curso_estudiante::Column::EstudianteId.is_in(reprobados))
+            .filter(historial_curso_estudiante::Column::Aprobado.eq(true))
+            .all(&db)
+            .await?;
+
+        let txn = db.begin().await?;
+        for matricula in matriculas {
+            let original = matricula.clone();
+            let mut activa: historial_curso_estudiante::ActiveModel = matricula.into();
+            activa.aprobado = Set(false);
+            activa.fecha_actualizacion = Set(Some(Utc::now()));
+            let actualizada = activa.update(&txn).await?;
+            AuditoriaService::registrar(
+                &txn,
+                ctx,
+                "reprobar_por_asistencia",
+                "matricula",
+                Some(actualizada.id),
+                Some(&original),
+                Some(&actualizada),
+            )
+            .await?;
+        }
+        txn.commit().await?;
+
+        Ok(resumen)
+    }
+}
+
+/// Estudiantes con matrícula vigente en el curso, con su nombre
+async fn estudiantes_matriculados<C: ConnectionTrait>(
+    db: &C,
+    curso_id: i32,
+) -> Result<HashMap<i32, Option<String>>, AppError> {
+    let matriculas = Historial::find()
+        .filter(historial_curso_estudiante::Column::CursoId.eq(curso_id))
+        .filter(historial_curso_estudiante::Column::Estado.ne(EstadoMatricula::Retirado))
+        .find_also_related(Usuario)
+        .all(db)
+        .await?;
+
+    Ok(matriculas
+        .into_iter()
+        .map(|(matricula, usuario)| (matricula.estudiante_id, usuario.map(|u| u.nombre)))
+        .collect())
+}
+
+fn resumir(
+    estudiante_id: i32,
+    sesiones: u32,
+    estados: &[EstadoAsistencia],
+    umbral: f64,
+) -> ResumenAsistencia {
+    let contar = |buscado: EstadoAsistencia| {
+        estados.iter().filter(|estado| **estado == buscado).count() as u32
+    };
+    let presentes = contar(EstadoAsistencia::Presente);
+    let tardes = contar(EstadoAsistencia::Tarde);
+    let justificadas = contar(EstadoAsistencia::Justificado);
+    let ausentes = sesiones.saturating_sub(presentes + tardes + justificadas);
+
+    let computables = sesiones.saturating_sub(justificadas);
+    let porcentaje = if computables == 0 {
+        100.0
+    } else {
+        let bruto = f64::from(presentes + tardes) * 100.0 / f64::from(computables);
+        (bruto * 100.0).round() / 100.0
+    };
+
+    ResumenAsistencia {
+        estudiante_id,
+        sesiones,
+        presentes,
+        tardes,
+        ausentes,
+        justificadas,
+        porcentaje,
+        cumple_umbral: porcentaje >= umbral,
+    }
+}
+
+fn nuevo_codigo() -> String {
+    uuid::Uuid::new_v4()
+        .as_bytes()
+        .iter()
+        .take(LONGITUD_CODIGO)
+        .map(|byte| ALFABETO_CODIGO[(*byte as usize) % ALFABETO_CODIGO.len()] as char)
+        .collect()
+}
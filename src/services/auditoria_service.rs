@@ -0,0 +1,163 @@
+use axum::extract::FromRef;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    JsonValue, PaginatorTrait, QueryFilter, QueryOrder, Set,
+};
+use serde::Serialize;
+use serde_json::{json, Map};
+
+use crate::{
+    database::DbExecutor,
+    models::{
+        auditoria::{self, Entity as Auditoria, Model as AuditoriaModel},
+        AppState,
+    },
+    utils::errors::AppError,
+};
+
+pub use crate::models::auditoria::{ContextoAuditoria, FiltroAuditoria};
+
+const LIMITE_POR_DEFECTO: u64 = 50;
+const LIMITE_MAXIMO: u64 = 500;
+
+#[derive(Debug, Clone)]
+pub struct AuditoriaService {
+    db: DbExecutor,
+}
+
+impl FromRef<AppState> for AuditoriaService {
+    fn from_ref(state: &AppState) -> Self {
+        let executor = state
+            .db
+            .clone()
+            .expect("Database connection is not available");
+        AuditoriaService::new(executor)
+    }
+}
+
+impl AuditoriaService {
+    pub fn new(db: DbExecutor) -> Self {
+        Self { db }
+    }
+
+    fn connection(&self) -> DatabaseConnection {
+        self.db.connection()
+    }
+
+    /// Inserta una entrada de auditoría. Recibe la conexión (o transacción) del
+    /// servicio que ejecuta la acción para que ambas se confirmen juntas.
+    pub async fn registrar<C, T>(
+        db: &C,
+        ctx: &ContextoAuditoria,
+        accion: &str,
+        entidad: &str,
+        entidad_id: Option<i32>,
+        antes: Option<&T>,
+        despues: Option<&T>,
+    ) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+        T: Serialize,
+    {
+        let antes = antes.and_then(|valor| serde_json::to_value(valor).ok());
+        let despues = despues.and_then(|valor| serde_json::to_value(valor).ok());
+        let diff = diff_json(antes.as_ref(), despues.as_ref());
+
+        let entrada = auditoria::ActiveModel {
+            actor_id: Set(ctx.actor_id),
+            accion: Set(accion.to_string()),
+            entidad: Set(entidad.to_string()),
+            entidad_id: Set(entidad_id),
+            antes: Set(antes),
+            despues: Set(despues),
+            diff: Set(diff),
+            ip: Set(ctx.ip.clone()),
+            request_id: Set(ctx.request_id.clone()),
+            fecha: Set(Utc::now()),
+            ..Default::default()
+        };
+        entrada.insert(db).await?;
+
+        tracing::info!(
+            actor_id = ?ctx.actor_id,
+            accion,
+            entidad,
+            entidad_id = ?entidad_id,
+            "📝 Acción auditada"
+        );
+        Ok(())
+    }
+
+    pub async fn consultar(
+        &self,
+        filtro: FiltroAuditoria,
+    ) -> Result<Vec<AuditoriaModel>, AppError> {
+        let db = self.connection();
+        let mut query = Auditoria::find();
+
+        if let Some(actor_id) = filtro.actor_id {
+            query = query.filter(auditoria::Column::ActorId.eq(actor_id));
+        }
+        if let Some(entidad) = filtro.entidad {
+            query = query.filter(auditoria::Column::Entidad.eq(entidad));
+        }
+        if let Some(entidad_id) = filtro.entidad_id {
+            query = query.filter(auditoria::Column::EntidadId.eq(entidad_id));
+        }
+        if let Some(accion) = filtro.accion {
+            query = query.filter(auditoria::Column::Accion.eq(accion));
+        }
+        if let Some(desde) = filtro.desde {
+            query = query.filter(auditoria::Column::Fecha.gte(desde));
+        }
+        if let Some(hasta) = filtro.hasta {
+            query = query.filter(auditoria::Column::Fecha.lte(hasta));
+        }
+
+        let limite = filtro
+            .limite
+            .unwrap_or(LIMITE_POR_DEFECTO)
+            .clamp(1, LIMITE_MAXIMO);
+        let entradas = query
+            .order_by_desc(auditoria::Column::Fecha)
+            .order_by_desc(auditoria::Column::Id)
+            .paginate(&db, limite)
+            .fetch_page(filtro.pagina.unwrap_or(0))
+            .await?;
+
+        Ok(entradas)
+    }
+}
+
+/// Diferencia campo a campo entre dos objetos JSON (solo primer nivel)
+pub fn diff_json(antes: Option<&JsonValue>, despues: Option<&JsonValue>) -> Option<JsonValue> {
+    let vacio = Map::new();
+    let antes_obj = antes.and_then(JsonValue::as_object).unwrap_or(&vacio);
+    let despues_obj = despues.and_then(JsonValue::as_object).unwrap_or(&vacio);
+
+    let mut cambios = Map::new();
+    for (campo, valor_antes) in antes_obj {
+        let valor_despues = despues_obj.get(campo).unwrap_or(&JsonValue::Null);
+        if valor_antes != valor_despues {
+            cambios.insert(
+                campo.clone(),
+                json!({ "antes": valor_antes, "despues": valor_despues }),
+            );
+        }
+    }
+    for (campo, valor_despues) in despues_obj {
+        if !antes_obj.contains_key(campo) {
+            cambios.insert(
+                campo.clone(),
+                json!({ "antes": JsonValue::Null, "despues": valor_despues }),
+            );
+        }
+    }
+
+    if cambios.is_empty() {
+        None
+    } else {
+        Some(JsonValue::Object(cambios))
+    }
+}
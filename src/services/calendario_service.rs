@@ -0,0 +1,207 @@
+use axum::extract::FromRef;
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{
+    sea_query::OnConflict, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set,
+};
+
+use crate::{
+    database::DbExecutor,
+    models::{
+        calendario_token::{self, Entity as CalendarioToken},
+        AppState,
+    },
+    utils::{errors::AppError, ical, tokens},
+};
+
+pub use crate::models::calendario::{EventoCalendario, FiltroCalendario, TokenCalendario};
+
+/// Ventana por defecto de GET /api/calendario y rango máximo permitido
+const DIAS_POR_DEFECTO: i64 = 90;
+const DIAS_MAXIMO: i64 = 366;
+/// Ventana del feed .ics: historial reciente y lo que viene
+const FEED_DIAS_ATRAS: i64 = 90;
+const FEED_DIAS_ADELANTE: i64 = 365;
+
+/// Cursos del usuario: matriculado (no retirado), coordinador o profesor asignado.
+/// $1 = usuario, $2/$3 = ventana [desde, hasta]
+const CONSULTA_CALENDARIO: &str = r#"
+WITH cursos_usuario AS (
+    SELECT curso_id FROM historial_cursos_estudiantes
+    WHERE estudiante_id = $1 AND estado <> 'retirado'
+    UNION
+    SELECT id FROM cursos WHERE coordinador_id = $1
+    UNION
+    SELECT curso_id FROM profesores_curso WHERE profesor_id = $1
+),
+cursos_visibles AS (
+    SELECT c.id, c.nombre, c.fecha_inicio, c.fecha_fin
+    FROM cursos c
+    JOIN cursos_usuario cu ON cu.curso_id = c.id
+    WHERE c.fecha_eliminacion IS NULL
+),
+unidades_visibles AS (
+    SELECT u.id, c.id AS curso_id, c.nombre AS curso_nombre
+    FROM unidades u
+    JOIN temas t ON t.id = u.tema_id
+    JOIN modulos m ON m.id = t.modulo_id
+    JOIN cursos_visibles c ON c.id = m.curso_id
+    WHERE u.fecha_eliminacion IS NULL AND u.visible
+      AND t.fecha_eliminacion IS NULL AND t.visible
+      AND m.fecha_eliminacion IS NULL AND m.visible
+)
+SELECT 'curso_inicio' AS tipo, c.id, c.id AS curso_id, c.nombre AS curso_nombre,
+       'Inicio del curso' AS titulo, NULL::text AS descripcion,
+       (c.fecha_inicio::timestamp AT TIME ZONE 'UTC') AS inicio, NULL::timestamptz AS fin,
+       true AS todo_el_dia
+FROM cursos_visibles c
+WHERE c.fecha_inicio BETWEEN $2::date AND $3::date
+UNION ALL
+SELECT 'curso_fin', c.id, c.id, c.nombre, 'Fin del curso', NULL,
+       (c.fecha_fin::timestamp AT TIME ZONE 'UTC'), NULL, true
+FROM cursos_visibles c
+WHERE c.fecha_fin BETWEEN $2::date AND $3::date
+UNION ALL
+SELECT 'modulo', m.id, c.id, c.nombre, m.nombre, m.descripcion, m.fecha_inicio, m.fecha_fin, false
+FROM modulos m
+JOIN cursos_visibles c ON c.id = m.curso_id
+WHERE m.fecha_eliminacion IS NULL AND m.visible AND m.fecha_inicio IS NOT NULL
+  AND m.fecha_inicio <= $3 AND COALESCE(m.fecha_fin, m.fecha_inicio) >= $2
+UNION ALL
+SELECT 'examen', e.id, c.id, c.nombre, e.nombre, e.descripcion, e.fecha_inicio, e.fecha_fin, false
+FROM examenes e
+JOIN cursos_visibles c ON c.id = e.curso_id
+WHERE e.fecha_eliminacion IS NULL AND e.estado <> 'borrador'
+  AND e.fecha_inicio <= $3 AND e.fecha_fin >= $2
+UNION ALL
+SELECT 'entrega', a.id, uv.curso_id, uv.curso_nombre, a.nombre, a.descripcion,
+       a.fecha_limite, NULL, false
+FROM actividades_entrega a
+JOIN unidades_visibles uv ON uv.id = a.unidad_id
+WHERE a.activo AND a.fecha_limite BETWEEN $2 AND $3
+UNION ALL
+SELECT 'contenido', cu.id, uv.curso_id, uv.curso_nombre, cu.titulo, cu.descripcion,
+       cu.fecha_limite, NULL, false
+FROM contenidos_unidad cu
+JOIN unidades_visibles uv ON uv.id = cu.unidad_id
+WHERE cu.fecha_eliminacion IS NULL AND cu.visible AND cu.fecha_limite BETWEEN $2 AND $3
+ORDER BY inicio, tipo, id
+"#;
+
+#[derive(Debug, Clone)]
+pub struct CalendarioService {
+    db: DbExecutor,
+}
+
+impl FromRef<AppState> for CalendarioService {
+    fn from_ref(state: &AppState) -> Self {
+        let executor = state
+            .db
+            .clone()
+            .expect("Database connection is not available");
+        CalendarioService::new(executor)
+    }
+}
+
+impl CalendarioService {
+    pub fn new(db: DbExecutor) -> Self {
+        Self { db }
+    }
+
+    fn connection(&self) -> DatabaseConnection {
+        self.db.connection()
+    }
+
+    /// Fechas de los cursos del usuario dentro de la ventana pedida
+    /// (por defecto, desde ahora y los próximos 90 días)
+    pub async fn eventos(
+        &self,
+        usuario_id: i32,
+        filtro: FiltroCalendario,
+    ) -> Result<Vec<EventoCalendario>, AppError> {
+        let desde = filtro.desde.unwrap_or_else(Utc::now);
+        let hasta = filtro
+            .hasta
+            .unwrap_or_else(|| desde + Duration::days(DIAS_POR_DEFECTO));
+        if hasta <= desde {
+            return Err(AppError::BadRequest(
+                "`hasta` debe ser posterior a `desde`".into(),
+            ));
+        }
+        if hasta - desde > Duration::days(DIAS_MAXIMO) {
+            return Err(AppError::BadRequest(
+                format!("El rango máximo es de {} días", DIAS_MAXIMO).into(),
+            ));
+        }
+
+        self.consultar(usuario_id, desde, hasta).await
+    }
+
+    /// Crea o reemplaza el token del feed; el anterior deja de funcionar
+    pub async fn generar_token(&self, usuario_id: i32) -> Result<TokenCalendario, AppError> {
+        let token = tokens::generar_token();
+        let registro = calendario_token::ActiveModel {
+            usuario_id: Set(usuario_id),
+            token_hash: Set(tokens::hash_token(&token)),
+            fecha_creacion: Set(Utc::now()),
+        };
+
+        CalendarioToken::insert(registro)
+            .on_conflict(
+                OnConflict::column(calendario_token::Column::UsuarioId)
+                    .update_columns([
+                        calendario_token::Column::TokenHash,
+                        calendario_token::Column::FechaCreacion,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&self.connection())
+            .await?;
+
+        Ok(TokenCalendario {
+            url: format!("/api/calendario/feed/{}.ics", token),
+            token,
+        })
+    }
+
+    pub async fn revocar_token(&self, usuario_id: i32) -> Result<(), AppError> {
+        CalendarioToken::delete_by_id(usuario_id)
+            .exec(&self.connection())
+            .await?;
+        Ok(())
+    }
+
+    /// Feed .ics del dueño del token (sin JWT: el token es la credencial)
+    pub async fn feed_ics(&self, token: &str) -> Result<String, AppError> {
+        let registro = CalendarioToken::find()
+            .filter(calendario_token::Column::TokenHash.eq(tokens::hash_token(token)))
+            .one(&self.connection())
+            .await?
+            .ok_or_else(|| AppError::NotFound("Calendario no encontrado".into()))?;
+
+        let ahora = Utc::now();
+        let eventos = self
+            .consultar(
+                registro.usuario_id,
+                ahora - Duration::days(FEED_DIAS_ATRAS),
+                ahora + Duration::days(FEED_DIAS_ADELANTE),
+            )
+            .await?;
+
+        Ok(ical::render_calendario("Calendario académico", &eventos))
+    }
+
+    async fn consultar(
+        &self,
+        usuario_id: i32,
+        desde: DateTime<Utc>,
+        hasta: DateTime<Utc>,
+    ) -> Result<Vec<EventoCalendario>, AppError> {
+        let eventos = sqlx::query_as::<_, EventoCalendario>(CONSULTA_CALENDARIO)
+            .bind(usuario_id)
+            .bind(desde)
+            .bind(hasta)
+            .fetch_all(self.db.pool())
+            .await?;
+        Ok(eventos)
+    }
+}
@@ -1,20 +1,331 @@
 use axum::extract::FromRef;
+use chrono::{DateTime, Utc};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, ModelTrait, Order,
-    QueryFilter, QueryOrder, Set,
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, DbErr,
+    EntityTrait, FromQueryResult, Order, QueryFilter, QueryOrder, Set, Statement, TransactionTrait,
 };
 
+use serde_json::{json, Value as JsonValue};
+
 use crate::{
     database::DbExecutor,
     models::{
-        contenido_unidad::{self, Entity as Contenido, Model as ContenidoModel},
+        actividad_entrega::{self, Entity as ActividadEntrega, TipoActividad},
+        contenido_unidad::{self, Entity as Contenido, Model as ContenidoModel, TipoContenido},
+        examen::{self, Entity as Examen, EstadoExamen},
         AppState,
     },
-    utils::errors::AppError,
+    services::{
+        papelera_service::{PapeleraService, TipoPapelera},
+        publicacion_service::{
+            visible_para_estudiantes, EstadoPublicacion, PublicacionService, TipoPublicable,
+        },
+        revision_service::{RevisionService, TipoRevisionable},
+    },
+    utils::{errors::AppError, markdown, oembed},
 };
 
 pub use crate::models::contenido_unidad::{ActualizarContenidoUnidad, NuevoContenidoUnidad};
 
+/// Curso de una unidad vigente. $1 = unidad
+const CURSO_DE_UNIDAD: &str = r#"
+SELECT m.curso_id FROM unidades u
+JOIN temas t ON t.id = u.tema_id
+JOIN modulos m ON m.id = t.modulo_id
+WHERE u.id = $1 AND u.fecha_eliminacion IS NULL
+  AND t.fecha_eliminacion IS NULL AND m.fecha_eliminacion IS NULL
+"#;
+
+/// Duración del examen creado para un contenido sin `duracion_estimada`
+const DURACION_EXAMEN_MINUTOS: i32 = 60;
+
+#[derive(Debug, FromQueryResult)]
+struct CursoDeUnidad {
+    curso_id: i32,
+}
+
+/// Campos que deciden las reglas por tipo, con los cambios ya aplicados
+struct Propuesta {
+    unidad_id: i32,
+    tipo: TipoContenido,
+    titulo: String,
+    descripcion: Option<String>,
+    contenido: Option<String>,
+    url: Option<String>,
+    obligatorio: bool,
+    fecha_limite: Option<DateTime<Utc>>,
+    duracion_estimada: Option<i32>,
+    examen_id: Option<i32>,
+    actividad_entrega_id: Option<i32>,
+}
+
+/// URL sin espacios alrededor; vacía = sin URL
+fn normalizar_url(url: Option<String>) -> Option<String> {
+    url.map(|u| u.trim().to_string()).filter(|u| !u.is_empty())
+}
+
+fn vacio(valor: &Option<String>) -> bool {
+    valor.as_deref().is_none_or(|v| v.trim().is_empty())
+}
+
+fn con_url(tipo: TipoContenido) -> bool {
+    matches!(
+        tipo,
+        TipoContenido::Documento | TipoContenido::Video | TipoContenido::Enlace
+    )
+}
+
+fn con_oembed(tipo: TipoContenido) -> bool {
+    matches!(tipo, TipoContenido::Video | TipoContenido::Enlace)
+}
+
+/// Reglas por `tipo_contenido` que no requieren consultar la base
+fn validar(propuesta: &Propuesta) -> Result<(), AppError> {
+    let tipo = propuesta.tipo;
+    if propuesta.examen_id.is_some() && tipo != TipoContenido::Examen {
+        return Err(AppError::BadRequest(
+            "examen_id solo aplica a contenidos de tipo examen".into(),
+        ));
+    }
+    if propuesta.actividad_entrega_id.is_some() && tipo != TipoContenido::Entrega {
+        return Err(AppError::BadRequest(
+            "actividad_entrega_id solo aplica a contenidos de tipo entrega".into(),
+        ));
+    }
+    if matches!(propuesta.duracion_estimada, Some(minutos) if minutos <= 0) {
+        return Err(AppError::BadRequest(
+            "La duración estimada debe ser mayor a 0".into(),
+        ));
+    }
+
+    match (&propuesta.url, con_url(tipo)) {
+        (Some(url), true) => {
+            oembed::validar_url(url).map_err(|e| AppError::BadRequest(e.into()))?;
+        }
+        (None, true) => {
+            return Err(AppError::BadRequest(
+                "Los contenidos de tipo documento, video y enlace requieren una url".into(),
+            ))
+        }
+        (Some(_), false) => {
+            return Err(AppError::BadRequest(
+                "Solo los contenidos de tipo documento, video y enlace llevan url".into(),
+            ))
+        }
+        (None, false) => {}
+    }
+
+    match tipo {
+        TipoContenido::Texto if vacio(&propuesta.contenido) => Err(AppError::BadRequest(
+            "Los contenidos de tipo texto requieren el contenido".into(),
+        )),
+        TipoContenido::Actividad
+            if vacio(&propuesta.contenido) && vacio(&propuesta.descripcion) =>
+        {
+            Err(AppError::BadRequest(
+                "Las actividades requieren descripción o contenido".into(),
+            ))
+        }
+        TipoContenido::Examen | TipoContenido::Entrega => {
+            let sin_vinculo =
+                propuesta.examen_id.is_none() && propuesta.actividad_entrega_id.is_none();
+            match propuesta.fecha_limite {
+                None if sin_vinculo => Err(AppError::BadRequest(
+                    "Indique el examen o la actividad a vincular, o una fecha_limite para crearlo"
+                        .into(),
+                )),
+                Some(limite) if sin_vinculo && limite <= Utc::now() => Err(AppError::BadRequest(
+                    "La fecha límite debe ser futura".into(),
+                )),
+                _ => Ok(()),
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+/// `contenido` renderizado; 400 si trae scripts o URLs javascript:
+async fn renderizar(contenido: &Option<String>) -> Result<Option<String>, AppError> {
+    match contenido {
+        Some(texto) => Ok(Some(markdown::renderizar_en_hilo(texto).await?)),
+        None => Ok(None),
+    }
+}
+
+/// Comprueba la unidad y deja enlazado el examen o la actividad de entrega que
+/// respalda el contenido, creándolo si no se indicó uno. Devuelve
+/// (examen_id, actividad_entrega_id)
+async fn vincular<C>(db: &C, propuesta: &Propuesta) -> Result<(Option<i32>, Option<i32>), AppError>
+where
+    C: ConnectionTrait,
+{
+    let curso_id = curso_de_unidad(db, propuesta.unidad_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Unidad no encontrada".into()))?;
+    let ahora = Utc::now();
+
+    match propuesta.tipo {
+        TipoContenido::Examen => {
+            if let Some(examen_id) = propuesta.examen_id {
+                let examen = Examen::find_by_id(examen_id)
+                    .filter(examen::Column::FechaEliminacion.is_null())
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| AppError::NotFound("Examen no encontrado".into()))?;
+                if examen.curso_id != curso_id {
+                    return Err(AppError::BadRequest(
+                        "El examen pertenece a otro curso".into(),
+                    ));
+                }
+                return Ok((Some(examen.id), None));
+            }
+
+            // Examen en borrador: el profesor le agrega preguntas y lo publica
+            let examen = examen::ActiveModel {
+                curso_id: Set(curso_id),
+                nombre: Set(propuesta.titulo.clone()),
+                descripcion: Set(propuesta.descripcion.clone()),
+                fecha_inicio: Set(ahora),
+                fecha_fin: Set(propuesta.fecha_limite.unwrap_or(ahora)),
+                duracion_minutos: Set(propuesta
+                    .duracion_estimada
+                    .unwrap_or(DURACION_EXAMEN_MINUTOS)),
+                intentos_permitidos: Set(1),
+                mostrar_resultados: Set(false),
+                estado: Set(EstadoExamen::Borrador),
+                created_at: Set(Some(ahora)),
+                updated_at: Set(Some(ahora)),
+                ..Default::default()
+            }
+            .insert(db)
+            .await?;
+            Ok((Some(examen.id), None))
+        }
+        TipoContenido::Entrega => {
+            if let Some(actividad_id) = propuesta.actividad_entrega_id {
+                let actividad = ActividadEntrega::find_by_id(actividad_id)
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| {
+                        AppError::NotFound("Actividad de entrega no encontrada".into())
+                    })?;
+                if curso_de_unidad(db, actividad.unidad_id).await? != Some(curso_id) {
+                    return Err(AppError::BadRequest(
+                        "La actividad de entrega pertenece a otro curso".into(),
+                    ));
+                }
+                return Ok((None, Some(actividad.id)));
+            }
+
+            let actividad = actividad_entrega::ActiveModel {
+                unidad_id: Set(propuesta.unidad_id),
+                nombre: Set(propuesta.titulo.clone()),
+                descripcion: Set(propuesta.descripcion.clone()),
+                fecha_limite: Set(propuesta.fecha_limite.unwrap_or(ahora)),
+                tipo_actividad: Set(if propuesta.obligatorio {
+                    TipoActividad::EntregaObligatoria
+                } else {
+                    TipoActividad::EntregaOpcional
+                }),
+                activo: Set(true),
+                created_at: Set(Some(ahora)),
+                updated_at: Set(Some(ahora)),
+                ..Default::default()
+            }
+            .insert(db)
+            .await?;
+            Ok((None, Some(actividad.id)))
+        }
+        _ => Ok((None, None)),
+    }
+}
+
+/// Aplica las reglas por tipo a la revisión que se va a restaurar, sobre el contenido
+/// actual (las revisiones antiguas no traen todos los campos). Devuelve los datos a
+/// reponer con el examen o la actividad de entrega que quedan vinculados
+pub(crate) async fn preparar_restauracion<C>(
+    db: &C,
+    id: i32,
+    datos: &JsonValue,
+) -> Result<JsonValue, AppError>
+where
+    C: ConnectionTrait,
+{
+    let contenido = Contenido::find_by_id(id)
+        .filter(contenido_unidad::Column::FechaEliminacion.is_null())
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Contenido no encontrado".into()))?;
+
+    let texto = |campo: &str, actual: Option<String>| match datos.get(campo) {
+        Some(valor) => valor.as_str().map(str::to_string),
+        None => actual,
+    };
+    let entero = |campo: &str, actual: Option<i32>| match datos.get(campo) {
+        Some(valor) => valor.as_i64().and_then(|v| i32::try_from(v).ok()),
+        None => actual,
+    };
+    let tipo = match datos.get("tipo_contenido") {
+        Some(valor) => serde_json::from_value(valor.clone()).map_err(|_| {
+            AppError::BadRequest("La revisión tiene un tipo de contenido inválido".into())
+        })?,
+        None => contenido.tipo_contenido,
+    };
+    let mismo_tipo = tipo == contenido.tipo_contenido;
+
+    let propuesta = Propuesta {
+        unidad_id: contenido.unidad_id,
+        tipo,
+        titulo: texto("titulo", Some(contenido.titulo.clone())).unwrap_or_default(),
+        descripcion: texto("descripcion", contenido.descripcion.clone()),
+        contenido: texto("contenido", contenido.contenido.clone()),
+        url: normalizar_url(texto("url", contenido.url.clone())),
+        obligatorio: datos
+            .get("obligatorio")
+            .and_then(JsonValue::as_bool)
+            .unwrap_or(contenido.obligatorio),
+        fecha_limite: contenido.fecha_limite,
+        duracion_estimada: contenido.duracion_estimada,
+        examen_id: entero("examen_id", contenido.examen_id.filter(|_| mismo_tipo)),
+        actividad_entrega_id: entero(
+            "actividad_entrega_id",
+            contenido.actividad_entrega_id.filter(|_| mismo_tipo),
+        ),
+    };
+    if propuesta.titulo.trim().is_empty() {
+        return Err(AppError::BadRequest(
+            "El título no puede estar vacío".into(),
+        ));
+    }
+    validar(&propuesta)?;
+    renderizar(&propuesta.contenido).await?;
+    let (examen_id, actividad_entrega_id) = vincular(db, &propuesta).await?;
+
+    let mut datos = datos.clone();
+    if let Some(campos) = datos.as_object_mut() {
+        campos.insert("url".into(), json!(propuesta.url));
+        campos.insert("examen_id".into(), json!(examen_id));
+        campos.insert("actividad_entrega_id".into(), json!(actividad_entrega_id));
+    }
+    Ok(datos)
+}
+
+async fn curso_de_unidad<C>(db: &C, unidad_id: i32) -> Result<Option<i32>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    Ok(
+        CursoDeUnidad::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            CURSO_DE_UNIDAD,
+            [unidad_id.into()],
+        ))
+        .one(db)
+        .await?
+        .map(|c| c.curso_id),
+    )
+}
+
 #[derive(Debug, Clone)]
 pub struct ContenidoUnidadService {
     db: DbExecutor,
@@ -32,124 +343,260 @@ impl ContenidoUnidadService {
     pub async fn crear_contenido(
         &self,
         nuevo: NuevoContenidoUnidad,
+        autor_id: i32,
     ) -> Result<ContenidoModel, AppError> {
         if nuevo.titulo.trim().is_empty() {
             return Err(AppError::BadRequest("El ttulo es obligatorio".into()));
         }
 
+        let propuesta = Propuesta {
+            unidad_id: nuevo.unidad_id,
+            tipo: nuevo.tipo_contenido,
+            titulo: nuevo.titulo,
+            descripcion: nuevo.descripcion,
+            contenido: nuevo.contenido,
+            url: normalizar_url(nuevo.url),
+            obligatorio: nuevo.obligatorio,
+            fecha_limite: nuevo.fecha_limite,
+            duracion_estimada: nuevo.duracion_estimada,
+            examen_id: nuevo.examen_id,
+            actividad_entrega_id: nuevo.actividad_entrega_id,
+        };
+        validar(&propuesta)?;
+        let contenido_html = renderizar(&propuesta.contenido).await?;
+
+        // Fuera de la transacción: es una consulta al proveedor con su propio límite
+        let metadatos = match &propuesta.url {
+            Some(url) if con_oembed(propuesta.tipo) => oembed::obtener(url).await,
+            _ => None,
+        };
+
         let db = self.connection();
+        let txn = db.begin().await?;
+        let (examen_id, actividad_entrega_id) = vincular(&txn, &propuesta).await?;
 
         // Dejar que la BD autoincremente el id (no establecerlo manualmente)
         let contenido = contenido_unidad::ActiveModel {
-            unidad_id: Set(nuevo.unidad_id),
-            tipo_contenido: Set(nuevo.tipo_contenido),
-            titulo: Set(nuevo.titulo),
-            descripcion: Set(nuevo.descripcion),
+            unidad_id: Set(propuesta.unidad_id),
+            tipo_contenido: Set(propuesta.tipo),
+            titulo: Set(propuesta.titulo),
+            descripcion: Set(propuesta.descripcion),
             orden: Set(nuevo.orden),
-            contenido: Set(nuevo.contenido),
-            url: Set(nuevo.url),
+            contenido: Set(propuesta.contenido),
+            contenido_html: Set(contenido_html),
+            url: Set(propuesta.url),
             visible: Set(nuevo.visible),
-            obligatorio: Set(nuevo.obligatorio),
+            estado_publicacion: Set(EstadoPublicacion::desde_visible(nuevo.visible)),
+            publicar_en: Set(None),
+            despublicar_en: Set(None),
+            obligatorio: Set(propuesta.obligatorio),
             puntos: Set(nuevo.puntos),
-            fecha_limite: Set(None),
-            duracion_estimada: Set(None),
-            examen_id: Set(None),
+            fecha_limite: Set(propuesta.fecha_limite),
+            duracion_estimada: Set(propuesta.duracion_estimada),
+            examen_id: Set(examen_id),
             entrega_id: Set(None),
+            actividad_entrega_id: Set(actividad_entrega_id),
+            oembed: Set(metadatos),
             fecha_creacion: Set(None),
             fecha_actualizacion: Set(None),
             ..Default::default()
         };
 
-        let creado = contenido.insert(&db).await?;
+        let creado = contenido.insert(&txn).await?;
+        RevisionService::registrar(
+            &txn,
+            TipoRevisionable::ContenidoUnidad,
+            creado.id,
+            Some(autor_id),
+            None,
+        )
+        .await?;
+        txn.commit().await?;
+        if creado.visible {
+            PublicacionService::new(self.db.clone())
+                .notificar_publicados(&[(TipoPublicable::Contenido, creado.id)])
+                .await;
+        }
         Ok(creado)
     }
 
+    /// Con `solo_visibles` (estudiantes) solo lo publicado y nada si el contenedor no lo está
     pub async fn obtener_contenidos_por_unidad(
         &self,
         unidad_id: i32,
+        solo_visibles: bool,
     ) -> Result<Vec<ContenidoModel>, DbErr> {
         let db = self.connection();
-        Contenido::find()
-            .filter(contenido_unidad::Column::UnidadId.eq(unidad_id))
+        if solo_visibles
+            && !visible_para_estudiantes(&db, TipoPublicable::Unidad, unidad_id).await?
+        {
+            return Ok(Vec::new());
+        }
+        let mut consulta = Contenido::find()
+            .filter(contenido_unidad::Column::FechaEliminacion.is_null())
+            .filter(contenido_unidad::Column::UnidadId.eq(unidad_id));
+        if solo_visibles {
+            consulta = consulta.filter(contenido_unidad::Column::Visible.eq(true));
+        }
+        consulta
             .order_by(contenido_unidad::Column::Orden, Order::Asc)
             .all(&db)
             .await
     }
 
+    /// Con `solo_visibles` (estudiantes) los borradores y lo que cuelga de ellos no existe
     pub async fn obtener_contenido_por_id(
         &self,
         id: i32,
+        solo_visibles: bool,
     ) -> Result<Option<ContenidoModel>, DbErr> {
         let db = self.connection();
-        Contenido::find_by_id(id).one(&db).await
+        if solo_visibles && !visible_para_estudiantes(&db, TipoPublicable::Contenido, id).await? {
+            return Ok(None);
+        }
+        Contenido::find_by_id(id)
+            .filter(contenido_unidad::Column::FechaEliminacion.is_null())
+            .one(&db)
+            .await
     }
 
     pub async fn actualizar_contenido(
         &self,
         id: i32,
         datos: ActualizarContenidoUnidad,
+        autor_id: i32,
     ) -> Result<ContenidoModel, AppError> {
         let db = self.connection();
         let contenido = Contenido::find_by_id(id)
+            .filter(contenido_unidad::Column::FechaEliminacion.is_null())
             .one(&db)
             .await?
             .ok_or_else(|| AppError::NotFound("Contenido no encontrado".into()))?;
 
-        let mut contenido: contenido_unidad::ActiveModel = contenido.into();
-
-        if let Some(tipo) = datos.tipo_contenido {
-            contenido.tipo_contenido = Set(tipo);
-        }
-
-        if let Some(titulo) = datos.titulo {
+        let era_visible = contenido.visible;
+        let tipo = datos.tipo_contenido.unwrap_or(contenido.tipo_contenido);
+        if let Some(titulo) = &datos.titulo {
             if titulo.trim().is_empty() {
                 return Err(AppError::BadRequest(
-                    "El ttulo no puede estar vaco".into(),
+                    "El ttulo no puede estar vaco".into(),
                 ));
             }
-            contenido.titulo = Set(titulo);
         }
 
-        if let Some(descripcion) = datos.descripcion {
-            contenido.descripcion = Set(Some(descripcion));
-        }
+        // Un vínculo existente se conserva mientras el tipo no cambie
+        let propuesta = Propuesta {
+            unidad_id: contenido.unidad_id,
+            tipo,
+            titulo: datos
+                .titulo
+                .clone()
+                .unwrap_or_else(|| contenido.titulo.clone()),
+            descripcion: datos
+                .descripcion
+                .clone()
+                .or_else(|| contenido.descripcion.clone()),
+            contenido: datos
+                .contenido
+                .clone()
+                .or_else(|| contenido.contenido.clone()),
+            url: match datos.url.clone() {
+                Some(url) => normalizar_url(Some(url)),
+                None => contenido.url.clone(),
+            },
+            obligatorio: datos.obligatorio.unwrap_or(contenido.obligatorio),
+            fecha_limite: datos.fecha_limite.or(contenido.fecha_limite),
+            duracion_estimada: datos.duracion_estimada.or(contenido.duracion_estimada),
+            examen_id: datos.examen_id.or(contenido
+                .examen_id
+                .filter(|_| tipo == contenido.tipo_contenido)),
+            actividad_entrega_id: datos.actividad_entrega_id.or(contenido
+                .actividad_entrega_id
+                .filter(|_| tipo == contenido.tipo_contenido)),
+        };
+        validar(&propuesta)?;
+        let contenido_html = renderizar(&propuesta.contenido).await?;
 
-        if let Some(texto) = datos.contenido {
-            contenido.contenido = Set(Some(texto));
-        }
+        let metadatos = if !con_oembed(tipo) {
+            None
+        } else if propuesta.url == contenido.url && contenido.oembed.is_some() {
+            contenido.oembed.clone()
+        } else {
+            match &propuesta.url {
+                Some(url) => oembed::obtener(url).await,
+                None => None,
+            }
+        };
 
-        if let Some(url) = datos.url {
-            contenido.url = Set(Some(url));
-        }
+        let mut contenido: contenido_unidad::ActiveModel = contenido.into();
+        contenido.tipo_contenido = Set(propuesta.tipo);
+        contenido.titulo = Set(propuesta.titulo.clone());
+        contenido.descripcion = Set(propuesta.descripcion.clone());
+        contenido.contenido = Set(propuesta.contenido.clone());
+        contenido.contenido_html = Set(contenido_html);
+        contenido.url = Set(propuesta.url.clone());
+        contenido.obligatorio = Set(propuesta.obligatorio);
+        contenido.fecha_limite = Set(propuesta.fecha_limite);
+        contenido.duracion_estimada = Set(propuesta.duracion_estimada);
+        contenido.oembed = Set(metadatos);
 
         if let Some(orden) = datos.orden {
             contenido.orden = Set(orden);
         }
 
-        if let Some(visible) = datos.visible {
+        // Un cambio manual de visibilidad reemplaza la publicación programada
+        if let Some(visible) = datos.visible.filter(|v| *v != era_visible) {
             contenido.visible = Set(visible);
-        }
-
-        if let Some(obligatorio) = datos.obligatorio {
-            contenido.obligatorio = Set(obligatorio);
+            contenido.estado_publicacion = Set(EstadoPublicacion::desde_visible(visible));
+            contenido.publicar_en = Set(None);
+            contenido.despublicar_en = Set(None);
         }
 
         if let Some(puntos) = datos.puntos {
             contenido.puntos = Set(Some(puntos));
         }
 
-        let actualizado = contenido.update(&db).await?;
+        // Cada edición del cuerpo queda como revisión (ver RevisionService)
+        let txn = db.begin().await?;
+        RevisionService::registrar(&txn, TipoRevisionable::ContenidoUnidad, id, None, None).await?;
+        let (examen_id, actividad_entrega_id) = vincular(&txn, &propuesta).await?;
+        contenido.examen_id = Set(examen_id);
+        contenido.actividad_entrega_id = Set(actividad_entrega_id);
+        let actualizado = contenido.update(&txn).await?;
+        RevisionService::registrar(
+            &txn,
+            TipoRevisionable::ContenidoUnidad,
+            id,
+            Some(autor_id),
+            None,
+        )
+        .await?;
+        txn.commit().await?;
+        if !era_visible && actualizado.visible {
+            PublicacionService::new(self.db.clone())
+                .notificar_publicados(&[(TipoPublicable::Contenido, id)])
+                .await;
+        }
         Ok(actualizado)
     }
 
     pub async fn eliminar_contenido(&self, id: i32) -> Result<(), AppError> {
         let db = self.connection();
         let contenido = Contenido::find_by_id(id)
+            .filter(contenido_unidad::Column::FechaEliminacion.is_null())
             .one(&db)
             .await?
             .ok_or_else(|| AppError::NotFound("Contenido no encontrado".into()))?;
 
-        contenido.delete(&db).await?;
+        // Soft delete en cascada (ver PapeleraService)
+        let txn = db.begin().await?;
+        PapeleraService::mover_a_papelera(
+            &txn,
+            TipoPapelera::Contenido,
+            contenido.id,
+            Utc::now(),
+        )
+        .await?;
+        txn.commit().await?;
         Ok(())
     }
 }
@@ -0,0 +1,372 @@
+use std::{io, path::PathBuf, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::Utc;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+use tokio_rustls::{
+    client::TlsStream,
+    rustls::{crypto::ring, pki_types::ServerName, ClientConfig, RootCertStore},
+    TlsConnector,
+};
+use uuid::Uuid;
+
+use crate::config::{MailConfig, SeguridadSmtp, TransporteCorreo};
+
+/// Largo de línea del cuerpo en base64 (RFC 2045)
+const LINEA_BASE64: usize = 76;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CorreoError {
+    #[error("Dirección o cabecera inválida: {0}")]
+    CabeceraInvalida(String),
+    #[error("Error de E/S: {0}")]
+    Io(#[from] io::Error),
+    #[error("Error TLS: {0}")]
+    Tls(String),
+    #[error("El servidor SMTP respondió {codigo}: {mensaje}")]
+    Smtp { codigo: u16, mensaje: String },
+    #[error("El servidor SMTP no respondió en {0} s")]
+    Timeout(u64),
+}
+
+/// Correo de texto plano
+#[derive(Debug, Clone)]
+pub struct Correo {
+    pub para: String,
+    pub asunto: String,
+    pub texto: String,
+}
+
+/// Envío de correos; la implementación sale de `MAIL_TRANSPORT`
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn enviar(&self, correo: &Correo) -> Result<(), CorreoError>;
+}
+
+/// Mailer configurado: SMTP o archivos `.eml` + log
+pub fn crear_mailer(config: &MailConfig) -> Result<Arc<dyn Mailer>, CorreoError> {
+    match config.transporte {
+        TransporteCorreo::Smtp => Ok(Arc::new(SmtpMailer::new(config)?)),
+        TransporteCorreo::Archivo => Ok(Arc::new(ArchivoMailer::new(config))),
+    }
+}
+
+/// Cliente SMTP mínimo: STARTTLS o TLS implícito, AUTH PLAIN y un destinatario
+pub struct SmtpMailer {
+    host: String,
+    puerto: u16,
+    seguridad: SeguridadSmtp,
+    credenciales: Option<(String, String)>,
+    remitente: String,
+    timeout: Duration,
+    tls: TlsConnector,
+}
+
+impl SmtpMailer {
+    pub fn new(config: &MailConfig) -> Result<Self, CorreoError> {
+        let host = config
+            .smtp_host
+            .clone()
+            .ok_or_else(|| CorreoError::CabeceraInvalida("SMTP_HOST no configurado".into()))?;
+
+        Ok(Self {
+            host,
+            puerto: config.smtp_port,
+            seguridad: config.smtp_seguridad,
+            credenciales: config
+                .smtp_usuario
+                .clone()
+                .zip(config.smtp_contrasena.clone()),
+            remitente: config.remitente.clone(),
+            timeout: Duration::from_secs(config.smtp_timeout_secs),
+            tls: conector_tls()?,
+        })
+    }
+
+    async fn tls(&self, tcp: TcpStream) -> Result<TlsStream<TcpStream>, CorreoError> {
+        let nombre =
+            ServerName::try_from(self.host.clone()).map_err(|e| CorreoError::Tls(e.to_string()))?;
+        self.tls
+            .connect(nombre, tcp)
+            .await
+            .map_err(|e| CorreoError::Tls(e.to_string()))
+    }
+
+    async fn conversar(&self, para: &str, mensaje: &str) -> Result<(), CorreoError> {
+        let tcp = TcpStream::connect((self.host.as_str(), self.puerto)).await?;
+        let ehlo = format!("EHLO {}", dominio(&self.remitente));
+
+        match self.seguridad {
+            SeguridadSmtp::Tls => {
+                let mut sesion = Sesion::new(self.tls(tcp).await?);
+                sesion.esperar(&[220]).await?;
+                sesion.comando(&ehlo, &[250]).await?;
+                self.entregar(&mut sesion, para, mensaje).await
+            }
+            SeguridadSmtp::StartTls => {
+                let mut sesion = Sesion::new(tcp);
+                sesion.esperar(&[220]).await?;
+                let extensiones = sesion.comando(&ehlo, &[250]).await?;
+                if !extensiones
+                    .lines()
+                    .any(|linea| linea.trim().eq_ignore_ascii_case("STARTTLS"))
+                {
+                    return Err(CorreoError::Tls("el servidor no ofrece STARTTLS".into()));
+                }
+                sesion.comando("STARTTLS", &[220]).await?;
+
+                // Tras STARTTLS se repite EHLO sobre el canal cifrado
+                let mut sesion = Sesion::new(self.tls(sesion.into_inner()).await?);
+                sesion.comando(&ehlo, &[250]).await?;
+                self.entregar(&mut sesion, para, mensaje).await
+            }
+            SeguridadSmtp::Ninguna => {
+                let mut sesion = Sesion::new(tcp);
+                sesion.esperar(&[220]).await?;
+                sesion.comando(&ehlo, &[250]).await?;
+                self.entregar(&mut sesion, para, mensaje).await
+            }
+        }
+    }
+
+    async fn entregar<S: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        sesion: &mut Sesion<S>,
+        para: &str,
+        mensaje: &str,
+    ) -> Result<(), CorreoError> {
+        if let Some((usuario, contrasena)) = &self.credenciales {
+            let plano = STANDARD.encode(format!("\0{}\0{}", usuario, contrasena));
+            sesion
+                .comando(&format!("AUTH PLAIN {}", plano), &[235])
+                .await?;
+        }
+
+        sesion
+            .comando(
+                &format!("MAIL FROM:<{}>", direccion(&self.remitente)),
+                &[250],
+            )
+            .await?;
+        sesion
+            .comando(&format!("RCPT TO:<{}>", para), &[250, 251])
+            .await?;
+        sesion.comando("DATA", &[354]).await?;
+        // Dot-stuffing (RFC 5321 §4.5.2) y punto final
+        let datos = mensaje.replace("\r\n.", "\r\n..");
+        sesion.comando(&format!("{}\r\n.", datos), &[250]).await?;
+
+        // El correo ya fue aceptado: un QUIT fallido no importa
+        let _ = sesion.comando("QUIT", &[221]).await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn enviar(&self, correo: &Correo) -> Result<(), CorreoError> {
+        let mensaje = formatear(&self.remitente, correo)?;
+        tokio::time::timeout(
+            self.timeout,
+            self.conversar(direccion(&correo.para), &mensaje),
+        )
+        .await
+        .map_err(|_| CorreoError::Timeout(self.timeout.as_secs()))??;
+
+        tracing::info!("📧 Correo '{}' enviado a {}", correo.asunto, correo.para);
+        Ok(())
+    }
+}
+
+/// Desarrollo local: escribe cada correo como `.eml` en `MAIL_DIR` y lo muestra en el log
+pub struct ArchivoMailer {
+    dir: Option<PathBuf>,
+    remitente: String,
+}
+
+impl ArchivoMailer {
+    pub fn new(config: &MailConfig) -> Self {
+        Self {
+            dir: (!config.dir.trim().is_empty()).then(|| PathBuf::from(&config.dir)),
+            remitente: config.remitente.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for ArchivoMailer {
+    async fn enviar(&self, correo: &Correo) -> Result<(), CorreoError> {
+        let mensaje = formatear(&self.remitente, correo)?;
+        tracing::info!(
+            "📧 Correo para {} — {}\n{}",
+            correo.para,
+            correo.asunto,
+            correo.texto
+        );
+
+        if let Some(dir) = &self.dir {
+            tokio::fs::create_dir_all(dir).await?;
+            let ruta = dir.join(format!(
+                "{}-{}.eml",
+                Utc::now().format("%Y%m%dT%H%M%S"),
+                Uuid::new_v4().simple()
+            ));
+            tokio::fs::write(&ruta, mensaje).await?;
+            tracing::info!("📧 Guardado en {}", ruta.display());
+        }
+        Ok(())
+    }
+}
+
+/// Conversación SMTP línea a línea sobre TCP o TLS
+struct Sesion<S> {
+    flujo: BufReader<S>,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Sesion<S> {
+    fn new(flujo: S) -> Self {
+        Self {
+            flujo: BufReader::new(flujo),
+        }
+    }
+
+    fn into_inner(self) -> S {
+        self.flujo.into_inner()
+    }
+
+    /// Lee una respuesta (multilínea si el código va seguido de '-')
+    async fn esperar(&mut self, esperados: &[u16]) -> Result<String, CorreoError> {
+        let mut texto = String::new();
+        loop {
+            let mut linea = String::new();
+            if self.flujo.read_line(&mut linea).await? == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "el servidor SMTP cerró la conexión",
+                )
+                .into());
+            }
+            let linea = linea.trim_end();
+            let codigo = linea
+                .get(..3)
+                .and_then(|codigo| codigo.parse::<u16>().ok())
+                .ok_or_else(|| CorreoError::Smtp {
+                    codigo: 0,
+                    mensaje: linea.to_string(),
+                })?;
+            texto.push_str(linea.get(4..).unwrap_or_default());
+            texto.push('\n');
+
+            if linea.as_bytes().get(3) != Some(&b'-') {
+                if esperados.contains(&codigo) {
+                    return Ok(texto);
+                }
+                return Err(CorreoError::Smtp {
+                    codigo,
+                    mensaje: texto.trim_end().to_string(),
+                });
+            }
+        }
+    }
+
+    async fn comando(&mut self, linea: &str, esperados: &[u16]) -> Result<String, CorreoError> {
+        let flujo = self.flujo.get_mut();
+        flujo.write_all(linea.as_bytes()).await?;
+        flujo.write_all(b"\r\n").await?;
+        flujo.flush().await?;
+        self.esperar(esperados).await
+    }
+}
+
+fn conector_tls() -> Result<TlsConnector, CorreoError> {
+    let raices = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    // Proveedor explícito: rustls llega con ring y aws-lc-rs por otras dependencias
+    let config = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| CorreoError::Tls(e.to_string()))?
+        .with_root_certificates(raices)
+        .with_no_client_auth();
+    Ok(TlsConnector::from(Arc::new(config)))
+}
+
+/// "Nombre <correo@dominio>" → "correo@dominio"
+fn direccion(valor: &str) -> &str {
+    match (valor.find('<'), valor.rfind('>')) {
+        (Some(inicio), Some(fin)) if inicio < fin => valor[inicio + 1..fin].trim(),
+        _ => valor.trim(),
+    }
+}
+
+fn dominio(valor: &str) -> &str {
+    direccion(valor)
+        .rsplit_once('@')
+        .map(|(_, dominio)| dominio)
+        .unwrap_or("localhost")
+}
+
+/// Texto de cabecera: tal cual si es ASCII, si no como encoded-word (RFC 2047)
+fn cabecera(valor: &str) -> String {
+    if valor.is_ascii() {
+        valor.to_string()
+    } else {
+        format!("=?UTF-8?B?{}?=", STANDARD.encode(valor))
+    }
+}
+
+/// Remitente con el nombre codificado si lleva tildes
+fn cabecera_remitente(valor: &str) -> String {
+    match valor.find('<') {
+        Some(inicio) if inicio > 0 => format!(
+            "{} <{}>",
+            cabecera(valor[..inicio].trim()),
+            direccion(valor)
+        ),
+        _ => direccion(valor).to_string(),
+    }
+}
+
+/// Mensaje RFC 5322 con cuerpo UTF-8 en base64 (líneas CRLF)
+fn formatear(remitente: &str, correo: &Correo) -> Result<String, CorreoError> {
+    for valor in [remitente, &correo.para, &correo.asunto] {
+        if valor.contains(['\r', '\n']) {
+            return Err(CorreoError::CabeceraInvalida(
+                valor.escape_debug().to_string(),
+            ));
+        }
+    }
+    let para = direccion(&correo.para);
+    if !para.contains('@') || para.contains(['<', '>', ' ']) {
+        return Err(CorreoError::CabeceraInvalida(para.to_string()));
+    }
+
+    let cuerpo = STANDARD.encode(correo.texto.replace("\r\n", "\n").replace('\n', "\r\n"));
+    let lineas = cuerpo
+        .as_bytes()
+        .chunks(LINEA_BASE64)
+        .map(|linea| String::from_utf8_lossy(linea).into_owned())
+        .collect::<Vec<_>>();
+
+    Ok([
+        format!("From: {}", cabecera_remitente(remitente)),
+        format!("To: {}", para),
+        format!("Subject: {}", cabecera(&correo.asunto)),
+        format!("Date: {}", Utc::now().to_rfc2822()),
+        format!(
+            "Message-ID: <{}@{}>",
+            Uuid::new_v4().simple(),
+            dominio(remitente)
+        ),
+        "MIME-Version: 1.0".to_string(),
+        "Content-Type: text/plain; charset=UTF-8".to_string(),
+        "Content-Transfer-Encoding: base64".to_string(),
+        String::new(),
+        lineas.join("\r\n"),
+    ]
+    .join("\r\n"))
+}
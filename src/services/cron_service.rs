@@ -1,8 +1,20 @@
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
 
 use tokio::task::JoinHandle;
 
+/// Identificadores de los jobs periódicos de la aplicación
+pub const JOB_LIMPIEZA_LOGS: i32 = 1;
+pub const JOB_PURGA_PAPELERA: i32 = 2;
+pub const JOB_LIMPIEZA_LIMITES: i32 = 3;
+pub const JOB_PURGA_TOKENS_CUENTA: i32 = 4;
+pub const JOB_COLA_NOTIFICACIONES: i32 = 5;
+pub const JOB_RESUMEN_NOTIFICACIONES: i32 = 6;
+pub const JOB_PUBLICACION_PROGRAMADA: i32 = 7;
+pub const JOB_LIBERACION_CONDICIONAL: i32 = 8;
+
 /// Registro global de jobs corriendo. Cada job se gestiona con un JoinHandle.
 static JOBS: OnceLock<Mutex<HashMap<i32, JoinHandle<()>>>> = OnceLock::new();
 
@@ -10,6 +22,36 @@ fn get_jobs_map() -> &'static Mutex<HashMap<i32, JoinHandle<()>>> {
     JOBS.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
+/// Programa una tarea periódica y la registra en JOBS (reemplaza un job previo con el mismo id).
+/// El primer tick se ejecuta inmediatamente.
+pub fn programar_tarea<F, Fut>(job_id: i32, intervalo: Duration, tarea: F)
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let handle = tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(intervalo);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            ticker.tick().await;
+            tarea().await;
+        }
+    });
+
+    match get_jobs_map().lock() {
+        Ok(mut jobs) => {
+            if let Some(anterior) = jobs.insert(job_id, handle) {
+                anterior.abort();
+            }
+            tracing::info!("⏰ Job {} programado cada {:?}", job_id, intervalo);
+        }
+        Err(err) => {
+            tracing::error!(error = %err, "Failed to acquire JOBS lock (schedule)");
+            handle.abort();
+        }
+    }
+}
+
 /// Función estática para obtener el número de jobs activos
 pub fn get_jobs_count() -> usize {
     match get_jobs_map().lock() {
@@ -0,0 +1,319 @@
+use std::sync::Arc;
+
+use axum::extract::FromRef;
+use chrono::{Duration, Utc};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, QueryFilter, QuerySelect, Set, TransactionTrait,
+};
+
+use crate::{
+    config::CuentaConfig,
+    database::DbExecutor,
+    models::{
+        token_cuenta::{self, ContrasenaConToken, Entity as TokenCuenta, TipoTokenCuenta},
+        usuario::{self, Entity as Usuario, Model as UsuarioModel},
+        AppState,
+    },
+    services::correo_service::{Correo, Mailer},
+    utils::{errors::AppError, password, tokens},
+};
+
+/// Mínimo entre dos enlaces de recuperación de la misma cuenta (evita inundar el buzón)
+const ESPERA_ENTRE_RECUPERACIONES_SECS: i64 = 60;
+
+/// Días que se conservan los tokens vencidos o usados antes de purgarlos
+const RETENCION_TOKENS_DIAS: i64 = 7;
+
+const ENLACE_INVALIDO: &str = "El enlace no es válido o ya expiró";
+
+/// Activación de cuentas creadas por un administrador y recuperación de contraseña.
+/// Los enlaces llevan un token de un solo uso; en `tokens_cuenta` queda solo su hash
+#[derive(Clone)]
+pub struct CuentaService {
+    db: DbExecutor,
+    mailer: Arc<dyn Mailer>,
+    config: CuentaConfig,
+}
+
+impl FromRef<AppState> for CuentaService {
+    fn from_ref(state: &AppState) -> Self {
+        let executor = state
+            .db
+            .clone()
+            .expect("Database connection is not available");
+        CuentaService::new(
+            executor,
+            Arc::clone(&state.mailer),
+            state.config.cuenta.clone(),
+        )
+    }
+}
+
+impl CuentaService {
+    pub fn new(db: DbExecutor, mailer: Arc<dyn Mailer>, config: CuentaConfig) -> Self {
+        Self { db, mailer, config }
+    }
+
+    fn connection(&self) -> DatabaseConnection {
+        self.db.connection()
+    }
+
+    /// Envía el enlace de activación de una cuenta pendiente (`token_primer_ingreso`)
+    pub async fn enviar_activacion(&self, usuario: &UsuarioModel) -> Result<(), AppError> {
+        let token = self
+            .emitir(
+                usuario.id,
+                TipoTokenCuenta::Activacion,
+                Duration::hours(self.config.activacion_horas),
+            )
+            .await?;
+        let enlace = format!(
+            "{}/activar-cuenta?token={}",
+            self.config.frontend_url, token
+        );
+
+        self.enviar(Correo {
+            para: usuario.correo.clone(),
+            asunto: "Activa tu cuenta del Aula Virtual".to_string(),
+            texto: format!(
+                "Hola {},\n\n\
+                 Se creó una cuenta a tu nombre en el Aula Virtual. Para activarla y elegir \
+                 tu contraseña abre este enlace (válido por {} horas):\n\n{}\n\n\
+                 Si no esperabas este correo, ignóralo.",
+                usuario.nombre, self.config.activacion_horas, enlace
+            ),
+        })
+        .await
+    }
+
+    /// Reenvía la activación; el enlace anterior deja de servir
+    pub async fn reenviar_activacion(&self, usuario_id: i32) -> Result<(), AppError> {
+        let usuario = Usuario::find_by_id(usuario_id)
+            .filter(usuario::Column::FechaEliminacion.is_null())
+            .one(&self.connection())
+            .await?
+            .ok_or_else(|| AppError::NotFound("Usuario no encontrado".into()))?;
+
+        if usuario.token_primer_ingreso.is_none() {
+            return Err(AppError::Conflict("La cuenta ya está activada".into()));
+        }
+        self.enviar_activacion(&usuario).await
+    }
+
+    /// "Olvidé mi contraseña": responde igual exista o no la cuenta y envía el
+    /// correo en segundo plano para no delatar por tiempo de respuesta
+    pub fn solicitar_recuperacion(&self, correo: String) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.procesar_recuperacion(correo.trim()).await {
+                tracing::warn!(
+                    "⚠️  No se pudo procesar la recuperación de contraseña: {}",
+                    e
+                );
+            }
+        });
+    }
+
+    async fn procesar_recuperacion(&self, correo: &str) -> Result<(), AppError> {
+        let db = self.connection();
+        let Some(usuario) = Usuario::find()
+            .filter(usuario::Column::Correo.eq(correo))
+            .filter(usuario::Column::Estado.eq(true))
+            .filter(usuario::Column::FechaEliminacion.is_null())
+            .one(&db)
+            .await?
+        else {
+            tracing::info!(
+                "Recuperación de contraseña pedida para una cuenta inexistente o inactiva"
+            );
+            return Ok(());
+        };
+
+        // Una cuenta sin activar no tiene contraseña que recuperar: se reenvía la activación
+        if usuario.token_primer_ingreso.is_some() {
+            return self.enviar_activacion(&usuario).await;
+        }
+
+        let reciente = TokenCuenta::find()
+            .filter(token_cuenta::Column::UsuarioId.eq(usuario.id))
+            .filter(token_cuenta::Column::Tipo.eq(TipoTokenCuenta::Recuperacion))
+            .filter(
+                token_cuenta::Column::FechaCreacion
+                    .gt(Utc::now() - Duration::seconds(ESPERA_ENTRE_RECUPERACIONES_SECS)),
+            )
+            .one(&db)
+            .await?;
+        if reciente.is_some() {
+            return Ok(());
+        }
+
+        let token = self
+            .emitir(
+                usuario.id,
+                TipoTokenCuenta::Recuperacion,
+                Duration::minutes(self.config.recuperacion_minutos),
+            )
+            .await?;
+        let enlace = format!(
+            "{}/restablecer-contrasena?token={}",
+            self.config.frontend_url, token
+        );
+
+        self.enviar(Correo {
+            para: usuario.correo,
+            asunto: "Restablece tu contraseña del Aula Virtual".to_string(),
+            texto: format!(
+                "Hola {},\n\n\
+                 Recibimos una solicitud para restablecer tu contraseña. Abre este enlace \
+                 (válido por {} minutos y de un solo uso):\n\n{}\n\n\
+                 Si no fuiste tú, ignora este correo: tu contraseña no cambiará.",
+                usuario.nombre, self.config.recuperacion_minutos, enlace
+            ),
+        })
+        .await
+    }
+
+    /// Activa la cuenta con la contraseña elegida por el usuario
+    pub async fn activar(&self, datos: ContrasenaConToken) -> Result<(), AppError> {
+        let usuario = self.consumir(TipoTokenCuenta::Activacion, datos).await?;
+        tracing::info!("✅ Cuenta {} activada", usuario.id);
+        Ok(())
+    }
+
+    /// Cambia la contraseña con un enlace de recuperación y avisa por correo
+    pub async fn restablecer(&self, datos: ContrasenaConToken) -> Result<(), AppError> {
+        let usuario = self.consumir(TipoTokenCuenta::Recuperacion, datos).await?;
+        tracing::info!("🔑 Contraseña restablecida para la cuenta {}", usuario.id);
+
+        // Aviso informativo: si falla, el cambio ya quedó hecho
+        let _ = self
+            .enviar(Correo {
+                para: usuario.correo,
+                asunto: "Tu contraseña del Aula Virtual cambió".to_string(),
+                texto: format!(
+                    "Hola {},\n\n\
+                     La contraseña de tu cuenta se cambió el {} (UTC). Si no fuiste tú, \
+                     solicita un nuevo restablecimiento y avisa al administrador.",
+                    usuario.nombre,
+                    Utc::now().format("%Y-%m-%d %H:%M")
+                ),
+            })
+            .await;
+        Ok(())
+    }
+
+    /// Borra los tokens vencidos o usados hace más de una semana
+    pub async fn purgar_tokens(&self) -> Result<u64, AppError> {
+        let limite = Utc::now() - Duration::days(RETENCION_TOKENS_DIAS);
+        let resultado = TokenCuenta::delete_many()
+            .filter(
+                token_cuenta::Column::FechaExpiracion
+                    .lt(limite)
+                    .or(token_cuenta::Column::FechaUso.lt(limite)),
+            )
+            .exec(&self.connection())
+            .await?;
+        Ok(resultado.rows_affected)
+    }
+
+    /// Invalida los enlaces pendientes del mismo tipo y crea uno nuevo;
+    /// devuelve el token en claro (solo viaja en el correo)
+    async fn emitir(
+        &self,
+        usuario_id: i32,
+        tipo: TipoTokenCuenta,
+        vigencia: Duration,
+    ) -> Result<String, AppError> {
+        let token = tokens::generar_token();
+        let ahora = Utc::now();
+
+        let txn = self.connection().begin().await?;
+        TokenCuenta::update_many()
+            .col_expr(token_cuenta::Column::FechaUso, Expr::value(ahora))
+            .filter(token_cuenta::Column::UsuarioId.eq(usuario_id))
+            .filter(token_cuenta::Column::Tipo.eq(tipo))
+            .filter(token_cuenta::Column::FechaUso.is_null())
+            .exec(&txn)
+            .await?;
+        token_cuenta::ActiveModel {
+            usuario_id: Set(usuario_id),
+            tipo: Set(tipo),
+            token_hash: Set(tokens::hash_token(&token)),
+            fecha_expiracion: Set(ahora + vigencia),
+            fecha_uso: Set(None),
+            fecha_creacion: Set(ahora),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+
+        Ok(token)
+    }
+
+    /// Valida el token, aplica la política y guarda la contraseña (bcrypt). El token
+    /// y cualquier otro enlace pendiente de la cuenta quedan usados; si la contraseña
+    /// no cumple la política el token sigue vigente para reintentar
+    async fn consumir(
+        &self,
+        tipo: TipoTokenCuenta,
+        datos: ContrasenaConToken,
+    ) -> Result<UsuarioModel, AppError> {
+        let txn = self.connection().begin().await?;
+
+        let registro = TokenCuenta::find()
+            .filter(token_cuenta::Column::TokenHash.eq(tokens::hash_token(datos.token.trim())))
+            .filter(token_cuenta::Column::Tipo.eq(tipo))
+            .filter(token_cuenta::Column::FechaUso.is_null())
+            .filter(token_cuenta::Column::FechaExpiracion.gt(Utc::now()))
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or_else(|| AppError::BadRequest(ENLACE_INVALIDO.into()))?;
+
+        let usuario = Usuario::find_by_id(registro.usuario_id)
+            .filter(usuario::Column::FechaEliminacion.is_null())
+            .one(&txn)
+            .await?
+            .ok_or_else(|| AppError::BadRequest(ENLACE_INVALIDO.into()))?;
+
+        password::validar_politica(
+            &datos.contrasena,
+            self.config.contrasena_min,
+            &[&usuario.correo, &usuario.documento_nit],
+        )?;
+        let hash = password::hashear(&datos.contrasena).await?;
+
+        let ahora = Utc::now();
+        let mut activo = usuario.into_active_model();
+        activo.contrasena = Set(hash);
+        activo.token_primer_ingreso = Set(None);
+        activo.intentos_fallidos = Set(0);
+        activo.bloqueado_hasta = Set(None);
+        activo.fecha_actualizacion = Set(ahora);
+        let usuario = activo.update(&txn).await?;
+
+        TokenCuenta::update_many()
+            .col_expr(token_cuenta::Column::FechaUso, Expr::value(ahora))
+            .filter(token_cuenta::Column::UsuarioId.eq(usuario.id))
+            .filter(token_cuenta::Column::FechaUso.is_null())
+            .exec(&txn)
+            .await?;
+        txn.commit().await?;
+
+        Ok(usuario)
+    }
+
+    async fn enviar(&self, correo: Correo) -> Result<(), AppError> {
+        self.mailer.enviar(&correo).await.map_err(|e| {
+            tracing::error!(
+                "❌ Error enviando '{}' a {}: {}",
+                correo.asunto,
+                correo.para,
+                e
+            );
+            AppError::ServiceUnavailable("No se pudo enviar el correo, intenta más tarde".into())
+        })
+    }
+}
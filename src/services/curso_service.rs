@@ -9,17 +9,26 @@ use crate::{
     database::DbExecutor,
     models::{
         area_conocimiento::Entity as AreaConocimientoEntity,
+        contenido_unidad::{self, Entity as Contenido, Model as ContenidoModel},
         curso::{self, Entity as Curso, Model as CursoModel},
         modulo::{self},
+        publicacion::TipoPublicable,
         tema::{self, Entity as Tema, Model as TemaModel},
         unidad::{self, Entity as Unidad, Model as UnidadModel},
         usuario::Entity as Usuario,
         AppState,
     },
+    services::{
+        auditoria_service::{AuditoriaService, ContextoAuditoria},
+        liberacion_service::{Bloqueos, LiberacionService},
+        papelera_service::{PapeleraService, TipoPapelera},
+    },
     utils::errors::AppError,
 };
 
-pub use crate::models::curso::{ActualizarCurso, AulaCurso, CursoDetallado, NuevoCurso, TemaAula, UnidadAula};
+pub use crate::models::curso::{
+    ActualizarCurso, AulaCurso, ContenidoAula, CursoDetallado, FiltroAula, NuevoCurso, TemaAula, UnidadAula, VistaAula,
+};
 
 #[derive(Debug, Clone)]
 pub struct CursoService {
@@ -48,6 +57,7 @@ impl CursoService {
     pub async fn obtener_cursos(&self) -> Result<Vec<CursoDetallado>, AppError> {
         let db = self.connection();
         let cursos = Curso::find()
+            .filter(curso::Column::FechaEliminacion.is_null())
             .order_by_desc(curso::Column::CreadoEn)
             .find_also_related(AreaConocimientoEntity)
             .all(&db)
@@ -63,6 +73,7 @@ impl CursoService {
     pub async fn obtener_curso_por_id(&self, id: i32) -> Result<CursoDetallado, AppError> {
         let db = self.connection();
         let result = Curso::find_by_id(id)
+            .filter(curso::Column::FechaEliminacion.is_null())
             .find_also_related(AreaConocimientoEntity)
             .one(&db)
             .await
@@ -77,7 +88,11 @@ impl CursoService {
         })
     }
 
-    pub async fn crear_curso(&self, datos: NuevoCurso) -> Result<CursoModel, AppError> {
+    pub async fn crear_curso(
+        &self,
+        datos: NuevoCurso,
+        ctx: &ContextoAuditoria,
+    ) -> Result<CursoModel, AppError> {
         if datos.nombre.trim().is_empty() {
             return Err(AppError::BadRequest(
                 "El nombre del curso es obligatorio".into(),
@@ -138,10 +153,25 @@ impl CursoService {
             area_conocimiento_id: Set(datos.area_conocimiento_id),
             fecha_eliminacion: Set(None),
             fecha_actualizacion: Set(ahora),
+            cupo_maximo: Set(None),
+            fecha_apertura_matricula: Set(None),
+            fecha_cierre_matricula: Set(None),
         };
 
         let curso_creado = curso_activo.insert(&mut txn).await.map_err(map_db_err)?;
 
+        AuditoriaService::registrar(
+            &txn,
+            ctx,
+            "crear",
+            "curso",
+            Some(curso_creado.id),
+            None,
+            Some(&curso_creado),
+        )
+        .await
+        .map_err(map_db_err)?;
+
         txn.commit().await.map_err(map_db_err)?;
 
         Ok(curso_creado)
@@ -151,15 +181,18 @@ impl CursoService {
         &self,
         id: i32,
         datos: ActualizarCurso,
+        ctx: &ContextoAuditoria,
     ) -> Result<CursoModel, AppError> {
         let db = self.connection();
         let mut txn = db.begin().await.map_err(map_db_err)?;
 
         let mut curso_model = Curso::find_by_id(id)
+            .filter(curso::Column::FechaEliminacion.is_null())
             .one(&mut txn)
             .await
             .map_err(map_db_err)?
             .ok_or_else(|| AppError::NotFound("Curso no encontrado".into()))?;
+        let curso_original = curso_model.clone();
 
         if let Some(nombre) = datos.nombre {
             if nombre.trim().is_empty() {
@@ -232,25 +265,57 @@ impl CursoService {
         let curso_activo: curso::ActiveModel = curso_model.into();
         let curso_actualizado = curso_activo.update(&mut txn).await.map_err(map_db_err)?;
 
+        AuditoriaService::registrar(
+            &txn,
+            ctx,
+            "actualizar",
+            "curso",
+            Some(id),
+            Some(&curso_original),
+            Some(&curso_actualizado),
+        )
+        .await
+        .map_err(map_db_err)?;
+
         txn.commit().await.map_err(map_db_err)?;
 
         Ok(curso_actualizado)
     }
 
-    pub async fn eliminar_curso(&self, id: i32) -> Result<(), AppError> {
+    pub async fn eliminar_curso(&self, id: i32, ctx: &ContextoAuditoria) -> Result<(), AppError> {
         let db = self.connection();
         let mut txn = db.begin().await.map_err(map_db_err)?;
 
         let mut curso = Curso::find_by_id(id)
+            .filter(curso::Column::FechaEliminacion.is_null())
             .one(&mut txn)
             .await
             .map_err(map_db_err)?
             .ok_or_else(|| AppError::NotFound("Curso no encontrado".into()))?;
 
-        curso.fecha_eliminacion = Some(Utc::now());
+        let curso_original = curso.clone();
+        let ahora = Utc::now();
+        curso.fecha_eliminacion = Some(ahora);
         let curso_activo: curso::ActiveModel = curso.into();
-        curso_activo.update(&mut txn).await.map_err(map_db_err)?;
-        
+        let curso_eliminado = curso_activo.update(&mut txn).await.map_err(map_db_err)?;
+
+        // Módulos, exámenes y su contenido pasan a la papelera con la misma fecha
+        PapeleraService::mover_a_papelera(&txn, TipoPapelera::Curso, id, ahora)
+            .await
+            .map_err(map_db_err)?;
+
+        AuditoriaService::registrar(
+            &txn,
+            ctx,
+            "eliminar",
+            "curso",
+            Some(id),
+            Some(&curso_original),
+            Some(&curso_eliminado),
+        )
+        .await
+        .map_err(map_db_err)?;
+
         txn.commit().await.map_err(map_db_err)?;
         Ok(())
     }
@@ -261,6 +326,7 @@ impl CursoService {
     ) -> Result<Vec<CursoModel>, AppError> {
         let db = self.connection();
         let cursos = Curso::find()
+            .filter(curso::Column::FechaEliminacion.is_null())
             .filter(curso::Column::PlantillaBaseId.eq(plantilla_id))
             .all(&db)
             .await
@@ -276,6 +342,7 @@ impl CursoService {
     ) -> Result<Vec<CursoDetallado>, AppError> {
         let db = self.connection();
         let cursos = Curso::find()
+            .filter(curso::Column::FechaEliminacion.is_null())
             .filter(curso::Column::AreaConocimientoId.eq(area_conocimiento_id))
             .filter(curso::Column::Periodo.eq(periodo))
             .find_also_related(AreaConocimientoEntity)
@@ -289,23 +356,50 @@ impl CursoService {
         Ok(cursos)
     }
 
-    pub async fn obtener_aula_por_curso_id(&self, id: i32) -> Result<AulaCurso, AppError> {
+    /// Temas y unidades del curso. En la vista de estudiante solo aparece lo publicado
+    /// (incluido el módulo que los contiene); la docente muestra también los borradores
+    /// Árbol del aula. En la vista estudiante se ocultan los borradores y se evalúan
+    /// las condiciones de liberación para `usuario_id`
+    pub async fn obtener_aula_por_curso_id(
+        &self,
+        id: i32,
+        vista: VistaAula,
+        usuario_id: i32,
+    ) -> Result<AulaCurso, AppError> {
         let db = self.connection();
 
         // Obtener curso para incluirlo en la respuesta
         let curso = Curso::find_by_id(id)
+            .filter(curso::Column::FechaEliminacion.is_null())
             .one(&db)
             .await
             .map_err(map_db_err)?
             .ok_or_else(|| AppError::NotFound("Curso no encontrado".into()))?;
 
+        let solo_publicado = vista == VistaAula::Estudiante;
+        let bloqueos = if solo_publicado {
+            LiberacionService::new(self.db.clone())
+                .bloqueos(id, usuario_id)
+                .await?
+        } else {
+            Bloqueos::default()
+        };
+
         // Obtener todos los temas de los módulos que pertenecen al curso dado
-        let temas: Vec<TemaModel> = Tema::find()
+        let mut consulta_temas = Tema::find()
             .join(
                 sea_orm::JoinType::InnerJoin,
                 tema::Relation::Modulo.def(),
             )
             .filter(modulo::Column::CursoId.eq(id))
+            .filter(modulo::Column::FechaEliminacion.is_null())
+            .filter(tema::Column::FechaEliminacion.is_null());
+        if solo_publicado {
+            consulta_temas = consulta_temas
+                .filter(modulo::Column::Visible.eq(true))
+                .filter(tema::Column::Visible.eq(true));
+        }
+        let temas: Vec<TemaModel> = consulta_temas
             .order_by(tema::Column::Orden, Order::Asc)
             .all(&db)
             .await
@@ -316,43 +410,126 @@ impl CursoService {
         let unidades: Vec<UnidadModel> = if tema_ids.is_empty() {
             Vec::new()
         } else {
-            Unidad::find()
+            let mut consulta_unidades = Unidad::find()
                 .filter(unidad::Column::TemaId.is_in(tema_ids.clone()))
+                .filter(unidad::Column::FechaEliminacion.is_null());
+            if solo_publicado {
+                consulta_unidades = consulta_unidades.filter(unidad::Column::Visible.eq(true));
+            }
+            consulta_unidades
                 .order_by(unidad::Column::Orden, Order::Asc)
                 .all(&db)
                 .await
                 .map_err(map_db_err)?
         };
 
-        let mut unidades_por_tema: std::collections::HashMap<i32, Vec<UnidadAula>> =
+        let unidad_ids: Vec<i32> = unidades.iter().map(|u| u.id).collect();
+
+        let contenidos: Vec<ContenidoModel> = if unidad_ids.is_empty() {
+            Vec::new()
+        } else {
+            let mut consulta_contenidos = Contenido::find()
+                .filter(contenido_unidad::Column::UnidadId.is_in(unidad_ids))
+                .filter(contenido_unidad::Column::FechaEliminacion.is_null());
+            if solo_publicado {
+                consulta_contenidos =
+                    consulta_contenidos.filter(contenido_unidad::Column::Visible.eq(true));
+            }
+            consulta_contenidos
+                .order_by(contenido_unidad::Column::Orden, Order::Asc)
+                .all(&db)
+                .await
+                .map_err(map_db_err)?
+        };
+
+        // Los motivos de bloqueo se heredan: módulo → tema → unidad → contenido
+        let motivos = |heredados: &[String], tipo: TipoPublicable, id: i32| -> Vec<String> {
+            heredados
+                .iter()
+                .chain(bloqueos.motivos(tipo, id))
+                .cloned()
+                .collect()
+        };
+
+        let mut contenidos_por_unidad: std::collections::HashMap<i32, Vec<ContenidoModel>> =
             std::collections::HashMap::new();
+        for c in contenidos {
+            contenidos_por_unidad.entry(c.unidad_id).or_default().push(c);
+        }
 
+        let mut unidades_por_tema: std::collections::HashMap<i32, Vec<UnidadModel>> =
+            std::collections::HashMap::new();
         for u in unidades {
-            let entry = unidades_por_tema.entry(u.tema_id).or_default();
-            entry.push(UnidadAula {
-                id: u.id,
-                nombre: u.nombre,
-                descripcion: u.descripcion,
-                orden: Some(u.orden),
-                tema_id: u.tema_id,
-            });
+            unidades_por_tema.entry(u.tema_id).or_default().push(u);
         }
 
         let temas_aula: Vec<TemaAula> = temas
             .into_iter()
             .map(|t| {
-                let unidades = unidades_por_tema.remove(&t.id).unwrap_or_default();
+                let motivos_modulo = motivos(&[], TipoPublicable::Modulo, t.modulo_id);
+                let motivos_tema = motivos(&motivos_modulo, TipoPublicable::Tema, t.id);
+                let unidades = unidades_por_tema
+                    .remove(&t.id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|u| {
+                        let motivos_unidad = motivos(&motivos_tema, TipoPublicable::Unidad, u.id);
+                        let contenidos = contenidos_por_unidad
+                            .remove(&u.id)
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|c| {
+                                let motivos_bloqueo =
+                                    motivos(&motivos_unidad, TipoPublicable::Contenido, c.id);
+                                ContenidoAula {
+                                    id: c.id,
+                                    titulo: c.titulo,
+                                    tipo_contenido: c.tipo_contenido,
+                                    orden: c.orden,
+                                    estado_publicacion: c.estado_publicacion,
+                                    publicar_en: c.publicar_en,
+                                    despublicar_en: c.despublicar_en,
+                                    completado: bloqueos.completados.contains(&c.id),
+                                    bloqueado: !motivos_bloqueo.is_empty(),
+                                    motivos_bloqueo,
+                                }
+                            })
+                            .collect();
+                        UnidadAula {
+                            id: u.id,
+                            nombre: u.nombre,
+                            descripcion: u.descripcion,
+                            orden: Some(u.orden),
+                            tema_id: u.tema_id,
+                            estado_publicacion: u.estado_publicacion,
+                            publicar_en: u.publicar_en,
+                            despublicar_en: u.despublicar_en,
+                            bloqueado: !motivos_unidad.is_empty(),
+                            motivos_bloqueo: motivos_unidad,
+                            contenidos,
+                        }
+                    })
+                    .collect();
                 TemaAula {
                     id: t.id,
                     nombre: t.nombre,
                     descripcion: t.descripcion,
                     orden: Some(t.orden),
+                    estado_publicacion: t.estado_publicacion,
+                    publicar_en: t.publicar_en,
+                    despublicar_en: t.despublicar_en,
+                    bloqueado: !motivos_tema.is_empty(),
+                    motivos_bloqueo: motivos_tema,
                     unidades,
                 }
             })
             .collect();
 
-        Ok(AulaCurso { curso, temas: temas_aula })
+        Ok(AulaCurso {
+            curso,
+            vista,
+            temas: temas_aula,
+        })
     }
 }
 
@@ -0,0 +1,320 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, OnceLock},
+};
+
+use chrono::{Duration, Timelike, Utc};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+
+use crate::{
+    config::NotificacionesConfig,
+    database::DbExecutor,
+    models::{
+        envio_notificacion::{self, Entity as Envio, EstadoEnvio, Model as EnvioModel},
+        notificacion::{self, Entity as Notificacion},
+        usuario::{self, Entity as Usuario},
+    },
+    services::correo_service::{Correo, CorreoError, Mailer},
+    utils::{errors::AppError, plantillas_notificacion},
+};
+
+/// Correos que se toman de la cola en cada pasada
+const LOTE_COLA: u64 = 50;
+
+/// Tiempo que un correo reclamado queda fuera de la cola mientras se envía
+const RECLAMO_SECS: i64 = 300;
+
+/// Días que se conservan los envíos terminados (enviados o fallidos)
+const RETENCION_ENVIOS_DIAS: i64 = 30;
+
+/// Servicio de entrega registrado al iniciar (solo si hay base de datos)
+static ENTREGA: OnceLock<EntregaNotificacionService> = OnceLock::new();
+
+pub fn inicializar(service: EntregaNotificacionService) {
+    let _ = ENTREGA.set(service);
+}
+
+/// Procesa la cola en segundo plano sin esperar al próximo tick del job
+pub fn despachar() {
+    if let Some(service) = ENTREGA.get() {
+        let service = service.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.procesar_cola().await {
+                tracing::warn!("⚠️  Error procesando la cola de notificaciones: {}", e);
+            }
+        });
+    }
+}
+
+/// Espera antes del siguiente intento: 2, 4, 8... minutos
+fn espera(intentos: i32) -> Duration {
+    Duration::minutes(1_i64 << intentos.clamp(1, 10))
+}
+
+/// Envío por correo de las notificaciones encoladas en `envios_notificacion`:
+/// las inmediatas con reintentos y las demás en un resumen diario por usuario
+#[derive(Clone)]
+pub struct EntregaNotificacionService {
+    db: DbExecutor,
+    mailer: Arc<dyn Mailer>,
+    config: NotificacionesConfig,
+    frontend_url: String,
+    utc_offset_secs: i32,
+}
+
+impl EntregaNotificacionService {
+    pub fn new(
+        db: DbExecutor,
+        mailer: Arc<dyn Mailer>,
+        config: NotificacionesConfig,
+        frontend_url: String,
+        utc_offset_secs: i32,
+    ) -> Self {
+        Self {
+            db,
+            mailer,
+            config,
+            frontend_url,
+            utc_offset_secs,
+        }
+    }
+
+    fn connection(&self) -> DatabaseConnection {
+        self.db.connection()
+    }
+
+    /// Envía los correos inmediatos pendientes (nuevos o con reintento vencido);
+    /// devuelve cuántos salieron
+    pub async fn procesar_cola(&self) -> Result<u64, AppError> {
+        let db = self.connection();
+        let candidatos = Envio::find()
+            .filter(envio_notificacion::Column::Estado.eq(EstadoEnvio::Pendiente))
+            .filter(envio_notificacion::Column::ProximoIntento.lte(Utc::now()))
+            .order_by_asc(envio_notificacion::Column::ProximoIntento)
+            .limit(LOTE_COLA)
+            .all(&db)
+            .await?;
+
+        let mut enviados = 0;
+        for envio in candidatos {
+            if !self.reclamar(&envio).await? {
+                continue;
+            }
+
+            let notificacion = Notificacion::find_by_id(envio.notificacion_id)
+                .one(&db)
+                .await?;
+            let usuario = self.destinatario(envio.usuario_id).await?;
+            let (Some(notificacion), Some(usuario)) = (notificacion, usuario) else {
+                self.descartar(&[envio]).await?;
+                continue;
+            };
+
+            let (asunto, texto) = plantillas_notificacion::correo_inmediato(
+                &usuario.nombre,
+                &notificacion,
+                &self.frontend_url,
+            );
+            let resultado = self
+                .mailer
+                .enviar(&Correo {
+                    para: usuario.correo,
+                    asunto,
+                    texto,
+                })
+                .await;
+            if resultado.is_ok() {
+                enviados += 1;
+            }
+            self.registrar(&[envio], resultado).await?;
+        }
+
+        Ok(enviados)
+    }
+
+    /// Job horario: a la hora del resumen (NOTIFICATION_DIGEST_HOUR) envía un correo por
+    /// usuario con sus notificaciones acumuladas y purga envíos antiguos; en las demás
+    /// horas solo reintenta los resúmenes que fallaron
+    pub async fn enviar_resumenes(&self) -> Result<u64, AppError> {
+        let db = self.connection();
+        let ahora = Utc::now();
+        let hora_local = (ahora + Duration::seconds(self.utc_offset_secs.into())).hour();
+        let es_hora = hora_local == self.config.hora_resumen;
+
+        let mut consulta = Envio::find()
+            .filter(envio_notificacion::Column::Estado.eq(EstadoEnvio::Resumen))
+            .filter(envio_notificacion::Column::ProximoIntento.lte(ahora));
+        if !es_hora {
+            consulta = consulta.filter(envio_notificacion::Column::Intentos.gt(0));
+        }
+        let pendientes = consulta
+            .order_by_asc(envio_notificacion::Column::Id)
+            .all(&db)
+            .await?;
+
+        let mut por_usuario: BTreeMap<i32, Vec<EnvioModel>> = BTreeMap::new();
+        for envio in pendientes {
+            por_usuario.entry(envio.usuario_id).or_default().push(envio);
+        }
+
+        let mut enviados = 0;
+        for (usuario_id, envios) in por_usuario {
+            let Some(usuario) = self.destinatario(usuario_id).await? else {
+                self.descartar(&envios).await?;
+                continue;
+            };
+            let notificaciones = Notificacion::find()
+                .filter(notificacion::Column::Id.is_in(envios.iter().map(|e| e.notificacion_id)))
+                .order_by_asc(notificacion::Column::CreatedAt)
+                .all(&db)
+                .await?;
+            if notificaciones.is_empty() {
+                self.descartar(&envios).await?;
+                continue;
+            }
+
+            let (asunto, texto) = plantillas_notificacion::correo_resumen(
+                &usuario.nombre,
+                &notificaciones,
+                &self.frontend_url,
+                self.utc_offset_secs,
+            );
+            let resultado = self
+                .mailer
+                .enviar(&Correo {
+                    para: usuario.correo,
+                    asunto,
+                    texto,
+                })
+                .await;
+            if resultado.is_ok() {
+                enviados += 1;
+            }
+            self.registrar(&envios, resultado).await?;
+        }
+
+        if es_hora {
+            let purgados = Envio::delete_many()
+                .filter(
+                    envio_notificacion::Column::Estado
+                        .is_in([EstadoEnvio::Enviado, EstadoEnvio::Fallido]),
+                )
+                .filter(
+                    envio_notificacion::Column::FechaCreacion
+                        .lt(ahora - Duration::days(RETENCION_ENVIOS_DIAS)),
+                )
+                .exec(&db)
+                .await?;
+            if purgados.rows_affected > 0 {
+                tracing::info!(
+                    "📭 {} envíos de notificaciones antiguos purgados",
+                    purgados.rows_affected
+                );
+            }
+        }
+
+        Ok(enviados)
+    }
+
+    /// Aparta el envío de la cola mientras se procesa; false si otra pasada lo tomó antes
+    async fn reclamar(&self, envio: &EnvioModel) -> Result<bool, AppError> {
+        let resultado = Envio::update_many()
+            .col_expr(
+                envio_notificacion::Column::ProximoIntento,
+                Expr::value(Utc::now() + Duration::seconds(RECLAMO_SECS)),
+            )
+            .filter(envio_notificacion::Column::Id.eq(envio.id))
+            .filter(envio_notificacion::Column::Estado.eq(envio.estado))
+            .filter(envio_notificacion::Column::ProximoIntento.eq(envio.proximo_intento))
+            .exec(&self.connection())
+            .await?;
+        Ok(resultado.rows_affected == 1)
+    }
+
+    /// Cuenta activa que recibe el correo (None si se eliminó o se desactivó)
+    async fn destinatario(&self, usuario_id: i32) -> Result<Option<usuario::Model>, AppError> {
+        Ok(Usuario::find_by_id(usuario_id)
+            .filter(usuario::Column::Estado.eq(true))
+            .filter(usuario::Column::FechaEliminacion.is_null())
+            .one(&self.connection())
+            .await?)
+    }
+
+    /// Marca el resultado del intento: enviado, o reintento con espera creciente
+    /// hasta agotar NOTIFICATION_MAX_RETRIES
+    async fn registrar(
+        &self,
+        envios: &[EnvioModel],
+        resultado: Result<(), CorreoError>,
+    ) -> Result<(), AppError> {
+        let db = self.connection();
+        let ahora = Utc::now();
+
+        for envio in envios {
+            let intentos = envio.intentos + 1;
+            let mut actualizacion = Envio::update_many()
+                .col_expr(envio_notificacion::Column::Intentos, Expr::value(intentos))
+                .filter(envio_notificacion::Column::Id.eq(envio.id));
+
+            actualizacion = match &resultado {
+                Ok(()) => actualizacion
+                    .col_expr(
+                        envio_notificacion::Column::Estado,
+                        Expr::value(EstadoEnvio::Enviado),
+                    )
+                    .col_expr(envio_notificacion::Column::FechaEnvio, Expr::value(ahora))
+                    .col_expr(
+                        envio_notificacion::Column::UltimoError,
+                        Expr::value(Option::<String>::None),
+                    ),
+                Err(e) => {
+                    let agotado = intentos >= self.config.max_reintentos as i32;
+                    tracing::warn!(
+                        "⚠️  Correo de la notificación {} (intento {}{}): {}",
+                        envio.notificacion_id,
+                        intentos,
+                        if agotado { ", sin más reintentos" } else { "" },
+                        e
+                    );
+                    let estado = if agotado {
+                        EstadoEnvio::Fallido
+                    } else {
+                        envio.estado
+                    };
+                    actualizacion
+                        .col_expr(envio_notificacion::Column::Estado, Expr::value(estado))
+                        .col_expr(
+                            envio_notificacion::Column::ProximoIntento,
+                            Expr::value(ahora + espera(intentos)),
+                        )
+                        .col_expr(
+                            envio_notificacion::Column::UltimoError,
+                            Expr::value(e.to_string()),
+                        )
+                }
+            };
+            actualizacion.exec(&db).await?;
+        }
+        Ok(())
+    }
+
+    /// Cierra envíos cuya notificación o destinatario ya no existen
+    async fn descartar(&self, envios: &[EnvioModel]) -> Result<(), AppError> {
+        Envio::update_many()
+            .col_expr(
+                envio_notificacion::Column::Estado,
+                Expr::value(EstadoEnvio::Fallido),
+            )
+            .col_expr(
+                envio_notificacion::Column::UltimoError,
+                Expr::value("La notificación o la cuenta ya no existen"),
+            )
+            .filter(envio_notificacion::Column::Id.is_in(envios.iter().map(|e| e.id)))
+            .exec(&self.connection())
+            .await?;
+        Ok(())
+    }
+}
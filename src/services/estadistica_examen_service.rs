@@ -0,0 +1,493 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use axum::extract::FromRef;
+use chrono::Utc;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use serde_json::Value as JsonValue;
+
+use crate::{
+    database::DbExecutor,
+    models::{
+        intento_examen::{self, Entity as IntentoExamen, EstadoIntento, Model as IntentoModel},
+        pregunta_examen::{Model as PreguntaModel, TipoPregunta},
+        respuesta_examen::{self, Entity as Respuesta, Model as RespuestaModel},
+        AppState,
+    },
+    services::intento_examen_service::{como_lista, examen_del_docente, preguntas_de},
+    utils::errors::AppError,
+};
+
+pub use crate::models::estadistica_examen::{
+    AnalisisPregunta, EstadisticasExamen, FrecuenciaOpcion, RangoHistograma,
+};
+
+/// Rangos del histograma de puntajes
+const RANGOS_HISTOGRAMA: usize = 10;
+
+/// Fracción de estudiantes de cada extremo para el índice de discriminación
+const GRUPO_EXTREMO: f64 = 0.27;
+
+fn redondear(valor: f64) -> f64 {
+    (valor * 10_000.0).round() / 10_000.0
+}
+
+fn media(valores: &[f64]) -> Option<f64> {
+    if valores.is_empty() {
+        return None;
+    }
+    Some(valores.iter().sum::<f64>() / valores.len() as f64)
+}
+
+fn mediana(valores: &[f64]) -> Option<f64> {
+    if valores.is_empty() {
+        return None;
+    }
+    let mut ordenados = valores.to_vec();
+    ordenados.sort_by(f64::total_cmp);
+    let centro = ordenados.len() / 2;
+    Some(if ordenados.len().is_multiple_of(2) {
+        (ordenados[centro - 1] + ordenados[centro]) / 2.0
+    } else {
+        ordenados[centro]
+    })
+}
+
+/// Desviación estándar poblacional
+fn desviacion_estandar(valores: &[f64]) -> Option<f64> {
+    let promedio = media(valores)?;
+    let varianza =
+        valores.iter().map(|v| (v - promedio).powi(2)).sum::<f64>() / valores.len() as f64;
+    Some(varianza.sqrt())
+}
+
+fn histograma(puntajes: &[f64], maximo: f64) -> Vec<RangoHistograma> {
+    if maximo <= 0.0 {
+        return Vec::new();
+    }
+    let ancho = maximo / RANGOS_HISTOGRAMA as f64;
+    let mut rangos: Vec<RangoHistograma> = (0..RANGOS_HISTOGRAMA)
+        .map(|i| RangoHistograma {
+            desde: redondear(ancho * i as f64),
+            hasta: redondear(ancho * (i + 1) as f64),
+            cantidad: 0,
+        })
+        .collect();
+    for puntaje in puntajes {
+        let i = ((puntaje / ancho).floor().max(0.0) as usize).min(RANGOS_HISTOGRAMA - 1);
+        rangos[i].cantidad += 1;
+    }
+    rangos
+}
+
+/// Texto de una opción para el CSV
+fn texto_opcion(opcion: &JsonValue) -> String {
+    match opcion {
+        JsonValue::String(texto) => texto.clone(),
+        otro => otro.to_string(),
+    }
+}
+
+/// Análisis de una pregunta. `intentos` va ordenado de menor a mayor puntaje
+fn analizar(
+    pregunta: PreguntaModel,
+    intentos: &[IntentoModel],
+    respuestas: &HashMap<(i32, i32), RespuestaModel>,
+) -> AnalisisPregunta {
+    let de_intentos: Vec<Option<&RespuestaModel>> = intentos
+        .iter()
+        .map(|i| respuestas.get(&(i.id, pregunta.id)))
+        .collect();
+    let contestadas: Vec<&RespuestaModel> = de_intentos.iter().flatten().copied().collect();
+
+    // Fracción del puntaje de la pregunta en cada intento; sin responder vale 0
+    let fracciones: Vec<f64> = if pregunta.valor_puntos > 0 {
+        de_intentos
+            .iter()
+            .map(|r| r.and_then(|r| r.puntaje).unwrap_or(0.0) / pregunta.valor_puntos as f64)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let discriminacion = if fracciones.len() >= 2 {
+        let grupo = ((fracciones.len() as f64 * GRUPO_EXTREMO).ceil() as usize)
+            .clamp(1, fracciones.len() / 2);
+        let inferior = media(&fracciones[..grupo]);
+        let superior = media(&fracciones[fracciones.len() - grupo..]);
+        superior.zip(inferior).map(|(s, i)| redondear(s - i))
+    } else {
+        None
+    };
+
+    let opciones = match pregunta.tipo_pregunta {
+        TipoPregunta::Abierta => None,
+        TipoPregunta::SeleccionUnica | TipoPregunta::SeleccionMultiple => {
+            let mut lista: Vec<JsonValue> = match &pregunta.opciones {
+                Some(JsonValue::Array(opciones)) => opciones.clone(),
+                _ => Vec::new(),
+            };
+            // Opciones elegidas que ya no están en la pregunta
+            for respuesta in &contestadas {
+                for valor in como_lista(&respuesta.respuesta) {
+                    if !lista.contains(valor) {
+                        lista.push(valor.clone());
+                    }
+                }
+            }
+            let correctas = pregunta
+                .respuesta_correcta
+                .as_ref()
+                .map(como_lista)
+                .unwrap_or_default();
+            Some(
+                lista
+                    .into_iter()
+                    .map(|opcion| {
+                        let veces = contestadas
+                            .iter()
+                            .filter(|r| como_lista(&r.respuesta).contains(&&opcion))
+                            .count() as u64;
+                        FrecuenciaOpcion {
+                            correcta: correctas.contains(&&opcion),
+                            proporcion: if contestadas.is_empty() {
+                                0.0
+                            } else {
+                                redondear(veces as f64 / contestadas.len() as f64)
+                            },
+                            opcion,
+                            veces,
+                        }
+                    })
+                    .collect(),
+            )
+        }
+    };
+
+    AnalisisPregunta {
+        pregunta_id: pregunta.id,
+        orden: pregunta.orden,
+        pregunta: pregunta.pregunta,
+        tipo: pregunta.tipo_pregunta,
+        valor_puntos: pregunta.valor_puntos,
+        respuestas: contestadas.len() as u64,
+        omitidas: (intentos.len() - contestadas.len()) as u64,
+        dificultad: media(&fracciones).map(redondear),
+        discriminacion,
+        opciones,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EstadisticaExamenService {
+    db: DbExecutor,
+}
+
+impl FromRef<AppState> for EstadisticaExamenService {
+    fn from_ref(state: &AppState) -> Self {
+        let executor = state
+            .db
+            .clone()
+            .expect("Database connection is not available");
+        EstadisticaExamenService::new(executor)
+    }
+}
+
+impl EstadisticaExamenService {
+    pub fn new(db: DbExecutor) -> Self {
+        Self { db }
+    }
+
+    fn connection(&self) -> DatabaseConnection {
+        self.db.connection()
+    }
+
+    /// Estadísticas del examen y análisis de ítems. De cada estudiante se toma su
+    /// mejor intento calificado (el primero si empatan). Con `profesor_id`, solo de
+    /// sus cursos
+    pub async fn estadisticas(
+        &self,
+        examen_id: i32,
+        profesor_id: Option<i32>,
+    ) -> Result<EstadisticasExamen, AppError> {
+        let db = self.connection();
+        let examen = examen_del_docente(&db, examen_id, profesor_id).await?;
+        let preguntas = preguntas_de(&db, examen_id).await?;
+        let intentos = IntentoExamen::find()
+            .filter(intento_examen::Column::ExamenId.eq(examen_id))
+            .filter(intento_examen::Column::Estado.ne(EstadoIntento::EnCurso))
+            .order_by_asc(intento_examen::Column::Id)
+            .all(&db)
+            .await?;
+
+        let intentos_pendientes = intentos
+            .iter()
+            .filter(|i| i.estado == EstadoIntento::Entregado)
+            .count() as u64;
+        let calificados: Vec<IntentoModel> = intentos
+            .into_iter()
+            .filter(|i| i.estado == EstadoIntento::Calificado)
+            .collect();
+        let mut mejores: HashMap<i32, &IntentoModel> = HashMap::new();
+        for intento in &calificados {
+            match mejores.entry(intento.estudiante_id) {
+                Entry::Vacant(libre) => {
+                    libre.insert(intento);
+                }
+                Entry::Occupied(mut actual) => {
+                    if intento.puntaje.unwrap_or(0.0) > actual.get().puntaje.unwrap_or(0.0) {
+                        actual.insert(intento);
+                    }
+                }
+            }
+        }
+        let mut analizados: Vec<IntentoModel> = mejores.into_values().cloned().collect();
+        analizados.sort_by(|a, b| {
+            a.puntaje
+                .unwrap_or(0.0)
+                .total_cmp(&b.puntaje.unwrap_or(0.0))
+                .then(a.id.cmp(&b.id))
+        });
+
+        let respuestas: HashMap<(i32, i32), RespuestaModel> = Respuesta::find()
+            .filter(
+                respuesta_examen::Column::IntentoId
+                    .is_in(analizados.iter().map(|i| i.id).collect::<Vec<_>>()),
+            )
+            .all(&db)
+            .await?
+            .into_iter()
+            .map(|r| ((r.intento_id, r.pregunta_id), r))
+            .collect();
+
+        let puntaje_maximo: f64 = preguntas.iter().map(|p| p.valor_puntos as f64).sum();
+        let puntajes: Vec<f64> = analizados
+            .iter()
+            .map(|i| i.puntaje.unwrap_or(0.0))
+            .collect();
+        let duraciones: Vec<f64> = analizados
+            .iter()
+            .filter_map(|i| i.fecha_entrega.map(|f| (f - i.fecha_inicio).num_seconds()))
+            .map(|segundos| segundos as f64 / 60.0)
+            .collect();
+
+        Ok(EstadisticasExamen {
+            examen_id: examen.id,
+            nombre: examen.nombre,
+            cerrado: Utc::now() > examen.fecha_fin,
+            puntaje_maximo,
+            estudiantes: analizados.len() as u64,
+            intentos_calificados: calificados.len() as u64,
+            intentos_pendientes,
+            media: media(&puntajes).map(redondear),
+            mediana: mediana(&puntajes).map(redondear),
+            desviacion_estandar: desviacion_estandar(&puntajes).map(redondear),
+            minimo: puntajes.first().copied(),
+            maximo: puntajes.last().copied(),
+            histograma: histograma(&puntajes, puntaje_maximo),
+            duracion_media_minutos: media(&duraciones).map(redondear),
+            duracion_mediana_minutos: mediana(&duraciones).map(redondear),
+            preguntas: preguntas
+                .into_iter()
+                .map(|p| analizar(p, &analizados, &respuestas))
+                .collect(),
+        })
+    }
+
+    /// Análisis de ítems en CSV, una fila por pregunta. Las opciones van en una
+    /// celda como `opción: veces (proporción)`, con `*` en las correctas
+    pub async fn estadisticas_csv(
+        &self,
+        examen_id: i32,
+        profesor_id: Option<i32>,
+    ) -> Result<String, AppError> {
+        let estadisticas = self.estadisticas(examen_id, profesor_id).await?;
+
+        let mut escritor = csv::Writer::from_writer(Vec::new());
+        escritor
+            .write_record([
+                "pregunta_id",
+                "orden",
+                "pregunta",
+                "tipo",
+                "valor_puntos",
+                "respuestas",
+                "omitidas",
+                "dificultad",
+                "discriminacion",
+                "opciones",
+            ])
+            .map_err(|e| AppError::InternalServerError(e.to_string().into()))?;
+        for pregunta in &estadisticas.preguntas {
+            let tipo = serde_json::to_value(pregunta.tipo)
+                .ok()
+                .as_ref()
+                .map(texto_opcion)
+                .unwrap_or_default();
+            let opciones = pregunta
+                .opciones
+                .as_deref()
+                .unwrap_or_default()
+                .iter()
+                .map(|o| {
+                    format!(
+                        "{}{}: {} ({})",
+                        texto_opcion(&o.opcion),
+                        if o.correcta { "*" } else { "" },
+                        o.veces,
+                        o.proporcion
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(" | ");
+            escritor
+                .write_record([
+                    pregunta.pregunta_id.to_string(),
+                    pregunta.orden.to_string(),
+                    pregunta.pregunta.clone(),
+                    tipo,
+                    pregunta.valor_puntos.to_string(),
+                    pregunta.respuestas.to_string(),
+                    pregunta.omitidas.to_string(),
+                    pregunta
+                        .dificultad
+                        .map(|v| v.to_string())
+                        .unwrap_or_default(),
+                    pregunta
+                        .discriminacion
+                        .map(|v| v.to_string())
+                        .unwrap_or_default(),
+                    opciones,
+                ])
+                .map_err(|e| AppError::InternalServerError(e.to_string().into()))?;
+        }
+
+        let bytes = escritor
+            .into_inner()
+            .map_err(|e| AppError::InternalServerError(e.to_string().into()))?;
+        String::from_utf8(bytes).map_err(|e| AppError::InternalServerError(e.to_string().into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn pregunta(valor_puntos: i32) -> PreguntaModel {
+        PreguntaModel {
+            id: 1,
+            examen_id: 1,
+            pregunta: "¿Cuál?".into(),
+            pregunta_html: None,
+            tipo_pregunta: TipoPregunta::SeleccionUnica,
+            opciones: Some(json!(["a", "b"])),
+            respuesta_correcta: Some(json!("a")),
+            valor_puntos,
+            orden: 1,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    /// Un intento por respuesta (opción elegida y puntaje), en el orden dado
+    fn intentos(
+        respuestas: &[(&str, f64)],
+    ) -> (Vec<IntentoModel>, HashMap<(i32, i32), RespuestaModel>) {
+        let mut modelos = Vec::new();
+        let mut por_intento = HashMap::new();
+        for (i, (opcion, puntaje)) in respuestas.iter().enumerate() {
+            let id = i as i32 + 1;
+            modelos.push(IntentoModel {
+                id,
+                examen_id: 1,
+                estudiante_id: id,
+                numero: 1,
+                estado: EstadoIntento::Calificado,
+                puntaje: Some(*puntaje),
+                puntaje_maximo: Some(1.0),
+                fecha_inicio: Utc::now(),
+                fecha_entrega: None,
+            });
+            por_intento.insert(
+                (id, 1),
+                RespuestaModel {
+                    id,
+                    intento_id: id,
+                    pregunta_id: 1,
+                    respuesta: json!(opcion),
+                    puntaje: Some(*puntaje),
+                    retroalimentacion: None,
+                    calificado_por: None,
+                    fecha_calificacion: None,
+                },
+            );
+        }
+        (modelos, por_intento)
+    }
+
+    #[test]
+    fn sin_puntajes_no_hay_medidas() {
+        assert_eq!(media(&[]), None);
+        assert_eq!(mediana(&[]), None);
+        assert_eq!(desviacion_estandar(&[]), None);
+        let rangos = histograma(&[], 10.0);
+        assert_eq!(rangos.len(), RANGOS_HISTOGRAMA);
+        assert!(rangos.iter().all(|r| r.cantidad == 0));
+
+        let analisis = analizar(pregunta(2), &[], &HashMap::new());
+        assert_eq!((analisis.respuestas, analisis.omitidas), (0, 0));
+        assert_eq!(analisis.dificultad, None);
+        assert_eq!(analisis.discriminacion, None);
+        let opciones = analisis.opciones.unwrap();
+        assert!(opciones.iter().all(|o| o.veces == 0 && o.proporcion == 0.0));
+    }
+
+    #[test]
+    fn un_solo_estudiante() {
+        assert_eq!(media(&[7.0]), Some(7.0));
+        assert_eq!(mediana(&[7.0]), Some(7.0));
+        assert_eq!(desviacion_estandar(&[7.0]), Some(0.0));
+        // El puntaje máximo cae en el último rango
+        let rangos = histograma(&[10.0], 10.0);
+        assert_eq!(rangos[RANGOS_HISTOGRAMA - 1].cantidad, 1);
+
+        let (modelos, respuestas) = intentos(&[("a", 2.0)]);
+        let analisis = analizar(pregunta(2), &modelos, &respuestas);
+        assert_eq!(analisis.respuestas, 1);
+        assert_eq!(analisis.dificultad, Some(1.0));
+        assert_eq!(analisis.discriminacion, None);
+        let opciones = analisis.opciones.unwrap();
+        assert!(opciones[0].correcta && opciones[0].veces == 1 && opciones[0].proporcion == 1.0);
+        assert_eq!(opciones[1].veces, 0);
+    }
+
+    #[test]
+    fn puntajes_empatados() {
+        assert_eq!(media(&[5.0, 5.0, 5.0, 5.0]), Some(5.0));
+        assert_eq!(mediana(&[8.0, 4.0, 2.0, 4.0]), Some(4.0));
+        assert_eq!(desviacion_estandar(&[5.0, 5.0, 5.0]), Some(0.0));
+        assert_eq!(
+            desviacion_estandar(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]),
+            Some(2.0)
+        );
+        assert_eq!(histograma(&[5.0, 5.0, 5.0], 10.0)[5].cantidad, 3);
+
+        // Todos con la misma nota: la pregunta no distingue
+        let (modelos, respuestas) = intentos(&[("a", 1.0), ("a", 1.0), ("a", 1.0), ("a", 1.0)]);
+        let analisis = analizar(pregunta(1), &modelos, &respuestas);
+        assert_eq!(analisis.dificultad, Some(1.0));
+        assert_eq!(analisis.discriminacion, Some(0.0));
+    }
+
+    #[test]
+    fn pregunta_sin_puntos() {
+        assert!(histograma(&[0.0, 0.0], 0.0).is_empty());
+
+        let (modelos, respuestas) = intentos(&[("b", 0.0), ("a", 0.0)]);
+        let analisis = analizar(pregunta(0), &modelos, &respuestas);
+        assert_eq!((analisis.respuestas, analisis.omitidas), (2, 0));
+        assert_eq!(analisis.dificultad, None);
+        assert_eq!(analisis.discriminacion, None);
+        let opciones = analisis.opciones.unwrap();
+        assert!(opciones.iter().all(|o| o.veces == 1 && o.proporcion == 0.5));
+    }
+}
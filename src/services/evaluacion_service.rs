@@ -0,0 +1,567 @@
+use std::collections::HashSet;
+
+use axum::extract::FromRef;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, Set, TransactionTrait,
+};
+use serde_json::json;
+
+use crate::{
+    database::DbExecutor,
+    models::{
+        calificacion_evaluacion::{
+            self, Entity as CalificacionEvaluacion, EstadoCalificacion,
+            Model as CalificacionEvaluacionModel,
+        },
+        curso::{self, Entity as Curso},
+        evaluacion::{self, Entity as Evaluacion, EstadoEvaluacion, Model as EvaluacionModel},
+        historial_curso_estudiante::{self, Entity as Historial, EstadoMatricula},
+        AppState,
+    },
+    services::{
+        auditoria_service::{AuditoriaService, ContextoAuditoria},
+        matricula_service::exigir_curso_asignado,
+        matricula_service::exigir_matricula_activa,
+    },
+    utils::errors::AppError,
+};
+
+pub use crate::models::calificacion_evaluacion::{CalificarEvaluacion, CriterioCalificado};
+pub use crate::models::evaluacion::{ActualizarEvaluacion, NuevaEvaluacion, Rubrica};
+
+/// Peso y puntaje máximo por defecto del DDL
+const PESO_POR_DEFECTO: Decimal = Decimal::ONE;
+const PUNTAJE_MAXIMO_POR_DEFECTO: Decimal = Decimal::ONE_HUNDRED;
+
+#[derive(Debug, Clone)]
+pub struct EvaluacionService {
+    db: DbExecutor,
+}
+
+impl FromRef<AppState> for EvaluacionService {
+    fn from_ref(state: &AppState) -> Self {
+        let executor = state
+            .db
+            .clone()
+            .expect("Database connection is not available");
+        EvaluacionService::new(executor)
+    }
+}
+
+impl EvaluacionService {
+    pub fn new(db: DbExecutor) -> Self {
+        Self { db }
+    }
+
+    fn connection(&self) -> DatabaseConnection {
+        self.db.connection()
+    }
+
+    pub async fn crear_evaluacion(
+        &self,
+        nueva: NuevaEvaluacion,
+        creado_por: i32,
+        profesor_id: Option<i32>,
+    ) -> Result<EvaluacionModel, AppError> {
+        if nueva.titulo.trim().is_empty() {
+            return Err(AppError::BadRequest("El título es obligatorio".into()));
+        }
+        validar_fechas(&nueva.fecha_disponible_desde, &nueva.fecha_disponible_hasta)?;
+
+        let db = self.connection();
+        Curso::find_by_id(nueva.id_curso)
+            .filter(curso::Column::FechaEliminacion.is_null())
+            .one(&db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Curso no encontrado".into()))?;
+        exigir_curso_asignado(&db, nueva.id_curso, profesor_id).await?;
+
+        let peso = nueva.peso.unwrap_or(PESO_POR_DEFECTO);
+        validar_puntaje("peso", peso)?;
+
+        let (configuracion, puntaje_maximo) = match nueva.rubrica {
+            Some(rubrica) => {
+                validar_rubrica(&rubrica)?;
+                let puntaje_maximo = rubrica.puntaje_maximo();
+                (json!({ "rubrica": rubrica }), puntaje_maximo)
+            }
+            None => (
+                json!({}),
+                nueva.puntaje_maximo.unwrap_or(PUNTAJE_MAXIMO_POR_DEFECTO),
+            ),
+        };
+        validar_puntaje("puntaje_maximo", puntaje_maximo)?;
+
+        let ahora = Utc::now();
+        let evaluacion = evaluacion::ActiveModel {
+            id_curso: Set(nueva.id_curso),
+            tipo: Set(nueva.tipo),
+            titulo: Set(nueva.titulo),
+            instrucciones: Set(nueva.instrucciones),
+            configuracion: Set(configuracion),
+            peso: Set(peso),
+            puntaje_maximo: Set(puntaje_maximo),
+            fecha_disponible_desde: Set(nueva.fecha_disponible_desde),
+            fecha_disponible_hasta: Set(nueva.fecha_disponible_hasta),
+            fecha_limite_entrega: Set(nueva.fecha_limite_entrega),
+            estado: Set(EstadoEvaluacion::Borrador),
+            creado_por: Set(Some(creado_por)),
+            fecha_creacion: Set(Some(ahora)),
+            fecha_actualizacion: Set(Some(ahora)),
+            ..Default::default()
+        };
+
+        Ok(evaluacion.insert(&db).await?)
+    }
+
+    /// Evaluaciones del curso; con `solo_publicadas` se ocultan borradores y archivadas.
+    /// `profesor_id` y `estudiante_id` limitan a cursos asignados o con matrícula activa
+    pub async fn listar_por_curso(
+        &self,
+        curso_id: i32,
+        solo_publicadas: bool,
+        profesor_id: Option<i32>,
+        estudiante_id: Option<i32>,
+    ) -> Result<Vec<EvaluacionModel>, AppError> {
+        let db = self.connection();
+        exigir_curso_asignado(&db, curso_id, profesor_id).await?;
+        exigir_matricula_activa(&db, curso_id, estudiante_id).await?;
+        let mut consulta = Evaluacion::find()
+            .filter(evaluacion::Column::IdCurso.eq(curso_id))
+            .filter(evaluacion::Column::FechaEliminacion.is_null());
+        if solo_publicadas {
+            consulta = consulta.filter(evaluacion::Column::Estado.eq(EstadoEvaluacion::Publicado));
+        }
+
+        Ok(consulta
+            .order_by_asc(evaluacion::Column::FechaCreacion)
+            .all(&db)
+            .await?)
+    }
+
+    pub async fn obtener_evaluacion(
+        &self,
+        id: i32,
+        solo_publicadas: bool,
+        profesor_id: Option<i32>,
+        estudiante_id: Option<i32>,
+    ) -> Result<EvaluacionModel, AppError> {
+        let evaluacion = self.buscar(id, profesor_id).await?;
+        if solo_publicadas && evaluacion.estado != EstadoEvaluacion::Publicado {
+            return Err(AppError::NotFound("Evaluación no encontrada".into()));
+        }
+        exigir_matricula_activa(&self.connection(), evaluacion.id_curso, estudiante_id).await?;
+        Ok(evaluacion)
+    }
+
+    pub async fn actualizar_evaluacion(
+        &self,
+        id: i32,
+        datos: ActualizarEvaluacion,
+        profesor_id: Option<i32>,
+    ) -> Result<EvaluacionModel, AppError> {
+        let actual = self.buscar(id, profesor_id).await?;
+        if actual.estado == EstadoEvaluacion::Archivado {
+            return Err(AppError::Conflict(
+                "No se puede modificar una evaluación archivada".into(),
+            ));
+        }
+
+        let desde = datos
+            .fecha_disponible_desde
+            .or(actual.fecha_disponible_desde);
+        let hasta = datos
+            .fecha_disponible_hasta
+            .or(actual.fecha_disponible_hasta);
+        validar_fechas(&desde, &hasta)?;
+
+        let tiene_rubrica = actual.rubrica().is_some();
+        let mut evaluacion: evaluacion::ActiveModel = actual.into();
+
+        if let Some(tipo) = datos.tipo {
+            evaluacion.tipo = Set(tipo);
+        }
+        if let Some(titulo) = datos.titulo {
+            if titulo.trim().is_empty() {
+                return Err(AppError::BadRequest(
+                    "El título no puede estar vacío".into(),
+                ));
+            }
+            evaluacion.titulo = Set(titulo);
+        }
+        if let Some(instrucciones) = datos.instrucciones {
+            evaluacion.instrucciones = Set(Some(instrucciones));
+        }
+        if let Some(peso) = datos.peso {
+            validar_puntaje("peso", peso)?;
+            evaluacion.peso = Set(peso);
+        }
+        if let Some(puntaje_maximo) = datos.puntaje_maximo {
+            if tiene_rubrica {
+                return Err(AppError::BadRequest(
+                    "El puntaje máximo se calcula a partir de la rúbrica".into(),
+                ));
+            }
+            validar_puntaje("puntaje_maximo", puntaje_maximo)?;
+            evaluacion.puntaje_maximo = Set(puntaje_maximo);
+        }
+        if datos.fecha_disponible_desde.is_some() {
+            evaluacion.fecha_disponible_desde = Set(desde);
+        }
+        if datos.fecha_disponible_hasta.is_some() {
+            evaluacion.fecha_disponible_hasta = Set(hasta);
+        }
+        if let Some(fecha_limite) = datos.fecha_limite_entrega {
+            evaluacion.fecha_limite_entrega = Set(Some(fecha_limite));
+        }
+
+        evaluacion.fecha_actualizacion = Set(Some(Utc::now()));
+        Ok(evaluacion.update(&self.connection()).await?)
+    }
+
+    /// Soft delete: la evaluación y sus calificaciones dejan de listarse
+    pub async fn eliminar_evaluacion(
+        &self,
+        id: i32,
+        profesor_id: Option<i32>,
+    ) -> Result<(), AppError> {
+        let mut evaluacion: evaluacion::ActiveModel = self.buscar(id, profesor_id).await?.into();
+        evaluacion.fecha_eliminacion = Set(Some(Utc::now()));
+        evaluacion.update(&self.connection()).await?;
+        Ok(())
+    }
+
+    /// Reemplaza la rúbrica y recalcula el puntaje máximo.
+    /// No se permite una vez que hay calificaciones registradas
+    pub async fn asignar_rubrica(
+        &self,
+        id: i32,
+        rubrica: Rubrica,
+        profesor_id: Option<i32>,
+    ) -> Result<EvaluacionModel, AppError> {
+        validar_rubrica(&rubrica)?;
+        let actual = self.buscar(id, profesor_id).await?;
+        if actual.estado == EstadoEvaluacion::Archivado {
+            return Err(AppError::Conflict(
+                "No se puede modificar una evaluación archivada".into(),
+            ));
+        }
+
+        let db = self.connection();
+        let calificaciones = CalificacionEvaluacion::find()
+            .filter(calificacion_evaluacion::Column::IdEvaluacion.eq(id))
+            .count(&db)
+            .await?;
+        if calificaciones > 0 {
+            return Err(AppError::Conflict(
+                "La evaluación ya tiene calificaciones; la rúbrica no puede cambiar".into(),
+            ));
+        }
+
+        let mut configuracion = actual.configuracion.clone();
+        if !configuracion.is_object() {
+            configuracion = json!({});
+        }
+        configuracion["rubrica"] = json!(rubrica);
+
+        let mut evaluacion: evaluacion::ActiveModel = actual.into();
+        evaluacion.puntaje_maximo = Set(rubrica.puntaje_maximo());
+        evaluacion.configuracion = Set(configuracion);
+        evaluacion.fecha_actualizacion = Set(Some(Utc::now()));
+        Ok(evaluacion.update(&db).await?)
+    }
+
+    /// borrador → publicado, o reapertura de una archivada
+    pub async fn publicar(
+        &self,
+        id: i32,
+        profesor_id: Option<i32>,
+    ) -> Result<EvaluacionModel, AppError> {
+        self.cambiar_estado(
+            id,
+            profesor_id,
+            &[EstadoEvaluacion::Borrador, EstadoEvaluacion::Archivado],
+            EstadoEvaluacion::Publicado,
+        )
+        .await
+    }
+
+    /// publicado → archivado: deja de ser visible para los estudiantes
+    pub async fn archivar(
+        &self,
+        id: i32,
+        profesor_id: Option<i32>,
+    ) -> Result<EvaluacionModel, AppError> {
+        self.cambiar_estado(
+            id,
+            profesor_id,
+            &[EstadoEvaluacion::Publicado],
+            EstadoEvaluacion::Archivado,
+        )
+        .await
+    }
+
+    /// Califica (o recalifica) a un estudiante según la rúbrica. Si faltan
+    /// criterios la calificación queda `en_progreso` con el puntaje parcial
+    pub async fn calificar(
+        &self,
+        id: i32,
+        datos: CalificarEvaluacion,
+        profesor_id: Option<i32>,
+        ctx: &ContextoAuditoria,
+    ) -> Result<CalificacionEvaluacionModel, AppError> {
+        let evaluacion = self.buscar(id, profesor_id).await?;
+        if evaluacion.estado != EstadoEvaluacion::Publicado {
+            return Err(AppError::Conflict(
+                "Solo se califican evaluaciones publicadas".into(),
+            ));
+        }
+        let rubrica = evaluacion
+            .rubrica()
+            .ok_or_else(|| AppError::BadRequest("La evaluación no tiene rúbrica".into()))?;
+
+        let db = self.connection();
+        let matriculado = Historial::find()
+            .filter(historial_curso_estudiante::Column::CursoId.eq(evaluacion.id_curso))
+            .filter(historial_curso_estudiante::Column::EstudianteId.eq(datos.id_estudiante))
+            .filter(historial_curso_estudiante::Column::Estado.ne(EstadoMatricula::Retirado))
+            .count(&db)
+            .await?;
+        if matriculado == 0 {
+            return Err(AppError::BadRequest(
+                "El estudiante no está matriculado en el curso".into(),
+            ));
+        }
+
+        let criterios = puntuar_criterios(&rubrica, datos.criterios)?;
+        let puntaje: Decimal = criterios.iter().map(|criterio| criterio.puntos).sum();
+        let estado = if criterios.len() == rubrica.criterios.len() {
+            EstadoCalificacion::Calificado
+        } else {
+            EstadoCalificacion::EnProgreso
+        };
+
+        let ahora = Utc::now();
+        let txn = db.begin().await?;
+        let anterior = CalificacionEvaluacion::find()
+            .filter(calificacion_evaluacion::Column::IdEvaluacion.eq(id))
+            .filter(calificacion_evaluacion::Column::IdEstudiante.eq(datos.id_estudiante))
+            .one(&txn)
+            .await?;
+
+        let mut calificacion = match &anterior {
+            Some(existente) => existente.clone().into(),
+            None => calificacion_evaluacion::ActiveModel {
+                id_evaluacion: Set(id),
+                id_estudiante: Set(datos.id_estudiante),
+                fecha_creacion: Set(Some(ahora)),
+                ..Default::default()
+            },
+        };
+        calificacion.puntaje_obtenido = Set(Some(puntaje));
+        calificacion.puntaje_maximo = Set(evaluacion.puntaje_maximo);
+        calificacion.retroalimentacion = Set(datos.retroalimentacion);
+        calificacion.detalle_rubrica = Set(Some(json!(criterios)));
+        calificacion.calificado_por = Set(ctx.actor_id);
+        calificacion.fecha_calificacion = Set(Some(ahora));
+        calificacion.estado = Set(estado);
+        calificacion.fecha_actualizacion = Set(Some(ahora));
+
+        let calificacion = match anterior {
+            Some(_) => calificacion.update(&txn).await?,
+            None => calificacion.insert(&txn).await?,
+        };
+        AuditoriaService::registrar(
+            &txn,
+            ctx,
+            "calificar",
+            "calificacion_evaluacion",
+            Some(calificacion.id),
+            anterior.as_ref(),
+            Some(&calificacion),
+        )
+        .await?;
+        txn.commit().await?;
+
+        tracing::info!(
+            "📝 Evaluación {} calificada para estudiante {}: {}/{}",
+            id,
+            calificacion.id_estudiante,
+            puntaje,
+            calificacion.puntaje_maximo
+        );
+        Ok(calificacion)
+    }
+
+    /// Calificaciones de la evaluación; `estudiante_id` limita a las de un estudiante
+    pub async fn listar_calificaciones(
+        &self,
+        id: i32,
+        estudiante_id: Option<i32>,
+        profesor_id: Option<i32>,
+    ) -> Result<Vec<CalificacionEvaluacionModel>, AppError> {
+        self.buscar(id, profesor_id).await?;
+
+        let mut consulta = CalificacionEvaluacion::find()
+            .filter(calificacion_evaluacion::Column::IdEvaluacion.eq(id));
+        if let Some(estudiante_id) = estudiante_id {
+            consulta =
+                consulta.filter(calificacion_evaluacion::Column::IdEstudiante.eq(estudiante_id));
+        }
+
+        Ok(consulta
+            .order_by_asc(calificacion_evaluacion::Column::IdEstudiante)
+            .all(&self.connection())
+            .await?)
+    }
+
+    /// Evaluación vigente; con `profesor_id`, solo de un curso que tenga asignado
+    async fn buscar(&self, id: i32, profesor_id: Option<i32>) -> Result<EvaluacionModel, AppError> {
+        let db = self.connection();
+        let evaluacion = Evaluacion::find_by_id(id)
+            .filter(evaluacion::Column::FechaEliminacion.is_null())
+            .one(&db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Evaluación no encontrada".into()))?;
+        exigir_curso_asignado(&db, evaluacion.id_curso, profesor_id).await?;
+        Ok(evaluacion)
+    }
+
+    async fn cambiar_estado(
+        &self,
+        id: i32,
+        profesor_id: Option<i32>,
+        desde: &[EstadoEvaluacion],
+        hacia: EstadoEvaluacion,
+    ) -> Result<EvaluacionModel, AppError> {
+        let actual = self.buscar(id, profesor_id).await?;
+        if !desde.contains(&actual.estado) {
+            return Err(AppError::Conflict(
+                format!("Transición no permitida: {:?} → {:?}", actual.estado, hacia).into(),
+            ));
+        }
+
+        let mut evaluacion: evaluacion::ActiveModel = actual.into();
+        evaluacion.estado = Set(hacia);
+        evaluacion.fecha_actualizacion = Set(Some(Utc::now()));
+        Ok(evaluacion.update(&self.connection()).await?)
+    }
+}
+
+fn validar_fechas(
+    desde: &Option<chrono::DateTime<Utc>>,
+    hasta: &Option<chrono::DateTime<Utc>>,
+) -> Result<(), AppError> {
+    if let (Some(desde), Some(hasta)) = (desde, hasta) {
+        if desde >= hasta {
+            return Err(AppError::BadRequest(
+                "fecha_disponible_desde debe ser anterior a fecha_disponible_hasta".into(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// numeric(5,2): entre 0 y 999.99
+fn validar_puntaje(campo: &str, valor: Decimal) -> Result<(), AppError> {
+    if valor <= Decimal::ZERO || valor >= Decimal::ONE_THOUSAND {
+        return Err(AppError::BadRequest(
+            format!("{} debe ser mayor a 0 y menor a 1000", campo).into(),
+        ));
+    }
+    Ok(())
+}
+
+fn validar_rubrica(rubrica: &Rubrica) -> Result<(), AppError> {
+    if rubrica.criterios.is_empty() {
+        return Err(AppError::BadRequest(
+            "La rúbrica debe tener al menos un criterio".into(),
+        ));
+    }
+
+    let mut ids_criterios = HashSet::new();
+    for criterio in &rubrica.criterios {
+        if criterio.id.trim().is_empty() || !ids_criterios.insert(criterio.id.as_str()) {
+            return Err(AppError::BadRequest(
+                format!("Id de criterio vacío o repetido: '{}'", criterio.id).into(),
+            ));
+        }
+        if criterio.niveles.is_empty() {
+            return Err(AppError::BadRequest(
+                format!("El criterio '{}' no tiene niveles", criterio.id).into(),
+            ));
+        }
+
+        let mut ids_niveles = HashSet::new();
+        for nivel in &criterio.niveles {
+            if nivel.id.trim().is_empty() || !ids_niveles.insert(nivel.id.as_str()) {
+                return Err(AppError::BadRequest(
+                    format!(
+                        "Id de nivel vacío o repetido en el criterio '{}': '{}'",
+                        criterio.id, nivel.id
+                    )
+                    .into(),
+                ));
+            }
+            if nivel.puntos < Decimal::ZERO {
+                return Err(AppError::BadRequest(
+                    format!("El nivel '{}' tiene puntaje negativo", nivel.id).into(),
+                ));
+            }
+        }
+    }
+
+    validar_puntaje("El puntaje total de la rúbrica", rubrica.puntaje_maximo())
+}
+
+/// Valida los niveles elegidos contra la rúbrica y fija el puntaje de cada criterio
+fn puntuar_criterios(
+    rubrica: &Rubrica,
+    seleccion: Vec<CriterioCalificado>,
+) -> Result<Vec<CriterioCalificado>, AppError> {
+    let mut vistos = HashSet::new();
+    let mut criterios = Vec::with_capacity(seleccion.len());
+
+    for mut elegido in seleccion {
+        let criterio = rubrica
+            .criterios
+            .iter()
+            .find(|criterio| criterio.id == elegido.criterio_id)
+            .ok_or_else(|| {
+                AppError::BadRequest(
+                    format!("Criterio inexistente: '{}'", elegido.criterio_id).into(),
+                )
+            })?;
+        if !vistos.insert(criterio.id.clone()) {
+            return Err(AppError::BadRequest(
+                format!("Criterio calificado dos veces: '{}'", criterio.id).into(),
+            ));
+        }
+        let nivel = criterio
+            .niveles
+            .iter()
+            .find(|nivel| nivel.id == elegido.nivel_id)
+            .ok_or_else(|| {
+                AppError::BadRequest(
+                    format!(
+                        "Nivel '{}' inexistente en el criterio '{}'",
+                        elegido.nivel_id, criterio.id
+                    )
+                    .into(),
+                )
+            })?;
+
+        elegido.puntos = nivel.puntos;
+        criterios.push(elegido);
+    }
+
+    if criterios.is_empty() {
+        return Err(AppError::BadRequest(
+            "Debe calificar al menos un criterio".into(),
+        ));
+    }
+    Ok(criterios)
+}
@@ -1,8 +1,8 @@
 use axum::extract::FromRef;
 use chrono::Utc;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, ModelTrait, QueryFilter,
-    Set,
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set,
+    TransactionTrait,
 };
 
 use crate::database::DbExecutor;
@@ -10,6 +10,7 @@ use crate::models::{
     examen::{self, Entity as Examen, Model as ExamenModel},
     AppState,
 };
+use crate::services::papelera_service::{PapeleraService, TipoPapelera};
 use crate::utils::errors::AppError;
 
 pub use crate::models::examen::{ActualizarExamen, NuevoExamen};
@@ -75,6 +76,7 @@ impl ExamenService {
     ) -> Result<Vec<ExamenModel>, DbErr> {
         let db = self.connection();
         Examen::find()
+            .filter(examen::Column::FechaEliminacion.is_null())
             .filter(examen::Column::CursoId.eq(curso_id))
             .all(&db)
             .await
@@ -82,7 +84,10 @@ impl ExamenService {
 
     pub async fn obtener_examen_por_id(&self, id: i32) -> Result<Option<ExamenModel>, DbErr> {
         let db = self.connection();
-        Examen::find_by_id(id).one(&db).await
+        Examen::find_by_id(id)
+            .filter(examen::Column::FechaEliminacion.is_null())
+            .one(&db)
+            .await
     }
 
     pub async fn actualizar_examen(
@@ -92,6 +97,7 @@ impl ExamenService {
     ) -> Result<ExamenModel, AppError> {
         let db = self.connection();
         let examen = Examen::find_by_id(id)
+            .filter(examen::Column::FechaEliminacion.is_null())
             .one(&db)
             .await?
             .ok_or_else(|| AppError::NotFound("Examen no encontrado".into()))?;
@@ -145,11 +151,16 @@ impl ExamenService {
     pub async fn eliminar_examen(&self, id: i32) -> Result<(), AppError> {
         let db = self.connection();
         let examen = Examen::find_by_id(id)
+            .filter(examen::Column::FechaEliminacion.is_null())
             .one(&db)
             .await?
             .ok_or_else(|| AppError::NotFound("Examen no encontrado".into()))?;
 
-        examen.delete(&db).await?;
+        // Soft delete en cascada (ver PapeleraService)
+        let txn = db.begin().await?;
+        PapeleraService::mover_a_papelera(&txn, TipoPapelera::Examen, examen.id, Utc::now())
+            .await?;
+        txn.commit().await?;
         Ok(())
     }
 }
@@ -25,10 +25,6 @@ impl HistorialCursoEstudianteService {
         &self,
         nuevo_historial: NuevoHistorial,
     ) -> Result<HistorialCursoEstudianteModel, AppError> {
-        if nuevo_historial.estado.trim().is_empty() {
-            return Err(AppError::BadRequest("El estado es obligatorio".to_string()));
-        }
-
         let ahora = Utc::now();
         let historial = historial_curso_estudiante::ActiveModel {
             curso_id: Set(nuevo_historial.curso_id),
@@ -83,9 +79,6 @@ impl HistorialCursoEstudianteService {
         let ahora = Utc::now();
 
         if let Some(estado) = datos_actualizados.estado {
-            if estado.trim().is_empty() {
-                return Err(AppError::BadRequest("El estado no puede estar vacío".to_string()));
-            }
             historial.estado = Set(estado);
         }
 
@@ -0,0 +1,194 @@
+use axum::body::Bytes;
+use image::{
+    codecs::{jpeg::JpegEncoder, png::PngEncoder, webp::WebPEncoder},
+    imageops::FilterType,
+    DynamicImage, ImageDecoder, ImageReader, Limits,
+};
+use std::io::Cursor;
+
+use crate::services::storage_service::{StorageError, StorageService};
+
+/// Dimensión máxima (ancho o alto) aceptada al decodificar, evita "decompression bombs"
+const MAX_DIMENSION_ENTRADA: u32 = 10_000;
+const CALIDAD_JPEG: u8 = 85;
+
+/// Tipo de imagen a procesar; define los tamaños de cada variante
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TipoImagen {
+    Avatar,
+    Portada,
+}
+
+impl TipoImagen {
+    pub fn desde_nombre(valor: &str) -> Option<Self> {
+        match valor.trim().to_lowercase().as_str() {
+            "avatar" => Some(Self::Avatar),
+            "portada" => Some(Self::Portada),
+            _ => None,
+        }
+    }
+
+    /// Lado máximo (px) de cada variante: (nombre, lado)
+    fn variantes(&self) -> [(&'static str, u32); 3] {
+        match self {
+            TipoImagen::Avatar => [("thumbnail", 128), ("medium", 512), ("original", 2048)],
+            TipoImagen::Portada => [("thumbnail", 320), ("medium", 1280), ("original", 2560)],
+        }
+    }
+}
+
+/// Variante codificada lista para subir
+pub struct VarianteCodificada {
+    pub nombre: &'static str,
+    pub ancho: u32,
+    pub alto: u32,
+    pub extension: &'static str,
+    pub content_type: &'static str,
+    pub bytes: Bytes,
+    /// Solo cuando el WebP ocupa menos que el formato base
+    pub webp: Option<Bytes>,
+}
+
+/// Variante ya almacenada en R2
+pub struct VarianteSubida {
+    pub nombre: &'static str,
+    pub ancho: u32,
+    pub alto: u32,
+    pub file_key: String,
+    pub webp_file_key: Option<String>,
+}
+
+/// Decodifica la imagen, aplica la orientación EXIF y genera las variantes.
+/// Al re-codificar desde los píxeles se descartan todos los metadatos (EXIF, GPS, etc.).
+pub fn procesar_imagen(
+    datos: &[u8],
+    tipo: TipoImagen,
+) -> Result<Vec<VarianteCodificada>, StorageError> {
+    let mut reader = ImageReader::new(Cursor::new(datos))
+        .with_guessed_format()
+        .map_err(|e| StorageError::InvalidImage(e.to_string()))?;
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION_ENTRADA);
+    limits.max_image_height = Some(MAX_DIMENSION_ENTRADA);
+    reader.limits(limits);
+
+    let mut decoder = reader
+        .into_decoder()
+        .map_err(|e| StorageError::InvalidImage(e.to_string()))?;
+    let orientacion = decoder
+        .orientation()
+        .map_err(|e| StorageError::InvalidImage(e.to_string()))?;
+    let mut imagen =
+        DynamicImage::from_decoder(decoder).map_err(|e| StorageError::InvalidImage(e.to_string()))?;
+    imagen.apply_orientation(orientacion);
+
+    let con_transparencia = imagen.color().has_alpha();
+
+    tipo.variantes()
+        .into_iter()
+        .map(|(nombre, lado)| {
+            let variante = redimensionar(&imagen, lado);
+            codificar_variante(nombre, &variante, con_transparencia)
+        })
+        .collect()
+}
+
+/// Sube todas las variantes (formato base + WebP si lo hay) bajo `prefijo/<variante>.<ext>`
+pub async fn subir_variantes(
+    storage: &StorageService,
+    prefijo: &str,
+    variantes: Vec<VarianteCodificada>,
+) -> Result<Vec<VarianteSubida>, StorageError> {
+    let mut subidas = Vec::with_capacity(variantes.len());
+
+    for variante in variantes {
+        let file_key = format!("{}/{}.{}", prefijo, variante.nombre, variante.extension);
+        storage
+            .upload_file(
+                file_key.clone(),
+                variante.content_type.to_string(),
+                variante.bytes,
+            )
+            .await?;
+        let webp_file_key = match variante.webp {
+            Some(webp) => {
+                let webp_file_key = format!("{}/{}.webp", prefijo, variante.nombre);
+                storage
+                    .upload_file(webp_file_key.clone(), "image/webp".to_string(), webp)
+                    .await?;
+                Some(webp_file_key)
+            }
+            None => None,
+        };
+
+        subidas.push(VarianteSubida {
+            nombre: variante.nombre,
+            ancho: variante.ancho,
+            alto: variante.alto,
+            file_key,
+            webp_file_key,
+        });
+    }
+
+    Ok(subidas)
+}
+
+/// Reduce la imagen para que su lado mayor no supere `lado`; nunca la amplía
+fn redimensionar(imagen: &DynamicImage, lado: u32) -> DynamicImage {
+    if imagen.width() <= lado && imagen.height() <= lado {
+        return imagen.clone();
+    }
+    imagen.resize(lado, lado, FilterType::Lanczos3)
+}
+
+fn codificar_variante(
+    nombre: &'static str,
+    imagen: &DynamicImage,
+    con_transparencia: bool,
+) -> Result<VarianteCodificada, StorageError> {
+    let error = |e: image::ImageError| StorageError::InvalidImage(e.to_string());
+
+    // JPEG no soporta canal alfa: las imágenes con transparencia se guardan en PNG
+    let mut base = Vec::new();
+    let (extension, content_type) = if con_transparencia {
+        imagen
+            .to_rgba8()
+            .write_with_encoder(PngEncoder::new(&mut base))
+            .map_err(error)?;
+        ("png", "image/png")
+    } else {
+        imagen
+            .to_rgb8()
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut base, CALIDAD_JPEG))
+            .map_err(error)?;
+        ("jpg", "image/jpeg")
+    };
+
+    // El encoder WebP disponible es solo lossless: en fotos suele pesar más que el
+    // JPEG, así que solo se conserva cuando es más pequeño
+    let mut webp = Vec::new();
+    if con_transparencia {
+        imagen
+            .to_rgba8()
+            .write_with_encoder(WebPEncoder::new_lossless(&mut webp))
+            .map_err(error)?;
+    } else {
+        imagen
+            .to_rgb8()
+            .write_with_encoder(WebPEncoder::new_lossless(&mut webp))
+            .map_err(error)?;
+    }
+
+    let webp = (webp.len() < base.len()).then(|| Bytes::from(webp));
+
+    Ok(VarianteCodificada {
+        nombre,
+        ancho: imagen.width(),
+        alto: imagen.height(),
+        extension,
+        content_type,
+        bytes: Bytes::from(base),
+        webp,
+    })
+}
@@ -0,0 +1,793 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::extract::FromRef;
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, DbErr,
+    EntityTrait, FromQueryResult, IntoActiveModel, QueryFilter, QueryOrder, QuerySelect, Set,
+    Statement, TransactionTrait, Value,
+};
+use serde_json::{json, Value as JsonValue};
+
+use crate::{
+    database::DbExecutor,
+    models::{
+        examen::{self, Entity as Examen, EstadoExamen, Model as ExamenModel},
+        historial_curso_estudiante::{self, Entity as Historial, EstadoMatricula},
+        intento_examen::{self, Entity as IntentoExamen, EstadoIntento, Model as IntentoModel},
+        pregunta_examen::{self, Entity as Pregunta, Model as PreguntaModel, TipoPregunta},
+        respuesta_examen::{self, Entity as Respuesta, Model as RespuestaModel},
+        AppState,
+    },
+    services::{
+        auditoria_service::{AuditoriaService, ContextoAuditoria},
+        liberacion_service::LiberacionService,
+        matricula_service::exigir_curso_asignado,
+        metrics_service::{get_metrics_service, EventoDominio},
+        notificacion_service::{NotificacionService, NuevaNotificacion},
+    },
+    utils::errors::AppError,
+};
+
+pub use crate::models::{
+    intento_examen::{DetalleIntento, EntregaIntento, PreguntaIntento},
+    respuesta_examen::{
+        CalificacionRespuesta, ColaCalificacion, FiltroCola, RespuestaPorCalificar,
+        ResultadoCalificacion,
+    },
+};
+
+const LIMITE_POR_DEFECTO: u64 = 20;
+const LIMITE_MAXIMO: u64 = 200;
+/// Margen tras el vencimiento para entregas que salieron a tiempo del navegador
+const MARGEN_ENTREGA_SEGUNDOS: i64 = 60;
+
+/// Intentos entregados del examen numerados por id ("Intento N" en la vista anónima).
+/// $1 = examen
+const INTENTOS_ENTREGADOS: &str = r#"
+WITH intentos AS (
+    SELECT id, estudiante_id, fecha_entrega, ROW_NUMBER() OVER (ORDER BY id) AS n
+    FROM intentos_examen
+    WHERE examen_id = $1 AND estado <> 'en_curso'
+)
+"#;
+
+/// Respuestas que requieren calificación manual: preguntas abiertas o sin clave.
+/// $2 = pregunta (opcional), $3 = incluir las ya calificadas
+const RESPUESTAS_MANUALES: &str = r#"
+FROM respuestas_examen r
+JOIN intentos i ON i.id = r.intento_id
+JOIN preguntas_examen p ON p.id = r.pregunta_id
+LEFT JOIN usuarios u ON u.id = i.estudiante_id
+WHERE (p.tipo = 'abierta' OR p.respuesta_correcta IS NULL)
+  AND ($2::int IS NULL OR r.pregunta_id = $2)
+  AND ($3 OR r.puntaje IS NULL)
+"#;
+
+/// Suma de puntajes y respuestas sin calificar de un intento. $1 = intento
+const TOTALES_INTENTO: &str = r#"
+SELECT COUNT(*) FILTER (WHERE puntaje IS NULL) AS pendientes,
+       COALESCE(SUM(puntaje), 0)::float8 AS puntaje
+FROM respuestas_examen
+WHERE intento_id = $1
+"#;
+
+#[derive(Debug, FromQueryResult)]
+struct Conteo {
+    total: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct Totales {
+    pendientes: i64,
+    puntaje: f64,
+}
+
+fn sentencia<I>(sql: &str, valores: I) -> Statement
+where
+    I: IntoIterator<Item = Value>,
+{
+    Statement::from_sql_and_values(DbBackend::Postgres, sql, valores)
+}
+
+/// Comprueba que la respuesta tenga la forma del tipo de pregunta y use sus opciones
+fn validar_respuesta(pregunta: &PreguntaModel, respuesta: &JsonValue) -> Result<(), String> {
+    let seleccion: Vec<&JsonValue> = match pregunta.tipo_pregunta {
+        TipoPregunta::Abierta => {
+            return match respuesta {
+                JsonValue::String(_) => Ok(()),
+                _ => Err("la respuesta de una pregunta abierta debe ser texto".to_string()),
+            }
+        }
+        TipoPregunta::SeleccionUnica => match respuesta {
+            JsonValue::String(_) | JsonValue::Number(_) | JsonValue::Bool(_) => vec![respuesta],
+            _ => return Err("se espera una sola opción".to_string()),
+        },
+        TipoPregunta::SeleccionMultiple => match respuesta {
+            JsonValue::Array(valores) => {
+                if valores.iter().any(|v| v.is_array() || v.is_object()) {
+                    return Err("se espera una lista de opciones".to_string());
+                }
+                valores.iter().collect()
+            }
+            _ => return Err("se espera una lista de opciones".to_string()),
+        },
+    };
+
+    for (i, valor) in seleccion.iter().enumerate() {
+        if seleccion[..i].contains(valor) {
+            return Err("hay opciones repetidas".to_string());
+        }
+    }
+    if let Some(JsonValue::Array(opciones)) = &pregunta.opciones {
+        if !opciones.is_empty() && seleccion.iter().any(|v| !opciones.contains(v)) {
+            return Err("la opción elegida no existe".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Valores de una clave o respuesta de selección como lista
+pub(crate) fn como_lista(valor: &JsonValue) -> Vec<&JsonValue> {
+    match valor {
+        JsonValue::Array(valores) => valores.iter().collect(),
+        otro => vec![otro],
+    }
+}
+
+/// Puntaje automático de una respuesta; None si requiere calificación manual.
+/// Selección múltiple: todo o nada
+fn puntuar(pregunta: &PreguntaModel, respuesta: &JsonValue) -> Option<f64> {
+    let correcta = pregunta.respuesta_correcta.as_ref()?;
+    let acierto = match pregunta.tipo_pregunta {
+        TipoPregunta::Abierta => return None,
+        TipoPregunta::SeleccionUnica => como_lista(correcta) == vec![respuesta],
+        TipoPregunta::SeleccionMultiple => {
+            let esperadas = como_lista(correcta);
+            let elegidas = como_lista(respuesta);
+            esperadas.len() == elegidas.len() && elegidas.iter().all(|v| esperadas.contains(v))
+        }
+    };
+    Some(if acierto {
+        pregunta.valor_puntos as f64
+    } else {
+        0.0
+    })
+}
+
+/// Examen que no está en la papelera
+pub(crate) async fn examen_activo<C>(db: &C, examen_id: i32) -> Result<ExamenModel, AppError>
+where
+    C: ConnectionTrait,
+{
+    Examen::find_by_id(examen_id)
+        .filter(examen::Column::FechaEliminacion.is_null())
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Examen no encontrado".into()))
+}
+
+/// Examen activo. Con `profesor_id`, solo si es de un curso que ese profesor tiene
+/// asignado
+pub(crate) async fn examen_del_docente<C>(
+    db: &C,
+    examen_id: i32,
+    profesor_id: Option<i32>,
+) -> Result<ExamenModel, AppError>
+where
+    C: ConnectionTrait,
+{
+    let examen = examen_activo(db, examen_id).await?;
+    exigir_curso_asignado(db, examen.curso_id, profesor_id).await?;
+    Ok(examen)
+}
+
+/// Momento en que vence el intento: su duración o el cierre del examen, lo primero
+fn vencimiento(intento: &IntentoModel, examen: &ExamenModel) -> DateTime<Utc> {
+    (intento.fecha_inicio + Duration::minutes(examen.duracion_minutos.into())).min(examen.fecha_fin)
+}
+
+/// Preguntas del examen en su orden
+pub(crate) async fn preguntas_de<C>(db: &C, examen_id: i32) -> Result<Vec<PreguntaModel>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    Pregunta::find()
+        .filter(pregunta_examen::Column::ExamenId.eq(examen_id))
+        .order_by_asc(pregunta_examen::Column::Orden)
+        .order_by_asc(pregunta_examen::Column::Id)
+        .all(db)
+        .await
+}
+
+/// Cuenta las respuestas manuales del examen (ver `RESPUESTAS_MANUALES`)
+async fn contar<C>(
+    db: &C,
+    examen_id: i32,
+    pregunta_id: Option<i32>,
+    incluir_calificadas: bool,
+) -> Result<u64, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let sql = format!("{INTENTOS_ENTREGADOS} SELECT COUNT(*) AS total {RESPUESTAS_MANUALES}");
+    let conteo = Conteo::find_by_statement(sentencia(
+        &sql,
+        [
+            examen_id.into(),
+            Value::Int(pregunta_id),
+            incluir_calificadas.into(),
+        ],
+    ))
+    .one(db)
+    .await?;
+    Ok(conteo.map(|c| c.total as u64).unwrap_or(0))
+}
+
+#[derive(Debug, Clone)]
+pub struct IntentoExamenService {
+    db: DbExecutor,
+}
+
+impl FromRef<AppState> for IntentoExamenService {
+    fn from_ref(state: &AppState) -> Self {
+        let executor = state
+            .db
+            .clone()
+            .expect("Database connection is not available");
+        IntentoExamenService::new(executor)
+    }
+}
+
+impl IntentoExamenService {
+    pub fn new(db: DbExecutor) -> Self {
+        Self { db }
+    }
+
+    fn connection(&self) -> DatabaseConnection {
+        self.db.connection()
+    }
+
+    /// Abre un intento del estudiante en un examen publicado y vigente, o devuelve
+    /// el que tenga en curso
+    pub async fn iniciar(
+        &self,
+        examen_id: i32,
+        estudiante_id: i32,
+    ) -> Result<DetalleIntento, AppError> {
+        let db = self.connection();
+        let txn = db.begin().await?;
+        // El bloqueo del examen serializa los inicios concurrentes al contar y numerar
+        // los intentos
+        let examen = Examen::find_by_id(examen_id)
+            .filter(examen::Column::FechaEliminacion.is_null())
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Examen no encontrado".into()))?;
+        if examen.estado != EstadoExamen::Publicado {
+            return Err(AppError::NotFound("Examen no encontrado".into()));
+        }
+
+        let matriculado = Historial::find()
+            .filter(historial_curso_estudiante::Column::CursoId.eq(examen.curso_id))
+            .filter(historial_curso_estudiante::Column::EstudianteId.eq(estudiante_id))
+            .filter(historial_curso_estudiante::Column::Estado.eq(EstadoMatricula::Activo))
+            .one(&txn)
+            .await?
+            .is_some();
+        if !matriculado {
+            return Err(AppError::Forbidden(
+                "Solo los estudiantes matriculados pueden rendir el examen".into(),
+            ));
+        }
+
+        // Bloqueados también frente a una entrega del intento en curso
+        let intentos = IntentoExamen::find()
+            .filter(intento_examen::Column::ExamenId.eq(examen_id))
+            .filter(intento_examen::Column::EstudianteId.eq(estudiante_id))
+            .order_by_asc(intento_examen::Column::Numero)
+            .lock_exclusive()
+            .all(&txn)
+            .await?;
+        let ahora = Utc::now();
+        if let Some(abierto) = intentos.iter().find(|i| i.estado == EstadoIntento::EnCurso) {
+            if ahora <= vencimiento(abierto, &examen) {
+                txn.commit().await?;
+                return self.detalle(&db, abierto.clone(), &examen, false).await;
+            }
+            // Vencido sin entregar: se cierra sin respuestas y cuenta como intento
+            let mut activo = abierto.clone().into_active_model();
+            activo.estado = Set(EstadoIntento::Calificado);
+            activo.puntaje = Set(Some(0.0));
+            activo.fecha_entrega = Set(Some(vencimiento(abierto, &examen)));
+            activo.update(&txn).await?;
+        }
+
+        let preguntas = preguntas_de(&txn, examen_id).await?;
+        let impedimento = if ahora < examen.fecha_inicio || ahora > examen.fecha_fin {
+            Some("El examen no está disponible en este momento".to_string())
+        } else if intentos.len() as i32 >= examen.intentos_permitidos {
+            Some(format!(
+                "Ya se usaron los {} intentos permitidos",
+                examen.intentos_permitidos
+            ))
+        } else if preguntas.is_empty() {
+            Some("El examen no tiene preguntas".to_string())
+        } else {
+            None
+        };
+        if let Some(motivo) = impedimento {
+            // El intento vencido queda cerrado aunque no se pueda abrir otro
+            txn.commit().await?;
+            return Err(AppError::Conflict(motivo.into()));
+        }
+
+        let intento = intento_examen::ActiveModel {
+            examen_id: Set(examen_id),
+            estudiante_id: Set(estudiante_id),
+            numero: Set(intentos.iter().map(|i| i.numero).max().unwrap_or(0) + 1),
+            estado: Set(EstadoIntento::EnCurso),
+            puntaje: Set(None),
+            puntaje_maximo: Set(Some(preguntas.iter().map(|p| p.valor_puntos as f64).sum())),
+            fecha_inicio: Set(ahora),
+            fecha_entrega: Set(None),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+        get_metrics_service().registrar_evento(EventoDominio::IntentoExamen);
+
+        self.detalle(&db, intento, &examen, false).await
+    }
+
+    /// Intento con sus respuestas. El estudiante solo ve los suyos y, hasta que
+    /// esté calificado y el examen muestre resultados, sin puntajes; con
+    /// `profesor_id`, solo los de exámenes de cursos que tiene asignados
+    pub async fn obtener(
+        &self,
+        intento_id: i32,
+        usuario_id: i32,
+        es_docente: bool,
+        profesor_id: Option<i32>,
+    ) -> Result<DetalleIntento, AppError> {
+        let db = self.connection();
+        let intento = IntentoExamen::find_by_id(intento_id)
+            .one(&db)
+            .await?
+            .filter(|i| es_docente || i.estudiante_id == usuario_id)
+            .ok_or_else(|| AppError::NotFound("Intento no encontrado".into()))?;
+        let examen = examen_del_docente(&db, intento.examen_id, profesor_id).await?;
+        self.detalle(&db, intento, &examen, es_docente).await
+    }
+
+    /// Entrega el intento: califica las preguntas de selección con clave y deja las
+    /// abiertas en la cola de calificación manual
+    pub async fn entregar(
+        &self,
+        intento_id: i32,
+        estudiante_id: i32,
+        entrega: EntregaIntento,
+    ) -> Result<DetalleIntento, AppError> {
+        let db = self.connection();
+        let txn = db.begin().await?;
+        // Con el intento bloqueado, una segunda entrega simultánea espera y ve el
+        // estado ya cambiado
+        let intento = IntentoExamen::find_by_id(intento_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .filter(|i| i.estudiante_id == estudiante_id)
+            .ok_or_else(|| AppError::NotFound("Intento no encontrado".into()))?;
+        if intento.estado != EstadoIntento::EnCurso {
+            return Err(AppError::Conflict("El intento ya fue entregado".into()));
+        }
+        let examen = examen_activo(&txn, intento.examen_id).await?;
+        if examen.estado != EstadoExamen::Publicado {
+            return Err(AppError::Conflict("El examen ya no está publicado".into()));
+        }
+        let limite = vencimiento(&intento, &examen) + Duration::seconds(MARGEN_ENTREGA_SEGUNDOS);
+        if Utc::now() > limite {
+            return Err(AppError::Conflict(
+                "Se venció el tiempo para entregar el intento".into(),
+            ));
+        }
+        let preguntas: HashMap<i32, PreguntaModel> = preguntas_de(&txn, examen.id)
+            .await?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+
+        let mut vistas = HashSet::new();
+        let mut respuestas = Vec::with_capacity(entrega.respuestas.len());
+        let mut puntajes = Vec::with_capacity(entrega.respuestas.len());
+        let ahora = Utc::now();
+        for enviada in entrega.respuestas {
+            let pregunta = preguntas.get(&enviada.pregunta_id).ok_or_else(|| {
+                AppError::BadRequest(
+                    format!("La pregunta {} no pertenece al examen", enviada.pregunta_id).into(),
+                )
+            })?;
+            if !vistas.insert(pregunta.id) {
+                return Err(AppError::BadRequest(
+                    format!("La pregunta {} está respondida dos veces", pregunta.id).into(),
+                ));
+            }
+            validar_respuesta(pregunta, &enviada.respuesta).map_err(|e| {
+                AppError::BadRequest(format!("Pregunta {}: {}", pregunta.id, e).into())
+            })?;
+
+            let puntaje = puntuar(pregunta, &enviada.respuesta);
+            puntajes.push(puntaje);
+            respuestas.push(respuesta_examen::ActiveModel {
+                intento_id: Set(intento.id),
+                pregunta_id: Set(pregunta.id),
+                respuesta: Set(enviada.respuesta),
+                puntaje: Set(puntaje),
+                retroalimentacion: Set(None),
+                calificado_por: Set(None),
+                fecha_calificacion: Set(puntaje.map(|_| ahora)),
+                ..Default::default()
+            });
+        }
+
+        let pendientes = puntajes.iter().any(Option::is_none);
+        let puntaje: f64 = puntajes.into_iter().flatten().sum();
+
+        if !respuestas.is_empty() {
+            Respuesta::insert_many(respuestas).exec(&txn).await?;
+        }
+        let mut activo = intento.into_active_model();
+        activo.fecha_entrega = Set(Some(ahora));
+        if pendientes {
+            activo.estado = Set(EstadoIntento::Entregado);
+        } else {
+            activo.estado = Set(EstadoIntento::Calificado);
+            activo.puntaje = Set(Some(puntaje));
+        }
+        let intento = activo.update(&txn).await?;
+        txn.commit().await?;
+        get_metrics_service().registrar_evento(EventoDominio::EntregaExamen);
+
+        if intento.estado == EstadoIntento::Calificado {
+            self.revisar_liberaciones(examen.curso_id, vec![intento.estudiante_id])
+                .await;
+        }
+        self.detalle(&db, intento, &examen, false).await
+    }
+
+    /// Respuestas entregadas que esperan calificación manual, agrupadas por pregunta
+    pub async fn cola(
+        &self,
+        examen_id: i32,
+        filtro: FiltroCola,
+        profesor_id: Option<i32>,
+    ) -> Result<ColaCalificacion, AppError> {
+        let db = self.connection();
+        examen_del_docente(&db, examen_id, profesor_id).await?;
+
+        let incluir_calificadas = filtro.incluir_calificadas.unwrap_or(false);
+        let limite = filtro
+            .limite
+            .unwrap_or(LIMITE_POR_DEFECTO)
+            .clamp(1, LIMITE_MAXIMO);
+        let pagina = filtro.pagina.unwrap_or(0);
+
+        let sql = format!(
+            "{INTENTOS_ENTREGADOS}
+SELECT r.id AS respuesta_id, r.intento_id, r.pregunta_id, p.pregunta, p.pregunta_html,
+       p.valor_puntos, r.respuesta, r.puntaje, r.retroalimentacion, i.fecha_entrega,
+       'Intento ' || i.n AS alias, i.estudiante_id, u.nombre AS estudiante
+{RESPUESTAS_MANUALES}
+ORDER BY p.orden, p.id, i.id
+LIMIT {limite} OFFSET {}",
+            pagina.saturating_mul(limite).min(i64::MAX as u64)
+        );
+        let mut elementos = RespuestaPorCalificar::find_by_statement(sentencia(
+            &sql,
+            [
+                examen_id.into(),
+                Value::Int(filtro.pregunta_id),
+                incluir_calificadas.into(),
+            ],
+        ))
+        .all(&db)
+        .await?;
+        if filtro.anonimo.unwrap_or(false) {
+            for elemento in &mut elementos {
+                elemento.estudiante_id = None;
+                elemento.estudiante = None;
+            }
+        }
+
+        Ok(ColaCalificacion {
+            total: contar(&db, examen_id, filtro.pregunta_id, incluir_calificadas).await?,
+            pendientes: contar(&db, examen_id, None, false).await?,
+            pagina,
+            limite,
+            elementos,
+        })
+    }
+
+    /// Califica un lote de respuestas del examen (también recalifica). Los intentos
+    /// sin respuestas pendientes reciben su nota final y, si el examen muestra
+    /// resultados, se avisa al estudiante
+    pub async fn calificar(
+        &self,
+        examen_id: i32,
+        calificaciones: Vec<CalificacionRespuesta>,
+        calificador_id: i32,
+        profesor_id: Option<i32>,
+        ctx: &ContextoAuditoria,
+    ) -> Result<ResultadoCalificacion, AppError> {
+        let db = self.connection();
+        let examen = examen_del_docente(&db, examen_id, profesor_id).await?;
+        if calificaciones.is_empty() {
+            return Err(AppError::BadRequest(
+                "No hay calificaciones que guardar".into(),
+            ));
+        }
+
+        let ids: Vec<i32> = calificaciones.iter().map(|c| c.respuesta_id).collect();
+        if ids.iter().collect::<HashSet<_>>().len() != ids.len() {
+            return Err(AppError::BadRequest(
+                "Una respuesta aparece más de una vez".into(),
+            ));
+        }
+        let respuestas: HashMap<i32, RespuestaModel> = Respuesta::find()
+            .filter(respuesta_examen::Column::Id.is_in(ids.clone()))
+            .all(&db)
+            .await?
+            .into_iter()
+            .map(|r| (r.id, r))
+            .collect();
+        let intentos: HashMap<i32, IntentoModel> = IntentoExamen::find()
+            .filter(
+                intento_examen::Column::Id.is_in(
+                    respuestas
+                        .values()
+                        .map(|r| r.intento_id)
+                        .collect::<Vec<_>>(),
+                ),
+            )
+            .all(&db)
+            .await?
+            .into_iter()
+            .map(|i| (i.id, i))
+            .collect();
+        let preguntas: HashMap<i32, PreguntaModel> = preguntas_de(&db, examen_id)
+            .await?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+
+        for calificacion in &calificaciones {
+            let id = calificacion.respuesta_id;
+            let respuesta = respuestas.get(&id).ok_or_else(|| {
+                AppError::NotFound(format!("Respuesta {} no encontrada", id).into())
+            })?;
+            let intento = intentos
+                .get(&respuesta.intento_id)
+                .filter(|i| i.examen_id == examen_id)
+                .ok_or_else(|| {
+                    AppError::BadRequest(
+                        format!("La respuesta {} no pertenece al examen", id).into(),
+                    )
+                })?;
+            if intento.estado == EstadoIntento::EnCurso {
+                return Err(AppError::Conflict(
+                    format!("La respuesta {} es de un intento sin entregar", id).into(),
+                ));
+            }
+            let maximo = preguntas
+                .get(&respuesta.pregunta_id)
+                .map(|p| p.valor_puntos as f64)
+                .unwrap_or(0.0);
+            if !calificacion.puntaje.is_finite()
+                || calificacion.puntaje < 0.0
+                || calificacion.puntaje > maximo
+            {
+                return Err(AppError::BadRequest(
+                    format!(
+                        "El puntaje de la respuesta {} debe estar entre 0 y {}",
+                        id, maximo
+                    )
+                    .into(),
+                ));
+            }
+        }
+
+        let ahora = Utc::now();
+        let mut antes = serde_json::Map::new();
+        let mut despues = serde_json::Map::new();
+        let txn = db.begin().await?;
+        for calificacion in &calificaciones {
+            let respuesta = respuestas[&calificacion.respuesta_id].clone();
+            let retroalimentacion = calificacion
+                .retroalimentacion
+                .as_deref()
+                .map(str::trim)
+                .filter(|r| !r.is_empty())
+                .map(str::to_string);
+            antes.insert(
+                respuesta.id.to_string(),
+                json!({ "puntaje": respuesta.puntaje, "retroalimentacion": respuesta.retroalimentacion }),
+            );
+            despues.insert(
+                respuesta.id.to_string(),
+                json!({ "puntaje": calificacion.puntaje, "retroalimentacion": retroalimentacion }),
+            );
+
+            let mut activo = respuesta.into_active_model();
+            activo.puntaje = Set(Some(calificacion.puntaje));
+            activo.retroalimentacion = Set(retroalimentacion);
+            activo.calificado_por = Set(Some(calificador_id));
+            activo.fecha_calificacion = Set(Some(ahora));
+            activo.update(&txn).await?;
+        }
+
+        // Nota final de los intentos que ya no tienen respuestas pendientes
+        let mut afectados: Vec<&IntentoModel> = intentos.values().collect();
+        afectados.sort_by_key(|i| i.id);
+        let mut cerrados = Vec::new();
+        let mut avisos = Vec::new();
+        for intento in afectados {
+            let totales =
+                Totales::find_by_statement(sentencia(TOTALES_INTENTO, [intento.id.into()]))
+                    .one(&txn)
+                    .await?
+                    .ok_or_else(|| DbErr::RecordNotFound("respuestas_examen".to_string()))?;
+            if totales.pendientes > 0 {
+                continue;
+            }
+            if intento.estado == EstadoIntento::Entregado {
+                cerrados.push(intento.id);
+            } else if intento.puntaje == Some(totales.puntaje) {
+                continue;
+            }
+
+            let mut activo = intento.clone().into_active_model();
+            activo.estado = Set(EstadoIntento::Calificado);
+            activo.puntaje = Set(Some(totales.puntaje));
+            avisos.push((activo.update(&txn).await?, intento.estado));
+        }
+
+        AuditoriaService::registrar(
+            &txn,
+            ctx,
+            "calificar_respuestas",
+            "examen",
+            Some(examen_id),
+            Some(&JsonValue::Object(antes)),
+            Some(&JsonValue::Object(despues)),
+        )
+        .await?;
+        txn.commit().await?;
+
+        if !avisos.is_empty() {
+            if examen.mostrar_resultados {
+                for (intento, estado_anterior) in &avisos {
+                    self.notificar_nota(&examen, intento, *estado_anterior)
+                        .await;
+                }
+            }
+            let estudiantes = avisos.iter().map(|(i, _)| i.estudiante_id).collect();
+            self.revisar_liberaciones(examen.curso_id, estudiantes)
+                .await;
+        }
+
+        Ok(ResultadoCalificacion {
+            calificadas: calificaciones.len(),
+            intentos_cerrados: cerrados,
+            pendientes: contar(&db, examen_id, None, false).await?,
+        })
+    }
+
+    async fn detalle<C>(
+        &self,
+        db: &C,
+        mut intento: IntentoModel,
+        examen: &ExamenModel,
+        es_docente: bool,
+    ) -> Result<DetalleIntento, AppError>
+    where
+        C: ConnectionTrait,
+    {
+        let resultados_visibles = es_docente
+            || (intento.estado == EstadoIntento::Calificado && examen.mostrar_resultados);
+        let mut respuestas: HashMap<i32, RespuestaModel> = Respuesta::find()
+            .filter(respuesta_examen::Column::IntentoId.eq(intento.id))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|r| (r.pregunta_id, r))
+            .collect();
+
+        let preguntas = preguntas_de(db, examen.id)
+            .await?
+            .into_iter()
+            .map(|p| {
+                let respuesta = respuestas.remove(&p.id);
+                let (puntaje, retroalimentacion) = match &respuesta {
+                    Some(r) if resultados_visibles => (r.puntaje, r.retroalimentacion.clone()),
+                    _ => (None, None),
+                };
+                PreguntaIntento {
+                    pregunta_id: p.id,
+                    pregunta: p.pregunta,
+                    pregunta_html: p.pregunta_html,
+                    tipo: p.tipo_pregunta,
+                    opciones: p.opciones,
+                    valor_puntos: p.valor_puntos,
+                    respuesta: respuesta.map(|r| r.respuesta),
+                    puntaje,
+                    retroalimentacion,
+                }
+            })
+            .collect();
+
+        if !resultados_visibles {
+            intento.puntaje = None;
+        }
+        Ok(DetalleIntento {
+            intento,
+            resultados_visibles,
+            preguntas,
+        })
+    }
+
+    async fn notificar_nota(
+        &self,
+        examen: &ExamenModel,
+        intento: &IntentoModel,
+        estado_anterior: EstadoIntento,
+    ) {
+        let titulo = if estado_anterior == EstadoIntento::Calificado {
+            format!("Calificación actualizada en {}", examen.nombre)
+        } else {
+            format!("Examen calificado: {}", examen.nombre)
+        };
+        let notificacion = NuevaNotificacion {
+            usuario_id: intento.estudiante_id,
+            titulo,
+            mensaje: format!(
+                "Tu intento {} obtuvo **{} de {}** puntos.",
+                intento.numero,
+                intento.puntaje.unwrap_or(0.0),
+                intento.puntaje_maximo.unwrap_or(0.0)
+            ),
+            tipo: "examen".to_string(),
+            leida: None,
+            enlace: None,
+            datos_adicionales: Some(json!({
+                "examen_id": examen.id,
+                "intento_id": intento.id,
+            })),
+        };
+        if let Err(e) = NotificacionService::new(self.db.clone())
+            .crear_notificacion(notificacion)
+            .await
+        {
+            tracing::warn!(
+                "⚠️  No se pudo notificar la calificación al estudiante {}: {}",
+                intento.estudiante_id,
+                e
+            );
+        }
+    }
+
+    /// Un intento calificado puede cumplir condiciones de liberación del curso
+    async fn revisar_liberaciones(&self, curso_id: i32, estudiantes: Vec<i32>) {
+        if let Err(e) = LiberacionService::new(self.db.clone())
+            .revisar_estudiantes(curso_id, estudiantes)
+            .await
+        {
+            tracing::warn!(
+                "⚠️  No se pudieron revisar las liberaciones del curso {}: {}",
+                curso_id,
+                e
+            );
+        }
+    }
+}
@@ -0,0 +1,750 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::extract::FromRef;
+use chrono::Utc;
+use sea_orm::{
+    sea_query::OnConflict, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QuerySelect, Set, TransactionTrait,
+};
+use serde_json::json;
+
+use crate::{
+    database::DbExecutor,
+    models::{
+        actividad_entrega::Entity as ActividadEntrega,
+        condicion_liberacion::{self, Entity as Condicion, Model as CondicionModel},
+        contenido_unidad::{self, Entity as Contenido},
+        entrega::{self, Entity as Entrega},
+        examen::{self, Entity as Examen},
+        historial_curso_estudiante::{self, Entity as Historial, EstadoMatricula},
+        intento_examen::{self, Entity as IntentoExamen, EstadoIntento},
+        liberacion_estudiante::{self, Entity as LiberacionEstudiante},
+        progreso_contenido::{self, Entity as ProgresoContenido, Model as ProgresoContenidoModel},
+        publicacion::TipoPublicable,
+        tema::Entity as Tema,
+        unidad::Entity as Unidad,
+        AppState,
+    },
+    services::{
+        auditoria_service::{AuditoriaService, ContextoAuditoria},
+        notificacion_service::{NotificacionService, NuevaNotificacion},
+        publicacion_service::ubicar,
+    },
+    utils::errors::AppError,
+};
+
+pub use crate::models::condicion_liberacion::{CondicionLiberacion, TipoCondicion};
+
+/// Situación de un estudiante en un curso: por qué sigue bloqueado cada elemento
+/// con condiciones y qué contenidos completó
+#[derive(Debug, Default)]
+pub struct Bloqueos {
+    motivos: HashMap<(TipoPublicable, i32), Vec<String>>,
+    pub completados: HashSet<i32>,
+}
+
+impl Bloqueos {
+    /// Condiciones propias del elemento que aún no se cumplen (vacío = liberado)
+    pub fn motivos(&self, tipo: TipoPublicable, id: i32) -> &[String] {
+        self.motivos
+            .get(&(tipo, id))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+/// El elemento y sus contenedores, del módulo hacia abajo
+async fn ancestros<C>(
+    db: &C,
+    tipo: TipoPublicable,
+    id: i32,
+) -> Result<Vec<(TipoPublicable, i32)>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let mut cadena = vec![(tipo, id)];
+    let (mut tipo, mut id) = (tipo, id);
+    loop {
+        let padre = match tipo {
+            TipoPublicable::Contenido => Contenido::find_by_id(id)
+                .one(db)
+                .await?
+                .map(|c| (TipoPublicable::Unidad, c.unidad_id)),
+            TipoPublicable::Unidad => Unidad::find_by_id(id)
+                .one(db)
+                .await?
+                .map(|u| (TipoPublicable::Tema, u.tema_id)),
+            TipoPublicable::Tema => Tema::find_by_id(id)
+                .one(db)
+                .await?
+                .map(|t| (TipoPublicable::Modulo, t.modulo_id)),
+            TipoPublicable::Modulo => None,
+        };
+        match padre {
+            Some(padre) => {
+                cadena.insert(0, padre);
+                (tipo, id) = padre;
+            }
+            None => return Ok(cadena),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LiberacionService {
+    db: DbExecutor,
+}
+
+impl FromRef<AppState> for LiberacionService {
+    fn from_ref(state: &AppState) -> Self {
+        let executor = state
+            .db
+            .clone()
+            .expect("Database connection is not available");
+        LiberacionService::new(executor)
+    }
+}
+
+impl LiberacionService {
+    pub fn new(db: DbExecutor) -> Self {
+        Self { db }
+    }
+
+    fn connection(&self) -> DatabaseConnection {
+        self.db.connection()
+    }
+
+    pub async fn obtener_condiciones(
+        &self,
+        tipo: TipoPublicable,
+        id: i32,
+    ) -> Result<Vec<CondicionLiberacion>, AppError> {
+        let db = self.connection();
+        ubicar(&db, tipo, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Elemento no encontrado".into()))?;
+
+        Ok(condiciones_de(&db, tipo, id)
+            .await?
+            .into_iter()
+            .map(CondicionLiberacion::from)
+            .collect())
+    }
+
+    /// Reemplaza las condiciones del elemento (lista vacía = sin condiciones). Los
+    /// estudiantes que ya las cumplen quedan liberados sin aviso; el resto recibe
+    /// una notificación cuando las cumpla
+    pub async fn reemplazar_condiciones(
+        &self,
+        tipo: TipoPublicable,
+        id: i32,
+        condiciones: Vec<CondicionLiberacion>,
+        ctx: &ContextoAuditoria,
+    ) -> Result<Vec<CondicionLiberacion>, AppError> {
+        let db = self.connection();
+        let curso_id = ubicar(&db, tipo, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Elemento no encontrado".into()))?
+            .curso_id;
+
+        let mut normalizadas = Vec::with_capacity(condiciones.len());
+        for condicion in condiciones {
+            normalizadas.push(self.validar(curso_id, tipo, id, condicion).await?);
+        }
+
+        let txn = db.begin().await?;
+        let antes: Vec<CondicionLiberacion> = condiciones_de(&txn, tipo, id)
+            .await?
+            .into_iter()
+            .map(CondicionLiberacion::from)
+            .collect();
+
+        Condicion::delete_many()
+            .filter(condicion_liberacion::Column::TipoElemento.eq(tipo))
+            .filter(condicion_liberacion::Column::ElementoId.eq(id))
+            .exec(&txn)
+            .await?;
+        LiberacionEstudiante::delete_many()
+            .filter(liberacion_estudiante::Column::TipoElemento.eq(tipo))
+            .filter(liberacion_estudiante::Column::ElementoId.eq(id))
+            .exec(&txn)
+            .await?;
+        if !normalizadas.is_empty() {
+            Condicion::insert_many(normalizadas.iter().map(|c| {
+                condicion_liberacion::ActiveModel {
+                    curso_id: Set(curso_id),
+                    tipo_elemento: Set(tipo),
+                    elemento_id: Set(id),
+                    tipo: Set(c.tipo),
+                    contenido_id: Set(c.contenido_id),
+                    examen_id: Set(c.examen_id),
+                    puntaje_minimo: Set(c.puntaje_minimo),
+                    fecha: Set(c.fecha),
+                    actividad_entrega_id: Set(c.actividad_entrega_id),
+                    ..Default::default()
+                }
+            }))
+            .exec(&txn)
+            .await?;
+        }
+
+        AuditoriaService::registrar(
+            &txn,
+            ctx,
+            "actualizar_condiciones_liberacion",
+            tipo.nombre(),
+            Some(id),
+            Some(&antes),
+            Some(&normalizadas),
+        )
+        .await?;
+        txn.commit().await?;
+
+        self.revisar(curso_id, None, false).await?;
+        Ok(normalizadas)
+    }
+
+    /// Comprueba que la condición tenga los campos de su tipo y que lo referenciado
+    /// sea del mismo curso; descarta los campos que no aplican
+    async fn validar(
+        &self,
+        curso_id: i32,
+        tipo: TipoPublicable,
+        id: i32,
+        condicion: CondicionLiberacion,
+    ) -> Result<CondicionLiberacion, AppError> {
+        let db = self.connection();
+        let mut normalizada = CondicionLiberacion {
+            tipo: condicion.tipo,
+            contenido_id: None,
+            examen_id: None,
+            puntaje_minimo: None,
+            fecha: None,
+            actividad_entrega_id: None,
+        };
+
+        match condicion.tipo {
+            TipoCondicion::ContenidoCompletado => {
+                let contenido_id = condicion.contenido_id.ok_or_else(|| {
+                    AppError::BadRequest("`contenido_completado` requiere `contenido_id`".into())
+                })?;
+                let del_curso = ubicar(&db, TipoPublicable::Contenido, contenido_id)
+                    .await?
+                    .is_some_and(|u| u.curso_id == curso_id);
+                if !del_curso {
+                    return Err(AppError::BadRequest(
+                        format!("El contenido {} no pertenece al curso", contenido_id).into(),
+                    ));
+                }
+                // Completar un contenido exige poder abrirlo
+                if ancestros(&db, TipoPublicable::Contenido, contenido_id)
+                    .await?
+                    .contains(&(tipo, id))
+                {
+                    return Err(AppError::BadRequest(
+                        format!(
+                            "El contenido {} está dentro del elemento que bloquea",
+                            contenido_id
+                        )
+                        .into(),
+                    ));
+                }
+                normalizada.contenido_id = Some(contenido_id);
+            }
+            TipoCondicion::PuntajeExamen => {
+                let (Some(examen_id), Some(puntaje_minimo)) =
+                    (condicion.examen_id, condicion.puntaje_minimo)
+                else {
+                    return Err(AppError::BadRequest(
+                        "`puntaje_examen` requiere `examen_id` y `puntaje_minimo`".into(),
+                    ));
+                };
+                if !puntaje_minimo.is_finite() || puntaje_minimo < 0.0 {
+                    return Err(AppError::BadRequest(
+                        "`puntaje_minimo` debe ser mayor o igual a 0".into(),
+                    ));
+                }
+                let del_curso = Examen::find_by_id(examen_id)
+                    .filter(examen::Column::FechaEliminacion.is_null())
+                    .one(&db)
+                    .await?
+                    .is_some_and(|e| e.curso_id == curso_id);
+                if !del_curso {
+                    return Err(AppError::BadRequest(
+                        format!("El examen {} no pertenece al curso", examen_id).into(),
+                    ));
+                }
+                normalizada.examen_id = Some(examen_id);
+                normalizada.puntaje_minimo = Some(puntaje_minimo);
+            }
+            TipoCondicion::Fecha => {
+                normalizada.fecha = Some(condicion.fecha.ok_or_else(|| {
+                    AppError::BadRequest("`fecha` requiere el campo `fecha`".into())
+                })?);
+            }
+            TipoCondicion::EntregaEnviada => {
+                let actividad_id = condicion.actividad_entrega_id.ok_or_else(|| {
+                    AppError::BadRequest("`entrega_enviada` requiere `actividad_entrega_id`".into())
+                })?;
+                let unidad_id = ActividadEntrega::find_by_id(actividad_id)
+                    .one(&db)
+                    .await?
+                    .map(|a| a.unidad_id);
+                let del_curso = match unidad_id {
+                    Some(unidad_id) => ubicar(&db, TipoPublicable::Unidad, unidad_id)
+                        .await?
+                        .is_some_and(|u| u.curso_id == curso_id),
+                    None => false,
+                };
+                if !del_curso {
+                    return Err(AppError::BadRequest(
+                        format!(
+                            "La actividad de entrega {} no pertenece al curso",
+                            actividad_id
+                        )
+                        .into(),
+                    ));
+                }
+                normalizada.actividad_entrega_id = Some(actividad_id);
+            }
+        }
+
+        Ok(normalizada)
+    }
+
+    /// Evalúa todas las condiciones del curso para un estudiante
+    pub async fn bloqueos(&self, curso_id: i32, estudiante_id: i32) -> Result<Bloqueos, AppError> {
+        let db = self.connection();
+        let condiciones = Condicion::find()
+            .filter(condicion_liberacion::Column::CursoId.eq(curso_id))
+            .all(&db)
+            .await?;
+        Ok(evaluar(&db, &condiciones, estudiante_id).await?)
+    }
+
+    /// Condiciones sin cumplir del elemento y de sus contenedores para un estudiante
+    /// (vacío = liberado o inexistente)
+    pub async fn motivos_bloqueo(
+        &self,
+        tipo: TipoPublicable,
+        id: i32,
+        estudiante_id: i32,
+    ) -> Result<Vec<String>, AppError> {
+        let db = self.connection();
+        let Some(ubicacion) = ubicar(&db, tipo, id).await? else {
+            return Ok(Vec::new());
+        };
+        let bloqueos = self.bloqueos(ubicacion.curso_id, estudiante_id).await?;
+        Ok(ancestros(&db, tipo, id)
+            .await?
+            .into_iter()
+            .flat_map(|(tipo, id)| bloqueos.motivos(tipo, id).to_vec())
+            .collect())
+    }
+
+    /// Marca un contenido visible y liberado como completado por un estudiante
+    /// matriculado; puede liberar otros elementos del curso
+    pub async fn completar_contenido(
+        &self,
+        contenido_id: i32,
+        estudiante_id: i32,
+    ) -> Result<ProgresoContenidoModel, AppError> {
+        let db = self.connection();
+        let ubicacion = ubicar(&db, TipoPublicable::Contenido, contenido_id)
+            .await?
+            .filter(|u| u.visible)
+            .ok_or_else(|| AppError::NotFound("Contenido no encontrado".into()))?;
+
+        let matriculado = Historial::find()
+            .filter(historial_curso_estudiante::Column::CursoId.eq(ubicacion.curso_id))
+            .filter(historial_curso_estudiante::Column::EstudianteId.eq(estudiante_id))
+            .filter(historial_curso_estudiante::Column::Estado.eq(EstadoMatricula::Activo))
+            .one(&db)
+            .await?
+            .is_some();
+        if !matriculado {
+            return Err(AppError::Forbidden(
+                "Solo los estudiantes matriculados pueden completar contenidos del curso".into(),
+            ));
+        }
+
+        let motivos = self
+            .motivos_bloqueo(TipoPublicable::Contenido, contenido_id, estudiante_id)
+            .await?;
+        if !motivos.is_empty() {
+            return Err(AppError::Conflict(
+                format!("Contenido bloqueado: {}", motivos.join("; ")).into(),
+            ));
+        }
+
+        ProgresoContenido::insert(progreso_contenido::ActiveModel {
+            contenido_id: Set(contenido_id),
+            estudiante_id: Set(estudiante_id),
+            fecha_completado: Set(Utc::now()),
+            ..Default::default()
+        })
+        .on_conflict(
+            OnConflict::columns([
+                progreso_contenido::Column::ContenidoId,
+                progreso_contenido::Column::EstudianteId,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(&db)
+        .await?;
+
+        let progreso = ProgresoContenido::find()
+            .filter(progreso_contenido::Column::ContenidoId.eq(contenido_id))
+            .filter(progreso_contenido::Column::EstudianteId.eq(estudiante_id))
+            .one(&db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Contenido no encontrado".into()))?;
+
+        if let Err(e) = self
+            .revisar(ubicacion.curso_id, Some(vec![estudiante_id]), true)
+            .await
+        {
+            tracing::warn!(
+                "⚠️  No se pudieron revisar las liberaciones del curso {}: {}",
+                ubicacion.curso_id,
+                e
+            );
+        }
+        Ok(progreso)
+    }
+
+    /// Job de liberación: revisa los cursos con condiciones (fechas que llegaron,
+    /// intentos calificados, entregas enviadas...) y avisa a los estudiantes.
+    /// Devuelve cuántas liberaciones nuevas hubo
+    pub async fn revisar_todo(&self) -> Result<u64, AppError> {
+        let cursos: Vec<i32> = Condicion::find()
+            .select_only()
+            .column(condicion_liberacion::Column::CursoId)
+            .distinct()
+            .into_tuple()
+            .all(&self.connection())
+            .await?;
+
+        let mut total = 0;
+        for curso_id in cursos {
+            total += self.revisar(curso_id, None, true).await?;
+        }
+        Ok(total)
+    }
+
+    /// Revisa las liberaciones de algunos estudiantes tras un cambio que puede cumplir
+    /// condiciones (p. ej. un intento de examen calificado) y les avisa
+    pub async fn revisar_estudiantes(
+        &self,
+        curso_id: i32,
+        estudiantes: Vec<i32>,
+    ) -> Result<u64, AppError> {
+        self.revisar(curso_id, Some(estudiantes), true).await
+    }
+
+    /// Registra como liberados los elementos con condiciones que los estudiantes
+    /// (por defecto, los de matrícula activa) ya cumplen y, con `notificar`, les avisa
+    /// de los que quedaron accesibles
+    async fn revisar(
+        &self,
+        curso_id: i32,
+        estudiantes: Option<Vec<i32>>,
+        notificar: bool,
+    ) -> Result<u64, AppError> {
+        let db = self.connection();
+        let condiciones = Condicion::find()
+            .filter(condicion_liberacion::Column::CursoId.eq(curso_id))
+            .all(&db)
+            .await?;
+        if condiciones.is_empty() {
+            return Ok(0);
+        }
+        let elementos: HashSet<(TipoPublicable, i32)> = condiciones
+            .iter()
+            .map(|c| (c.tipo_elemento, c.elemento_id))
+            .collect();
+
+        let estudiantes = match estudiantes {
+            Some(estudiantes) => estudiantes,
+            None => {
+                Historial::find()
+                    .select_only()
+                    .column(historial_curso_estudiante::Column::EstudianteId)
+                    .filter(historial_curso_estudiante::Column::CursoId.eq(curso_id))
+                    .filter(historial_curso_estudiante::Column::Estado.eq(EstadoMatricula::Activo))
+                    .into_tuple()
+                    .all(&db)
+                    .await?
+            }
+        };
+
+        let mut total = 0;
+        for estudiante_id in estudiantes {
+            let bloqueos = evaluar(&db, &condiciones, estudiante_id).await?;
+            let liberados: HashSet<(TipoPublicable, i32)> = LiberacionEstudiante::find()
+                .filter(liberacion_estudiante::Column::EstudianteId.eq(estudiante_id))
+                .all(&db)
+                .await?
+                .into_iter()
+                .map(|l| (l.tipo_elemento, l.elemento_id))
+                .collect();
+
+            let nuevos: Vec<(TipoPublicable, i32)> = elementos
+                .iter()
+                .copied()
+                .filter(|&(tipo, id)| {
+                    bloqueos.motivos(tipo, id).is_empty() && !liberados.contains(&(tipo, id))
+                })
+                .collect();
+            if nuevos.is_empty() {
+                continue;
+            }
+
+            let ahora = Utc::now();
+            LiberacionEstudiante::insert_many(nuevos.iter().map(|&(tipo, id)| {
+                liberacion_estudiante::ActiveModel {
+                    tipo_elemento: Set(tipo),
+                    elemento_id: Set(id),
+                    estudiante_id: Set(estudiante_id),
+                    fecha: Set(ahora),
+                    ..Default::default()
+                }
+            }))
+            .on_conflict(
+                OnConflict::columns([
+                    liberacion_estudiante::Column::TipoElemento,
+                    liberacion_estudiante::Column::ElementoId,
+                    liberacion_estudiante::Column::EstudianteId,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .exec_without_returning(&db)
+            .await?;
+            total += nuevos.len() as u64;
+
+            if notificar {
+                self.notificar_liberados(&db, curso_id, estudiante_id, &bloqueos, &nuevos)
+                    .await?;
+            }
+        }
+        Ok(total)
+    }
+
+    /// Un aviso por estudiante con los elementos que ya puede abrir: visibles y sin
+    /// contenedores que sigan bloqueados
+    async fn notificar_liberados(
+        &self,
+        db: &DatabaseConnection,
+        curso_id: i32,
+        estudiante_id: i32,
+        bloqueos: &Bloqueos,
+        nuevos: &[(TipoPublicable, i32)],
+    ) -> Result<(), AppError> {
+        let mut curso = None;
+        let mut nombres = Vec::new();
+        let mut elementos = Vec::new();
+        for &(tipo, id) in nuevos {
+            let Some(ubicacion) = ubicar(db, tipo, id).await?.filter(|u| u.visible) else {
+                continue;
+            };
+            let contenedor_bloqueado = ancestros(db, tipo, id)
+                .await?
+                .into_iter()
+                .any(|(tipo, id)| !bloqueos.motivos(tipo, id).is_empty());
+            if contenedor_bloqueado {
+                continue;
+            }
+            nombres.push(format!("«{}»", ubicacion.nombre));
+            elementos.push(json!({ "tipo": tipo, "id": id }));
+            curso.get_or_insert(ubicacion.curso);
+        }
+        let Some(curso) = curso else {
+            return Ok(());
+        };
+
+        let notificacion = NuevaNotificacion {
+            usuario_id: estudiante_id,
+            titulo: format!("Contenido desbloqueado en {}", curso),
+            mensaje: format!("Ya puedes acceder a: {}.", nombres.join(", ")),
+            tipo: "curso".to_string(),
+            leida: None,
+            enlace: None,
+            datos_adicionales: Some(json!({ "curso_id": curso_id, "elementos": elementos })),
+        };
+        if let Err(e) = NotificacionService::new(self.db.clone())
+            .crear_notificacion(notificacion)
+            .await
+        {
+            tracing::warn!(
+                "⚠️  No se pudo notificar el contenido desbloqueado al estudiante {}: {}",
+                estudiante_id,
+                e
+            );
+        }
+        Ok(())
+    }
+}
+
+async fn condiciones_de<C>(
+    db: &C,
+    tipo: TipoPublicable,
+    id: i32,
+) -> Result<Vec<CondicionModel>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    Condicion::find()
+        .filter(condicion_liberacion::Column::TipoElemento.eq(tipo))
+        .filter(condicion_liberacion::Column::ElementoId.eq(id))
+        .all(db)
+        .await
+}
+
+/// Motivos de bloqueo de un estudiante para las condiciones dadas, con los nombres
+/// de lo que le falta
+async fn evaluar<C>(
+    db: &C,
+    condiciones: &[CondicionModel],
+    estudiante_id: i32,
+) -> Result<Bloqueos, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let completados: HashSet<i32> = ProgresoContenido::find()
+        .select_only()
+        .column(progreso_contenido::Column::ContenidoId)
+        .filter(progreso_contenido::Column::EstudianteId.eq(estudiante_id))
+        .into_tuple::<i32>()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect();
+
+    let examenes: Vec<i32> = condiciones.iter().filter_map(|c| c.examen_id).collect();
+    let actividades: Vec<i32> = condiciones
+        .iter()
+        .filter_map(|c| c.actividad_entrega_id)
+        .collect();
+    let contenidos: Vec<i32> = condiciones.iter().filter_map(|c| c.contenido_id).collect();
+
+    let mut mejores: HashMap<i32, f64> = HashMap::new();
+    let mut nombres_examen: HashMap<i32, String> = HashMap::new();
+    if !examenes.is_empty() {
+        for intento in IntentoExamen::find()
+            .filter(intento_examen::Column::EstudianteId.eq(estudiante_id))
+            .filter(intento_examen::Column::ExamenId.is_in(examenes.clone()))
+            .filter(intento_examen::Column::Estado.eq(EstadoIntento::Calificado))
+            .all(db)
+            .await?
+        {
+            let puntaje = intento.puntaje.unwrap_or(0.0);
+            let mejor = mejores.entry(intento.examen_id).or_insert(puntaje);
+            *mejor = mejor.max(puntaje);
+        }
+        nombres_examen = Examen::find()
+            .filter(examen::Column::Id.is_in(examenes))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|e| (e.id, e.nombre))
+            .collect();
+    }
+
+    let mut enviadas: HashSet<i32> = HashSet::new();
+    let mut nombres_actividad: HashMap<i32, String> = HashMap::new();
+    if !actividades.is_empty() {
+        enviadas = Entrega::find()
+            .select_only()
+            .column(entrega::Column::ActividadEntregaId)
+            .filter(entrega::Column::EstudianteId.eq(estudiante_id))
+            .filter(entrega::Column::ActividadEntregaId.is_in(actividades.clone()))
+            .into_tuple::<i32>()
+            .all(db)
+            .await?
+            .into_iter()
+            .collect();
+        nombres_actividad = ActividadEntrega::find()
+            .filter(crate::models::actividad_entrega::Column::Id.is_in(actividades))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|a| (a.id, a.nombre))
+            .collect();
+    }
+
+    let titulos: HashMap<i32, String> = if contenidos.is_empty() {
+        HashMap::new()
+    } else {
+        Contenido::find()
+            .filter(contenido_unidad::Column::Id.is_in(contenidos))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|c| (c.id, c.titulo))
+            .collect()
+    };
+
+    let ahora = Utc::now();
+    let mut bloqueos = Bloqueos {
+        completados,
+        ..Default::default()
+    };
+    for condicion in condiciones {
+        let pendiente = match condicion.tipo {
+            TipoCondicion::ContenidoCompletado => condicion
+                .contenido_id
+                .filter(|id| !bloqueos.completados.contains(id))
+                .map(|id| {
+                    format!(
+                        "Completa «{}»",
+                        titulos
+                            .get(&id)
+                            .map(String::as_str)
+                            .unwrap_or("un contenido")
+                    )
+                }),
+            TipoCondicion::PuntajeExamen => {
+                let examen_id = condicion.examen_id.unwrap_or_default();
+                let minimo = condicion.puntaje_minimo.unwrap_or_default();
+                let alcanzado = mejores.get(&examen_id).is_some_and(|p| *p >= minimo);
+                (!alcanzado).then(|| {
+                    format!(
+                        "Obtén al menos {} puntos en «{}»",
+                        minimo,
+                        nombres_examen
+                            .get(&examen_id)
+                            .map(String::as_str)
+                            .unwrap_or("el examen")
+                    )
+                })
+            }
+            TipoCondicion::Fecha => condicion
+                .fecha
+                .filter(|fecha| *fecha > ahora)
+                .map(|fecha| format!("Disponible desde {}", fecha.format("%Y-%m-%d %H:%M UTC"))),
+            TipoCondicion::EntregaEnviada => condicion
+                .actividad_entrega_id
+                .filter(|id| !enviadas.contains(id))
+                .map(|id| {
+                    format!(
+                        "Envía la entrega «{}»",
+                        nombres_actividad
+                            .get(&id)
+                            .map(String::as_str)
+                            .unwrap_or("pendiente")
+                    )
+                }),
+        };
+        if let Some(motivo) = pendiente {
+            bloqueos
+                .motivos
+                .entry((condicion.tipo_elemento, condicion.elemento_id))
+                .or_default()
+                .push(motivo);
+        }
+    }
+
+    Ok(bloqueos)
+}
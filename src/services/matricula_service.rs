@@ -1,26 +1,108 @@
+use std::collections::BTreeSet;
+
 use axum::extract::FromRef;
 use chrono::Utc;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, Order, PaginatorTrait,
-    QueryFilter, QueryOrder, Set,
+    ActiveEnum, ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend,
+    EntityTrait, Order, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set, Statement,
+    TransactionTrait,
 };
 
 use crate::{
     database::DbExecutor,
     models::{
-        curso::Entity as Curso,
-        historial_curso_estudiante::{self, Entity as Historial, Model as HistorialModel},
-        usuario::Entity as Usuario,
+        cambio_estado_matricula::{
+            self, Entity as CambioEstadoMatricula, Model as CambioEstadoModel,
+        },
+        curso::{
+            self, CursoPrerequisito, Entity as Curso, Model as CursoModel, ReglasMatricula,
+            ReglasMatriculaCurso,
+        },
+        historial_curso_estudiante::{
+            self, CambioEstado, CierreCurso, Entity as Historial, EstadoMatricula,
+            Model as HistorialModel, ResumenCierre,
+        },
+        lista_espera::{self, Entity as ListaEspera, EntradaListaEspera},
+        notificacion::NuevaNotificacion,
+        prerequisito_curso::{self, Entity as Prerequisito},
+        usuario::{Entity as Usuario, Model as UsuarioModel},
         AppState,
     },
+    services::{
+        auditoria_service::{AuditoriaService, ContextoAuditoria},
+        notificacion_service::NotificacionService,
+    },
     utils::errors::AppError,
 };
 
+/// Resultado de pedir una matrícula: cupo asignado o puesto en la lista de espera
+#[derive(Debug, Clone)]
+pub enum ResultadoMatricula {
+    Matriculado(HistorialModel),
+    EnEspera(EntradaListaEspera),
+}
+
 #[derive(Debug, Clone)]
 pub struct MatriculaService {
     db: DbExecutor,
 }
 
+/// Con `profesor_id`, exige que ese profesor tenga el curso asignado en `profesores_curso`
+/// (sin él no hay nada que comprobar: Administrador o Coordinador)
+pub(crate) async fn exigir_curso_asignado<C>(
+    db: &C,
+    curso_id: i32,
+    profesor_id: Option<i32>,
+) -> Result<(), AppError>
+where
+    C: ConnectionTrait,
+{
+    let Some(profesor_id) = profesor_id else {
+        return Ok(());
+    };
+    let asignado = db
+        .query_one(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "SELECT 1 FROM profesores_curso WHERE curso_id = $1 AND profesor_id = $2",
+            [curso_id.into(), profesor_id.into()],
+        ))
+        .await?
+        .is_some();
+    if !asignado {
+        return Err(AppError::Forbidden(
+            "El curso no está asignado a este profesor".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Con `estudiante_id`, exige que ese estudiante tenga una matrícula activa en el curso
+pub(crate) async fn exigir_matricula_activa<C>(
+    db: &C,
+    curso_id: i32,
+    estudiante_id: Option<i32>,
+) -> Result<(), AppError>
+where
+    C: ConnectionTrait,
+{
+    let Some(estudiante_id) = estudiante_id else {
+        return Ok(());
+    };
+    let matriculado = Historial::find()
+        .filter(historial_curso_estudiante::Column::CursoId.eq(curso_id))
+        .filter(historial_curso_estudiante::Column::EstudianteId.eq(estudiante_id))
+        .filter(historial_curso_estudiante::Column::Estado.eq(EstadoMatricula::Activo))
+        .count(db)
+        .await?
+        > 0;
+    if !matriculado {
+        return Err(AppError::Forbidden(
+            "Solo los estudiantes matriculados en el curso pueden verlo".into(),
+        ));
+    }
+    Ok(())
+}
+
 impl MatriculaService {
     pub fn new(db: DbExecutor) -> Self {
         Self { db }
@@ -34,47 +116,390 @@ impl MatriculaService {
         &self,
         estudiante_id: i32,
         curso_id: i32,
-    ) -> Result<HistorialModel, AppError> {
-        let db = self.connection();
+        ctx: &ContextoAuditoria,
+    ) -> Result<ResultadoMatricula, AppError> {
+        let txn = self.connection().begin().await?;
+        let resultado = Self::matricular_en(&txn, estudiante_id, curso_id, ctx).await?;
+        txn.commit().await?;
 
-        Usuario::find_by_id(estudiante_id)
-            .one(&db)
+        Ok(resultado)
+    }
+
+    /// Matricula dentro de la transacción de quien llama (p. ej. la importación masiva
+    /// de usuarios), que decide si se confirma. Aplica las reglas del curso y, si no
+    /// queda cupo, deja al estudiante en la lista de espera
+    pub async fn matricular_en<C: ConnectionTrait>(
+        db: &C,
+        estudiante_id: i32,
+        curso_id: i32,
+        ctx: &ContextoAuditoria,
+    ) -> Result<ResultadoMatricula, AppError> {
+        let estudiante = Usuario::find_by_id(estudiante_id)
+            .one(db)
             .await?
             .ok_or_else(|| AppError::NotFound("Estudiante no encontrado".into()))?;
 
-        Curso::find_by_id(curso_id)
-            .one(&db)
+        // El bloqueo del curso serializa las matrículas concurrentes al contar el cupo
+        let curso = Curso::find_by_id(curso_id)
+            .filter(curso::Column::FechaEliminacion.is_null())
+            .lock_exclusive()
+            .one(db)
             .await?
             .ok_or_else(|| AppError::NotFound("Curso no encontrado".into()))?;
 
-        if Historial::find()
+        // Retirado, reprobado o finalizado pueden volver a matricularse (nuevo intento)
+        let intentos = Historial::find()
             .filter(historial_curso_estudiante::Column::EstudianteId.eq(estudiante_id))
             .filter(historial_curso_estudiante::Column::CursoId.eq(curso_id))
-            .one(&db)
+            .all(db)
+            .await?;
+        if intentos.iter().any(|m| m.estado == EstadoMatricula::Activo) {
+            return Err(AppError::BadRequest("Estudiante ya matriculado".into()));
+        }
+        if intentos
+            .iter()
+            .any(|m| m.estado == EstadoMatricula::Aprobado)
+        {
+            return Err(AppError::BadRequest(
+                "El estudiante ya aprobó el curso".into(),
+            ));
+        }
+        if ListaEspera::find()
+            .filter(lista_espera::Column::EstudianteId.eq(estudiante_id))
+            .filter(lista_espera::Column::CursoId.eq(curso_id))
+            .one(db)
             .await?
             .is_some()
         {
-            return Err(AppError::BadRequest("Estudiante ya matriculado".into()));
+            return Err(AppError::BadRequest(
+                "El estudiante ya está en la lista de espera del curso".into(),
+            ));
         }
 
+        let motivos = Self::motivos_rechazo(db, &estudiante, &curso).await?;
+        if !motivos.is_empty() {
+            return Err(AppError::Conflict(
+                format!("Matrícula rechazada: {}", motivos.join("; ")).into(),
+            ));
+        }
+
+        if let Some(cupo) = curso.cupo_maximo {
+            if Self::ocupados(db, curso_id).await? >= cupo as u64 {
+                let entrada = lista_espera::ActiveModel {
+                    curso_id: Set(curso_id),
+                    estudiante_id: Set(estudiante_id),
+                    fecha_solicitud: Set(Utc::now()),
+                    ..Default::default()
+                }
+                .insert(db)
+                .await?;
+                AuditoriaService::registrar(
+                    db,
+                    ctx,
+                    "lista_espera",
+                    "matricula",
+                    Some(entrada.id),
+                    None,
+                    Some(&entrada),
+                )
+                .await?;
+
+                let posicion = ListaEspera::find()
+                    .filter(lista_espera::Column::CursoId.eq(curso_id))
+                    .filter(lista_espera::Column::Id.lte(entrada.id))
+                    .count(db)
+                    .await?;
+                return Ok(ResultadoMatricula::EnEspera(EntradaListaEspera {
+                    curso_id,
+                    estudiante_id,
+                    nombre: Some(estudiante.nombre),
+                    posicion,
+                    fecha_solicitud: entrada.fecha_solicitud,
+                }));
+            }
+        }
+
+        let matricula =
+            Self::insertar_matricula(db, estudiante_id, curso_id, "matricular", None, ctx).await?;
+        Ok(ResultadoMatricula::Matriculado(matricula))
+    }
+
+    /// Reglas del curso que el estudiante no cumple: ventana de matrícula, semestre
+    /// y prerrequisitos aprobados
+    async fn motivos_rechazo<C: ConnectionTrait>(
+        db: &C,
+        estudiante: &UsuarioModel,
+        curso: &CursoModel,
+    ) -> Result<Vec<String>, AppError> {
+        let mut motivos = Vec::new();
+        let ahora = Utc::now();
+
+        if let Some(apertura) = curso.fecha_apertura_matricula.filter(|f| ahora < *f) {
+            motivos.push(format!(
+                "la matrícula abre el {}",
+                apertura.format("%Y-%m-%d %H:%M UTC")
+            ));
+        }
+        if let Some(cierre) = curso.fecha_cierre_matricula.filter(|f| ahora > *f) {
+            motivos.push(format!(
+                "la matrícula cerró el {}",
+                cierre.format("%Y-%m-%d %H:%M UTC")
+            ));
+        }
+
+        if let Some(semestre) = curso.semestre {
+            match estudiante.semestre {
+                None => motivos.push(format!(
+                    "el curso es de semestre {} y el estudiante no tiene semestre registrado",
+                    semestre
+                )),
+                Some(actual) if actual < semestre => motivos.push(format!(
+                    "el curso es de semestre {} y el estudiante cursa el semestre {}",
+                    semestre, actual
+                )),
+                Some(_) => {}
+            }
+        }
+
+        let requeridos: Vec<i32> = Prerequisito::find()
+            .filter(prerequisito_curso::Column::CursoId.eq(curso.id))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|p| p.prerequisito_id)
+            .collect();
+        if !requeridos.is_empty() {
+            let aprobados: BTreeSet<i32> = Historial::find()
+                .filter(historial_curso_estudiante::Column::EstudianteId.eq(estudiante.id))
+                .filter(historial_curso_estudiante::Column::CursoId.is_in(requeridos.clone()))
+                .filter(historial_curso_estudiante::Column::Aprobado.eq(true))
+                .all(db)
+                .await?
+                .into_iter()
+                .map(|h| h.curso_id)
+                .collect();
+            let pendientes: Vec<i32> = requeridos
+                .into_iter()
+                .filter(|id| !aprobados.contains(id))
+                .collect();
+            if !pendientes.is_empty() {
+                let nombres: Vec<String> = Curso::find()
+                    .filter(curso::Column::Id.is_in(pendientes))
+                    .order_by_asc(curso::Column::Nombre)
+                    .all(db)
+                    .await?
+                    .into_iter()
+                    .map(|c| c.nombre)
+                    .collect();
+                motivos.push(format!(
+                    "faltan prerrequisitos aprobados: {}",
+                    nombres.join(", ")
+                ));
+            }
+        }
+
+        Ok(motivos)
+    }
+
+    /// Matrículas activas, las únicas que ocupan cupo
+    async fn ocupados<C: ConnectionTrait>(db: &C, curso_id: i32) -> Result<u64, AppError> {
+        Ok(Historial::find()
+            .filter(historial_curso_estudiante::Column::CursoId.eq(curso_id))
+            .filter(historial_curso_estudiante::Column::Estado.eq(EstadoMatricula::Activo))
+            .count(db)
+            .await?)
+    }
+
+    /// Crea la matrícula activa como intento siguiente al último del estudiante en
+    /// el curso (si lo hay)
+    async fn insertar_matricula<C: ConnectionTrait>(
+        db: &C,
+        estudiante_id: i32,
+        curso_id: i32,
+        accion: &str,
+        motivo: Option<String>,
+        ctx: &ContextoAuditoria,
+    ) -> Result<HistorialModel, AppError> {
+        let anterior = Historial::find()
+            .filter(historial_curso_estudiante::Column::EstudianteId.eq(estudiante_id))
+            .filter(historial_curso_estudiante::Column::CursoId.eq(curso_id))
+            .order_by_desc(historial_curso_estudiante::Column::Intento)
+            .one(db)
+            .await?;
+
         let ahora = Utc::now();
         let matricula = historial_curso_estudiante::ActiveModel {
             estudiante_id: Set(estudiante_id),
             curso_id: Set(curso_id),
             fecha_inscripcion: Set(ahora),
-            estado: Set("activo".into()),
+            estado: Set(EstadoMatricula::Activo),
             fecha_creacion: Set(Some(ahora)),
             fecha_actualizacion: Set(Some(ahora)),
+            intento: Set(anterior.as_ref().map_or(1, |m| m.intento + 1)),
+            matricula_anterior_id: Set(anterior.map(|m| m.id)),
             ..Default::default()
         };
 
-        Ok(matricula.insert(&db).await?)
+        let matricula = matricula.insert(db).await?;
+        Self::registrar_cambio(db, &matricula, None, motivo, ctx).await?;
+        AuditoriaService::registrar(
+            db,
+            ctx,
+            accion,
+            "matricula",
+            Some(matricula.id),
+            None,
+            Some(&matricula),
+        )
+        .await?;
+
+        Ok(matricula)
+    }
+
+    /// Anota el paso de `anterior` al estado actual de la matrícula
+    async fn registrar_cambio<C: ConnectionTrait>(
+        db: &C,
+        matricula: &HistorialModel,
+        anterior: Option<EstadoMatricula>,
+        motivo: Option<String>,
+        ctx: &ContextoAuditoria,
+    ) -> Result<(), AppError> {
+        cambio_estado_matricula::ActiveModel {
+            matricula_id: Set(matricula.id),
+            estado_anterior: Set(anterior),
+            estado_nuevo: Set(matricula.estado),
+            motivo: Set(motivo.filter(|m| !m.trim().is_empty())),
+            actor_id: Set(ctx.actor_id),
+            fecha: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok(())
+    }
+
+    /// Aplica una transición del ciclo de vida (409 si no está permitida), mantiene
+    /// `aprobado` al día y la deja en el historial de estados y en la auditoría
+    async fn cambiar_estado_en<C: ConnectionTrait>(
+        db: &C,
+        matricula: HistorialModel,
+        destino: EstadoMatricula,
+        motivo: Option<String>,
+        accion: &str,
+        ctx: &ContextoAuditoria,
+    ) -> Result<HistorialModel, AppError> {
+        if !matricula.estado.puede_pasar_a(destino) {
+            return Err(AppError::Conflict(
+                format!(
+                    "La matrícula no puede pasar de {} a {}",
+                    matricula.estado.to_value(),
+                    destino.to_value()
+                )
+                .into(),
+            ));
+        }
+
+        let original = matricula.clone();
+        let mut activa: historial_curso_estudiante::ActiveModel = matricula.into();
+        activa.estado = Set(destino);
+        match destino {
+            EstadoMatricula::Aprobado => activa.aprobado = Set(true),
+            EstadoMatricula::Reprobado => activa.aprobado = Set(false),
+            _ => {}
+        }
+        activa.fecha_actualizacion = Set(Some(Utc::now()));
+        let actualizada = activa.update(db).await?;
+
+        Self::registrar_cambio(db, &actualizada, Some(original.estado), motivo, ctx).await?;
+        AuditoriaService::registrar(
+            db,
+            ctx,
+            accion,
+            "matricula",
+            Some(actualizada.id),
+            Some(&original),
+            Some(&actualizada),
+        )
+        .await?;
+
+        Ok(actualizada)
+    }
+
+    /// Matricula a los primeros de la lista de espera mientras quede cupo
+    async fn promover_lista_espera<C: ConnectionTrait>(
+        db: &C,
+        curso: &CursoModel,
+        ctx: &ContextoAuditoria,
+    ) -> Result<Vec<HistorialModel>, AppError> {
+        // Sin cupo máximo se matricula a toda la lista
+        let libres = match curso.cupo_maximo {
+            Some(cupo) => {
+                let ocupados = Self::ocupados(db, curso.id).await?;
+                Some((cupo.max(0) as u64).saturating_sub(ocupados))
+            }
+            None => None,
+        };
+        if libres == Some(0) {
+            return Ok(Vec::new());
+        }
+
+        let entradas = ListaEspera::find()
+            .filter(lista_espera::Column::CursoId.eq(curso.id))
+            .order_by_asc(lista_espera::Column::FechaSolicitud)
+            .order_by_asc(lista_espera::Column::Id)
+            .limit(libres)
+            .all(db)
+            .await?;
+
+        let mut promovidos = Vec::with_capacity(entradas.len());
+        for entrada in entradas {
+            ListaEspera::delete_by_id(entrada.id).exec(db).await?;
+            promovidos.push(
+                Self::insertar_matricula(
+                    db,
+                    entrada.estudiante_id,
+                    curso.id,
+                    "promover",
+                    Some("Cupo liberado desde la lista de espera".to_string()),
+                    ctx,
+                )
+                .await?,
+            );
+        }
+        Ok(promovidos)
+    }
+
+    /// Avisa (bandeja, WebSocket y correo) a quienes salieron de la lista de espera
+    async fn notificar_promovidos(&self, curso: &CursoModel, promovidos: &[HistorialModel]) {
+        let service = NotificacionService::new(self.db.clone());
+        for matricula in promovidos {
+            let notificacion = NuevaNotificacion {
+                usuario_id: matricula.estudiante_id,
+                titulo: format!("Tienes cupo en {}", curso.nombre),
+                mensaje: format!(
+                    "Se liberó un cupo en {} y quedaste matriculado desde la lista de espera.",
+                    curso.nombre
+                ),
+                tipo: "matricula".to_string(),
+                leida: None,
+                enlace: None,
+                datos_adicionales: Some(serde_json::json!({ "curso_id": curso.id })),
+            };
+            if let Err(e) = service.crear_notificacion(notificacion).await {
+                tracing::warn!(
+                    "⚠️  No se pudo notificar la matrícula desde la lista de espera {}: {}",
+                    matricula.id,
+                    e
+                );
+            }
+        }
     }
 
     pub async fn desmatricular_estudiante(
         &self,
         estudiante_id: i32,
         curso_id: i32,
+        ctx: &ContextoAuditoria,
     ) -> Result<HistorialModel, AppError> {
         let db = self.connection();
 
@@ -83,25 +508,422 @@ impl MatriculaService {
             .await?
             .ok_or_else(|| AppError::NotFound("Estudiante no encontrado".into()))?;
 
-        Curso::find_by_id(curso_id)
-            .one(&db)
+        let txn = db.begin().await?;
+        let curso = Curso::find_by_id(curso_id)
+            .lock_exclusive()
+            .one(&txn)
             .await?
             .ok_or_else(|| AppError::NotFound("Curso no encontrado".into()))?;
 
         let matricula = Historial::find()
             .filter(historial_curso_estudiante::Column::EstudianteId.eq(estudiante_id))
             .filter(historial_curso_estudiante::Column::CursoId.eq(curso_id))
-            .one(&db)
+            .filter(historial_curso_estudiante::Column::Estado.eq(EstadoMatricula::Activo))
+            .one(&txn)
             .await?
             .ok_or_else(|| AppError::BadRequest("Estudiante no matriculado".into()))?;
 
-        let ahora = Utc::now();
-        let mut matricula: historial_curso_estudiante::ActiveModel = matricula.into();
-        matricula.estado = Set("inactivo".into());
-        matricula.fecha_actualizacion = Set(Some(ahora));
+        let retirada = Self::cambiar_estado_en(
+            &txn,
+            matricula,
+            EstadoMatricula::Retirado,
+            None,
+            "desmatricular",
+            ctx,
+        )
+        .await?;
+
+        // El cupo liberado pasa al primero de la lista de espera
+        let promovidos = Self::promover_lista_espera(&txn, &curso, ctx).await?;
+        txn.commit().await?;
+
+        self.notificar_promovidos(&curso, &promovidos).await;
+        Ok(retirada)
+    }
+
+    /// Cambia el estado de una matrícula según las transiciones permitidas; retirar
+    /// una matrícula activa libera su cupo para la lista de espera
+    pub async fn cambiar_estado(
+        &self,
+        matricula_id: i32,
+        cambio: CambioEstado,
+        profesor_id: Option<i32>,
+        ctx: &ContextoAuditoria,
+    ) -> Result<HistorialModel, AppError> {
+        let txn = self.connection().begin().await?;
+        let curso_id = Historial::find_by_id(matricula_id)
+            .one(&txn)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Matrícula no encontrada".into()))?
+            .curso_id;
+        // Primero el curso (mismo orden que matricular/desmatricular/cierre) y después
+        // la matrícula, que ya no puede cambiar mientras dure el bloqueo
+        let curso = Curso::find_by_id(curso_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Curso no encontrado".into()))?;
+        exigir_curso_asignado(&txn, curso.id, profesor_id).await?;
+        let matricula = Historial::find_by_id(matricula_id)
+            .one(&txn)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Matrícula no encontrada".into()))?;
+
+        let libera_cupo = matricula.estado == EstadoMatricula::Activo;
+        let actualizada = Self::cambiar_estado_en(
+            &txn,
+            matricula,
+            cambio.estado,
+            cambio.motivo,
+            "cambiar_estado",
+            ctx,
+        )
+        .await?;
+
+        let promovidos = if libera_cupo && cambio.estado == EstadoMatricula::Retirado {
+            Self::promover_lista_espera(&txn, &curso, ctx).await?
+        } else {
+            Vec::new()
+        };
+        txn.commit().await?;
+
+        self.notificar_promovidos(&curso, &promovidos).await;
+        Ok(actualizada)
+    }
+
+    /// Historial de estados de una matrícula, del más antiguo al más reciente
+    pub async fn obtener_cambios_estado(
+        &self,
+        matricula_id: i32,
+    ) -> Result<Vec<CambioEstadoModel>, AppError> {
+        let db = self.connection();
+        Historial::find_by_id(matricula_id)
+            .one(&db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Matrícula no encontrada".into()))?;
+
+        Ok(CambioEstadoMatricula::find()
+            .filter(cambio_estado_matricula::Column::MatriculaId.eq(matricula_id))
+            .order_by_asc(cambio_estado_matricula::Column::Fecha)
+            .order_by_asc(cambio_estado_matricula::Column::Id)
+            .all(&db)
+            .await?)
+    }
+
+    /// Cierre del curso: cada matrícula activa pasa a un estado final (aprobado o
+    /// reprobado según `nota_minima`, o finalizado) y se descarta la lista de espera
+    pub async fn cerrar_curso(
+        &self,
+        curso_id: i32,
+        cierre: CierreCurso,
+        ctx: &ContextoAuditoria,
+    ) -> Result<ResumenCierre, AppError> {
+        let txn = self.connection().begin().await?;
+        Curso::find_by_id(curso_id)
+            .filter(curso::Column::FechaEliminacion.is_null())
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Curso no encontrado".into()))?;
+
+        let activas = Historial::find()
+            .filter(historial_curso_estudiante::Column::CursoId.eq(curso_id))
+            .filter(historial_curso_estudiante::Column::Estado.eq(EstadoMatricula::Activo))
+            .all(&txn)
+            .await?;
+
+        let mut resumen = ResumenCierre {
+            curso_id,
+            aprobados: 0,
+            reprobados: 0,
+            finalizados: 0,
+            lista_espera_descartada: 0,
+        };
+        let motivo = cierre
+            .motivo
+            .clone()
+            .or_else(|| Some("Cierre del curso".to_string()));
+        for matricula in activas {
+            let destino = match (cierre.nota_minima, matricula.calificacion_final) {
+                (Some(minima), Some(nota)) if nota >= minima => {
+                    resumen.aprobados += 1;
+                    EstadoMatricula::Aprobado
+                }
+                (Some(_), Some(_)) => {
+                    resumen.reprobados += 1;
+                    EstadoMatricula::Reprobado
+                }
+                _ => {
+                    resumen.finalizados += 1;
+                    EstadoMatricula::Finalizado
+                }
+            };
+            Self::cambiar_estado_en(&txn, matricula, destino, motivo.clone(), "cerrar", ctx)
+                .await?;
+        }
+
+        resumen.lista_espera_descartada = ListaEspera::delete_many()
+            .filter(lista_espera::Column::CursoId.eq(curso_id))
+            .exec(&txn)
+            .await?
+            .rows_affected;
+
+        AuditoriaService::registrar(
+            &txn,
+            ctx,
+            "cerrar",
+            "curso",
+            Some(curso_id),
+            None,
+            Some(&resumen),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(resumen)
+    }
+
+    pub async fn obtener_reglas(&self, curso_id: i32) -> Result<ReglasMatriculaCurso, AppError> {
+        let db = self.connection();
+        let curso = Curso::find_by_id(curso_id)
+            .filter(curso::Column::FechaEliminacion.is_null())
+            .one(&db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Curso no encontrado".into()))?;
+
+        Self::reglas_de(&db, curso).await
+    }
+
+    async fn reglas_de<C: ConnectionTrait>(
+        db: &C,
+        curso: CursoModel,
+    ) -> Result<ReglasMatriculaCurso, AppError> {
+        let prerequisitos = Prerequisito::find()
+            .filter(prerequisito_curso::Column::CursoId.eq(curso.id))
+            .find_also_related(Curso)
+            .all(db)
+            .await?
+            .into_iter()
+            .filter_map(|(_, prerequisito)| prerequisito)
+            .map(|c| CursoPrerequisito {
+                id: c.id,
+                nombre: c.nombre,
+            })
+            .collect();
+        let en_espera = ListaEspera::find()
+            .filter(lista_espera::Column::CursoId.eq(curso.id))
+            .count(db)
+            .await?;
+
+        Ok(ReglasMatriculaCurso {
+            curso_id: curso.id,
+            semestre: curso.semestre,
+            prerequisitos,
+            cupo_maximo: curso.cupo_maximo,
+            fecha_apertura_matricula: curso.fecha_apertura_matricula,
+            fecha_cierre_matricula: curso.fecha_cierre_matricula,
+            matriculados: Self::ocupados(db, curso.id).await?,
+            en_espera,
+        })
+    }
+
+    /// Reemplaza prerrequisitos, cupo y ventana de matrícula; si el cupo crece (o se
+    /// quita) se matricula a los siguientes de la lista de espera
+    pub async fn actualizar_reglas(
+        &self,
+        curso_id: i32,
+        reglas: ReglasMatricula,
+        ctx: &ContextoAuditoria,
+    ) -> Result<ReglasMatriculaCurso, AppError> {
+        if reglas.cupo_maximo.is_some_and(|cupo| cupo < 1) {
+            return Err(AppError::BadRequest(
+                "El cupo máximo debe ser mayor que cero".into(),
+            ));
+        }
+        if let (Some(apertura), Some(cierre)) = (
+            reglas.fecha_apertura_matricula,
+            reglas.fecha_cierre_matricula,
+        ) {
+            if cierre <= apertura {
+                return Err(AppError::BadRequest(
+                    "El cierre de matrícula debe ser posterior a la apertura".into(),
+                ));
+            }
+        }
+        let prerequisitos: BTreeSet<i32> = reglas.prerequisitos.iter().copied().collect();
+        if prerequisitos.contains(&curso_id) {
+            return Err(AppError::BadRequest(
+                "Un curso no puede ser prerrequisito de sí mismo".into(),
+            ));
+        }
+
+        let txn = self.connection().begin().await?;
+        let curso = Curso::find_by_id(curso_id)
+            .filter(curso::Column::FechaEliminacion.is_null())
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Curso no encontrado".into()))?;
+
+        let existentes: BTreeSet<i32> = Curso::find()
+            .filter(curso::Column::Id.is_in(prerequisitos.iter().copied()))
+            .filter(curso::Column::FechaEliminacion.is_null())
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        let faltantes: Vec<String> = prerequisitos
+            .difference(&existentes)
+            .map(|id| id.to_string())
+            .collect();
+        if !faltantes.is_empty() {
+            return Err(AppError::NotFound(
+                format!(
+                    "Cursos prerrequisito no encontrados: {}",
+                    faltantes.join(", ")
+                )
+                .into(),
+            ));
+        }
+        if Self::genera_ciclo(&txn, curso_id, &prerequisitos).await? {
+            return Err(AppError::BadRequest(
+                "Los prerrequisitos forman un ciclo: algún prerrequisito exige este curso".into(),
+            ));
+        }
+
+        let antes = Self::reglas_de(&txn, curso.clone()).await?;
+
+        let mut activo: curso::ActiveModel = curso.into();
+        activo.cupo_maximo = Set(reglas.cupo_maximo);
+        activo.fecha_apertura_matricula = Set(reglas.fecha_apertura_matricula);
+        activo.fecha_cierre_matricula = Set(reglas.fecha_cierre_matricula);
+        activo.fecha_actualizacion = Set(Utc::now());
+        let curso = activo.update(&txn).await?;
+
+        Prerequisito::delete_many()
+            .filter(prerequisito_curso::Column::CursoId.eq(curso_id))
+            .exec(&txn)
+            .await?;
+        if !prerequisitos.is_empty() {
+            Prerequisito::insert_many(prerequisitos.iter().map(|id| {
+                prerequisito_curso::ActiveModel {
+                    curso_id: Set(curso_id),
+                    prerequisito_id: Set(*id),
+                    ..Default::default()
+                }
+            }))
+            .exec(&txn)
+            .await?;
+        }
+
+        let promovidos = Self::promover_lista_espera(&txn, &curso, ctx).await?;
+        let despues = Self::reglas_de(&txn, curso.clone()).await?;
+        AuditoriaService::registrar(
+            &txn,
+            ctx,
+            "actualizar_reglas_matricula",
+            "curso",
+            Some(curso_id),
+            Some(&antes),
+            Some(&despues),
+        )
+        .await?;
+        txn.commit().await?;
+
+        self.notificar_promovidos(&curso, &promovidos).await;
+        Ok(despues)
+    }
+
+    /// Indica si `curso_id` ya es prerrequisito (directo o indirecto) de alguno de los
+    /// cursos propuestos como sus prerrequisitos
+    async fn genera_ciclo<C: ConnectionTrait>(
+        db: &C,
+        curso_id: i32,
+        prerequisitos: &BTreeSet<i32>,
+    ) -> Result<bool, AppError> {
+        let mut visitados = BTreeSet::new();
+        let mut pendientes: Vec<i32> = prerequisitos.iter().copied().collect();
+        while !pendientes.is_empty() {
+            let nivel: Vec<i32> = pendientes
+                .drain(..)
+                .filter(|id| visitados.insert(*id))
+                .collect();
+            if nivel.is_empty() {
+                break;
+            }
+            let siguientes = Prerequisito::find()
+                .filter(prerequisito_curso::Column::CursoId.is_in(nivel))
+                .all(db)
+                .await?;
+            for siguiente in siguientes {
+                if siguiente.prerequisito_id == curso_id {
+                    return Ok(true);
+                }
+                pendientes.push(siguiente.prerequisito_id);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Lista de espera del curso por orden de llegada
+    pub async fn obtener_lista_espera(
+        &self,
+        curso_id: i32,
+    ) -> Result<Vec<EntradaListaEspera>, AppError> {
+        let db = self.connection();
+        let entradas = ListaEspera::find()
+            .filter(lista_espera::Column::CursoId.eq(curso_id))
+            .order_by_asc(lista_espera::Column::FechaSolicitud)
+            .order_by_asc(lista_espera::Column::Id)
+            .find_also_related(Usuario)
+            .all(&db)
+            .await?;
+
+        Ok(entradas
+            .into_iter()
+            .zip(1..)
+            .map(|((entrada, estudiante), posicion)| EntradaListaEspera {
+                curso_id: entrada.curso_id,
+                estudiante_id: entrada.estudiante_id,
+                nombre: estudiante.map(|e| e.nombre),
+                posicion,
+                fecha_solicitud: entrada.fecha_solicitud,
+            })
+            .collect())
+    }
+
+    pub async fn salir_lista_espera(
+        &self,
+        estudiante_id: i32,
+        curso_id: i32,
+        ctx: &ContextoAuditoria,
+    ) -> Result<(), AppError> {
+        let db = self.connection();
+        let entrada = ListaEspera::find()
+            .filter(lista_espera::Column::EstudianteId.eq(estudiante_id))
+            .filter(lista_espera::Column::CursoId.eq(curso_id))
+            .one(&db)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound("El estudiante no está en la lista de espera".into())
+            })?;
+
+        let txn = db.begin().await?;
+        ListaEspera::delete_by_id(entrada.id).exec(&txn).await?;
+        AuditoriaService::registrar(
+            &txn,
+            ctx,
+            "salir_lista_espera",
+            "matricula",
+            Some(entrada.id),
+            Some(&entrada),
+            None,
+        )
+        .await?;
+        txn.commit().await?;
 
-        let matricula_actualizada = matricula.update(&db).await?;
-        Ok(matricula_actualizada)
+        Ok(())
     }
 
     pub async fn obtener_matriculas_estudiante(
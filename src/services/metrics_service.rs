@@ -0,0 +1,157 @@
+use std::sync::OnceLock;
+
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+use sqlx::PgPool;
+
+use crate::services::{cron_service, socket_service::get_socket_service};
+
+/// Buckets de latencia HTTP (segundos)
+const BUCKETS_LATENCIA: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Eventos de dominio contabilizados en `aula_eventos_total`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventoDominio {
+    LoginExitoso,
+    LoginFallido,
+    /// Intento de examen iniciado
+    IntentoExamen,
+    /// Intento de examen entregado
+    EntregaExamen,
+}
+
+impl EventoDominio {
+    const TODOS: [EventoDominio; 4] = [
+        EventoDominio::LoginExitoso,
+        EventoDominio::LoginFallido,
+        EventoDominio::IntentoExamen,
+        EventoDominio::EntregaExamen,
+    ];
+
+    fn etiqueta(&self) -> &'static str {
+        match self {
+            EventoDominio::LoginExitoso => "login_exitoso",
+            EventoDominio::LoginFallido => "login_fallido",
+            EventoDominio::IntentoExamen => "intento_examen",
+            EventoDominio::EntregaExamen => "entrega_examen",
+        }
+    }
+}
+
+/// Registro global de métricas Prometheus
+pub struct MetricsService {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_duracion: HistogramVec,
+    eventos: IntCounterVec,
+    db_pool_conexiones: IntGaugeVec,
+    db_pool_max: IntGauge,
+    ws_conexiones: IntGauge,
+    cron_jobs: IntGauge,
+}
+
+static METRICS_SERVICE: OnceLock<MetricsService> = OnceLock::new();
+
+/// Obtiene la instancia global, registrando las métricas en el primer acceso
+pub fn get_metrics_service() -> &'static MetricsService {
+    METRICS_SERVICE.get_or_init(|| {
+        MetricsService::new().expect("Las métricas Prometheus deben registrarse una sola vez")
+    })
+}
+
+impl MetricsService {
+    fn new() -> Result<Self, prometheus::Error> {
+        let registry = Registry::new_custom(Some("aula".to_string()), None)?;
+
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "Peticiones HTTP por ruta, método y status"),
+            &["method", "route", "status"],
+        )?;
+        let http_duracion = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "Latencia de peticiones HTTP por ruta y método",
+            )
+            .buckets(BUCKETS_LATENCIA.to_vec()),
+            &["method", "route"],
+        )?;
+        let eventos = IntCounterVec::new(
+            Opts::new("eventos_total", "Eventos de dominio (logins, entregas, intentos de examen)"),
+            &["evento"],
+        )?;
+        let db_pool_conexiones = IntGaugeVec::new(
+            Opts::new("db_pool_conexiones", "Conexiones del PgPool por estado"),
+            &["estado"],
+        )?;
+        let db_pool_max = IntGauge::new("db_pool_max_conexiones", "Máximo de conexiones del PgPool")?;
+        let ws_conexiones = IntGauge::new("ws_conexiones_activas", "Conexiones WebSocket activas")?;
+        let cron_jobs = IntGauge::new("cron_jobs_activos", "Cron jobs registrados")?;
+
+        registry.register(Box::new(http_requests.clone()))?;
+        registry.register(Box::new(http_duracion.clone()))?;
+        registry.register(Box::new(eventos.clone()))?;
+        registry.register(Box::new(db_pool_conexiones.clone()))?;
+        registry.register(Box::new(db_pool_max.clone()))?;
+        registry.register(Box::new(ws_conexiones.clone()))?;
+        registry.register(Box::new(cron_jobs.clone()))?;
+
+        // Inicializar en 0 para que las series existan desde el primer scrape
+        for evento in EventoDominio::TODOS {
+            eventos.with_label_values(&[evento.etiqueta()]);
+        }
+
+        Ok(Self {
+            registry,
+            http_requests,
+            http_duracion,
+            eventos,
+            db_pool_conexiones,
+            db_pool_max,
+            ws_conexiones,
+            cron_jobs,
+        })
+    }
+
+    /// Registra una petición HTTP terminada (usado por `performance_metrics`)
+    pub fn registrar_request(&self, method: &str, route: &str, status: u16, segundos: f64) {
+        self.http_requests
+            .with_label_values(&[method, route, &status.to_string()])
+            .inc();
+        self.http_duracion
+            .with_label_values(&[method, route])
+            .observe(segundos);
+    }
+
+    /// Incrementa el contador de un evento de dominio
+    pub fn registrar_evento(&self, evento: EventoDominio) {
+        self.eventos.with_label_values(&[evento.etiqueta()]).inc();
+    }
+
+    /// Actualiza los gauges y serializa todo el registro en formato de texto Prometheus
+    pub async fn render(&self, pool: Option<&PgPool>) -> Result<String, prometheus::Error> {
+        if let Some(pool) = pool {
+            let total = pool.size() as i64;
+            let inactivas = pool.num_idle() as i64;
+            self.db_pool_conexiones
+                .with_label_values(&["activas"])
+                .set(total - inactivas);
+            self.db_pool_conexiones
+                .with_label_values(&["inactivas"])
+                .set(inactivas);
+            self.db_pool_max
+                .set(pool.options().get_max_connections() as i64);
+        }
+
+        self.ws_conexiones
+            .set(get_socket_service().get_total_connections().await as i64);
+        self.cron_jobs.set(cron_service::get_jobs_count() as i64);
+
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        String::from_utf8(buffer).map_err(|e| prometheus::Error::Msg(e.to_string()))
+    }
+}
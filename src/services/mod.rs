@@ -1,10 +1,27 @@
+pub mod acceso_service; // Espera entre intentos de login y bloqueo de cuentas
 pub mod actividad_service;
 pub mod area_conocimiento_service;
+pub mod asistencia_service; // Sesiones de curso, pase de lista y check-in
+pub mod auditoria_service;
+pub mod calendario_service; // Calendario académico unificado y feed .ics
+pub mod correo_service; // Mailer: SMTP o archivos .eml + log (desarrollo)
+pub mod cuenta_service; // Activación de cuentas y recuperación de contraseña
 pub mod cron_service; // /* Cambio nuevo */ Agregar cron_service al módulo
 pub mod curso_service;
+pub mod entrega_notificacion_service; // Correos de notificaciones: cola con reintentos y resumen diario
+pub mod estadistica_examen_service; // Estadísticas de exámenes y análisis de ítems
+pub mod evaluacion_service; // Evaluaciones con rúbrica y calificaciones
 pub mod examen_service;
+pub mod image_service;
+pub mod intento_examen_service; // Intentos de examen, calificación automática y cola de calificación manual // Variantes de imágenes (avatares y portadas)
+pub mod liberacion_service; // Liberación condicional del aula y progreso de contenidos
 pub mod matricula_service;
+pub mod metrics_service; // Registro Prometheus (/metrics)
 pub mod modulo_service;
+pub mod orden_service; // Reordenamiento en bloque de elementos con `orden`
+pub mod papelera_service; // Soft delete en cascada, restauración y purga
+pub mod publicacion_service; // Borradores, publicación programada y avisos de contenido nuevo
+pub mod revision_service; // Historial de revisiones de contenidos y personalizaciones
 pub mod rol_service;
 pub mod socket_service;
 pub mod storage_service; // Servicio de almacenamiento para S3/R2
@@ -1,13 +1,20 @@
 use crate::{
     database::DbExecutor,
-    models::modulo::{self, Entity as Modulo, Model as ModuloModel},
+    models::modulo::{self, Entity as Modulo, Model as ModuloModel, TipoModulo},
     models::AppState,
+    services::{
+        papelera_service::{PapeleraService, TipoPapelera},
+        publicacion_service::{
+            visible_para_estudiantes, EstadoPublicacion, PublicacionService, TipoPublicable,
+        },
+    },
     utils::errors::AppError,
 };
 use axum::extract::FromRef;
+use chrono::Utc;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, ModelTrait, Order,
-    QueryFilter, QueryOrder, Set,
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, Order, QueryFilter,
+    QueryOrder, Set, TransactionTrait,
 };
 
 pub use crate::models::modulo::{ActualizarModulo, NuevoModulo};
@@ -38,10 +45,11 @@ impl ModuloService {
             nombre: Set(nuevo_modulo.nombre),
             descripcion: Set(nuevo_modulo.descripcion),
             orden: Set(nuevo_modulo.orden),
-            tipo: Set(nuevo_modulo
-                .tipo
-                .unwrap_or_else(|| "estructura_contenido".to_string())),
+            tipo: Set(nuevo_modulo.tipo.unwrap_or(TipoModulo::EstructuraContenido)),
             visible: Set(nuevo_modulo.visible),
+            estado_publicacion: Set(EstadoPublicacion::desde_visible(nuevo_modulo.visible)),
+            publicar_en: Set(None),
+            despublicar_en: Set(None),
             fecha_inicio: Set(nuevo_modulo.fecha_inicio),
             fecha_fin: Set(nuevo_modulo.fecha_fin),
             duracion_estimada: Set(nuevo_modulo.duracion_estimada),
@@ -50,24 +58,47 @@ impl ModuloService {
         };
 
         let modulo_creado = modulo.insert(&db).await?;
+        if modulo_creado.visible {
+            PublicacionService::new(self.db.clone())
+                .notificar_publicados(&[(TipoPublicable::Modulo, modulo_creado.id)])
+                .await;
+        }
         Ok(modulo_creado)
     }
 
+    /// Con `solo_visibles` (estudiantes) solo los publicados
     pub async fn obtener_modulos_por_curso(
         &self,
         curso_id: i32,
+        solo_visibles: bool,
     ) -> Result<Vec<ModuloModel>, DbErr> {
         let db = self.connection();
-        Modulo::find()
-            .filter(modulo::Column::CursoId.eq(curso_id))
+        let mut consulta = Modulo::find()
+            .filter(modulo::Column::FechaEliminacion.is_null())
+            .filter(modulo::Column::CursoId.eq(curso_id));
+        if solo_visibles {
+            consulta = consulta.filter(modulo::Column::Visible.eq(true));
+        }
+        consulta
             .order_by(modulo::Column::Orden, Order::Asc)
             .all(&db)
             .await
     }
 
-    pub async fn obtener_modulo_por_id(&self, id: i32) -> Result<Option<ModuloModel>, DbErr> {
+    /// Con `solo_visibles` (estudiantes) los borradores y lo que cuelga de ellos no existe
+    pub async fn obtener_modulo_por_id(
+        &self,
+        id: i32,
+        solo_visibles: bool,
+    ) -> Result<Option<ModuloModel>, DbErr> {
         let db = self.connection();
-        Modulo::find_by_id(id).one(&db).await
+        if solo_visibles && !visible_para_estudiantes(&db, TipoPublicable::Modulo, id).await? {
+            return Ok(None);
+        }
+        Modulo::find_by_id(id)
+            .filter(modulo::Column::FechaEliminacion.is_null())
+            .one(&db)
+            .await
     }
 
     pub async fn actualizar_modulo(
@@ -77,10 +108,12 @@ impl ModuloService {
     ) -> Result<ModuloModel, AppError> {
         let db = self.connection();
         let modulo = Modulo::find_by_id(id)
+            .filter(modulo::Column::FechaEliminacion.is_null())
             .one(&db)
             .await?
             .ok_or_else(|| AppError::NotFound("Módulo no encontrado".into()))?;
 
+        let era_visible = modulo.visible;
         let mut modulo: modulo::ActiveModel = modulo.into();
 
         if let Some(nombre) = datos_actualizados.nombre {
@@ -100,8 +133,12 @@ impl ModuloService {
             modulo.orden = Set(orden);
         }
 
-        if let Some(visible) = datos_actualizados.visible {
+        // Un cambio manual de visibilidad reemplaza la publicación programada
+        if let Some(visible) = datos_actualizados.visible.filter(|v| *v != era_visible) {
             modulo.visible = Set(visible);
+            modulo.estado_publicacion = Set(EstadoPublicacion::desde_visible(visible));
+            modulo.publicar_en = Set(None);
+            modulo.despublicar_en = Set(None);
         }
 
         if let Some(tipo) = datos_actualizados.tipo {
@@ -125,6 +162,11 @@ impl ModuloService {
         }
 
         let modulo_actualizado = modulo.update(&db).await?;
+        if !era_visible && modulo_actualizado.visible {
+            PublicacionService::new(self.db.clone())
+                .notificar_publicados(&[(TipoPublicable::Modulo, id)])
+                .await;
+        }
 
         Ok(modulo_actualizado)
     }
@@ -132,11 +174,16 @@ impl ModuloService {
     pub async fn eliminar_modulo(&self, id: i32) -> Result<(), AppError> {
         let db = self.connection();
         let modulo = Modulo::find_by_id(id)
+            .filter(modulo::Column::FechaEliminacion.is_null())
             .one(&db)
             .await?
             .ok_or_else(|| AppError::NotFound("Módulo no encontrado".into()))?;
 
-        modulo.delete(&db).await?;
+        // Soft delete en cascada (ver PapeleraService)
+        let txn = db.begin().await?;
+        PapeleraService::mover_a_papelera(&txn, TipoPapelera::Modulo, modulo.id, Utc::now())
+            .await?;
+        txn.commit().await?;
         Ok(())
     }
 }
@@ -1,18 +1,23 @@
 use axum::extract::FromRef;
 use chrono::Utc;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DbErr, EntityTrait, ModelTrait, PaginatorTrait, QueryFilter,
-    QueryOrder, QuerySelect, Set,
+    sea_query::OnConflict, ActiveModelTrait, ColumnTrait, DbErr, EntityTrait, ModelTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
 };
-use serde_json::Value;
+use serde_json::{json, Value};
 
 use crate::{
     database::DbExecutor,
     models::{
+        envio_notificacion::{self, EstadoEnvio},
         notificacion::{self, Entity as Notificacion, Model as NotificacionModel},
+        preferencia_notificacion::{
+            self, Entity as Preferencia, FrecuenciaNotificacion, PreferenciaNotificacion,
+        },
         AppState,
     },
-    utils::errors::AppError,
+    services::{entrega_notificacion_service, socket_service::get_socket_service},
+    utils::{errors::AppError, markdown, plantillas_notificacion},
 };
 
 pub use crate::models::notificacion::NuevaNotificacion;
@@ -37,6 +42,9 @@ impl NotificacionService {
         Self { db }
     }
 
+    /// Guarda la notificación (bandeja de la aplicación) y la reparte según la
+    /// preferencia del usuario para su tipo: WebSocket y correo inmediato o en el
+    /// resumen diario
     pub async fn crear_notificacion(
         &self,
         nueva_notificacion: NuevaNotificacion,
@@ -58,10 +66,16 @@ impl NotificacionService {
             _ => None,
         };
 
+        let frecuencia = self
+            .frecuencia(nueva_notificacion.usuario_id, &nueva_notificacion.tipo)
+            .await?;
+
+        let mensaje_html = markdown::a_html_en_hilo(&nueva_notificacion.mensaje).await?;
         let ahora = Utc::now();
         let notificacion = notificacion::ActiveModel {
             usuario_id: Set(nueva_notificacion.usuario_id),
             titulo: Set(nueva_notificacion.titulo),
+            mensaje_html: Set(Some(mensaje_html)),
             mensaje: Set(nueva_notificacion.mensaje),
             tipo: Set(nueva_notificacion.tipo),
             leida: Set(nueva_notificacion.leida.unwrap_or(false)),
@@ -72,10 +86,151 @@ impl NotificacionService {
             ..Default::default()
         };
 
-        let notificacion_creada = notificacion.insert(&self.db.connection()).await?;
+        let txn = self.db.connection().begin().await?;
+        let notificacion_creada = notificacion.insert(&txn).await?;
+        let estado_correo = match frecuencia {
+            FrecuenciaNotificacion::Inmediata => Some(EstadoEnvio::Pendiente),
+            FrecuenciaNotificacion::ResumenDiario => Some(EstadoEnvio::Resumen),
+            FrecuenciaNotificacion::Desactivada => None,
+        };
+        if let Some(estado) = estado_correo {
+            envio_notificacion::ActiveModel {
+                notificacion_id: Set(notificacion_creada.id),
+                usuario_id: Set(notificacion_creada.usuario_id),
+                estado: Set(estado),
+                intentos: Set(0),
+                proximo_intento: Set(ahora),
+                ultimo_error: Set(None),
+                fecha_creacion: Set(ahora),
+                fecha_envio: Set(None),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?;
+        }
+        txn.commit().await?;
+
+        if frecuencia != FrecuenciaNotificacion::Desactivada {
+            get_socket_service()
+                .emit_notification_to_user(
+                    notificacion_creada.usuario_id as i64,
+                    json!({ "event": "notificacion", "data": &notificacion_creada }),
+                )
+                .await;
+        }
+        if frecuencia == FrecuenciaNotificacion::Inmediata {
+            entrega_notificacion_service::despachar();
+        }
+
         Ok(notificacion_creada)
     }
 
+    /// Frecuencia que aplica a un tipo: la elegida por el usuario, inmediata si no
+    /// eligió ninguna, y siempre inmediata para los tipos obligatorios
+    async fn frecuencia(
+        &self,
+        usuario_id: i32,
+        tipo: &str,
+    ) -> Result<FrecuenciaNotificacion, AppError> {
+        if plantillas_notificacion::es_obligatorio(tipo) {
+            return Ok(FrecuenciaNotificacion::Inmediata);
+        }
+        let preferencia = Preferencia::find()
+            .filter(preferencia_notificacion::Column::UsuarioId.eq(usuario_id))
+            .filter(preferencia_notificacion::Column::Tipo.eq(tipo))
+            .one(&self.db.connection())
+            .await?;
+        Ok(preferencia.map(|p| p.frecuencia).unwrap_or_default())
+    }
+
+    /// Preferencias del usuario: los tipos conocidos (con su valor por defecto si
+    /// no los cambió) más cualquier otro tipo que haya configurado
+    pub async fn obtener_preferencias(
+        &self,
+        usuario_id: i32,
+    ) -> Result<Vec<PreferenciaNotificacion>, AppError> {
+        let guardadas = Preferencia::find()
+            .filter(preferencia_notificacion::Column::UsuarioId.eq(usuario_id))
+            .order_by_asc(preferencia_notificacion::Column::Tipo)
+            .all(&self.db.connection())
+            .await?;
+
+        let mut preferencias: Vec<PreferenciaNotificacion> = plantillas_notificacion::TIPOS
+            .iter()
+            .map(|(tipo, _)| PreferenciaNotificacion {
+                tipo: tipo.to_string(),
+                frecuencia: FrecuenciaNotificacion::default(),
+                obligatoria: plantillas_notificacion::es_obligatorio(tipo),
+            })
+            .collect();
+        for guardada in guardadas {
+            if plantillas_notificacion::es_obligatorio(&guardada.tipo) {
+                continue;
+            }
+            match preferencias.iter_mut().find(|p| p.tipo == guardada.tipo) {
+                Some(preferencia) => preferencia.frecuencia = guardada.frecuencia,
+                None => preferencias.push(PreferenciaNotificacion {
+                    tipo: guardada.tipo,
+                    frecuencia: guardada.frecuencia,
+                    obligatoria: false,
+                }),
+            }
+        }
+
+        Ok(preferencias)
+    }
+
+    /// Guarda la frecuencia de los tipos enviados; los demás no cambian
+    pub async fn actualizar_preferencias(
+        &self,
+        usuario_id: i32,
+        preferencias: Vec<PreferenciaNotificacion>,
+    ) -> Result<Vec<PreferenciaNotificacion>, AppError> {
+        for preferencia in &preferencias {
+            let tipo = preferencia.tipo.trim();
+            if tipo.is_empty() || tipo.chars().count() > 50 {
+                return Err(AppError::BadRequest(
+                    "El tipo es obligatorio (máximo 50 caracteres)".into(),
+                ));
+            }
+            if plantillas_notificacion::es_obligatorio(tipo)
+                && preferencia.frecuencia != FrecuenciaNotificacion::Inmediata
+            {
+                return Err(AppError::BadRequest(
+                    format!("Las notificaciones de {} siempre se envían al momento", tipo).into(),
+                ));
+            }
+        }
+
+        let txn = self.db.connection().begin().await?;
+        let ahora = Utc::now();
+        for preferencia in preferencias {
+            Preferencia::insert(preferencia_notificacion::ActiveModel {
+                usuario_id: Set(usuario_id),
+                tipo: Set(preferencia.tipo.trim().to_string()),
+                frecuencia: Set(preferencia.frecuencia),
+                fecha_actualizacion: Set(ahora),
+                ..Default::default()
+            })
+            .on_conflict(
+                OnConflict::columns([
+                    preferencia_notificacion::Column::UsuarioId,
+                    preferencia_notificacion::Column::Tipo,
+                ])
+                .update_columns([
+                    preferencia_notificacion::Column::Frecuencia,
+                    preferencia_notificacion::Column::FechaActualizacion,
+                ])
+                .to_owned(),
+            )
+            .exec(&txn)
+            .await?;
+        }
+        txn.commit().await?;
+
+        self.obtener_preferencias(usuario_id).await
+    }
+
     pub async fn obtener_por_usuario(
         &self,
         usuario_id: i32,
@@ -0,0 +1,261 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use axum::extract::FromRef;
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DbBackend, DbErr, FromQueryResult, Statement,
+    TransactionTrait, Value,
+};
+
+use crate::{
+    database::DbExecutor,
+    models::AppState,
+    services::auditoria_service::{AuditoriaService, ContextoAuditoria},
+    utils::errors::AppError,
+};
+
+pub use crate::models::orden::{ElementoOrdenado, Reordenamiento, TipoOrdenable};
+
+#[derive(Debug, FromQueryResult)]
+struct Ambito {
+    ambito: i32,
+}
+
+fn sentencia<I>(sql: &str, valores: I) -> Statement
+where
+    I: IntoIterator<Item = Value>,
+{
+    Statement::from_sql_and_values(DbBackend::Postgres, sql, valores)
+}
+
+/// Hijos vigentes de un padre en su orden actual
+async fn hijos<C>(
+    db: &C,
+    tipo: TipoOrdenable,
+    padre_id: i32,
+) -> Result<Vec<ElementoOrdenado>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let sql = format!(
+        "SELECT id, {padre} AS padre_id, orden FROM {tabla} WHERE {padre} = $1 AND {vigente} ORDER BY orden, id",
+        padre = tipo.columna_padre(),
+        tabla = tipo.tabla(),
+        vigente = tipo.filtro_vigente()
+    );
+    ElementoOrdenado::find_by_statement(sentencia(&sql, [padre_id.into()]))
+        .all(db)
+        .await
+}
+
+async fn ambito<C>(db: &C, tipo: TipoOrdenable, padre_id: i32) -> Result<Option<i32>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    Ok(
+        Ambito::find_by_statement(sentencia(tipo.consulta_ambito(), [padre_id.into()]))
+            .one(db)
+            .await?
+            .map(|a| a.ambito),
+    )
+}
+
+#[derive(Debug, Clone)]
+pub struct OrdenService {
+    db: DbExecutor,
+}
+
+impl FromRef<AppState> for OrdenService {
+    fn from_ref(state: &AppState) -> Self {
+        let executor = state
+            .db
+            .clone()
+            .expect("Database connection is not available");
+        OrdenService::new(executor)
+    }
+}
+
+impl OrdenService {
+    pub fn new(db: DbExecutor) -> Self {
+        Self { db }
+    }
+
+    fn connection(&self) -> DatabaseConnection {
+        self.db.connection()
+    }
+
+    /// Reescribe el orden de los hijos de un padre como 1..n según `ids`, que debe
+    /// traer todos sus hijos vigentes. Los ids de otro padre del mismo ámbito se
+    /// mueven aquí y su padre anterior queda reordenado sin huecos
+    pub async fn reordenar(
+        &self,
+        tipo: TipoOrdenable,
+        padre_id: i32,
+        datos: Reordenamiento,
+        ctx: &ContextoAuditoria,
+    ) -> Result<Vec<ElementoOrdenado>, AppError> {
+        let db = self.connection();
+        let ambito_destino = ambito(&db, tipo, padre_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Elemento padre no encontrado".into()))?;
+
+        let mut vistos = HashSet::new();
+        let repetidos: BTreeSet<i32> = datos
+            .ids
+            .iter()
+            .copied()
+            .filter(|id| !vistos.insert(*id))
+            .collect();
+        if !repetidos.is_empty() {
+            return Err(AppError::BadRequest(
+                format!("Ids repetidos: {:?}", repetidos).into(),
+            ));
+        }
+
+        let txn = db.begin().await?;
+        let antes = hijos(&txn, tipo, padre_id).await?;
+
+        let faltantes: BTreeSet<i32> = antes
+            .iter()
+            .map(|h| h.id)
+            .filter(|id| !vistos.contains(id))
+            .collect();
+        if !faltantes.is_empty() {
+            return Err(AppError::BadRequest(
+                format!(
+                    "La lista debe incluir todos los elementos del padre; faltan: {:?}",
+                    faltantes
+                )
+                .into(),
+            ));
+        }
+
+        // Padre actual de cada id que llega de fuera
+        let propios: HashSet<i32> = antes.iter().map(|h| h.id).collect();
+        let externos: Vec<i32> = datos
+            .ids
+            .iter()
+            .copied()
+            .filter(|id| !propios.contains(id))
+            .collect();
+        let mut padres_origen: HashMap<i32, i32> = HashMap::new();
+        if !externos.is_empty() {
+            let sql = format!(
+                "SELECT id, {padre} AS padre_id, orden FROM {tabla} WHERE id IN ({ids}) AND {padre} IS NOT NULL AND {vigente}",
+                padre = tipo.columna_padre(),
+                tabla = tipo.tabla(),
+                ids = externos
+                    .iter()
+                    .map(i32::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                vigente = tipo.filtro_vigente()
+            );
+            let encontrados = ElementoOrdenado::find_by_statement(sentencia(&sql, []))
+                .all(&txn)
+                .await?;
+            padres_origen = encontrados.iter().map(|e| (e.id, e.padre_id)).collect();
+
+            let inexistentes: BTreeSet<i32> = externos
+                .iter()
+                .copied()
+                .filter(|id| !padres_origen.contains_key(id))
+                .collect();
+            if !inexistentes.is_empty() {
+                return Err(AppError::BadRequest(
+                    format!("Ids inexistentes: {:?}", inexistentes).into(),
+                ));
+            }
+
+            let origenes: BTreeSet<i32> = padres_origen.values().copied().collect();
+            for origen in &origenes {
+                if ambito(&txn, tipo, *origen).await? != Some(ambito_destino) {
+                    return Err(AppError::BadRequest(
+                        format!(
+                            "Solo se puede mover un {} a otro padre del mismo {}",
+                            tipo.nombre(),
+                            tipo.nombre_ambito()
+                        )
+                        .into(),
+                    ));
+                }
+            }
+
+            // Mover preguntas cambiaría el puntaje de intentos ya rendidos
+            if tipo == TipoOrdenable::Pregunta {
+                let examenes = origenes
+                    .iter()
+                    .chain([&padre_id])
+                    .map(i32::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let sql = format!(
+                    "SELECT DISTINCT examen_id AS ambito FROM intentos_examen WHERE examen_id IN ({})",
+                    examenes
+                );
+                let con_intentos: BTreeSet<i32> = Ambito::find_by_statement(sentencia(&sql, []))
+                    .all(&txn)
+                    .await?
+                    .into_iter()
+                    .map(|a| a.ambito)
+                    .collect();
+                if !con_intentos.is_empty() {
+                    return Err(AppError::Conflict(
+                        format!(
+                            "No se pueden mover preguntas entre exámenes con intentos: {:?}",
+                            con_intentos
+                        )
+                        .into(),
+                    ));
+                }
+            }
+        }
+
+        let actualizar = format!(
+            "UPDATE {tabla} SET {padre} = $1, orden = $2 WHERE id = $3",
+            tabla = tipo.tabla(),
+            padre = tipo.columna_padre()
+        );
+        for (posicion, id) in datos.ids.iter().enumerate() {
+            txn.execute(sentencia(
+                &actualizar,
+                [padre_id.into(), (posicion as i32 + 1).into(), (*id).into()],
+            ))
+            .await?;
+        }
+
+        // Los padres que cedieron elementos quedan sin huecos
+        let compactar = format!(
+            r#"
+            UPDATE {tabla} AS t SET orden = x.n
+            FROM (
+                SELECT id, ROW_NUMBER() OVER (ORDER BY orden, id) AS n
+                FROM {tabla} WHERE {padre} = $1 AND {vigente}
+            ) x
+            WHERE t.id = x.id
+            "#,
+            tabla = tipo.tabla(),
+            padre = tipo.columna_padre(),
+            vigente = tipo.filtro_vigente()
+        );
+        let origenes: BTreeSet<i32> = padres_origen.into_values().collect();
+        for origen in &origenes {
+            txn.execute(sentencia(&compactar, [(*origen).into()]))
+                .await?;
+        }
+
+        let despues = hijos(&txn, tipo, padre_id).await?;
+        AuditoriaService::registrar(
+            &txn,
+            ctx,
+            "reordenar",
+            tipo.nombre(),
+            Some(padre_id),
+            Some(&antes),
+            Some(&despues),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(despues)
+    }
+}
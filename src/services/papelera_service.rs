@@ -0,0 +1,553 @@
+use axum::extract::FromRef;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QuerySelect, TransactionTrait,
+};
+
+use crate::{
+    database::DbExecutor,
+    models::{
+        contenido_unidad::{self, Entity as Contenido},
+        curso::{self, Entity as Curso},
+        examen::{self, Entity as Examen},
+        modulo::{self, Entity as Modulo},
+        tema::{self, Entity as Tema},
+        unidad::{self, Entity as Unidad},
+        AppState,
+    },
+    services::auditoria_service::{AuditoriaService, ContextoAuditoria},
+    utils::errors::AppError,
+};
+
+pub use crate::models::papelera::{ElementoPapelera, FiltroPapelera, TipoPapelera};
+
+/// Ids de un elemento y de todos sus descendientes, por entidad
+#[derive(Debug, Default)]
+struct Jerarquia {
+    cursos: Vec<i32>,
+    modulos: Vec<i32>,
+    examenes: Vec<i32>,
+    temas: Vec<i32>,
+    unidades: Vec<i32>,
+    contenidos: Vec<i32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PapeleraService {
+    db: DbExecutor,
+}
+
+impl FromRef<AppState> for PapeleraService {
+    fn from_ref(state: &AppState) -> Self {
+        let executor = state
+            .db
+            .clone()
+            .expect("Database connection is not available");
+        PapeleraService::new(executor)
+    }
+}
+
+impl PapeleraService {
+    pub fn new(db: DbExecutor) -> Self {
+        Self { db }
+    }
+
+    fn connection(&self) -> DatabaseConnection {
+        self.db.connection()
+    }
+
+    /// Marca el elemento y sus descendientes con la misma `fecha` de eliminación.
+    /// Los descendientes que ya estaban en la papelera conservan su fecha original,
+    /// así una restauración posterior no los revive.
+    pub async fn mover_a_papelera<C>(
+        db: &C,
+        tipo: TipoPapelera,
+        id: i32,
+        fecha: DateTime<Utc>,
+    ) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let jerarquia = jerarquia(db, tipo, id).await?;
+        fijar_fecha(db, &jerarquia, None, Some(fecha)).await
+    }
+
+    pub async fn listar(&self, filtro: FiltroPapelera) -> Result<Vec<ElementoPapelera>, AppError> {
+        let db = self.connection();
+        let alcance = match filtro.curso_id {
+            Some(curso_id) => Some(jerarquia(&db, TipoPapelera::Curso, curso_id).await?),
+            None => None,
+        };
+
+        let tipos = match filtro.tipo {
+            Some(tipo) => vec![tipo],
+            None => TipoPapelera::TODOS.to_vec(),
+        };
+
+        let mut elementos = Vec::new();
+        for tipo in tipos {
+            elementos.extend(eliminados(&db, tipo, alcance.as_ref()).await?);
+        }
+        elementos.sort_by_key(|elemento| std::cmp::Reverse(elemento.fecha_eliminacion));
+
+        Ok(elementos)
+    }
+
+    /// Restaura el elemento y los descendientes eliminados junto con él
+    pub async fn restaurar(
+        &self,
+        tipo: TipoPapelera,
+        id: i32,
+        ctx: &ContextoAuditoria,
+    ) -> Result<(), AppError> {
+        let db = self.connection();
+        let (padre, fecha) = estado_eliminacion(&db, tipo, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Elemento no encontrado".into()))?;
+        let fecha = fecha
+            .ok_or_else(|| AppError::BadRequest("El elemento no está en la papelera".into()))?;
+
+        if let Some((tipo_padre, padre_id)) = padre {
+            if let Some((_, Some(_))) = estado_eliminacion(&db, tipo_padre, padre_id).await? {
+                return Err(AppError::Conflict(
+                    format!(
+                        "El {} que contiene este elemento está en la papelera; restáurelo primero",
+                        tipo_padre.nombre()
+                    )
+                    .into(),
+                ));
+            }
+        }
+        if tipo == TipoPapelera::Contenido {
+            let examen_id = Contenido::find_by_id(id)
+                .one(&db)
+                .await?
+                .and_then(|c| c.examen_id);
+            if let Some(examen_id) = examen_id {
+                if let Some((_, Some(_))) =
+                    estado_eliminacion(&db, TipoPapelera::Examen, examen_id).await?
+                {
+                    return Err(AppError::Conflict(
+                        "El examen vinculado a este contenido está en la papelera; restáurelo primero"
+                            .into(),
+                    ));
+                }
+            }
+        }
+
+        let txn = db.begin().await?;
+        let jerarquia = jerarquia(&txn, tipo, id).await?;
+        fijar_fecha(&txn, &jerarquia, Some(fecha), None).await?;
+        AuditoriaService::registrar(
+            &txn,
+            ctx,
+            "restaurar",
+            tipo.nombre(),
+            Some(id),
+            Some(&serde_json::json!({ "fecha_eliminacion": fecha })),
+            Some(&serde_json::json!({ "fecha_eliminacion": null })),
+        )
+        .await?;
+        txn.commit().await?;
+
+        tracing::info!("♻️  {} {} restaurado de la papelera", tipo.nombre(), id);
+        Ok(())
+    }
+
+    /// Borra definitivamente lo que lleva en la papelera más de `retencion_dias`.
+    /// Se eliminan de las hojas hacia la raíz; las FKs con ON DELETE CASCADE
+    /// se encargan de dependencias (matrículas, preguntas, etc.)
+    pub async fn purgar(&self, retencion_dias: u64) -> Result<u64, AppError> {
+        let db = self.connection();
+        let limite = Utc::now() - chrono::Duration::days(retencion_dias as i64);
+
+        let mut total = 0;
+        total += Contenido::delete_many()
+            .filter(contenido_unidad::Column::FechaEliminacion.lt(limite))
+            .exec(&db)
+            .await?
+            .rows_affected;
+        total += Unidad::delete_many()
+            .filter(unidad::Column::FechaEliminacion.lt(limite))
+            .exec(&db)
+            .await?
+            .rows_affected;
+        total += Tema::delete_many()
+            .filter(tema::Column::FechaEliminacion.lt(limite))
+            .exec(&db)
+            .await?
+            .rows_affected;
+        total += Modulo::delete_many()
+            .filter(modulo::Column::FechaEliminacion.lt(limite))
+            .exec(&db)
+            .await?
+            .rows_affected;
+        total += Examen::delete_many()
+            .filter(examen::Column::FechaEliminacion.lt(limite))
+            .exec(&db)
+            .await?
+            .rows_affected;
+        total += Curso::delete_many()
+            .filter(curso::Column::FechaEliminacion.lt(limite))
+            .exec(&db)
+            .await?
+            .rows_affected;
+
+        Ok(total)
+    }
+}
+
+/// Recorre la jerarquía desde `tipo`/`id` hacia abajo (incluye filas ya eliminadas)
+async fn jerarquia<C>(db: &C, tipo: TipoPapelera, id: i32) -> Result<Jerarquia, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let mut jerarquia = Jerarquia::default();
+    match tipo {
+        TipoPapelera::Curso => {
+            jerarquia.cursos = vec![id];
+            jerarquia.modulos = Modulo::find()
+                .select_only()
+                .column(modulo::Column::Id)
+                .filter(modulo::Column::CursoId.eq(id))
+                .into_tuple()
+                .all(db)
+                .await?;
+            jerarquia.examenes = Examen::find()
+                .select_only()
+                .column(examen::Column::Id)
+                .filter(examen::Column::CursoId.eq(id))
+                .into_tuple()
+                .all(db)
+                .await?;
+        }
+        TipoPapelera::Modulo => jerarquia.modulos = vec![id],
+        TipoPapelera::Tema => jerarquia.temas = vec![id],
+        TipoPapelera::Unidad => jerarquia.unidades = vec![id],
+        TipoPapelera::Contenido => jerarquia.contenidos = vec![id],
+        TipoPapelera::Examen => jerarquia.examenes = vec![id],
+    }
+
+    if !jerarquia.modulos.is_empty() {
+        let temas: Vec<i32> = Tema::find()
+            .select_only()
+            .column(tema::Column::Id)
+            .filter(tema::Column::ModuloId.is_in(jerarquia.modulos.clone()))
+            .into_tuple()
+            .all(db)
+            .await?;
+        jerarquia.temas.extend(temas);
+    }
+    if !jerarquia.temas.is_empty() {
+        let unidades: Vec<i32> = Unidad::find()
+            .select_only()
+            .column(unidad::Column::Id)
+            .filter(unidad::Column::TemaId.is_in(jerarquia.temas.clone()))
+            .into_tuple()
+            .all(db)
+            .await?;
+        jerarquia.unidades.extend(unidades);
+    }
+    if !jerarquia.unidades.is_empty() {
+        let contenidos: Vec<i32> = Contenido::find()
+            .select_only()
+            .column(contenido_unidad::Column::Id)
+            .filter(contenido_unidad::Column::UnidadId.is_in(jerarquia.unidades.clone()))
+            .into_tuple()
+            .all(db)
+            .await?;
+        jerarquia.contenidos.extend(contenidos);
+    }
+    // Los contenidos que presentan un examen lo siguen a la papelera
+    if !jerarquia.examenes.is_empty() {
+        let vinculados: Vec<i32> = Contenido::find()
+            .select_only()
+            .column(contenido_unidad::Column::Id)
+            .filter(contenido_unidad::Column::ExamenId.is_in(jerarquia.examenes.clone()))
+            .into_tuple()
+            .all(db)
+            .await?;
+        for contenido in vinculados {
+            if !jerarquia.contenidos.contains(&contenido) {
+                jerarquia.contenidos.push(contenido);
+            }
+        }
+    }
+
+    Ok(jerarquia)
+}
+
+/// Cambia `fecha_eliminacion` de `actual` (None = activo) a `nueva` en toda la jerarquía
+async fn fijar_fecha<C>(
+    db: &C,
+    jerarquia: &Jerarquia,
+    actual: Option<DateTime<Utc>>,
+    nueva: Option<DateTime<Utc>>,
+) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    fijar_fecha_entidad::<Curso, C>(
+        db,
+        curso::Column::Id,
+        curso::Column::FechaEliminacion,
+        &jerarquia.cursos,
+        actual,
+        nueva,
+    )
+    .await?;
+    fijar_fecha_entidad::<Modulo, C>(
+        db,
+        modulo::Column::Id,
+        modulo::Column::FechaEliminacion,
+        &jerarquia.modulos,
+        actual,
+        nueva,
+    )
+    .await?;
+    fijar_fecha_entidad::<Examen, C>(
+        db,
+        examen::Column::Id,
+        examen::Column::FechaEliminacion,
+        &jerarquia.examenes,
+        actual,
+        nueva,
+    )
+    .await?;
+    fijar_fecha_entidad::<Tema, C>(
+        db,
+        tema::Column::Id,
+        tema::Column::FechaEliminacion,
+        &jerarquia.temas,
+        actual,
+        nueva,
+    )
+    .await?;
+    fijar_fecha_entidad::<Unidad, C>(
+        db,
+        unidad::Column::Id,
+        unidad::Column::FechaEliminacion,
+        &jerarquia.unidades,
+        actual,
+        nueva,
+    )
+    .await?;
+    fijar_fecha_entidad::<Contenido, C>(
+        db,
+        contenido_unidad::Column::Id,
+        contenido_unidad::Column::FechaEliminacion,
+        &jerarquia.contenidos,
+        actual,
+        nueva,
+    )
+    .await
+}
+
+async fn fijar_fecha_entidad<E, C>(
+    db: &C,
+    columna_id: E::Column,
+    columna_fecha: E::Column,
+    ids: &[i32],
+    actual: Option<DateTime<Utc>>,
+    nueva: Option<DateTime<Utc>>,
+) -> Result<(), DbErr>
+where
+    E: EntityTrait,
+    C: ConnectionTrait,
+{
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    let condicion = match actual {
+        Some(fecha) => columna_fecha.eq(fecha),
+        None => columna_fecha.is_null(),
+    };
+    E::update_many()
+        .col_expr(columna_fecha, Expr::value(nueva))
+        .filter(columna_id.is_in(ids.to_vec()))
+        .filter(condicion)
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Contenedor directo y fecha de eliminación del elemento (None si no existe)
+#[allow(clippy::type_complexity)]
+async fn estado_eliminacion<C>(
+    db: &C,
+    tipo: TipoPapelera,
+    id: i32,
+) -> Result<Option<(Option<(TipoPapelera, i32)>, Option<DateTime<Utc>>)>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let estado = match tipo {
+        TipoPapelera::Curso => Curso::find_by_id(id)
+            .one(db)
+            .await?
+            .map(|c| (None, c.fecha_eliminacion)),
+        TipoPapelera::Modulo => Modulo::find_by_id(id)
+            .one(db)
+            .await?
+            .map(|m| (Some((TipoPapelera::Curso, m.curso_id)), m.fecha_eliminacion)),
+        TipoPapelera::Examen => Examen::find_by_id(id)
+            .one(db)
+            .await?
+            .map(|e| (Some((TipoPapelera::Curso, e.curso_id)), e.fecha_eliminacion)),
+        TipoPapelera::Tema => Tema::find_by_id(id).one(db).await?.map(|t| {
+            (
+                Some((TipoPapelera::Modulo, t.modulo_id)),
+                t.fecha_eliminacion,
+            )
+        }),
+        TipoPapelera::Unidad => Unidad::find_by_id(id)
+            .one(db)
+            .await?
+            .map(|u| (Some((TipoPapelera::Tema, u.tema_id)), u.fecha_eliminacion)),
+        TipoPapelera::Contenido => Contenido::find_by_id(id).one(db).await?.map(|c| {
+            (
+                Some((TipoPapelera::Unidad, c.unidad_id)),
+                c.fecha_eliminacion,
+            )
+        }),
+    };
+
+    Ok(estado)
+}
+
+/// Elementos de un tipo que están en la papelera, opcionalmente limitados a un curso
+async fn eliminados(
+    db: &DatabaseConnection,
+    tipo: TipoPapelera,
+    alcance: Option<&Jerarquia>,
+) -> Result<Vec<ElementoPapelera>, DbErr> {
+    let elementos = match tipo {
+        TipoPapelera::Curso => {
+            let mut query = Curso::find().filter(curso::Column::FechaEliminacion.is_not_null());
+            if let Some(alcance) = alcance {
+                query = query.filter(curso::Column::Id.is_in(alcance.cursos.clone()));
+            }
+            query
+                .all(db)
+                .await?
+                .into_iter()
+                .filter_map(|c| {
+                    Some(ElementoPapelera {
+                        tipo,
+                        id: c.id,
+                        nombre: c.nombre,
+                        padre_id: None,
+                        fecha_eliminacion: c.fecha_eliminacion?,
+                    })
+                })
+                .collect()
+        }
+        TipoPapelera::Modulo => {
+            let mut query = Modulo::find().filter(modulo::Column::FechaEliminacion.is_not_null());
+            if let Some(alcance) = alcance {
+                query = query.filter(modulo::Column::Id.is_in(alcance.modulos.clone()));
+            }
+            query
+                .all(db)
+                .await?
+                .into_iter()
+                .filter_map(|m| {
+                    Some(ElementoPapelera {
+                        tipo,
+                        id: m.id,
+                        nombre: m.nombre,
+                        padre_id: Some(m.curso_id),
+                        fecha_eliminacion: m.fecha_eliminacion?,
+                    })
+                })
+                .collect()
+        }
+        TipoPapelera::Examen => {
+            let mut query = Examen::find().filter(examen::Column::FechaEliminacion.is_not_null());
+            if let Some(alcance) = alcance {
+                query = query.filter(examen::Column::Id.is_in(alcance.examenes.clone()));
+            }
+            query
+                .all(db)
+                .await?
+                .into_iter()
+                .filter_map(|e| {
+                    Some(ElementoPapelera {
+                        tipo,
+                        id: e.id,
+                        nombre: e.nombre,
+                        padre_id: Some(e.curso_id),
+                        fecha_eliminacion: e.fecha_eliminacion?,
+                    })
+                })
+                .collect()
+        }
+        TipoPapelera::Tema => {
+            let mut query = Tema::find().filter(tema::Column::FechaEliminacion.is_not_null());
+            if let Some(alcance) = alcance {
+                query = query.filter(tema::Column::Id.is_in(alcance.temas.clone()));
+            }
+            query
+                .all(db)
+                .await?
+                .into_iter()
+                .filter_map(|t| {
+                    Some(ElementoPapelera {
+                        tipo,
+                        id: t.id,
+                        nombre: t.nombre,
+                        padre_id: Some(t.modulo_id),
+                        fecha_eliminacion: t.fecha_eliminacion?,
+                    })
+                })
+                .collect()
+        }
+        TipoPapelera::Unidad => {
+            let mut query = Unidad::find().filter(unidad::Column::FechaEliminacion.is_not_null());
+            if let Some(alcance) = alcance {
+                query = query.filter(unidad::Column::Id.is_in(alcance.unidades.clone()));
+            }
+            query
+                .all(db)
+                .await?
+                .into_iter()
+                .filter_map(|u| {
+                    Some(ElementoPapelera {
+                        tipo,
+                        id: u.id,
+                        nombre: u.nombre,
+                        padre_id: Some(u.tema_id),
+                        fecha_eliminacion: u.fecha_eliminacion?,
+                    })
+                })
+                .collect()
+        }
+        TipoPapelera::Contenido => {
+            let mut query =
+                Contenido::find().filter(contenido_unidad::Column::FechaEliminacion.is_not_null());
+            if let Some(alcance) = alcance {
+                query =
+                    query.filter(contenido_unidad::Column::Id.is_in(alcance.contenidos.clone()));
+            }
+            query
+                .all(db)
+                .await?
+                .into_iter()
+                .filter_map(|c| {
+                    Some(ElementoPapelera {
+                        tipo,
+                        id: c.id,
+                        nombre: c.titulo,
+                        padre_id: Some(c.unidad_id),
+                        fecha_eliminacion: c.fecha_eliminacion?,
+                    })
+                })
+                .collect()
+        }
+    };
+
+    Ok(elementos)
+}
@@ -1,11 +1,12 @@
 use chrono::Utc;
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, ModelTrait, QueryFilter,
-    Set,
+    Set, TransactionTrait,
 };
 
 use crate::{
     models::personalizacion_portafolio::{self, Entity as PersonalizacionPortafolio, Model as PersonalizacionPortafolioModel},
+    services::revision_service::{RevisionService, TipoRevisionable},
     utils::errors::AppError,
 };
 
@@ -24,6 +25,7 @@ impl PersonalizacionPortafolioService {
     pub async fn crear_personalizacion(
         &self,
         nueva_personalizacion: NuevaPersonalizacion,
+        autor_id: i32,
     ) -> Result<PersonalizacionPortafolioModel, AppError> {
         let ahora = Utc::now();
         let personalizacion = personalizacion_portafolio::ActiveModel {
@@ -36,7 +38,17 @@ impl PersonalizacionPortafolioService {
             ..Default::default()
         };
 
-        let personalizacion_creada = personalizacion.insert(&self.db).await?;
+        let txn = self.db.begin().await?;
+        let personalizacion_creada = personalizacion.insert(&txn).await?;
+        RevisionService::registrar(
+            &txn,
+            TipoRevisionable::PersonalizacionPortafolio,
+            personalizacion_creada.id,
+            Some(autor_id),
+            None,
+        )
+        .await?;
+        txn.commit().await?;
         Ok(personalizacion_creada)
     }
 
@@ -69,6 +81,7 @@ impl PersonalizacionPortafolioService {
         &self,
         id: i32,
         datos_actualizados: ActualizarPersonalizacion,
+        autor_id: i32,
     ) -> Result<PersonalizacionPortafolioModel, AppError> {
         let personalizacion = PersonalizacionPortafolio::find_by_id(id)
             .one(&self.db)
@@ -91,7 +104,25 @@ impl PersonalizacionPortafolioService {
         }
 
         personalizacion.updated_at = Set(Some(ahora));
-        let personalizacion_actualizada = personalizacion.update(&self.db).await?;
+        let txn = self.db.begin().await?;
+        RevisionService::registrar(
+            &txn,
+            TipoRevisionable::PersonalizacionPortafolio,
+            id,
+            None,
+            None,
+        )
+        .await?;
+        let personalizacion_actualizada = personalizacion.update(&txn).await?;
+        RevisionService::registrar(
+            &txn,
+            TipoRevisionable::PersonalizacionPortafolio,
+            id,
+            Some(autor_id),
+            None,
+        )
+        .await?;
+        txn.commit().await?;
 
         Ok(personalizacion_actualizada)
     }
@@ -1,13 +1,14 @@
 use chrono::Utc;
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
-    ModelTrait, Set,
+    ModelTrait, Set, TransactionTrait,
 };
 
 use crate::{
     models::portafolio_contenido::{self, Entity as PortafolioContenido, Model as PortafolioContenidoModel},
     models::portafolio_contenido::{ActualizarPortafolioContenido, NuevoPortafolioContenido},
-    utils::errors::AppError,
+    services::revision_service::{RevisionService, TipoRevisionable},
+    utils::{errors::AppError, markdown},
 };
 
 #[derive(Debug, Clone)]
@@ -23,6 +24,7 @@ impl PortafolioContenidoService {
     pub async fn crear_contenido(
         &self,
         nuevo_contenido: NuevoPortafolioContenido,
+        autor_id: i32,
     ) -> Result<PortafolioContenidoModel, AppError> {
         if nuevo_contenido.titulo.trim().is_empty() {
             return Err(AppError::BadRequest("El título es obligatorio".into()));
@@ -32,6 +34,8 @@ impl PortafolioContenidoService {
             return Err(AppError::BadRequest("El contenido es obligatorio".into()));
         }
 
+        let contenido_html = markdown::renderizar_en_hilo(&nuevo_contenido.contenido).await?;
+
         let ahora = Utc::now();
         let contenido = portafolio_contenido::ActiveModel {
             portafolio_id: Set(nuevo_contenido.portafolio_id),
@@ -39,13 +43,24 @@ impl PortafolioContenidoService {
             titulo: Set(nuevo_contenido.titulo),
             descripcion: Set(nuevo_contenido.descripcion),
             contenido: Set(nuevo_contenido.contenido),
+            contenido_html: Set(Some(contenido_html)),
             orden: Set(nuevo_contenido.orden),
             created_at: Set(Some(ahora)),
             updated_at: Set(Some(ahora)),
             ..Default::default()
         };
 
-        let contenido_creado = contenido.insert(&self.db).await?;
+        let txn = self.db.begin().await?;
+        let contenido_creado = contenido.insert(&txn).await?;
+        RevisionService::registrar(
+            &txn,
+            TipoRevisionable::PortafolioContenido,
+            contenido_creado.id,
+            Some(autor_id),
+            None,
+        )
+        .await?;
+        txn.commit().await?;
         Ok(contenido_creado)
     }
 
@@ -76,6 +91,7 @@ impl PortafolioContenidoService {
         &self,
         id: i32,
         datos_actualizados: ActualizarPortafolioContenido,
+        autor_id: i32,
     ) -> Result<PortafolioContenidoModel, AppError> {
         let contenido = PortafolioContenido::find_by_id(id)
             .one(&self.db)
@@ -104,7 +120,9 @@ impl PortafolioContenidoService {
             if contenido_valor.trim().is_empty() {
                 return Err(AppError::BadRequest("El contenido no puede estar vacío".into()));
             }
+            let contenido_html = markdown::renderizar_en_hilo(&contenido_valor).await?;
             contenido.contenido = Set(contenido_valor);
+            contenido.contenido_html = Set(Some(contenido_html));
         }
 
         if let Some(orden) = datos_actualizados.orden {
@@ -112,7 +130,25 @@ impl PortafolioContenidoService {
         }
 
         contenido.updated_at = Set(Some(ahora));
-        let contenido_actualizado = contenido.update(&self.db).await?;
+        let txn = self.db.begin().await?;
+        RevisionService::registrar(
+            &txn,
+            TipoRevisionable::PortafolioContenido,
+            id,
+            None,
+            None,
+        )
+        .await?;
+        let contenido_actualizado = contenido.update(&txn).await?;
+        RevisionService::registrar(
+            &txn,
+            TipoRevisionable::PortafolioContenido,
+            id,
+            Some(autor_id),
+            None,
+        )
+        .await?;
+        txn.commit().await?;
 
         Ok(contenido_actualizado)
     }
@@ -6,7 +6,9 @@ use serde_json::{json, Value as JsonValue};
 use chrono::Utc;
 
 use crate::{
-    models::pregunta_examen::{self, Entity as PreguntaExamen, Model as PreguntaExamenModel},
+    models::pregunta_examen::{
+        self, Entity as PreguntaExamen, Model as PreguntaExamenModel, TipoPregunta,
+    },
     utils::errors::AppError,
 };
 
@@ -25,7 +27,7 @@ impl PreguntaExamenService {
         &self,
         examen_id: i32,
         pregunta: String,
-        tipo_pregunta: String,
+        tipo_pregunta: TipoPregunta,
         opciones: Option<Vec<String>>,
         respuesta_correcta: Option<JsonValue>,
         valor_puntos: i32,
@@ -86,7 +88,7 @@ impl PreguntaExamenService {
         &self,
         id: i32,
         pregunta: Option<String>,
-        tipo_pregunta: Option<String>,
+        tipo_pregunta: Option<TipoPregunta>,
         opciones: Option<Vec<String>>,
         respuesta_correcta: Option<JsonValue>,
         valor_puntos: Option<i32>,
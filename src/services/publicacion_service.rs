@@ -0,0 +1,397 @@
+use std::collections::BTreeMap;
+
+use axum::extract::FromRef;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveEnum, ConnectionTrait, DatabaseConnection, DbBackend, DbErr, FromQueryResult, Statement,
+    TransactionTrait, Value,
+};
+use serde_json::json;
+
+use crate::{
+    database::DbExecutor,
+    models::AppState,
+    services::{
+        auditoria_service::{AuditoriaService, ContextoAuditoria},
+        notificacion_service::{NotificacionService, NuevaNotificacion},
+    },
+    utils::errors::AppError,
+};
+
+pub use crate::models::publicacion::{
+    CambioPublicacion, ElementoPublicable, EstadoPublicacion, TipoPublicable,
+};
+
+/// Curso del elemento y visibilidad efectiva: el elemento y todos sus contenedores
+/// publicados. $1 = id
+const UBICACION_MODULO: &str = r#"
+SELECT c.id AS curso_id, c.nombre AS curso, m.nombre AS nombre, m.visible AS visible
+FROM modulos m
+JOIN cursos c ON c.id = m.curso_id
+WHERE m.id = $1 AND m.fecha_eliminacion IS NULL
+"#;
+
+const UBICACION_TEMA: &str = r#"
+SELECT c.id AS curso_id, c.nombre AS curso, t.nombre AS nombre,
+       t.visible AND m.visible AS visible
+FROM temas t
+JOIN modulos m ON m.id = t.modulo_id
+JOIN cursos c ON c.id = m.curso_id
+WHERE t.id = $1 AND t.fecha_eliminacion IS NULL AND m.fecha_eliminacion IS NULL
+"#;
+
+const UBICACION_UNIDAD: &str = r#"
+SELECT c.id AS curso_id, c.nombre AS curso, u.nombre AS nombre,
+       u.visible AND t.visible AND m.visible AS visible
+FROM unidades u
+JOIN temas t ON t.id = u.tema_id
+JOIN modulos m ON m.id = t.modulo_id
+JOIN cursos c ON c.id = m.curso_id
+WHERE u.id = $1 AND u.fecha_eliminacion IS NULL
+  AND t.fecha_eliminacion IS NULL AND m.fecha_eliminacion IS NULL
+"#;
+
+const UBICACION_CONTENIDO: &str = r#"
+SELECT c.id AS curso_id, c.nombre AS curso, cu.titulo AS nombre,
+       cu.visible AND u.visible AND t.visible AND m.visible AS visible
+FROM contenidos_unidad cu
+JOIN unidades u ON u.id = cu.unidad_id
+JOIN temas t ON t.id = u.tema_id
+JOIN modulos m ON m.id = t.modulo_id
+JOIN cursos c ON c.id = m.curso_id
+WHERE cu.id = $1 AND cu.fecha_eliminacion IS NULL AND u.fecha_eliminacion IS NULL
+  AND t.fecha_eliminacion IS NULL AND m.fecha_eliminacion IS NULL
+"#;
+
+/// Estudiantes con matrícula activa en el curso. $1 = curso
+const ESTUDIANTES_ACTIVOS: &str =
+    "SELECT estudiante_id AS id FROM historial_cursos_estudiantes WHERE curso_id = $1 AND estado = 'activo'";
+
+#[derive(Debug, FromQueryResult)]
+struct FilaPublicacion {
+    id: i32,
+    nombre: String,
+    estado_publicacion: EstadoPublicacion,
+    visible: bool,
+    publicar_en: Option<DateTime<Utc>>,
+    despublicar_en: Option<DateTime<Utc>>,
+}
+
+impl FilaPublicacion {
+    fn en(self, tipo: TipoPublicable) -> ElementoPublicable {
+        ElementoPublicable {
+            tipo,
+            id: self.id,
+            nombre: self.nombre,
+            estado: self.estado_publicacion,
+            visible: self.visible,
+            publicar_en: self.publicar_en,
+            despublicar_en: self.despublicar_en,
+        }
+    }
+}
+
+/// Curso al que pertenece un elemento del aula, su nombre y si los estudiantes lo ven
+#[derive(Debug, FromQueryResult)]
+pub(crate) struct Ubicacion {
+    pub curso_id: i32,
+    pub curso: String,
+    pub nombre: String,
+    pub visible: bool,
+}
+
+/// Ubicación de un elemento no eliminado (None si no existe o está en la papelera)
+pub(crate) async fn ubicar<C>(
+    db: &C,
+    tipo: TipoPublicable,
+    id: i32,
+) -> Result<Option<Ubicacion>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let consulta = match tipo {
+        TipoPublicable::Modulo => UBICACION_MODULO,
+        TipoPublicable::Tema => UBICACION_TEMA,
+        TipoPublicable::Unidad => UBICACION_UNIDAD,
+        TipoPublicable::Contenido => UBICACION_CONTENIDO,
+    };
+    Ubicacion::find_by_statement(sentencia(consulta, [id.into()]))
+        .one(db)
+        .await
+}
+
+/// Si los estudiantes ven el elemento (él y sus contenedores publicados y vigentes)
+pub(crate) async fn visible_para_estudiantes<C>(
+    db: &C,
+    tipo: TipoPublicable,
+    id: i32,
+) -> Result<bool, DbErr>
+where
+    C: ConnectionTrait,
+{
+    Ok(ubicar(db, tipo, id).await?.is_some_and(|u| u.visible))
+}
+
+/// Elementos recién visibles de un curso, para un solo aviso por estudiante
+struct ContenidoNuevo {
+    curso: String,
+    nombres: Vec<String>,
+    elementos: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct Id {
+    id: i32,
+}
+
+/// Columnas devueltas por las consultas de estado de publicación
+fn columnas(tipo: TipoPublicable) -> String {
+    format!(
+        "id, {} AS nombre, estado_publicacion, visible, publicar_en, despublicar_en",
+        tipo.columna_nombre()
+    )
+}
+
+fn sentencia<I>(sql: &str, valores: I) -> Statement
+where
+    I: IntoIterator<Item = Value>,
+{
+    Statement::from_sql_and_values(DbBackend::Postgres, sql, valores)
+}
+
+#[derive(Debug, Clone)]
+pub struct PublicacionService {
+    db: DbExecutor,
+}
+
+impl FromRef<AppState> for PublicacionService {
+    fn from_ref(state: &AppState) -> Self {
+        let executor = state
+            .db
+            .clone()
+            .expect("Database connection is not available");
+        PublicacionService::new(executor)
+    }
+}
+
+impl PublicacionService {
+    pub fn new(db: DbExecutor) -> Self {
+        Self { db }
+    }
+
+    fn connection(&self) -> DatabaseConnection {
+        self.db.connection()
+    }
+
+    pub async fn obtener(
+        &self,
+        tipo: TipoPublicable,
+        id: i32,
+    ) -> Result<ElementoPublicable, AppError> {
+        let sql = format!(
+            "SELECT {} FROM {} WHERE id = $1 AND fecha_eliminacion IS NULL",
+            columnas(tipo),
+            tipo.tabla()
+        );
+        FilaPublicacion::find_by_statement(sentencia(&sql, [id.into()]))
+            .one(&self.connection())
+            .await?
+            .map(|fila| fila.en(tipo))
+            .ok_or_else(|| AppError::NotFound("Elemento no encontrado".into()))
+    }
+
+    /// Fija el estado y la programación del elemento. Si pasa a ser visible para
+    /// los estudiantes, se les notifica
+    pub async fn cambiar(
+        &self,
+        tipo: TipoPublicable,
+        id: i32,
+        cambio: CambioPublicacion,
+        ctx: &ContextoAuditoria,
+    ) -> Result<ElementoPublicable, AppError> {
+        validar(&cambio, Utc::now())?;
+        let antes = self.obtener(tipo, id).await?;
+
+        let txn = self.connection().begin().await?;
+        let sql = format!(
+            "UPDATE {} SET estado_publicacion = $2, visible = $3, publicar_en = $4, \
+             despublicar_en = $5 WHERE id = $1 AND fecha_eliminacion IS NULL RETURNING {}",
+            tipo.tabla(),
+            columnas(tipo)
+        );
+        let despues = FilaPublicacion::find_by_statement(sentencia(
+            &sql,
+            [
+                id.into(),
+                cambio.estado.to_value().into(),
+                cambio.estado.es_visible().into(),
+                cambio.publicar_en.into(),
+                cambio.despublicar_en.into(),
+            ],
+        ))
+        .one(&txn)
+        .await?
+        .map(|fila| fila.en(tipo))
+        .ok_or_else(|| AppError::NotFound("Elemento no encontrado".into()))?;
+
+        AuditoriaService::registrar(
+            &txn,
+            ctx,
+            "cambiar_publicacion",
+            tipo.nombre(),
+            Some(id),
+            Some(&antes),
+            Some(&despues),
+        )
+        .await?;
+        txn.commit().await?;
+
+        if !antes.visible && despues.visible {
+            self.notificar_publicados(&[(tipo, id)]).await;
+        }
+        Ok(despues)
+    }
+
+    /// Job de publicación programada: publica los borradores con `publicar_en`
+    /// vencido y devuelve a borrador lo que tenga `despublicar_en` vencido.
+    /// Devuelve cuántos elementos cambiaron
+    pub async fn aplicar_programadas(&self) -> Result<u64, AppError> {
+        let db = self.connection();
+        let ahora = Utc::now();
+        let mut publicados = Vec::new();
+        let mut total = 0;
+
+        for tipo in TipoPublicable::TODOS {
+            let sql = format!(
+                "UPDATE {} SET estado_publicacion = 'publicado', visible = true, publicar_en = NULL \
+                 WHERE estado_publicacion = 'borrador' AND publicar_en <= $1 \
+                 AND fecha_eliminacion IS NULL RETURNING id",
+                tipo.tabla()
+            );
+            let ids = Id::find_by_statement(sentencia(&sql, [ahora.into()]))
+                .all(&db)
+                .await?;
+            total += ids.len() as u64;
+            publicados.extend(ids.into_iter().map(|fila| (tipo, fila.id)));
+
+            let sql = format!(
+                "UPDATE {} SET estado_publicacion = 'borrador', visible = false, despublicar_en = NULL \
+                 WHERE estado_publicacion = 'publicado' AND despublicar_en <= $1 \
+                 AND fecha_eliminacion IS NULL RETURNING id",
+                tipo.tabla()
+            );
+            total += Id::find_by_statement(sentencia(&sql, [ahora.into()]))
+                .all(&db)
+                .await?
+                .len() as u64;
+        }
+
+        self.notificar_publicados(&publicados).await;
+        Ok(total)
+    }
+
+    /// Avisa a los estudiantes activos de cada curso de los elementos que acaban de
+    /// quedar visibles (uno por curso con todos sus elementos). Los que siguen ocultos
+    /// porque un contenedor es borrador no se anuncian
+    pub async fn notificar_publicados(&self, elementos: &[(TipoPublicable, i32)]) {
+        if elementos.is_empty() {
+            return;
+        }
+        if let Err(e) = self.notificar(elementos).await {
+            tracing::warn!("⚠️  No se pudo notificar el contenido publicado: {}", e);
+        }
+    }
+
+    async fn notificar(&self, elementos: &[(TipoPublicable, i32)]) -> Result<(), AppError> {
+        let db = self.connection();
+        let mut por_curso: BTreeMap<i32, ContenidoNuevo> = BTreeMap::new();
+
+        for &(tipo, id) in elementos {
+            let Some(ubicacion) = ubicar(&db, tipo, id).await? else {
+                continue;
+            };
+            if ubicacion.visible {
+                let nuevo = por_curso
+                    .entry(ubicacion.curso_id)
+                    .or_insert_with(|| ContenidoNuevo {
+                        curso: ubicacion.curso,
+                        nombres: Vec::new(),
+                        elementos: Vec::new(),
+                    });
+                nuevo.nombres.push(format!("«{}»", ubicacion.nombre));
+                nuevo.elementos.push(json!({ "tipo": tipo, "id": id }));
+            }
+        }
+
+        let service = NotificacionService::new(self.db.clone());
+        for (curso_id, nuevo) in por_curso {
+            let estudiantes =
+                Id::find_by_statement(sentencia(ESTUDIANTES_ACTIVOS, [curso_id.into()]))
+                    .all(&db)
+                    .await?;
+            let nombres = nuevo.nombres.join(", ");
+            let datos = json!({ "curso_id": curso_id, "elementos": nuevo.elementos });
+
+            for estudiante in estudiantes {
+                let notificacion = NuevaNotificacion {
+                    usuario_id: estudiante.id,
+                    titulo: format!("Nuevo contenido en {}", nuevo.curso),
+                    mensaje: format!("Ya está disponible: {}.", nombres),
+                    tipo: "curso".to_string(),
+                    leida: None,
+                    enlace: None,
+                    datos_adicionales: Some(datos.clone()),
+                };
+                if let Err(e) = service.crear_notificacion(notificacion).await {
+                    tracing::warn!(
+                        "⚠️  No se pudo notificar el contenido nuevo del curso {} al estudiante {}: {}",
+                        curso_id,
+                        estudiante.id,
+                        e
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Coherencia entre el estado y las fechas programadas (siempre futuras)
+fn validar(cambio: &CambioPublicacion, ahora: DateTime<Utc>) -> Result<(), AppError> {
+    if let Some(publicar_en) = cambio.publicar_en {
+        if cambio.estado == EstadoPublicacion::Publicado {
+            return Err(AppError::BadRequest(
+                "`publicar_en` solo aplica a borradores".into(),
+            ));
+        }
+        if publicar_en <= ahora {
+            return Err(AppError::BadRequest(
+                "`publicar_en` debe ser una fecha futura".into(),
+            ));
+        }
+    }
+
+    if let Some(despublicar_en) = cambio.despublicar_en {
+        if despublicar_en <= ahora {
+            return Err(AppError::BadRequest(
+                "`despublicar_en` debe ser una fecha futura".into(),
+            ));
+        }
+        match cambio.publicar_en {
+            None if cambio.estado == EstadoPublicacion::Borrador => {
+                return Err(AppError::BadRequest(
+                    "`despublicar_en` requiere un elemento publicado o con publicación programada"
+                        .into(),
+                ));
+            }
+            Some(publicar_en) if despublicar_en <= publicar_en => {
+                return Err(AppError::BadRequest(
+                    "`despublicar_en` debe ser posterior a `publicar_en`".into(),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,275 @@
+use axum::extract::FromRef;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait,
+    FromQueryResult, QueryFilter, QueryOrder, Statement, TransactionTrait, Value,
+};
+
+use crate::{
+    database::DbExecutor,
+    models::{
+        revision::{self, Entity as Revision, Model as RevisionModel},
+        AppState,
+    },
+    services::{
+        auditoria_service::{diff_json, AuditoriaService, ContextoAuditoria},
+        contenido_unidad_service,
+    },
+    utils::{errors::AppError, markdown},
+};
+
+pub use crate::models::revision::{DiffRevisiones, FiltroDiff, ResumenRevision, TipoRevisionable};
+
+/// Historial de la entidad, de la más reciente a la más antigua. $1 = tipo, $2 = id
+const HISTORIAL: &str = r#"
+SELECT r.numero, r.autor_id, u.nombre AS autor, r.fecha, r.restaurada_de
+FROM revisiones r
+LEFT JOIN usuarios u ON u.id = r.autor_id
+WHERE r.tipo = $1 AND r.entidad_id = $2
+ORDER BY r.numero DESC
+"#;
+
+#[derive(Debug, FromQueryResult)]
+struct Numero {
+    numero: i32,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct Fuente {
+    fuente: Option<String>,
+}
+
+fn sentencia<I>(sql: &str, valores: I) -> Statement
+where
+    I: IntoIterator<Item = Value>,
+{
+    Statement::from_sql_and_values(DbBackend::Postgres, sql, valores)
+}
+
+#[derive(Debug, Clone)]
+pub struct RevisionService {
+    db: DbExecutor,
+}
+
+impl FromRef<AppState> for RevisionService {
+    fn from_ref(state: &AppState) -> Self {
+        let executor = state
+            .db
+            .clone()
+            .expect("Database connection is not available");
+        RevisionService::new(executor)
+    }
+}
+
+impl RevisionService {
+    pub fn new(db: DbExecutor) -> Self {
+        Self { db }
+    }
+
+    fn connection(&self) -> DatabaseConnection {
+        self.db.connection()
+    }
+
+    /// Guarda el estado actual de la entidad como una nueva revisión, salvo que sea
+    /// igual a la última. Recibe la conexión (o transacción) del servicio que hizo el
+    /// cambio. Devuelve el número de la revisión creada
+    pub async fn registrar<C>(
+        db: &C,
+        tipo: TipoRevisionable,
+        entidad_id: i32,
+        autor_id: Option<i32>,
+        restaurada_de: Option<i32>,
+    ) -> Result<Option<i32>, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let datos = tipo
+            .campos()
+            .iter()
+            .map(|campo| format!("'{campo}', {campo}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            r#"
+            WITH actual AS (
+                SELECT jsonb_build_object({datos}) AS datos FROM {tabla} WHERE id = $2
+            ), ultima AS (
+                SELECT numero, datos FROM revisiones
+                WHERE tipo = $1 AND entidad_id = $2
+                ORDER BY numero DESC LIMIT 1
+            )
+            INSERT INTO revisiones (tipo, entidad_id, numero, autor_id, fecha, datos, restaurada_de)
+            SELECT $1, $2, COALESCE((SELECT numero FROM ultima), 0) + 1, $3, NOW(), actual.datos, $4
+            FROM actual
+            WHERE NOT EXISTS (SELECT 1 FROM ultima WHERE ultima.datos = actual.datos)
+            RETURNING numero
+            "#,
+            tabla = tipo.tabla()
+        );
+
+        let creada = Numero::find_by_statement(sentencia(
+            &sql,
+            [
+                tipo.nombre().into(),
+                entidad_id.into(),
+                autor_id.into(),
+                restaurada_de.into(),
+            ],
+        ))
+        .one(db)
+        .await?;
+        Ok(creada.map(|r| r.numero))
+    }
+
+    async fn exigir_entidad(&self, tipo: TipoRevisionable, id: i32) -> Result<(), AppError> {
+        let sql = format!(
+            "SELECT id AS numero FROM {} WHERE id = $1 AND {}",
+            tipo.tabla(),
+            tipo.filtro_vigente()
+        );
+        Numero::find_by_statement(sentencia(&sql, [id.into()]))
+            .one(&self.connection())
+            .await?
+            .ok_or_else(|| AppError::NotFound("Elemento no encontrado".into()))?;
+        Ok(())
+    }
+
+    pub async fn listar(
+        &self,
+        tipo: TipoRevisionable,
+        id: i32,
+    ) -> Result<Vec<ResumenRevision>, AppError> {
+        self.exigir_entidad(tipo, id).await?;
+        let historial = ResumenRevision::find_by_statement(sentencia(
+            HISTORIAL,
+            [tipo.nombre().into(), id.into()],
+        ))
+        .all(&self.connection())
+        .await?;
+        Ok(historial)
+    }
+
+    pub async fn obtener(
+        &self,
+        tipo: TipoRevisionable,
+        id: i32,
+        numero: i32,
+    ) -> Result<RevisionModel, AppError> {
+        Revision::find()
+            .filter(revision::Column::Tipo.eq(tipo))
+            .filter(revision::Column::EntidadId.eq(id))
+            .filter(revision::Column::Numero.eq(numero))
+            .one(&self.connection())
+            .await?
+            .ok_or_else(|| AppError::NotFound("Revisión no encontrada".into()))
+    }
+
+    pub async fn diff(
+        &self,
+        tipo: TipoRevisionable,
+        id: i32,
+        filtro: FiltroDiff,
+    ) -> Result<DiffRevisiones, AppError> {
+        let desde = self.obtener(tipo, id, filtro.desde).await?;
+        let hasta = self.obtener(tipo, id, filtro.hasta).await?;
+        Ok(DiffRevisiones {
+            desde: desde.numero,
+            hasta: hasta.numero,
+            cambios: diff_json(Some(&desde.datos), Some(&hasta.datos)),
+        })
+    }
+
+    /// Repone los campos de una revisión anterior. La restauración queda como una
+    /// revisión nueva (con `restaurada_de`) y en la auditoría
+    pub async fn restaurar(
+        &self,
+        tipo: TipoRevisionable,
+        id: i32,
+        numero: i32,
+        ctx: &ContextoAuditoria,
+    ) -> Result<RevisionModel, AppError> {
+        self.exigir_entidad(tipo, id).await?;
+        let restaurada = self.obtener(tipo, id, numero).await?;
+
+        let db = self.connection();
+        let txn = db.begin().await?;
+
+        // Cambios hechos por fuera del historial quedan guardados antes de pisarlos
+        Self::registrar(&txn, tipo, id, None, None).await?;
+        let antes = Revision::find()
+            .filter(revision::Column::Tipo.eq(tipo))
+            .filter(revision::Column::EntidadId.eq(id))
+            .order_by_desc(revision::Column::Numero)
+            .one(&txn)
+            .await?;
+
+        // Lo restaurado cumple las mismas reglas por tipo que una edición
+        let datos = match tipo {
+            TipoRevisionable::ContenidoUnidad => {
+                contenido_unidad_service::preparar_restauracion(&txn, id, &restaurada.datos).await?
+            }
+            _ => restaurada.datos.clone(),
+        };
+
+        let campos = tipo.campos();
+        let sql = format!(
+            r#"
+            UPDATE {tabla} AS t
+            SET ({columnas}) = (SELECT {valores} FROM jsonb_populate_record(t, $2) r),
+                {actualizacion} = NOW()
+            WHERE t.id = $1
+            "#,
+            tabla = tipo.tabla(),
+            columnas = campos.join(", "),
+            valores = campos
+                .iter()
+                .map(|campo| format!("r.{campo}"))
+                .collect::<Vec<_>>()
+                .join(", "),
+            actualizacion = tipo.columna_actualizacion(),
+        );
+        txn.execute(sentencia(&sql, [id.into(), datos.into()]))
+            .await?;
+
+        // La versión renderizada sale de la fuente restaurada
+        if let Some((fuente, html)) = tipo.columnas_html() {
+            let sql = format!(
+                "SELECT {fuente} AS fuente FROM {} WHERE id = $1",
+                tipo.tabla()
+            );
+            let restaurada = Fuente::find_by_statement(sentencia(&sql, [id.into()]))
+                .one(&txn)
+                .await?
+                .and_then(|f| f.fuente);
+            let restaurada_html = match restaurada {
+                Some(fuente) => Some(markdown::a_html_en_hilo(&fuente).await?),
+                None => None,
+            };
+            let sql = format!("UPDATE {} SET {html} = $2 WHERE id = $1", tipo.tabla());
+            txn.execute(sentencia(&sql, [id.into(), restaurada_html.into()]))
+                .await?;
+        }
+
+        Self::registrar(&txn, tipo, id, ctx.actor_id, Some(numero)).await?;
+        let despues = Revision::find()
+            .filter(revision::Column::Tipo.eq(tipo))
+            .filter(revision::Column::EntidadId.eq(id))
+            .order_by_desc(revision::Column::Numero)
+            .one(&txn)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Revisión no encontrada".into()))?;
+
+        AuditoriaService::registrar(
+            &txn,
+            ctx,
+            "restaurar_revision",
+            tipo.nombre(),
+            Some(id),
+            antes.as_ref().map(|r| &r.datos),
+            Some(&despues.datos),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(despues)
+    }
+}
@@ -1,9 +1,10 @@
 use once_cell::sync::OnceCell;
 use sea_orm::ModelTrait;
-use sea_orm::{ActiveModelTrait, DatabaseConnection, DbErr, EntityTrait, Set};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, DbErr, EntityTrait, Set, TransactionTrait};
 use std::sync::Arc;
 
 use crate::models::rol::{ActiveModel as RolActiveModel, Entity as RolEntity, Model as RolModel};
+use crate::services::auditoria_service::{AuditoriaService, ContextoAuditoria};
 
 static ROL_SERVICE: OnceCell<Arc<RolService>> = OnceCell::new();
 
@@ -26,32 +27,60 @@ impl RolService {
         RolEntity::find().all(&self.conn).await
     }
 
-    pub async fn create(&self, nombre: String) -> Result<RolModel, DbErr> {
+    pub async fn create(&self, nombre: String, ctx: &ContextoAuditoria) -> Result<RolModel, DbErr> {
         let new_rol = RolActiveModel {
             nombre: Set(nombre),
             ..Default::default()
         };
-        new_rol.insert(&self.conn).await
+        let txn = self.conn.begin().await?;
+        let rol = new_rol.insert(&txn).await?;
+        AuditoriaService::registrar(&txn, ctx, "crear", "rol", Some(rol.id), None, Some(&rol))
+            .await?;
+        txn.commit().await?;
+        Ok(rol)
     }
 
-    pub async fn update(&self, id: i32, nombre: String) -> Result<RolModel, DbErr> {
+    pub async fn update(
+        &self,
+        id: i32,
+        nombre: String,
+        ctx: &ContextoAuditoria,
+    ) -> Result<RolModel, DbErr> {
         let rol = RolEntity::find_by_id(id)
             .one(&self.conn)
             .await?
             .ok_or(DbErr::RecordNotFound("Rol no encontrado".into()))?;
+        let rol_original = rol.clone();
 
         let mut rol: RolActiveModel = rol.into();
         rol.nombre = Set(nombre);
-        rol.update(&self.conn).await
+        let txn = self.conn.begin().await?;
+        let rol = rol.update(&txn).await?;
+        AuditoriaService::registrar(
+            &txn,
+            ctx,
+            "actualizar",
+            "rol",
+            Some(id),
+            Some(&rol_original),
+            Some(&rol),
+        )
+        .await?;
+        txn.commit().await?;
+        Ok(rol)
     }
 
-    pub async fn delete(&self, id: i32) -> Result<u64, DbErr> {
+    pub async fn delete(&self, id: i32, ctx: &ContextoAuditoria) -> Result<u64, DbErr> {
         let rol = RolEntity::find_by_id(id)
             .one(&self.conn)
             .await?
             .ok_or(DbErr::RecordNotFound("Rol no encontrado".into()))?;
 
-        rol.delete(&self.conn).await?;
+        let txn = self.conn.begin().await?;
+        AuditoriaService::registrar(&txn, ctx, "eliminar", "rol", Some(id), Some(&rol), None)
+            .await?;
+        rol.delete(&txn).await?;
+        txn.commit().await?;
         Ok(1) // Asumiendo que siempre se elimina 1 registro
     }
 }
@@ -5,14 +5,16 @@ use axum::body::Bytes;
 use std::time::Duration;
 use thiserror::Error;
 
-const MAX_FILE_SIZE: u64 = 20 * 1024 * 1024; // 20MB
+use crate::config::StorageConfig;
 
 #[derive(Debug, Error)]
 pub enum StorageError {
-    #[error("File too large. Max size is 20MB")]
-    FileTooLarge,
+    #[error("File too large. Max size is {0}MB")]
+    FileTooLarge(u64),
     #[error("Invalid file type")]
     InvalidFileType,
+    #[error("Invalid image: {0}")]
+    InvalidImage(String),
     #[error("Storage error: {0}")]
     StorageError(String),
 }
@@ -57,22 +59,34 @@ impl From<aws_sdk_s3::presigning::PresigningConfigError> for StorageError {
 pub struct StorageService {
     client: Client,
     bucket: String,
+    max_file_size_mb: u64,
+    url_expiracion: Duration,
 }
 
 impl StorageService {
-    /// Crea un nuevo servicio de almacenamiento con configuración de R2
-    pub async fn new() -> Result<Self, Box<dyn std::error::Error>> {
+    /// Crea un nuevo servicio de almacenamiento con la configuración de R2 (`R2_*`)
+    pub async fn new(storage: &StorageConfig) -> Result<Self, Box<dyn std::error::Error>> {
         tracing::info!("🔧 [STORAGE] Inicializando StorageService...");
 
-        // Configuración hardcoded para coincidir con el frontend React
+        let (Some(endpoint), Some(access_key_id), Some(secret_access_key)) = (
+            storage.endpoint.as_deref(),
+            storage.access_key_id.as_deref(),
+            storage.secret_access_key.as_deref(),
+        ) else {
+            return Err(
+                "R2 no configurado: faltan R2_ENDPOINT, R2_ACCESS_KEY_ID o R2_SECRET_ACCESS_KEY"
+                    .into(),
+            );
+        };
+
         let config = aws_config::from_env()
-            .region("auto") // Cloudflare R2 uses 'auto' region
+            .region(aws_config::Region::new(storage.region.clone()))
             .credentials_provider(Credentials::new(
-                "94693ac7f6b38fa08f74fc9833f14fe2", // NEW Access Key ID
-                "ec37cebc510f3de5d59992e497d9870c1d6c59295d2a1c7e891d630a79ec17dc", // NEW Secret Access Key
+                access_key_id,
+                secret_access_key,
                 None,
                 None,
-                "aulatrix-r2-token-new",
+                "r2-config",
             ))
             .load()
             .await;
@@ -80,19 +94,28 @@ impl StorageService {
         tracing::info!("✅ [STORAGE] Configuración AWS cargada");
 
         let s3_config = aws_sdk_s3::config::Builder::from(&config)
-            .endpoint_url("https://fdf4a26651358f5e1f66f4af2b7cb4cb.r2.cloudflarestorage.com")
+            .endpoint_url(endpoint)
             .force_path_style(true) // Required for Cloudflare R2
             .build();
 
-        tracing::info!("✅ [STORAGE] Configuración S3 creada con endpoint: https://fdf4a26651358f5e1f66f4af2b7cb4cb.r2.cloudflarestorage.com/aulatrix-bucket");
+        tracing::info!(
+            "✅ [STORAGE] Configuración S3 creada con endpoint: {}/{}",
+            endpoint,
+            storage.bucket
+        );
 
         let client = aws_sdk_s3::Client::from_conf(s3_config);
-        let bucket = "aulatrix-bucket".to_string(); // Bucket name from React frontend
+        let bucket = storage.bucket.clone();
 
         tracing::info!("✅ [STORAGE] Cliente S3 creado, bucket: {}", bucket);
         tracing::info!("🚀 [STORAGE] StorageService inicializado correctamente");
 
-        Ok(Self { client, bucket })
+        Ok(Self {
+            client,
+            bucket,
+            max_file_size_mb: storage.max_file_size_mb,
+            url_expiracion: Duration::from_secs(storage.url_expiracion_secs),
+        })
     }
 
     /// Genera una URL pre-firmada para subir archivos
@@ -101,7 +124,7 @@ impl StorageService {
         file_name: &str,
         content_type: &str,
     ) -> Result<String, StorageError> {
-        let expires_in = Duration::from_secs(3600); // 1 hora de validez
+        let expires_in = self.url_expiracion; // R2_URL_EXPIRATION_SECS
 
         let presigned_request = self
             .client
@@ -131,13 +154,14 @@ impl StorageService {
         tracing::info!("📦 [STORAGE] Bucket configurado: {}", self.bucket);
 
         // Validar tamaño del archivo
-        if bytes.len() as u64 > MAX_FILE_SIZE {
+        let max_file_size = self.max_file_size_mb * 1024 * 1024;
+        if bytes.len() as u64 > max_file_size {
             tracing::error!(
                 "❌ [STORAGE] Archivo demasiado grande: {} bytes (máximo: {} bytes)",
                 bytes.len(),
-                MAX_FILE_SIZE
+                max_file_size
             );
-            return Err(StorageError::FileTooLarge);
+            return Err(StorageError::FileTooLarge(self.max_file_size_mb));
         }
         tracing::info!("✅ [STORAGE] Validación de tamaño: OK");
 
@@ -183,7 +207,7 @@ impl StorageService {
 
     /// Obtiene una URL pre-firmada para descargar archivos
     pub async fn generate_download_url(&self, file_name: &str) -> Result<String, StorageError> {
-        let expires_in = Duration::from_secs(3600); // 1 hora de validez
+        let expires_in = self.url_expiracion; // R2_URL_EXPIRATION_SECS
 
         let presigned_request = self
             .client
@@ -218,6 +242,7 @@ fn is_allowed_content_type(content_type: &str) -> bool {
         "image/jpg",
         "image/png",
         "image/gif",
+        "image/webp",
         "application/pdf",
         "text/plain",
         "application/msword",
@@ -1,8 +1,8 @@
 use axum::extract::FromRef;
 use chrono::Utc;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, ModelTrait, Order,
-    QueryFilter, QueryOrder, Set,
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, Order, QueryFilter,
+    QueryOrder, Set, TransactionTrait,
 };
 
 use crate::{
@@ -11,6 +11,12 @@ use crate::{
         tema::{self, Entity as Tema, Model as TemaModel},
         AppState,
     },
+    services::{
+        papelera_service::{PapeleraService, TipoPapelera},
+        publicacion_service::{
+            visible_para_estudiantes, EstadoPublicacion, PublicacionService, TipoPublicable,
+        },
+    },
     utils::errors::AppError,
 };
 
@@ -45,26 +51,61 @@ impl TemaService {
             descripcion: Set(nuevo_tema.descripcion),
             orden: Set(nuevo_tema.orden),
             visible: Set(nuevo_tema.visible),
+            estado_publicacion: Set(EstadoPublicacion::desde_visible(nuevo_tema.visible)),
+            publicar_en: Set(None),
+            despublicar_en: Set(None),
             created_at: Set(Some(ahora)),
             updated_at: Set(Some(ahora)),
+            fecha_eliminacion: Set(None),
         };
 
         let tema_creado = tema.insert(&db).await?;
+        if tema_creado.visible {
+            PublicacionService::new(self.db.clone())
+                .notificar_publicados(&[(TipoPublicable::Tema, tema_creado.id)])
+                .await;
+        }
         Ok(tema_creado)
     }
 
-    pub async fn obtener_temas_por_modulo(&self, modulo_id: i32) -> Result<Vec<TemaModel>, DbErr> {
+    /// Con `solo_visibles` (estudiantes) solo lo publicado y nada si el contenedor no lo está
+    pub async fn obtener_temas_por_modulo(
+        &self,
+        modulo_id: i32,
+        solo_visibles: bool,
+    ) -> Result<Vec<TemaModel>, DbErr> {
         let db = self.connection();
-        Tema::find()
-            .filter(tema::Column::ModuloId.eq(modulo_id))
+        if solo_visibles
+            && !visible_para_estudiantes(&db, TipoPublicable::Modulo, modulo_id).await?
+        {
+            return Ok(Vec::new());
+        }
+        let mut consulta = Tema::find()
+            .filter(tema::Column::FechaEliminacion.is_null())
+            .filter(tema::Column::ModuloId.eq(modulo_id));
+        if solo_visibles {
+            consulta = consulta.filter(tema::Column::Visible.eq(true));
+        }
+        consulta
             .order_by(tema::Column::Orden, Order::Asc)
             .all(&db)
             .await
     }
 
-    pub async fn obtener_tema_por_id(&self, id: i32) -> Result<Option<TemaModel>, DbErr> {
+    /// Con `solo_visibles` (estudiantes) los borradores y lo que cuelga de ellos no existe
+    pub async fn obtener_tema_por_id(
+        &self,
+        id: i32,
+        solo_visibles: bool,
+    ) -> Result<Option<TemaModel>, DbErr> {
         let db = self.connection();
-        Tema::find_by_id(id).one(&db).await
+        if solo_visibles && !visible_para_estudiantes(&db, TipoPublicable::Tema, id).await? {
+            return Ok(None);
+        }
+        Tema::find_by_id(id)
+            .filter(tema::Column::FechaEliminacion.is_null())
+            .one(&db)
+            .await
     }
 
     pub async fn actualizar_tema(
@@ -74,10 +115,12 @@ impl TemaService {
     ) -> Result<TemaModel, AppError> {
         let db = self.connection();
         let tema = Tema::find_by_id(id)
+            .filter(tema::Column::FechaEliminacion.is_null())
             .one(&db)
             .await?
             .ok_or_else(|| AppError::NotFound("Tema no encontrado".into()))?;
 
+        let era_visible = tema.visible;
         let mut tema: tema::ActiveModel = tema.into();
         let ahora = Utc::now();
 
@@ -98,23 +141,36 @@ impl TemaService {
             tema.orden = Set(orden);
         }
 
-        if let Some(visible) = datos.visible {
+        // Un cambio manual de visibilidad reemplaza la publicación programada
+        if let Some(visible) = datos.visible.filter(|v| *v != era_visible) {
             tema.visible = Set(visible);
+            tema.estado_publicacion = Set(EstadoPublicacion::desde_visible(visible));
+            tema.publicar_en = Set(None);
+            tema.despublicar_en = Set(None);
         }
 
         tema.updated_at = Set(Some(ahora));
         let tema_actualizado = tema.update(&db).await?;
+        if !era_visible && tema_actualizado.visible {
+            PublicacionService::new(self.db.clone())
+                .notificar_publicados(&[(TipoPublicable::Tema, id)])
+                .await;
+        }
         Ok(tema_actualizado)
     }
 
     pub async fn eliminar_tema(&self, id: i32) -> Result<(), AppError> {
         let db = self.connection();
         let tema = Tema::find_by_id(id)
+            .filter(tema::Column::FechaEliminacion.is_null())
             .one(&db)
             .await?
             .ok_or_else(|| AppError::NotFound("Tema no encontrado".into()))?;
 
-        tema.delete(&db).await?;
+        // Soft delete en cascada (ver PapeleraService)
+        let txn = db.begin().await?;
+        PapeleraService::mover_a_papelera(&txn, TipoPapelera::Tema, tema.id, Utc::now()).await?;
+        txn.commit().await?;
         Ok(())
     }
 }
@@ -1,7 +1,8 @@
 use axum::extract::FromRef;
+use chrono::Utc;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, ModelTrait, Order,
-    QueryFilter, QueryOrder, Set,
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, Order, QueryFilter,
+    QueryOrder, Set, TransactionTrait,
 };
 
 use crate::{
@@ -10,6 +11,12 @@ use crate::{
         unidad::{self, Entity as Unidad, Model as UnidadModel},
         AppState,
     },
+    services::{
+        papelera_service::{PapeleraService, TipoPapelera},
+        publicacion_service::{
+            visible_para_estudiantes, EstadoPublicacion, PublicacionService, TipoPublicable,
+        },
+    },
     utils::errors::AppError,
 };
 
@@ -43,24 +50,57 @@ impl UnidadService {
             descripcion: Set(nueva_unidad.descripcion),
             orden: Set(nueva_unidad.orden),
             visible: Set(nueva_unidad.visible),
+            estado_publicacion: Set(EstadoPublicacion::desde_visible(nueva_unidad.visible)),
+            publicar_en: Set(None),
+            despublicar_en: Set(None),
+            fecha_eliminacion: Set(None),
         };
 
         let unidad_creada = unidad.insert(&db).await?;
+        if unidad_creada.visible {
+            PublicacionService::new(self.db.clone())
+                .notificar_publicados(&[(TipoPublicable::Unidad, unidad_creada.id)])
+                .await;
+        }
         Ok(unidad_creada)
     }
 
-    pub async fn obtener_unidades_por_tema(&self, tema_id: i32) -> Result<Vec<UnidadModel>, DbErr> {
+    /// Con `solo_visibles` (estudiantes) solo lo publicado y nada si el contenedor no lo está
+    pub async fn obtener_unidades_por_tema(
+        &self,
+        tema_id: i32,
+        solo_visibles: bool,
+    ) -> Result<Vec<UnidadModel>, DbErr> {
         let db = self.connection();
-        Unidad::find()
-            .filter(unidad::Column::TemaId.eq(tema_id))
+        if solo_visibles && !visible_para_estudiantes(&db, TipoPublicable::Tema, tema_id).await? {
+            return Ok(Vec::new());
+        }
+        let mut consulta = Unidad::find()
+            .filter(unidad::Column::FechaEliminacion.is_null())
+            .filter(unidad::Column::TemaId.eq(tema_id));
+        if solo_visibles {
+            consulta = consulta.filter(unidad::Column::Visible.eq(true));
+        }
+        consulta
             .order_by(unidad::Column::Orden, Order::Asc)
             .all(&db)
             .await
     }
 
-    pub async fn obtener_unidad_por_id(&self, id: i32) -> Result<Option<UnidadModel>, DbErr> {
+    /// Con `solo_visibles` (estudiantes) los borradores y lo que cuelga de ellos no existe
+    pub async fn obtener_unidad_por_id(
+        &self,
+        id: i32,
+        solo_visibles: bool,
+    ) -> Result<Option<UnidadModel>, DbErr> {
         let db = self.connection();
-        Unidad::find_by_id(id).one(&db).await
+        if solo_visibles && !visible_para_estudiantes(&db, TipoPublicable::Unidad, id).await? {
+            return Ok(None);
+        }
+        Unidad::find_by_id(id)
+            .filter(unidad::Column::FechaEliminacion.is_null())
+            .one(&db)
+            .await
     }
 
     pub async fn actualizar_unidad(
@@ -70,10 +110,12 @@ impl UnidadService {
     ) -> Result<UnidadModel, AppError> {
         let db = self.connection();
         let unidad = Unidad::find_by_id(id)
+            .filter(unidad::Column::FechaEliminacion.is_null())
             .one(&db)
             .await?
             .ok_or_else(|| AppError::NotFound("Unidad no encontrada".into()))?;
 
+        let era_visible = unidad.visible;
         let mut unidad: unidad::ActiveModel = unidad.into();
 
         if let Some(nombre) = datos.nombre {
@@ -93,22 +135,36 @@ impl UnidadService {
             unidad.orden = Set(orden);
         }
 
-        if let Some(visible) = datos.visible {
+        // Un cambio manual de visibilidad reemplaza la publicación programada
+        if let Some(visible) = datos.visible.filter(|v| *v != era_visible) {
             unidad.visible = Set(visible);
+            unidad.estado_publicacion = Set(EstadoPublicacion::desde_visible(visible));
+            unidad.publicar_en = Set(None);
+            unidad.despublicar_en = Set(None);
         }
 
         let unidad_actualizada = unidad.update(&db).await?;
+        if !era_visible && unidad_actualizada.visible {
+            PublicacionService::new(self.db.clone())
+                .notificar_publicados(&[(TipoPublicable::Unidad, id)])
+                .await;
+        }
         Ok(unidad_actualizada)
     }
 
     pub async fn eliminar_unidad(&self, id: i32) -> Result<(), AppError> {
         let db = self.connection();
         let unidad = Unidad::find_by_id(id)
+            .filter(unidad::Column::FechaEliminacion.is_null())
             .one(&db)
             .await?
             .ok_or_else(|| AppError::NotFound("Unidad no encontrada".into()))?;
 
-        unidad.delete(&db).await?;
+        // Soft delete en cascada (ver PapeleraService)
+        let txn = db.begin().await?;
+        PapeleraService::mover_a_papelera(&txn, TipoPapelera::Unidad, unidad.id, Utc::now())
+            .await?;
+        txn.commit().await?;
         Ok(())
     }
 }
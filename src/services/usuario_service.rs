@@ -1,22 +1,48 @@
 use axum::extract::FromRef;
-use chrono::{DateTime, Utc};
+use chrono::{NaiveDate, Utc};
 use once_cell::sync::OnceCell;
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
-    Set,
+    Set, TransactionTrait,
+};
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
 };
-use std::sync::Arc;
 use tracing::instrument;
 
 use crate::{
     models::{
-        rol,
-        usuario::{self, Entity as Usuario, Model as UsuarioModel, NewUsuario, UpdateUsuario},
+        curso::{self, Entity as Curso},
+        rol::{self, ROL_ESTUDIANTE},
+        usuario::{
+            self, Entity as Usuario, ErrorImportacion, FilaUsuarioCsv, Model as UsuarioModel,
+            NewUsuario, OpcionesImportacion, ResultadoImportacion, UpdateUsuario, UsuarioImportado,
+        },
         AppState,
     },
-    utils::errors::AppError,
+    services::{
+        acceso_service::AccesoService,
+        auditoria_service::{AuditoriaService, ContextoAuditoria},
+        matricula_service::{MatriculaService, ResultadoMatricula},
+    },
+    utils::{errors::AppError, password},
 };
 
+/// Filas máximas de un CSV de importación
+const MAX_FILAS_IMPORTACION: usize = 2000;
+
+/// Columnas obligatorias del CSV de importación
+const COLUMNAS_IMPORTACION: [&str; 7] = [
+    "nombre",
+    "documento_nit",
+    "correo",
+    "rol",
+    "semestre",
+    "genero",
+    "fecha_nacimiento",
+];
+
 // Validación básica de email sin dependencia extra
 fn is_valid_email(email: &str) -> bool {
     let parts: Vec<&str> = email.split('@').collect();
@@ -27,6 +53,148 @@ fn is_valid_email(email: &str) -> bool {
     !local.is_empty() && domain.contains('.') && !domain.starts_with('.') && !domain.ends_with('.')
 }
 
+/// Reglas de formato de un usuario nuevo (las mismas en POST /api/usuarios y en la
+/// importación CSV); la unicidad de correo y documento se revisa aparte
+fn validar_campos(nuevo_usuario: &NewUsuario) -> Vec<String> {
+    let mut errores = Vec::new();
+    if nuevo_usuario.nombre.trim().is_empty() {
+        errores.push("El nombre completo es obligatorio".to_string());
+    }
+    if nuevo_usuario.correo.trim().is_empty() {
+        errores.push("El correo electrónico es obligatorio".to_string());
+    } else if !is_valid_email(&nuevo_usuario.correo) {
+        errores.push("El formato del correo electrónico no es válido".to_string());
+    }
+    if nuevo_usuario.fecha_nacimiento.parse::<NaiveDate>().is_err() {
+        errores.push("La fecha de nacimiento es inválida".to_string());
+    }
+    errores
+}
+
+/// Cuenta pendiente de activación: `contrasena` es un hash que nadie conoce
+fn usuario_pendiente(
+    nuevo_usuario: NewUsuario,
+    fecha_nacimiento: NaiveDate,
+    contrasena: String,
+) -> usuario::ActiveModel {
+    let ahora = Utc::now();
+    usuario::ActiveModel {
+        nombre: Set(nuevo_usuario.nombre),
+        documento_nit: Set(nuevo_usuario.documento_nit),
+        correo: Set(nuevo_usuario.correo),
+        contrasena: Set(contrasena),
+        foto_url: Set(nuevo_usuario.foto_url),
+        rol_id: Set(nuevo_usuario.rol_id),
+        semestre: Set(nuevo_usuario.semestre),
+        genero: Set(nuevo_usuario.genero),
+        fecha_nacimiento: Set(fecha_nacimiento),
+        estado: Set(nuevo_usuario.estado),
+        token_primer_ingreso: Set(Some(ahora)),
+        fecha_creacion: Set(ahora),
+        fecha_actualizacion: Set(ahora),
+        fecha_ultima_conexion: Set(ahora),
+        ..Default::default()
+    }
+}
+
+/// Ids de curso de `?cursos=1,2,3` sin repetir
+fn parse_cursos(cursos: Option<&str>) -> Result<Vec<i32>, AppError> {
+    let mut ids = BTreeSet::new();
+    for parte in cursos.unwrap_or_default().split(',') {
+        let parte = parte.trim();
+        if parte.is_empty() {
+            continue;
+        }
+        let id = parte.parse::<i32>().map_err(|_| {
+            AppError::BadRequest(
+                format!(
+                    "cursos debe ser una lista de ids separados por comas: '{}'",
+                    parte
+                )
+                .into(),
+            )
+        })?;
+        ids.insert(id);
+    }
+    Ok(ids.into_iter().collect())
+}
+
+/// Línea del CSV y la fila leída o el error que impidió leerla
+type FilaLeida = (u64, Result<FilaUsuarioCsv, String>);
+
+/// Filas del CSV con su número de línea. Usa `;` si la cabecera lo trae y no tiene
+/// comas (Excel en español) y `,` en otro caso
+fn leer_csv(contenido: &[u8]) -> Result<Vec<FilaLeida>, AppError> {
+    let primera_linea = contenido.split(|b| *b == b'\n').next().unwrap_or_default();
+    let separador = if primera_linea.contains(&b';') && !primera_linea.contains(&b',') {
+        b';'
+    } else {
+        b','
+    };
+
+    let mut lector = csv::ReaderBuilder::new()
+        .delimiter(separador)
+        .trim(csv::Trim::All)
+        .from_reader(contenido);
+    let cabeceras: csv::StringRecord = lector
+        .headers()
+        .map_err(|e| AppError::BadRequest(format!("CSV inválido: {}", e).into()))?
+        .iter()
+        .map(|c| c.trim_start_matches('\u{feff}').to_lowercase())
+        .collect();
+    let faltantes: Vec<&str> = COLUMNAS_IMPORTACION
+        .iter()
+        .copied()
+        .filter(|columna| !cabeceras.iter().any(|c| c == *columna))
+        .collect();
+    if !faltantes.is_empty() {
+        return Err(AppError::BadRequest(
+            format!("Faltan columnas en el CSV: {}", faltantes.join(", ")).into(),
+        ));
+    }
+    lector.set_headers(cabeceras.clone());
+
+    let mut filas = Vec::new();
+    for registro in lector.records() {
+        if filas.len() >= MAX_FILAS_IMPORTACION {
+            return Err(AppError::BadRequest(
+                format!("El CSV supera el máximo de {} filas", MAX_FILAS_IMPORTACION).into(),
+            ));
+        }
+        let registro = match registro {
+            Ok(registro) => registro,
+            Err(e) => {
+                let linea = e.position().map(|p| p.line()).unwrap_or_default();
+                filas.push((linea, Err(format!("Fila ilegible: {}", e))));
+                continue;
+            }
+        };
+        if registro.iter().all(str::is_empty) {
+            continue;
+        }
+        let linea = registro.position().map(|p| p.line()).unwrap_or_default();
+        let fila = registro
+            .deserialize::<FilaUsuarioCsv>(Some(&cabeceras))
+            .map_err(|e| match e.kind() {
+                csv::ErrorKind::Deserialize { err, .. } => match err.field() {
+                    Some(campo) => format!(
+                        "Valor inválido en '{}': {}",
+                        cabeceras.get(campo as usize).unwrap_or_default(),
+                        err.kind()
+                    ),
+                    None => format!("Formato inválido: {}", err.kind()),
+                },
+                _ => format!("Formato inválido: {}", e),
+            });
+        filas.push((linea, fila));
+    }
+
+    if filas.is_empty() {
+        return Err(AppError::BadRequest("El CSV no tiene filas".into()));
+    }
+    Ok(filas)
+}
+
 static USUARIO_SERVICE: OnceCell<Arc<UsuarioService>> = OnceCell::new();
 
 #[derive(Debug, Clone)]
@@ -48,31 +216,50 @@ impl UsuarioService {
     // No necesitamos el método begin_transaction separado ya que usamos begin() directamente
 
     // Iniciar sesión
-    #[instrument(skip(self))]
+    /// Los fallos cuentan para la espera por IP/cuenta y el bloqueo de `acceso`
+    #[instrument(skip(self, contrasena, acceso))]
     pub async fn login_usuario(
         &self,
         identificador: &str,
         contrasena: &str,
+        acceso: &AccesoService,
+        ip: &str,
     ) -> Result<UsuarioModel, AppError> {
         let db = self.get_connection().await;
+        acceso.verificar_espera(ip, identificador)?;
 
         // Buscar usuario
-        let usuario = Usuario::find()
+        let Some(usuario) = Usuario::find()
             .filter(
                 usuario::Column::DocumentoNit
                     .eq(identificador)
                     .or(usuario::Column::Correo.eq(identificador)),
             )
-            .filter(usuario::Column::Contrasena.eq(contrasena))
             .one(&db)
             .await?
-            .ok_or_else(|| AppError::NotFound("Credenciales inválidas".into()))?;
+        else {
+            acceso.registrar_fallo(ip, identificador, None).await;
+            return Err(AppError::NotFound("Credenciales inválidas".into()));
+        };
 
-        // Actualizar última conexión
+        acceso.verificar_bloqueo(usuario.id).await?;
+        if !password::verificar(contrasena, &usuario.contrasena).await {
+            acceso
+                .registrar_fallo(ip, identificador, Some(usuario.id))
+                .await;
+            return Err(AppError::NotFound("Credenciales inválidas".into()));
+        }
+        acceso.registrar_exito(ip, identificador, usuario.id).await;
+
+        // Actualizar última conexión y re-hashear la contraseña si seguía en claro
         let now = Utc::now();
+        let en_texto_plano = password::en_texto_plano(&usuario.contrasena);
         let mut usuario = usuario.into_active_model();
         usuario.fecha_actualizacion = Set(now);
         usuario.fecha_ultima_conexion = Set(now);
+        if en_texto_plano {
+            usuario.contrasena = Set(password::hashear(contrasena).await?);
+        }
         let usuario = usuario.update(&db).await?;
 
         Ok(usuario)
@@ -104,6 +291,7 @@ impl UsuarioService {
         let db = self.get_connection().await;
 
         let usuarios = Usuario::find()
+            .filter(usuario::Column::FechaEliminacion.is_null())
             .find_also_related(rol::Entity)
             .all(&db)
             .await?;
@@ -118,40 +306,16 @@ impl UsuarioService {
         Ok(usuarios_con_rol)
     }
 
-    // Crear un nuevo usuario
+    // Crear un nuevo usuario (pendiente de activación: sin contraseña utilizable)
     pub async fn crear_usuario(
         &self,
         nuevo_usuario: NewUsuario,
+        ctx: &ContextoAuditoria,
     ) -> Result<usuario::Model, AppError> {
         let db = self.get_connection().await;
-        // Validar campos obligatorios
-        if nuevo_usuario.nombre.trim().is_empty() {
-            return Err(AppError::BadRequest(
-                "El nombre completo es obligatorio".into(),
-            ));
-        }
-
-        if nuevo_usuario.correo.trim().is_empty() {
-            return Err(AppError::BadRequest(
-                "El correo electrónico es obligatorio".into(),
-            ));
-        }
-
-        // Validar formato de correo
-        if !is_valid_email(&nuevo_usuario.correo) {
-            return Err(AppError::BadRequest(
-                "El formato del correo electrónico no es válido".into(),
-            ));
-        }
-
-        if nuevo_usuario.contrasena.trim().is_empty() {
-            return Err(AppError::BadRequest("La contraseña es obligatoria".into()));
-        }
-
-        if nuevo_usuario.contrasena.len() < 6 {
-            return Err(AppError::BadRequest(
-                "La contraseña debe tener al menos 6 caracteres".into(),
-            ));
+        // Validar campos obligatorios y formato de correo y fecha
+        if let Some(error) = validar_campos(&nuevo_usuario).into_iter().next() {
+            return Err(AppError::BadRequest(error.into()));
         }
 
         // Verificar que el correo no esté en uso
@@ -183,32 +347,248 @@ impl UsuarioService {
         // Crear el nuevo usuario
         let fecha_nacimiento = nuevo_usuario
             .fecha_nacimiento
-            .parse::<chrono::NaiveDate>()
+            .parse::<NaiveDate>()
             .map_err(|_| AppError::BadRequest("La fecha de nacimiento es inválida".into()))?;
+        // Nadie conoce esta contraseña: el usuario elige la suya al activar la cuenta
+        let usuario = usuario_pendiente(
+            nuevo_usuario,
+            fecha_nacimiento,
+            password::hash_inutilizable().await?,
+        );
+
+        let txn = db.begin().await?;
+        let usuario = usuario.insert(&txn).await?;
+        AuditoriaService::registrar(
+            &txn,
+            ctx,
+            "crear",
+            "usuario",
+            Some(usuario.id),
+            None,
+            Some(&usuario),
+        )
+        .await?;
+        txn.commit().await?;
 
-        let usuario = usuario::ActiveModel {
-            nombre: Set(nuevo_usuario.nombre),
-            documento_nit: Set(nuevo_usuario.documento_nit),
-            correo: Set(nuevo_usuario.correo),
-            contrasena: Set(nuevo_usuario.contrasena),
-            foto_url: Set(nuevo_usuario.foto_url),
-            rol_id: Set(nuevo_usuario.rol_id),
-            semestre: Set(nuevo_usuario.semestre),
-            genero: Set(nuevo_usuario.genero),
-            fecha_nacimiento: Set(fecha_nacimiento),
-            estado: Set(nuevo_usuario.estado),
-            token_primer_ingreso: Set(nuevo_usuario
-                .token_primer_ingreso
-                .and_then(|fecha| fecha.parse::<chrono::NaiveDateTime>().ok())
-                .map(|dt| DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc))),
-            fecha_creacion: Set(Utc::now()),
-            fecha_actualizacion: Set(Utc::now()),
-            fecha_ultima_conexion: Set(Utc::now()),
-            ..Default::default()
+        Ok(usuario)
+    }
+
+    /// Importación masiva desde CSV: valida todas las filas (reglas de `NewUsuario`,
+    /// rol existente, correo y documento únicos en el archivo y en la base) y, si no hay
+    /// errores ni es simulación, crea las cuentas pendientes de activación y matricula a
+    /// los estudiantes en `cursos`, todo en una transacción. Devuelve el reporte y las
+    /// cuentas creadas
+    pub async fn importar_usuarios(
+        &self,
+        contenido: &[u8],
+        opciones: &OpcionesImportacion,
+        ctx: &ContextoAuditoria,
+    ) -> Result<(ResultadoImportacion, Vec<UsuarioModel>), AppError> {
+        let db = self.get_connection().await;
+
+        let cursos = parse_cursos(opciones.cursos.as_deref())?;
+        if !cursos.is_empty() {
+            let existentes: BTreeSet<i32> = Curso::find()
+                .filter(curso::Column::Id.is_in(cursos.clone()))
+                .filter(curso::Column::FechaEliminacion.is_null())
+                .all(&db)
+                .await?
+                .into_iter()
+                .map(|c| c.id)
+                .collect();
+            let faltantes: Vec<String> = cursos
+                .iter()
+                .filter(|id| !existentes.contains(id))
+                .map(|id| id.to_string())
+                .collect();
+            if !faltantes.is_empty() {
+                return Err(AppError::NotFound(
+                    format!("Cursos no encontrados: {}", faltantes.join(", ")).into(),
+                ));
+            }
+        }
+
+        let roles: HashMap<String, rol::Model> = rol::Entity::find()
+            .all(&db)
+            .await?
+            .into_iter()
+            .map(|r| (r.nombre.to_lowercase(), r))
+            .collect();
+
+        let filas = leer_csv(contenido)?;
+        let total_filas = filas.len();
+
+        // Primera pasada: formato, rol y repetidos dentro del archivo
+        let mut revisadas = Vec::with_capacity(total_filas);
+        let mut errores = Vec::new();
+        let mut correos_vistos: HashMap<String, u64> = HashMap::new();
+        let mut documentos_vistos: HashMap<String, u64> = HashMap::new();
+        for (fila, lectura) in filas {
+            let datos = match lectura {
+                Ok(datos) => datos,
+                Err(error) => {
+                    errores.push(ErrorImportacion {
+                        fila,
+                        errores: vec![error],
+                    });
+                    continue;
+                }
+            };
+
+            let rol = roles.get(&datos.rol.trim().to_lowercase()).cloned();
+            let nuevo = NewUsuario {
+                nombre: datos.nombre.trim().to_string(),
+                documento_nit: datos.documento_nit.trim().to_string(),
+                correo: datos.correo.trim().to_string(),
+                foto_url: None,
+                rol_id: rol.as_ref().map(|r| r.id).unwrap_or_default(),
+                estado: true,
+                semestre: datos.semestre,
+                genero: datos.genero.trim().to_string(),
+                fecha_nacimiento: datos.fecha_nacimiento.trim().to_string(),
+            };
+
+            let mut problemas = validar_campos(&nuevo);
+            if rol.is_none() {
+                problemas.push(format!("Rol desconocido: '{}'", datos.rol.trim()));
+            }
+            if !nuevo.correo.is_empty() {
+                if let Some(anterior) = correos_vistos.insert(nuevo.correo.to_lowercase(), fila) {
+                    problemas.push(format!("Correo repetido en la fila {}", anterior));
+                }
+            }
+            if !nuevo.documento_nit.is_empty() {
+                if let Some(anterior) = documentos_vistos.insert(nuevo.documento_nit.clone(), fila)
+                {
+                    problemas.push(format!("Documento repetido en la fila {}", anterior));
+                }
+            }
+            revisadas.push((fila, nuevo, rol, problemas));
+        }
+
+        // Segunda pasada: correos y documentos ya registrados
+        let correos_existentes: BTreeSet<String> = Usuario::find()
+            .filter(
+                usuario::Column::Correo
+                    .is_in(revisadas.iter().map(|(_, n, _, _)| n.correo.clone())),
+            )
+            .all(&db)
+            .await?
+            .into_iter()
+            .map(|u| u.correo)
+            .collect();
+        let documentos_existentes: BTreeSet<String> = Usuario::find()
+            .filter(
+                usuario::Column::DocumentoNit.is_in(
+                    revisadas
+                        .iter()
+                        .filter(|(_, n, _, _)| !n.documento_nit.is_empty())
+                        .map(|(_, n, _, _)| n.documento_nit.clone()),
+                ),
+            )
+            .all(&db)
+            .await?
+            .into_iter()
+            .map(|u| u.documento_nit)
+            .collect();
+
+        let mut validas = Vec::with_capacity(revisadas.len());
+        let mut usuarios = Vec::with_capacity(revisadas.len());
+        for (fila, nuevo, rol, mut problemas) in revisadas {
+            if correos_existentes.contains(&nuevo.correo) {
+                problemas.push("Ya existe un usuario con este correo electrónico".to_string());
+            }
+            if documentos_existentes.contains(&nuevo.documento_nit) {
+                problemas.push("Ya existe un usuario con este documento".to_string());
+            }
+            match rol {
+                Some(rol) if problemas.is_empty() => {
+                    let es_estudiante = rol::es_rol(&rol.nombre, ROL_ESTUDIANTE);
+                    usuarios.push(UsuarioImportado {
+                        fila,
+                        id: None,
+                        nombre: nuevo.nombre.clone(),
+                        correo: nuevo.correo.clone(),
+                        rol: rol.nombre,
+                        cursos: if es_estudiante {
+                            cursos.clone()
+                        } else {
+                            Vec::new()
+                        },
+                        en_espera: Vec::new(),
+                    });
+                    validas.push(nuevo);
+                }
+                _ => errores.push(ErrorImportacion {
+                    fila,
+                    errores: problemas,
+                }),
+            }
+        }
+        errores.sort_by_key(|e| e.fila);
+
+        let mut resultado = ResultadoImportacion {
+            simulacion: opciones.simular,
+            aplicada: false,
+            total_filas,
+            matriculas: usuarios.iter().map(|u| u.cursos.len()).sum(),
+            usuarios,
+            errores,
         };
+        if opciones.simular || !resultado.errores.is_empty() {
+            return Ok((resultado, Vec::new()));
+        }
 
-        let usuario = usuario.insert(&db).await?;
-        Ok(usuario)
+        // Un solo hash para el lote (bcrypt tarda ~250 ms); nadie conoce la contraseña
+        // y cada usuario elige la suya al activar la cuenta
+        let contrasena = password::hash_inutilizable().await?;
+        let mut creados = Vec::with_capacity(validas.len());
+
+        let txn = db.begin().await?;
+        for (nuevo, importado) in validas.into_iter().zip(resultado.usuarios.iter_mut()) {
+            let fecha_nacimiento = nuevo
+                .fecha_nacimiento
+                .parse::<NaiveDate>()
+                .map_err(|_| AppError::BadRequest("La fecha de nacimiento es inválida".into()))?;
+            let usuario = usuario_pendiente(nuevo, fecha_nacimiento, contrasena.clone())
+                .insert(&txn)
+                .await?;
+            AuditoriaService::registrar(
+                &txn,
+                ctx,
+                "importar",
+                "usuario",
+                Some(usuario.id),
+                None,
+                Some(&usuario),
+            )
+            .await?;
+            for curso_id in &importado.cursos {
+                let matricula = MatriculaService::matricular_en(&txn, usuario.id, *curso_id, ctx)
+                    .await
+                    .map_err(|e| match e {
+                        AppError::Conflict(motivo) => AppError::Conflict(
+                            format!("Fila {}, curso {}: {}", importado.fila, curso_id, motivo)
+                                .into(),
+                        ),
+                        e => e,
+                    })?;
+                if let ResultadoMatricula::EnEspera(_) = matricula {
+                    importado.en_espera.push(*curso_id);
+                }
+            }
+            importado.id = Some(usuario.id);
+            creados.push(usuario);
+        }
+        txn.commit().await?;
+
+        resultado.aplicada = true;
+        resultado.matriculas = resultado
+            .usuarios
+            .iter()
+            .map(|u| u.cursos.len() - u.en_espera.len())
+            .sum();
+        Ok((resultado, creados))
     }
 
     // Editar usuario existente
@@ -216,12 +596,15 @@ impl UsuarioService {
         &self,
         id: i32,
         datos_actualizados: UpdateUsuario,
+        longitud_minima_contrasena: usize,
+        ctx: &ContextoAuditoria,
     ) -> Result<usuario::Model, AppError> {
         let db = self.get_connection().await;
         let usuario = Usuario::find_by_id(id)
             .one(&db)
             .await?
             .ok_or_else(|| AppError::NotFound("Usuario no encontrado".into()))?;
+        let usuario_original = usuario.clone();
 
         let mut usuario: usuario::ActiveModel = usuario.into();
 
@@ -274,12 +657,12 @@ impl UsuarioService {
         }
 
         if let Some(contrasena) = datos_actualizados.contrasena {
-            if contrasena.len() < 6 {
-                return Err(AppError::BadRequest(
-                    "La contraseña debe tener al menos 6 caracteres".into(),
-                ));
-            }
-            usuario.contrasena = Set(contrasena);
+            password::validar_politica(
+                &contrasena,
+                longitud_minima_contrasena,
+                &[&usuario_original.correo, &usuario_original.documento_nit],
+            )?;
+            usuario.contrasena = Set(password::hashear(&contrasena).await?);
         }
 
         if let Some(rol_id) = datos_actualizados.rol_id {
@@ -291,11 +674,43 @@ impl UsuarioService {
         }
 
         usuario.fecha_actualizacion = Set(Utc::now());
-        let usuario = usuario.update(&db).await?;
+        // El diff registra también los cambios de rol (rol_id)
+        let txn = db.begin().await?;
+        let usuario = usuario.update(&txn).await?;
+        AuditoriaService::registrar(
+            &txn,
+            ctx,
+            "actualizar",
+            "usuario",
+            Some(id),
+            Some(&usuario_original),
+            Some(&usuario),
+        )
+        .await?;
+        txn.commit().await?;
 
         Ok(usuario)
     }
 
+    // Actualizar la foto de perfil (file_key de la variante en R2)
+    pub async fn actualizar_foto_url(
+        &self,
+        id: i32,
+        foto_url: String,
+    ) -> Result<usuario::Model, AppError> {
+        let db = self.get_connection().await;
+        let usuario = Usuario::find_by_id(id)
+            .one(&db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Usuario no encontrado".into()))?;
+
+        let mut usuario: usuario::ActiveModel = usuario.into();
+        usuario.foto_url = Set(Some(foto_url));
+        usuario.fecha_actualizacion = Set(Utc::now());
+
+        Ok(usuario.update(&db).await?)
+    }
+
     // Obtener usuario por ID
     pub async fn obtener_usuario_por_id(
         &self,
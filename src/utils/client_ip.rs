@@ -0,0 +1,167 @@
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+    sync::OnceLock,
+};
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{request::Parts, HeaderMap},
+};
+
+use crate::config::RedConfiable;
+
+/// Proxies de confianza (TRUSTED_PROXIES); sin registrar no se confía en ninguno
+static PROXIES: OnceLock<Vec<RedConfiable>> = OnceLock::new();
+
+pub fn inicializar(proxies: Vec<RedConfiable>) {
+    let _ = PROXIES.set(proxies);
+}
+
+fn es_proxy(proxies: &[RedConfiable], ip: IpAddr) -> bool {
+    proxies.iter().any(|red| red.contiene(ip))
+}
+
+/// IP del cliente: la dirección del socket (requiere `into_make_service_with_connect_info`).
+/// Solo si esa dirección es un proxy de confianza se leen las cabeceras: la última
+/// entrada de X-Forwarded-For que no sea otro proxy de confianza, o X-Real-IP.
+pub fn client_ip(parts: &Parts) -> Option<String> {
+    let par = parts
+        .extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())?;
+    let proxies = PROXIES.get().map(Vec::as_slice).unwrap_or_default();
+    Some(ip_cliente(par, &parts.headers, proxies).to_string())
+}
+
+/// Resuelve la IP del cliente a partir del par del socket y las cabeceras
+fn ip_cliente(par: IpAddr, cabeceras: &HeaderMap, proxies: &[RedConfiable]) -> IpAddr {
+    let par = par.to_canonical();
+    if !es_proxy(proxies, par) {
+        return par;
+    }
+
+    let reenviadas: Vec<&str> = cabeceras
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|valor| valor.to_str().ok())
+        .flat_map(|valor| valor.split(','))
+        .map(str::trim)
+        .collect();
+    if !reenviadas.is_empty() {
+        // De derecha a izquierda: cada proxy de confianza agrega a quien le habló
+        let mut cliente = par;
+        for valor in reenviadas.iter().rev() {
+            match valor.parse::<IpAddr>().map(|ip| ip.to_canonical()) {
+                Ok(ip) if es_proxy(proxies, ip) => cliente = ip,
+                Ok(ip) => return ip,
+                Err(_) => break,
+            }
+        }
+        return cliente;
+    }
+
+    cabeceras
+        .get("x-real-ip")
+        .and_then(|valor| valor.to_str().ok())
+        .and_then(|valor| valor.trim().parse::<IpAddr>().ok())
+        .map_or(par, |ip| ip.to_canonical())
+}
+
+/// Extractor con la IP del cliente (ver [`client_ip`]); "desconocida" si no hay ninguna
+pub struct IpCliente(pub String);
+
+impl<S: Send + Sync> FromRequestParts<S> for IpCliente {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(IpCliente(
+            client_ip(parts).unwrap_or_else(|| "desconocida".to_string()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn red(ip: &str, prefijo: u8) -> RedConfiable {
+        RedConfiable {
+            red: ip.parse().unwrap(),
+            prefijo,
+        }
+    }
+
+    fn cabeceras(pares: &[(&'static str, &str)]) -> HeaderMap {
+        let mut mapa = HeaderMap::new();
+        for (nombre, valor) in pares {
+            mapa.append(*nombre, valor.parse().unwrap());
+        }
+        mapa
+    }
+
+    fn resolver(par: &str, pares: &[(&'static str, &str)], proxies: &[RedConfiable]) -> String {
+        ip_cliente(par.parse().unwrap(), &cabeceras(pares), proxies).to_string()
+    }
+
+    #[test]
+    fn ignora_cabeceras_de_un_par_que_no_es_proxy() {
+        let proxies = [red("10.0.0.0", 8)];
+        let falsas = [("x-forwarded-for", "1.2.3.4"), ("x-real-ip", "5.6.7.8")];
+        assert_eq!(resolver("203.0.113.9", &falsas, &proxies), "203.0.113.9");
+        assert_eq!(resolver("203.0.113.9", &falsas, &[]), "203.0.113.9");
+    }
+
+    #[test]
+    fn toma_la_ultima_entrada_que_no_es_proxy() {
+        let proxies = [red("10.0.0.0", 8), red("192.168.1.5", 32)];
+        // El cliente antepuso una IP falsa; la agregada por el proxy es la real
+        let cadena = [("x-forwarded-for", "1.1.1.1, 198.51.100.7, 192.168.1.5")];
+        assert_eq!(resolver("10.0.0.2", &cadena, &proxies), "198.51.100.7");
+        // Varias cabeceras cuentan como una sola lista
+        let repetidas = [
+            ("x-forwarded-for", "1.1.1.1"),
+            ("x-forwarded-for", "198.51.100.7"),
+        ];
+        assert_eq!(resolver("10.0.0.2", &repetidas, &proxies), "198.51.100.7");
+    }
+
+    #[test]
+    fn cadena_solo_de_proxies_devuelve_el_primero() {
+        let proxies = [red("10.0.0.0", 8)];
+        let cadena = [("x-forwarded-for", "10.1.1.1, 10.2.2.2")];
+        assert_eq!(resolver("10.0.0.2", &cadena, &proxies), "10.1.1.1");
+    }
+
+    #[test]
+    fn entradas_malformadas_cortan_la_cadena() {
+        let proxies = [red("10.0.0.0", 8)];
+        let basura = [("x-forwarded-for", "1.1.1.1, no-es-ip, 10.2.2.2")];
+        assert_eq!(resolver("10.0.0.2", &basura, &proxies), "10.2.2.2");
+        let vacia = [("x-forwarded-for", "no-es-ip")];
+        assert_eq!(resolver("10.0.0.2", &vacia, &proxies), "10.0.0.2");
+        let real_ip_invalida = [("x-real-ip", "999.1.1.1")];
+        assert_eq!(
+            resolver("10.0.0.2", &real_ip_invalida, &proxies),
+            "10.0.0.2"
+        );
+    }
+
+    #[test]
+    fn usa_x_real_ip_sin_x_forwarded_for() {
+        let proxies = [red("10.0.0.0", 8)];
+        let cabecera = [("x-real-ip", " 198.51.100.7 ")];
+        assert_eq!(resolver("10.0.0.2", &cabecera, &proxies), "198.51.100.7");
+    }
+
+    #[test]
+    fn normaliza_ipv4_mapeadas_en_ipv6() {
+        let proxies = [red("10.0.0.0", 8)];
+        let cadena = [("x-forwarded-for", "::ffff:198.51.100.7")];
+        assert_eq!(
+            resolver("::ffff:10.0.0.2", &cadena, &proxies),
+            "198.51.100.7"
+        );
+        assert_eq!(resolver("::ffff:203.0.113.9", &[], &proxies), "203.0.113.9");
+    }
+}
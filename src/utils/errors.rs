@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Json, Response},
 };
 use thiserror::Error;
@@ -28,7 +28,6 @@ pub enum AppError {
     Unauthorized(std::borrow::Cow<'static, str>),
 
     #[error("Forbidden: {0}")]
-    #[allow(dead_code)]
     Forbidden(std::borrow::Cow<'static, str>),
 
     #[error("Not found: {0}")]
@@ -37,6 +36,10 @@ pub enum AppError {
     #[error("Conflict: {0}")]
     Conflict(std::borrow::Cow<'static, str>),
 
+    /// Límite de peticiones o bloqueo temporal; el segundo valor va en `Retry-After`
+    #[error("Too many requests: {0}")]
+    TooManyRequests(std::borrow::Cow<'static, str>, u64),
+
     #[error("Internal server error: {0}")]
     InternalServerError(std::borrow::Cow<'static, str>),
 
@@ -62,6 +65,9 @@ pub enum AppError {
 
     #[error("Multipart field error: {0}")]
     MultipartField(String),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] axum::extract::rejection::JsonRejection),
 }
 
 // Específicas para cada variante
@@ -111,6 +117,15 @@ impl IntoResponse for AppError {
             AppError::Forbidden(message) => (StatusCode::FORBIDDEN, message),
             AppError::NotFound(message) => (StatusCode::NOT_FOUND, message),
             AppError::Conflict(message) => (StatusCode::CONFLICT, message),
+            AppError::TooManyRequests(message, segundos) => {
+                let body = Json(ApiResponse::<()>::error(message.into_owned()));
+                return (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    [(header::RETRY_AFTER, segundos.max(1).to_string())],
+                    body,
+                )
+                    .into_response();
+            }
             AppError::InternalServerError(message) => {
                 tracing::error!("Internal server error: {}", message);
                 (StatusCode::INTERNAL_SERVER_ERROR, message)
@@ -137,6 +152,13 @@ impl IntoResponse for AppError {
                 tracing::error!("SeaORM database error: {:?}", e);
                 (StatusCode::INTERNAL_SERVER_ERROR, "Database error".into())
             }
+            AppError::Storage(StorageError::InvalidImage(ref e)) => {
+                tracing::warn!("Invalid image: {}", e);
+                (
+                    StatusCode::BAD_REQUEST,
+                    "El archivo no es una imagen válida (formatos: JPEG, PNG, GIF, WebP)".into(),
+                )
+            }
             AppError::Storage(ref e) => {
                 tracing::error!("Storage error: {:?}", e);
                 (StatusCode::INTERNAL_SERVER_ERROR, "Storage error".into())
@@ -149,9 +171,31 @@ impl IntoResponse for AppError {
                 tracing::error!("Multipart field error: {}", e);
                 (StatusCode::BAD_REQUEST, std::borrow::Cow::Owned(e.clone()))
             }
+            AppError::Json(ref e) => {
+                tracing::warn!("JSON rejection: {}", e.body_text());
+                (
+                    StatusCode::BAD_REQUEST,
+                    std::borrow::Cow::Owned(format!("JSON inválido: {}", detalle_json(e))),
+                )
+            }
         };
 
         let body = Json(ApiResponse::<()>::error(message.into_owned()));
         (status, body).into_response()
     }
 }
+
+/// Mensaje de serde sin el prefijo en inglés de axum
+/// (p. ej. "estado: unknown variant `x`, expected one of `borrador`, ...")
+fn detalle_json(rechazo: &axum::extract::rejection::JsonRejection) -> String {
+    use axum::extract::rejection::JsonRejection;
+
+    let texto = rechazo.body_text();
+    match rechazo {
+        JsonRejection::JsonDataError(_) | JsonRejection::JsonSyntaxError(_) => texto
+            .split_once(": ")
+            .map(|(_, detalle)| detalle.to_string())
+            .unwrap_or(texto),
+        _ => texto,
+    }
+}
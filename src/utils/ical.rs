@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+
+use crate::models::calendario::EventoCalendario;
+
+/// Longitud máxima de línea en octetos (RFC 5545 §3.1)
+const LONGITUD_LINEA: usize = 75;
+
+/// Genera un VCALENDAR (RFC 5545) con una entrada VEVENT por evento
+pub fn render_calendario(nombre: &str, eventos: &[EventoCalendario]) -> String {
+    let ahora = formato_fecha_hora(&Utc::now());
+    let mut salida = String::new();
+
+    linea(&mut salida, "BEGIN:VCALENDAR");
+    linea(&mut salida, "VERSION:2.0");
+    linea(
+        &mut salida,
+        "PRODID:-//backend-aula//Calendario academico//ES",
+    );
+    linea(&mut salida, "CALSCALE:GREGORIAN");
+    linea(&mut salida, "METHOD:PUBLISH");
+    linea(&mut salida, &format!("X-WR-CALNAME:{}", escapar(nombre)));
+
+    for evento in eventos {
+        linea(&mut salida, "BEGIN:VEVENT");
+        linea(
+            &mut salida,
+            &format!("UID:{}-{}@backend-aula", evento.tipo.nombre(), evento.id),
+        );
+        linea(&mut salida, &format!("DTSTAMP:{}", ahora));
+        if evento.todo_el_dia {
+            let dia = evento.inicio.date_naive();
+            linea(
+                &mut salida,
+                &format!("DTSTART;VALUE=DATE:{}", dia.format("%Y%m%d")),
+            );
+            if let Some(siguiente) = dia.succ_opt() {
+                linea(
+                    &mut salida,
+                    &format!("DTEND;VALUE=DATE:{}", siguiente.format("%Y%m%d")),
+                );
+            }
+        } else {
+            linea(
+                &mut salida,
+                &format!("DTSTART:{}", formato_fecha_hora(&evento.inicio)),
+            );
+            if let Some(fin) = evento.fin.filter(|fin| *fin > evento.inicio) {
+                linea(&mut salida, &format!("DTEND:{}", formato_fecha_hora(&fin)));
+            }
+        }
+
+        let resumen = format!("{} ({})", evento.titulo, evento.curso_nombre);
+        linea(&mut salida, &format!("SUMMARY:{}", escapar(&resumen)));
+        if let Some(descripcion) = evento.descripcion.as_deref().filter(|d| !d.is_empty()) {
+            linea(
+                &mut salida,
+                &format!("DESCRIPTION:{}", escapar(descripcion)),
+            );
+        }
+        linea(
+            &mut salida,
+            &format!("CATEGORIES:{}", evento.tipo.nombre().to_uppercase()),
+        );
+        linea(&mut salida, "END:VEVENT");
+    }
+
+    linea(&mut salida, "END:VCALENDAR");
+    salida
+}
+
+fn formato_fecha_hora(fecha: &DateTime<Utc>) -> String {
+    fecha.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escapa texto según RFC 5545 §3.3.11
+fn escapar(texto: &str) -> String {
+    let mut resultado = String::with_capacity(texto.len());
+    for c in texto.chars() {
+        match c {
+            '\\' => resultado.push_str("\\\\"),
+            ';' => resultado.push_str("\\;"),
+            ',' => resultado.push_str("\\,"),
+            '\n' => resultado.push_str("\\n"),
+            '\r' => {}
+            _ => resultado.push(c),
+        }
+    }
+    resultado
+}
+
+/// Agrega la línea con CRLF, plegándola cada 75 octetos sin cortar caracteres UTF-8
+fn linea(salida: &mut String, contenido: &str) {
+    let mut octetos = 0;
+    for c in contenido.chars() {
+        let largo = c.len_utf8();
+        if octetos + largo > LONGITUD_LINEA {
+            salida.push_str("\r\n ");
+            octetos = 1;
+        }
+        salida.push(c);
+        octetos += largo;
+    }
+    salida.push_str("\r\n");
+}
@@ -1,4 +1,4 @@
-use chrono::{FixedOffset, TimeZone, Utc};
+use chrono::{FixedOffset, Utc};
 use std::collections::VecDeque;
 use std::env;
 use std::fs::{self, OpenOptions};
@@ -7,16 +7,32 @@ use std::path::{Path, PathBuf};
 use std::sync::{Mutex, OnceLock};
 use tracing_subscriber::{
     fmt, fmt::writer::MakeWriter, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter,
+    Layer, Registry,
 };
 
-// Zona horaria de Bogotá (UTC-5)
-struct BogotaTime;
+use crate::config::{LogFormat, LoggingConfig};
 
-impl tracing_subscriber::fmt::time::FormatTime for BogotaTime {
+// Zona horaria configurable (LOG_TIMEZONE, por defecto Bogotá UTC-5)
+#[derive(Clone, Copy)]
+struct LocalTime {
+    offset: FixedOffset,
+}
+
+impl LocalTime {
+    fn new(utc_offset_secs: i32) -> Self {
+        let offset = FixedOffset::east_opt(utc_offset_secs)
+            .unwrap_or_else(|| FixedOffset::west_opt(5 * 3600).unwrap());
+        Self { offset }
+    }
+
+    fn now(&self) -> chrono::DateTime<FixedOffset> {
+        Utc::now().with_timezone(&self.offset)
+    }
+}
+
+impl tracing_subscriber::fmt::time::FormatTime for LocalTime {
     fn format_time(&self, w: &mut tracing_subscriber::fmt::format::Writer<'_>) -> std::fmt::Result {
-        let bogota_tz = FixedOffset::west_opt(5 * 3600).unwrap();
-        let now_bogota = bogota_tz.from_utc_datetime(&Utc::now().naive_utc());
-        write!(w, "{}", now_bogota.format("%Y-%m-%d %H:%M:%S%.3f"))
+        write!(w, "{}", self.now().format("%Y-%m-%dT%H:%M:%S%.3f%:z"))
     }
 }
 
@@ -29,15 +45,19 @@ struct CircularLogBuffer {
     log_dir: String,
     app_name: &'static str,
     bytes_written: usize,
+    time: LocalTime,
+    max_file_bytes: u64,
 }
 
 impl CircularLogBuffer {
-    fn new(log_dir: String, app_name: &'static str) -> Self {
+    fn new(log_dir: String, app_name: &'static str, time: LocalTime, max_file_bytes: u64) -> Self {
         Self {
             buffer: VecDeque::with_capacity(FLUSH_THRESHOLD),
             log_dir,
             app_name,
             bytes_written: 0,
+            time,
+            max_file_bytes,
         }
     }
 
@@ -67,17 +87,27 @@ impl CircularLogBuffer {
         // Crear directorio si no existe
         std::fs::create_dir_all(self.log_dir.clone()).ok();
 
-        // Zona horaria de Bogotá (UTC-5)
-        let bogota_tz = FixedOffset::west_opt(5 * 3600).unwrap();
-        let now_bogota = bogota_tz.from_utc_datetime(&chrono::Utc::now().naive_utc());
-
-        // Nombre del archivo con fecha actual (zona horaria Bogotá)
-        let log_file = format!(
-            "{}/{}.{}.log",
-            self.log_dir,
-            self.app_name,
-            now_bogota.format("%Y-%m-%d")
-        );
+        // Nombre del archivo con fecha actual (zona horaria configurada)
+        let fecha = self.time.now().format("%Y-%m-%d").to_string();
+        let log_file = format!("{}/{}.{}.log", self.log_dir, self.app_name, fecha);
+
+        // Rotación por tamaño: el archivo lleno pasa a <app>.<fecha>.<n>.log
+        if self.max_file_bytes > 0 {
+            if let Ok(meta) = fs::metadata(&log_file) {
+                if meta.len() >= self.max_file_bytes {
+                    let mut n = 1;
+                    let rotated = loop {
+                        let candidate =
+                            format!("{}/{}.{}.{}.log", self.log_dir, self.app_name, fecha, n);
+                        if !Path::new(&candidate).exists() {
+                            break candidate;
+                        }
+                        n += 1;
+                    };
+                    fs::rename(&log_file, rotated)?;
+                }
+            }
+        }
 
         // Abrir archivo en modo append, escribir y cerrar inmediatamente
         let mut file = OpenOptions::new()
@@ -96,6 +126,7 @@ impl CircularLogBuffer {
 }
 
 static LOG_BUFFER: OnceLock<Mutex<CircularLogBuffer>> = OnceLock::new();
+static LOG_DIR: OnceLock<String> = OnceLock::new();
 
 #[derive(Clone)]
 struct BufferedMakeWriter {
@@ -149,17 +180,23 @@ impl<'a> MakeWriter<'a> for BufferedMakeWriter {
 /// # Uso
 /// Ideal para desarrollo donde no necesitas persistencia de logs
 #[allow(dead_code)]
-pub fn init_logger_console_only() -> Result<(), Box<dyn std::error::Error>> {
+pub fn init_logger_console_only(config: &LoggingConfig) -> Result<(), Box<dyn std::error::Error>> {
     static INIT: OnceLock<()> = OnceLock::new();
 
     INIT.get_or_init(|| {
         let env_filter =
             EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+        let time = LocalTime::new(config.utc_offset_secs);
+
+        // Solo consola, sin archivo ni buffer
+        let console_layer = match config.format {
+            LogFormat::Json => fmt::layer().with_timer(time).json().boxed(),
+            LogFormat::Text => fmt::layer().with_timer(time).compact().boxed(),
+        };
 
-        // Solo consola, sin archivo ni buffer, con hora Bogotá
         tracing_subscriber::registry()
+            .with(console_layer)
             .with(env_filter)
-            .with(fmt::layer().with_timer(BogotaTime).compact())
             .init();
     });
 
@@ -169,7 +206,7 @@ pub fn init_logger_console_only() -> Result<(), Box<dyn std::error::Error>> {
 /// Inicializa el sistema de logging con buffer circular de 20KB
 ///
 /// # Arguments
-/// * `log_dir` - Directorio donde se guardarán los logs (relativo al ejecutable)
+/// * `config` - Directorio, formato (texto/JSON), zona horaria y rotación
 /// * `app_name` - Nombre de la aplicación para los archivos de log
 ///
 /// # Returns
@@ -179,27 +216,31 @@ pub fn init_logger_console_only() -> Result<(), Box<dyn std::error::Error>> {
 /// - Buffer circular de 20KB máximo en memoria
 /// - Escritura lazy (flush cada 4KB o al cerrar)
 /// - Archivo se abre/cierra en cada flush (sin mantener handle abierto)
-/// - Logs se persisten correctamente entre reinicios
+/// - Rotación por tamaño (`max_file_size_mb`) además de la diaria
 /// - Siempre usa directorio del ejecutable en ejecución para logs
 /// - Calcula path una sola vez para máxima eficiencia
 pub fn init_logger(
-    log_dir: &'static str,
+    config: &LoggingConfig,
     app_name: &'static str,
 ) -> Result<(), Box<dyn std::error::Error>> {
     static INIT: OnceLock<()> = OnceLock::new();
 
     INIT.get_or_init(|| {
-        // Calcular path una sola vez y convertir a &'static str para eficiencia
+        // Calcular path una sola vez
         let exe_path = env::current_exe().unwrap_or_else(|_| PathBuf::from("."));
-        let logs_dir_path = exe_path.parent().unwrap_or(Path::new(".")).join(log_dir);
-        let logs_dir_str = logs_dir_path.to_string_lossy().to_string();
-        let adjusted_log_dir: &'static str = Box::leak(logs_dir_str.into_boxed_str());
+        let logs_dir_path = exe_path.parent().unwrap_or(Path::new(".")).join(&config.dir);
+        let adjusted_log_dir = logs_dir_path.to_string_lossy().to_string();
+        let time = LocalTime::new(config.utc_offset_secs);
+
+        LOG_DIR.get_or_init(|| adjusted_log_dir.clone());
 
         // Inicializar buffer circular (lazy, solo si se usa)
         LOG_BUFFER.get_or_init(|| {
             Mutex::new(CircularLogBuffer::new(
-                adjusted_log_dir.to_string(),
+                adjusted_log_dir,
                 app_name,
+                time,
+                config.max_file_size_mb.saturating_mul(1024 * 1024),
             ))
         });
 
@@ -207,34 +248,48 @@ pub fn init_logger(
         let env_filter =
             EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
 
-        // Escritor con buffer circular
-        let file_writer = BufferedMakeWriter::new();
-
-        // Capa para archivo: SIN colores, formato compacto, hora Bogotá
-        let file_layer = fmt::layer()
-            .with_writer(file_writer)
-            .with_ansi(false)
-            .with_target(false)
-            .with_timer(BogotaTime)
-            .compact();
-
-        // Capa para consola: CON colores, hora Bogotá
-        let stdout_layer = fmt::layer()
-            .with_ansi(true)
-            .with_timer(BogotaTime)
-            .compact();
+        // Archivo sin colores; consola con colores solo en formato texto.
+        // En JSON cada línea incluye los campos del span (request_id, user_id, ...)
+        let layers: Vec<Box<dyn Layer<Registry> + Send + Sync>> = match config.format {
+            LogFormat::Json => vec![
+                fmt::layer()
+                    .with_writer(BufferedMakeWriter::new())
+                    .with_timer(time)
+                    .json()
+                    .boxed(),
+                fmt::layer().with_timer(time).json().boxed(),
+            ],
+            LogFormat::Text => vec![
+                fmt::layer()
+                    .with_writer(BufferedMakeWriter::new())
+                    .with_ansi(false)
+                    .with_target(false)
+                    .with_timer(time)
+                    .compact()
+                    .boxed(),
+                fmt::layer()
+                    .with_ansi(true)
+                    .with_timer(time)
+                    .compact()
+                    .boxed(),
+            ],
+        };
 
         // Configurar suscriptor
         tracing_subscriber::registry()
+            .with(layers)
             .with(env_filter)
-            .with(file_layer)
-            .with(stdout_layer)
             .init();
     });
 
     Ok(())
 }
 
+/// Directorio efectivo de logs (None si solo se registra en consola)
+pub fn log_dir() -> Option<&'static str> {
+    LOG_DIR.get().map(String::as_str)
+}
+
 /// Fuerza el flush del buffer de logs al disco
 /// Útil antes de shutdown para asegurar que todos los logs se persistan
 pub fn flush_logs() -> std::io::Result<()> {
@@ -251,7 +306,6 @@ pub fn flush_logs() -> std::io::Result<()> {
 /// # Arguments
 /// * `log_dir` - Directorio de logs
 /// * `max_age_days` - Número máximo de días de antigüedad para conservar los logs
-#[allow(dead_code)]
 pub fn cleanup_old_logs(log_dir: &str, max_age_days: u64) -> std::io::Result<()> {
     use std::time::{Duration, SystemTime};
 
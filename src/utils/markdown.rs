@@ -0,0 +1,1245 @@
+//! Markdown → HTML para el texto que escriben los usuarios. El HTML de salida solo
+//! contiene etiquetas generadas aquí o de la lista permitida, así que se puede
+//! insertar tal cual en el frontend. Las fórmulas LaTeX (`$..$`, `$$..$$` y bloques
+//! ```math) salen escapadas dentro de `.math` para que el frontend las dibuje con
+//! KaTeX/MathJax; los bloques de código salen con `class="language-.."`
+
+use std::collections::HashMap;
+
+use crate::utils::errors::AppError;
+
+/// Caracteres como máximo de un texto
+const LARGO_MAXIMO: usize = 200_000;
+
+/// Anidamiento máximo de citas, listas, énfasis, enlaces y etiquetas; lo que pase de
+/// ahí sale como texto escapado
+const PROFUNDIDAD_MAXIMA: usize = 32;
+
+/// Etiquetas HTML sueltas que se aceptan dentro del texto (sin atributos)
+const ETIQUETAS_PERMITIDAS: &[&str] = &[
+    "b", "strong", "i", "em", "u", "s", "del", "ins", "mark", "sub", "sup", "small", "kbd", "code",
+    "br",
+];
+
+/// Esquemas que ejecutan código en el navegador
+const ESQUEMAS_PELIGROSOS: &[&str] = &["javascript:", "vbscript:", "data:text/html"];
+
+/// Renderiza y rechaza scripts, atributos de evento (`onclick`...) y URLs
+/// `javascript:`. Los ejemplos dentro de bloques o spans de código no cuentan
+pub fn renderizar(fuente: &str) -> Result<String, String> {
+    if fuente.chars().count() > LARGO_MAXIMO {
+        return Err(format!("El texto supera los {} caracteres", LARGO_MAXIMO));
+    }
+    let mut renderizador = Renderizador::default();
+    let html = renderizador.documento(fuente);
+    match renderizador.error {
+        Some(motivo) => Err(motivo),
+        None => Ok(html),
+    }
+}
+
+/// Renderiza sin rechazar: lo peligroso queda como texto escapado. Para textos que
+/// arma el sistema (notificaciones)
+pub fn a_html(fuente: &str) -> String {
+    if fuente.chars().count() > LARGO_MAXIMO {
+        return format!("<p>{}</p>\n", escapar(fuente));
+    }
+    Renderizador::default().documento(fuente)
+}
+
+/// [`renderizar`] en un hilo bloqueante; 400 con el motivo si se rechaza
+pub async fn renderizar_en_hilo(fuente: &str) -> Result<String, AppError> {
+    let fuente = fuente.to_string();
+    tokio::task::spawn_blocking(move || renderizar(&fuente))
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Task join error: {}", e).into()))?
+        .map_err(|e| AppError::BadRequest(e.into()))
+}
+
+/// [`a_html`] en un hilo bloqueante
+pub async fn a_html_en_hilo(fuente: &str) -> Result<String, AppError> {
+    let fuente = fuente.to_string();
+    tokio::task::spawn_blocking(move || a_html(&fuente))
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Task join error: {}", e).into()))
+}
+
+#[derive(Default)]
+struct Renderizador {
+    error: Option<String>,
+    /// Bloques y tramos en línea abiertos ahora mismo
+    profundidad: usize,
+}
+
+impl Renderizador {
+    fn rechazar(&mut self, motivo: &str) {
+        if self.error.is_none() {
+            self.error = Some(motivo.to_string());
+        }
+    }
+
+    fn documento(&mut self, fuente: &str) -> String {
+        let lineas: Vec<String> = fuente
+            .lines()
+            .map(|linea| {
+                linea
+                    .replace('\t', "    ")
+                    .chars()
+                    .filter(|c| !c.is_control())
+                    .collect()
+            })
+            .collect();
+        let mut salida = String::new();
+        self.bloques(&lineas, &mut salida);
+        salida
+    }
+
+    fn bloques(&mut self, lineas: &[String], salida: &mut String) {
+        // Citas o listas anidadas de más: lo que queda va como un párrafo
+        if self.profundidad >= PROFUNDIDAD_MAXIMA {
+            let mut parrafo = lineas.iter().map(String::as_str).collect();
+            self.parrafo(&mut parrafo, salida);
+            return;
+        }
+        self.profundidad += 1;
+        let mut parrafo: Vec<&str> = Vec::new();
+        let mut i = 0;
+
+        while i < lineas.len() {
+            let linea = lineas[i].as_str();
+            let recortada = linea.trim();
+
+            if recortada.is_empty() {
+                self.parrafo(&mut parrafo, salida);
+                i += 1;
+                continue;
+            }
+
+            if let Some((cerca, info)) = apertura_cerca(recortada) {
+                self.parrafo(&mut parrafo, salida);
+                let mut cuerpo = Vec::new();
+                i += 1;
+                while i < lineas.len() && !cierra_cerca(lineas[i].trim(), &cerca) {
+                    cuerpo.push(lineas[i].as_str());
+                    i += 1;
+                }
+                i += 1;
+                let codigo = cuerpo.join("\n");
+                if info == "math" {
+                    formula_bloque(&codigo, salida);
+                } else if info.is_empty() {
+                    salida.push_str(&format!("<pre><code>{}</code></pre>\n", escapar(&codigo)));
+                } else {
+                    salida.push_str(&format!(
+                        "<pre><code class=\"language-{}\">{}</code></pre>\n",
+                        info,
+                        escapar(&codigo)
+                    ));
+                }
+                continue;
+            }
+
+            if let Some(resto) = recortada.strip_prefix("$$") {
+                self.parrafo(&mut parrafo, salida);
+                let mut formula = Vec::new();
+                match resto.strip_suffix("$$") {
+                    Some(unica) => {
+                        formula.push(unica);
+                        i += 1;
+                    }
+                    None => {
+                        formula.push(resto);
+                        i += 1;
+                        while i < lineas.len() {
+                            let actual = lineas[i].trim();
+                            i += 1;
+                            if let Some(ultima) = actual.strip_suffix("$$") {
+                                formula.push(ultima);
+                                break;
+                            }
+                            formula.push(actual);
+                        }
+                    }
+                }
+                formula_bloque(formula.join("\n").trim(), salida);
+                continue;
+            }
+
+            if let Some((nivel, texto)) = encabezado(recortada) {
+                self.parrafo(&mut parrafo, salida);
+                let contenido = self.en_linea(texto);
+                salida.push_str(&format!("<h{nivel}>{contenido}</h{nivel}>\n"));
+                i += 1;
+                continue;
+            }
+
+            if es_separador(recortada) {
+                self.parrafo(&mut parrafo, salida);
+                salida.push_str("<hr>\n");
+                i += 1;
+                continue;
+            }
+
+            if recortada.starts_with('>') {
+                self.parrafo(&mut parrafo, salida);
+                let mut citadas = Vec::new();
+                while i < lineas.len() {
+                    let Some(resto) = lineas[i].trim_start().strip_prefix('>') else {
+                        break;
+                    };
+                    citadas.push(resto.strip_prefix(' ').unwrap_or(resto).to_string());
+                    i += 1;
+                }
+                salida.push_str("<blockquote>\n");
+                self.bloques(&citadas, salida);
+                salida.push_str("</blockquote>\n");
+                continue;
+            }
+
+            if let Some(marcador) = marcador_lista(linea) {
+                self.parrafo(&mut parrafo, salida);
+                i = self.lista(lineas, i, marcador, salida);
+                continue;
+            }
+
+            if linea.contains('|')
+                && parrafo.is_empty()
+                && i + 1 < lineas.len()
+                && alineaciones(&lineas[i + 1])
+                    .is_some_and(|columnas| columnas.len() == celdas(linea).len())
+            {
+                i = self.tabla(lineas, i, salida);
+                continue;
+            }
+
+            parrafo.push(linea);
+            i += 1;
+        }
+
+        self.parrafo(&mut parrafo, salida);
+        self.profundidad -= 1;
+    }
+
+    fn parrafo(&mut self, parrafo: &mut Vec<&str>, salida: &mut String) {
+        if parrafo.is_empty() {
+            return;
+        }
+        let ultima = parrafo.len() - 1;
+        let mut partes = Vec::with_capacity(parrafo.len());
+        for (n, linea) in parrafo.iter().enumerate() {
+            let linea = linea.trim_start();
+            // Dos espacios o "\" al final de la línea = salto de línea
+            let salto = n < ultima && (linea.ends_with("  ") || linea.ends_with('\\'));
+            let linea = if salto {
+                linea.trim_end().trim_end_matches('\\')
+            } else {
+                linea.trim_end()
+            };
+            let mut html = self.en_linea(linea);
+            if salto {
+                html.push_str("<br>");
+            }
+            partes.push(html);
+        }
+        salida.push_str(&format!("<p>{}</p>\n", partes.join("\n")));
+        parrafo.clear();
+    }
+
+    /// Lista que empieza en `inicio`; devuelve la línea siguiente a la lista
+    fn lista(
+        &mut self,
+        lineas: &[String],
+        inicio: usize,
+        primero: Marcador,
+        salida: &mut String,
+    ) -> usize {
+        let mut items: Vec<(Vec<String>, bool)> = Vec::new();
+        let mut i = inicio;
+
+        while i < lineas.len() {
+            let linea = lineas[i].as_str();
+            match marcador_lista(linea) {
+                Some(marcador)
+                    if marcador.ordenada == primero.ordenada
+                        && marcador.sangria <= primero.sangria + 1 =>
+                {
+                    items.push((vec![linea[marcador.texto..].to_string()], false));
+                    i += 1;
+                }
+                _ if linea.trim().is_empty() => {
+                    // Un blanco sigue dentro de la lista solo si lo que viene está sangrado
+                    // o es otro elemento
+                    let siguiente = lineas[i + 1..]
+                        .iter()
+                        .position(|l| !l.trim().is_empty())
+                        .map_or(lineas.len(), |p| i + 1 + p);
+                    let continua = lineas.get(siguiente).is_some_and(|l| {
+                        sangria(l) >= primero.texto
+                            || marcador_lista(l).is_some_and(|m| m.ordenada == primero.ordenada)
+                    });
+                    if !continua {
+                        break;
+                    }
+                    // Todos los blancos seguidos de una vez
+                    if let Some((contenido, suelto)) = items.last_mut() {
+                        contenido.extend((i..siguiente).map(|_| String::new()));
+                        *suelto = true;
+                    }
+                    i = siguiente;
+                }
+                _ => {
+                    let quitar = sangria(linea).min(primero.texto);
+                    let anterior_en_blanco = lineas[i - 1].trim().is_empty();
+                    if quitar < 2
+                        && (anterior_en_blanco
+                            || inicia_bloque(linea)
+                            || marcador_lista(linea).is_some())
+                    {
+                        break;
+                    }
+                    if let Some((contenido, _)) = items.last_mut() {
+                        contenido.push(linea[quitar..].to_string());
+                    }
+                    i += 1;
+                }
+            }
+        }
+
+        let etiqueta = if primero.ordenada { "ol" } else { "ul" };
+        match primero.numero {
+            Some(numero) if numero != 1 => salida.push_str(&format!("<ol start=\"{numero}\">\n")),
+            _ => salida.push_str(&format!("<{etiqueta}>\n")),
+        }
+        for (contenido, suelto) in items {
+            let mut html = String::new();
+            self.bloques(&contenido, &mut html);
+            // Lista compacta: el primer párrafo va sin <p>
+            if !suelto {
+                if let Some(resto) = html.strip_prefix("<p>") {
+                    if let Some(fin) = resto.find("</p>\n") {
+                        html = format!("{}\n{}", &resto[..fin], &resto[fin + 5..]);
+                    }
+                }
+            }
+            salida.push_str(&format!("<li>{}</li>\n", html.trim_end()));
+        }
+        salida.push_str(&format!("</{etiqueta}>\n"));
+        i
+    }
+
+    /// Tabla estilo GFM; devuelve la línea siguiente a la tabla
+    fn tabla(&mut self, lineas: &[String], inicio: usize, salida: &mut String) -> usize {
+        let alineacion = alineaciones(&lineas[inicio + 1]).unwrap_or_default();
+        let atributo = |columna: usize| match alineacion.get(columna).copied().flatten() {
+            Some(lado) => format!(" style=\"text-align: {lado}\""),
+            None => String::new(),
+        };
+
+        salida.push_str("<table>\n<thead>\n<tr>");
+        for (columna, celda) in celdas(&lineas[inicio]).into_iter().enumerate() {
+            let contenido = self.en_linea(celda);
+            salida.push_str(&format!("<th{}>{}</th>", atributo(columna), contenido));
+        }
+        salida.push_str("</tr>\n</thead>\n<tbody>\n");
+
+        let mut i = inicio + 2;
+        while i < lineas.len() && lineas[i].contains('|') && !lineas[i].trim().is_empty() {
+            salida.push_str("<tr>");
+            for (columna, celda) in celdas(&lineas[i]).into_iter().enumerate() {
+                let contenido = self.en_linea(celda);
+                salida.push_str(&format!("<td{}>{}</td>", atributo(columna), contenido));
+            }
+            salida.push_str("</tr>\n");
+            i += 1;
+        }
+        salida.push_str("</tbody>\n</table>\n");
+        i
+    }
+
+    /// Texto en línea; pasado el anidamiento máximo queda escapado tal cual
+    fn en_linea(&mut self, texto: &str) -> String {
+        if self.profundidad >= PROFUNDIDAD_MAXIMA {
+            return escapar(texto);
+        }
+        self.profundidad += 1;
+        let html = self.tramo(texto);
+        self.profundidad -= 1;
+        html
+    }
+
+    fn tramo(&mut self, texto: &str) -> String {
+        let c: Vec<char> = texto.chars().collect();
+        let n = c.len();
+        let mut cierres = Cierres::new(&c);
+        let mut salida = String::with_capacity(texto.len());
+        // Etiquetas permitidas abiertas en este tramo, para dejarlas balanceadas
+        let mut abiertas = Vec::new();
+        let mut i = 0;
+
+        while i < n {
+            match c[i] {
+                '\\' if i + 1 < n && c[i + 1].is_ascii_punctuation() => {
+                    salida.push_str(&escapar(&c[i + 1].to_string()));
+                    i += 2;
+                }
+                '`' => {
+                    let largo = racha(&c, i, '`');
+                    match cierres.codigo.cierre(i + largo, largo) {
+                        Some(fin) => {
+                            let codigo: String = c[i + largo..fin].iter().collect();
+                            salida.push_str(&format!("<code>{}</code>", escapar(codigo.trim())));
+                            i = fin + largo;
+                        }
+                        None => {
+                            salida.push_str(&"`".repeat(largo));
+                            i += largo;
+                        }
+                    }
+                }
+                '$' => match cierres.formula(i) {
+                    Some(fin) => {
+                        let formula: String = c[i + 1..fin].iter().collect();
+                        salida.push_str(&format!(
+                            "<span class=\"math math-inline\">{}</span>",
+                            escapar(&formula)
+                        ));
+                        i = fin + 1;
+                    }
+                    None => {
+                        salida.push('$');
+                        i += 1;
+                    }
+                },
+                '!' if i + 1 < n && c[i + 1] == '[' => match cierres.enlace(i + 1) {
+                    Some(destino) => {
+                        let alt: String = c[i + 2..destino.fin_texto].iter().collect();
+                        match self.url(&destino.url, true) {
+                            Some(url) => salida.push_str(&format!(
+                                "<img src=\"{}\" alt=\"{}\"{} loading=\"lazy\">",
+                                escapar(&url),
+                                escapar(&alt),
+                                titulo(&destino.titulo)
+                            )),
+                            None => salida.push_str(&escapar(&alt)),
+                        }
+                        i = destino.fin;
+                    }
+                    None => {
+                        salida.push('!');
+                        i += 1;
+                    }
+                },
+                '[' => match cierres.enlace(i) {
+                    Some(destino) => {
+                        let texto: String = c[i + 1..destino.fin_texto].iter().collect();
+                        let contenido = self.en_linea(&texto);
+                        match self.url(&destino.url, false) {
+                            Some(url) => salida.push_str(&format!(
+                                "<a href=\"{}\"{} rel=\"nofollow noopener noreferrer\">{}</a>",
+                                escapar(&url),
+                                titulo(&destino.titulo),
+                                contenido
+                            )),
+                            None => salida.push_str(&contenido),
+                        }
+                        i = destino.fin;
+                    }
+                    None => {
+                        salida.push('[');
+                        i += 1;
+                    }
+                },
+                '<' => {
+                    let (html, avance) = self.etiqueta(&c, i, &mut abiertas);
+                    salida.push_str(&html);
+                    i += avance;
+                }
+                '*' | '_' | '~' => {
+                    let (html, avance) = self.enfasis(&mut cierres, i);
+                    salida.push_str(&html);
+                    i += avance;
+                }
+                '&' => {
+                    let largo = entidad(&c, i);
+                    if largo > 0 {
+                        salida.extend(&c[i..i + largo]);
+                        i += largo;
+                    } else {
+                        salida.push_str("&amp;");
+                        i += 1;
+                    }
+                }
+                otro => {
+                    salida.push_str(&escapar(&otro.to_string()));
+                    i += 1;
+                }
+            }
+        }
+
+        for nombre in abiertas.iter().rev() {
+            salida.push_str(&format!("</{nombre}>"));
+        }
+        salida
+    }
+
+    /// `**negrita**`, `*cursiva*`, `~~tachado~~` (y con `_`). Devuelve el HTML y
+    /// cuántos caracteres consumió
+    fn enfasis(&mut self, cierres: &mut Cierres, i: usize) -> (String, usize) {
+        let c = cierres.c;
+        let delimitador = c[i];
+        let largo = racha(c, i, delimitador);
+        let literal = (escapar(&c[i..i + largo].iter().collect::<String>()), largo);
+
+        let siguiente = c.get(i + largo);
+        if siguiente.is_none_or(|s| s.is_whitespace()) {
+            return literal;
+        }
+        // `_` dentro de una palabra (snake_case) no es énfasis
+        if delimitador == '_' && i > 0 && c[i - 1].is_alphanumeric() {
+            return literal;
+        }
+
+        let (usar, etiqueta) = match (delimitador, largo) {
+            ('~', 1) => return literal,
+            ('~', _) => (2, "del"),
+            (_, 1) => (1, "em"),
+            _ => (2, "strong"),
+        };
+        let Some(fin) = cierres.enfasis(i + usar, delimitador, usar) else {
+            return literal;
+        };
+        let interior: String = c[i + usar..fin].iter().collect();
+        let contenido = self.en_linea(&interior);
+        (
+            format!("<{etiqueta}>{contenido}</{etiqueta}>"),
+            fin + usar - i,
+        )
+    }
+
+    /// `<https://..>`, etiquetas permitidas o texto escapado. Un cierre cierra también
+    /// lo abierto después de su apertura; sin apertura se descarta
+    fn etiqueta(&mut self, c: &[char], i: usize, abiertas: &mut Vec<String>) -> (String, usize) {
+        // Hasta el primer `>`; si antes aparece otro `<`, este no abre nada
+        let Some(cierre) = c[i + 1..]
+            .iter()
+            .position(|x| *x == '>' || *x == '<')
+            .map(|p| i + 1 + p)
+            .filter(|cierre| c[*cierre] == '>')
+        else {
+            return ("&lt;".to_string(), 1);
+        };
+        let interior: String = c[i + 1..cierre].iter().collect();
+        let avance = cierre - i + 1;
+
+        if !interior.contains(char::is_whitespace) && interior.contains(':') {
+            return match self.url(&interior, false) {
+                Some(url) => (
+                    format!(
+                        "<a href=\"{}\" rel=\"nofollow noopener noreferrer\">{}</a>",
+                        escapar(&url),
+                        escapar(&interior)
+                    ),
+                    avance,
+                ),
+                None => (escapar(&format!("<{interior}>")), avance),
+            };
+        }
+
+        let Some(etiqueta) = Etiqueta::leer(&interior) else {
+            return ("&lt;".to_string(), 1);
+        };
+        let literal = (escapar(&format!("<{interior}>")), avance);
+
+        if etiqueta.nombre == "script" {
+            self.rechazar("No se permiten scripts en el texto");
+            return literal;
+        }
+        if etiqueta
+            .atributos
+            .iter()
+            .any(|(nombre, _)| nombre.starts_with("on"))
+        {
+            self.rechazar("No se permiten atributos de evento (onclick, onload...) en el texto");
+            return literal;
+        }
+        if etiqueta
+            .atributos
+            .iter()
+            .any(|(_, valor)| esquema_peligroso(valor))
+        {
+            self.rechazar("No se permiten URLs javascript: en el texto");
+            return literal;
+        }
+        // Con demasiadas abiertas a la vez, las aperturas quedan como texto
+        let sin_lugar =
+            !etiqueta.cierre && etiqueta.nombre != "br" && abiertas.len() >= PROFUNDIDAD_MAXIMA;
+        if !ETIQUETAS_PERMITIDAS.contains(&etiqueta.nombre.as_str()) || sin_lugar {
+            return literal;
+        }
+
+        let html = match (etiqueta.nombre.as_str(), etiqueta.cierre) {
+            ("br", _) => "<br>".to_string(),
+            (nombre, true) => match abiertas.iter().rposition(|abierta| abierta == nombre) {
+                Some(posicion) => abiertas
+                    .drain(posicion..)
+                    .rev()
+                    .map(|abierta| format!("</{abierta}>"))
+                    .collect(),
+                None => String::new(),
+            },
+            (nombre, false) => {
+                abiertas.push(nombre.to_string());
+                format!("<{nombre}>")
+            }
+        };
+        (html, avance)
+    }
+
+    /// URL apta para href/src: http(s), mailto (solo enlaces) o relativa
+    fn url(&mut self, url: &str, imagen: bool) -> Option<String> {
+        let url = url.trim();
+        if esquema_peligroso(url) {
+            self.rechazar("No se permiten URLs javascript: en el texto");
+            return None;
+        }
+        let minuscula = url.to_ascii_lowercase();
+        let fin_esquema = url.find(['/', '?', '#']).unwrap_or(url.len());
+        let relativa = !url[..fin_esquema].contains(':');
+        let permitida = minuscula.starts_with("https://")
+            || minuscula.starts_with("http://")
+            || (!imagen && minuscula.starts_with("mailto:"))
+            || relativa;
+        permitida.then(|| url.to_string())
+    }
+}
+
+struct Marcador {
+    ordenada: bool,
+    sangria: usize,
+    /// Columna donde empieza el texto del elemento
+    texto: usize,
+    numero: Option<u64>,
+}
+
+struct Destino {
+    /// Posición del `]`
+    fin_texto: usize,
+    url: String,
+    titulo: Option<String>,
+    /// Posición siguiente al `)`
+    fin: usize,
+}
+
+struct Etiqueta {
+    nombre: String,
+    cierre: bool,
+    atributos: Vec<(String, String)>,
+}
+
+impl Etiqueta {
+    /// Interior de `<..>`: `nombre atributo="valor" ...` o `/nombre`
+    fn leer(interior: &str) -> Option<Etiqueta> {
+        let (cierre, resto) = match interior.strip_prefix('/') {
+            Some(resto) => (true, resto),
+            None => (false, interior),
+        };
+        let resto = resto.trim_end_matches('/').trim_end();
+        let fin_nombre = resto
+            .find(|x: char| !x.is_ascii_alphanumeric())
+            .unwrap_or(resto.len());
+        let nombre = &resto[..fin_nombre];
+        if !nombre.starts_with(|x: char| x.is_ascii_alphabetic()) {
+            return None;
+        }
+
+        let mut atributos = Vec::new();
+        let mut resto = resto[fin_nombre..].trim_start();
+        while !resto.is_empty() {
+            let fin = resto
+                .find(|x: char| x == '=' || x.is_whitespace())
+                .unwrap_or(resto.len());
+            let atributo = resto[..fin].to_ascii_lowercase();
+            resto = resto[fin..].trim_start();
+            let valor = match resto.strip_prefix('=') {
+                Some(valor) => {
+                    let valor = valor.trim_start();
+                    let (texto, siguiente) = match valor.chars().next() {
+                        Some(comilla @ ('"' | '\'')) => {
+                            let fin = valor[1..].find(comilla).map(|p| p + 1)?;
+                            (&valor[1..fin], &valor[fin + 1..])
+                        }
+                        _ => {
+                            let fin = valor.find(char::is_whitespace).unwrap_or(valor.len());
+                            (&valor[..fin], &valor[fin..])
+                        }
+                    };
+                    resto = siguiente.trim_start();
+                    texto.to_string()
+                }
+                None => String::new(),
+            };
+            if atributo.is_empty() {
+                return None;
+            }
+            atributos.push((atributo, valor));
+        }
+
+        Some(Etiqueta {
+            nombre: nombre.to_ascii_lowercase(),
+            cierre,
+            atributos,
+        })
+    }
+}
+
+/// Escapa texto para contenido o atributos HTML
+pub fn escapar(texto: &str) -> String {
+    let mut salida = String::with_capacity(texto.len());
+    for c in texto.chars() {
+        match c {
+            '&' => salida.push_str("&amp;"),
+            '<' => salida.push_str("&lt;"),
+            '>' => salida.push_str("&gt;"),
+            '"' => salida.push_str("&quot;"),
+            '\'' => salida.push_str("&#39;"),
+            otro => salida.push(otro),
+        }
+    }
+    salida
+}
+
+/// Detecta `javascript:` aunque venga con mayúsculas, espacios o entidades numéricas
+fn esquema_peligroso(url: &str) -> bool {
+    let mut normalizada = String::new();
+    let mut resto = url;
+    while let Some(c) = resto.chars().next() {
+        let (c, largo) = match c {
+            '&' => decodificar_entidad(resto).unwrap_or((c, 1)),
+            _ => (c, c.len_utf8()),
+        };
+        // El navegador ignora tabuladores y saltos dentro del esquema
+        if !c.is_whitespace() && !c.is_control() {
+            normalizada.extend(c.to_lowercase());
+        }
+        resto = &resto[largo..];
+    }
+    ESQUEMAS_PELIGROSOS
+        .iter()
+        .any(|esquema| normalizada.starts_with(esquema))
+}
+
+/// `&#106;`, `&#x6A;`, `&colon;`... → (carácter, largo en bytes)
+fn decodificar_entidad(texto: &str) -> Option<(char, usize)> {
+    let fin = texto.find(';').filter(|fin| *fin <= 10)?;
+    let cuerpo = &texto[1..fin];
+    let caracter = if let Some(hexadecimal) = cuerpo
+        .strip_prefix("#x")
+        .or_else(|| cuerpo.strip_prefix("#X"))
+    {
+        char::from_u32(u32::from_str_radix(hexadecimal, 16).ok()?)?
+    } else if let Some(decimal) = cuerpo.strip_prefix('#') {
+        char::from_u32(decimal.parse().ok()?)?
+    } else {
+        match cuerpo {
+            "colon" => ':',
+            "tab" | "newline" => ' ',
+            _ => return None,
+        }
+    };
+    Some((caracter, fin + 1))
+}
+
+/// Largo de una entidad HTML válida en `i` (0 si no lo es)
+fn entidad(c: &[char], i: usize) -> usize {
+    let mut j = i + 1;
+    if c.get(j) == Some(&'#') {
+        j += 1;
+        if matches!(c.get(j), Some('x' | 'X')) {
+            j += 1;
+        }
+    }
+    let inicio = j;
+    while j < c.len() && j - i < 12 && c[j].is_ascii_alphanumeric() {
+        j += 1;
+    }
+    if j > inicio && c.get(j) == Some(&';') {
+        j + 1 - i
+    } else {
+        0
+    }
+}
+
+fn formula_bloque(formula: &str, salida: &mut String) {
+    salida.push_str(&format!(
+        "<div class=\"math math-display\">{}</div>\n",
+        escapar(formula)
+    ));
+}
+
+fn titulo(titulo: &Option<String>) -> String {
+    match titulo {
+        Some(titulo) => format!(" title=\"{}\"", escapar(titulo)),
+        None => String::new(),
+    }
+}
+
+fn sangria(linea: &str) -> usize {
+    linea.len() - linea.trim_start_matches(' ').len()
+}
+
+fn racha(c: &[char], i: usize, caracter: char) -> usize {
+    c[i..].iter().take_while(|x| **x == caracter).count()
+}
+
+/// ``` o ~~~ con lenguaje opcional → (cerca, lenguaje saneado)
+fn apertura_cerca(linea: &str) -> Option<(String, String)> {
+    let caracter = linea.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let largo = linea.chars().take_while(|c| *c == caracter).count();
+    if largo < 3 {
+        return None;
+    }
+    let info: String = linea[largo..]
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-' | '#'))
+        .collect();
+    Some((
+        caracter.to_string().repeat(largo),
+        info.to_ascii_lowercase(),
+    ))
+}
+
+fn cierra_cerca(linea: &str, cerca: &str) -> bool {
+    linea.starts_with(cerca) && linea.trim_start_matches(&cerca[..1]).is_empty()
+}
+
+fn encabezado(linea: &str) -> Option<(usize, &str)> {
+    let nivel = linea.chars().take_while(|c| *c == '#').count();
+    if !(1..=6).contains(&nivel) {
+        return None;
+    }
+    let resto = &linea[nivel..];
+    if !resto.is_empty() && !resto.starts_with(' ') {
+        return None;
+    }
+    Some((nivel, resto.trim().trim_end_matches('#').trim_end()))
+}
+
+fn es_separador(linea: &str) -> bool {
+    let sin_espacios: String = linea.chars().filter(|c| !c.is_whitespace()).collect();
+    sin_espacios.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|c| sin_espacios.chars().all(|x| x == *c))
+}
+
+fn marcador_lista(linea: &str) -> Option<Marcador> {
+    let sangria = sangria(linea);
+    let resto = &linea[sangria..];
+    let (ordenada, largo, numero) = if resto.starts_with(['-', '*', '+']) {
+        (false, 1, None)
+    } else {
+        let digitos = resto.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digitos == 0 || digitos > 9 || !resto[digitos..].starts_with(['.', ')']) {
+            return None;
+        }
+        (true, digitos + 1, resto[..digitos].parse().ok())
+    };
+    let despues = &resto[largo..];
+    if !despues.is_empty() && !despues.starts_with(' ') {
+        return None;
+    }
+    if !ordenada && es_separador(linea) {
+        return None;
+    }
+    let espacios = despues.len() - despues.trim_start_matches(' ').len();
+    Some(Marcador {
+        ordenada,
+        sangria,
+        texto: sangria + largo + espacios.clamp(1, 4).min(despues.len()),
+        numero,
+    })
+}
+
+/// Línea que empieza un bloque distinto de un párrafo
+fn inicia_bloque(linea: &str) -> bool {
+    let recortada = linea.trim();
+    apertura_cerca(recortada).is_some()
+        || recortada.starts_with("$$")
+        || recortada.starts_with('>')
+        || encabezado(recortada).is_some()
+        || es_separador(recortada)
+}
+
+/// Fila `| :--- | :---: | ---: |` → alineación de cada columna
+fn alineaciones(linea: &str) -> Option<Vec<Option<&'static str>>> {
+    if !linea.contains('-') {
+        return None;
+    }
+    celdas(linea)
+        .into_iter()
+        .map(|celda| {
+            let izquierda = celda.starts_with(':');
+            let derecha = celda.ends_with(':');
+            let guiones = celda.trim_matches(':');
+            if guiones.is_empty() || !guiones.chars().all(|c| c == '-') {
+                return None;
+            }
+            Some(match (izquierda, derecha) {
+                (true, true) => Some("center"),
+                (false, true) => Some("right"),
+                (true, false) => Some("left"),
+                (false, false) => None,
+            })
+        })
+        .collect()
+}
+
+fn celdas(linea: &str) -> Vec<&str> {
+    let linea = linea.trim();
+    let linea = linea.strip_prefix('|').unwrap_or(linea);
+    let linea = linea.strip_suffix('|').unwrap_or(linea);
+    linea.split('|').map(str::trim).collect()
+}
+
+/// Dónde cierra cada apertura de un tramo en línea. Lo que sale de una pasada se
+/// precalcula y los escaneos recuerdan lo ya recorrido, así que un tramo se resuelve
+/// en tiempo lineal aunque tenga miles de aperturas sin cerrar
+struct Cierres<'a> {
+    c: &'a [char],
+    /// Para cada `[` y `(`, el `]` o `)` que lo cierra
+    pareja: Vec<Option<usize>>,
+    codigo: Rachas,
+    /// Primera posición desde cada índice con `>`, con espacio y sin espacio
+    mayor: Vec<usize>,
+    espacio: Vec<usize>,
+    no_espacio: Vec<usize>,
+    formula: Memo,
+    /// Por delimitador y largo del cierre
+    enfasis: HashMap<(char, usize), Memo>,
+}
+
+impl<'a> Cierres<'a> {
+    fn new(c: &'a [char]) -> Self {
+        let n = c.len();
+        let mut pareja = vec![None; n];
+        // Los corchetes respetan `\`; los paréntesis del destino no
+        let mut abiertos = Vec::new();
+        let mut j = 0;
+        while j < n {
+            match c[j] {
+                '\\' => j += 1,
+                '[' => abiertos.push(j),
+                ']' => {
+                    if let Some(apertura) = abiertos.pop() {
+                        pareja[apertura] = Some(j);
+                    }
+                }
+                _ => {}
+            }
+            j += 1;
+        }
+        abiertos.clear();
+        for (j, x) in c.iter().enumerate() {
+            match x {
+                '(' => abiertos.push(j),
+                ')' => {
+                    if let Some(apertura) = abiertos.pop() {
+                        pareja[apertura] = Some(j);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let siguiente = |cumple: fn(char) -> bool| {
+            let mut posiciones = vec![n; n + 1];
+            for j in (0..n).rev() {
+                posiciones[j] = if cumple(c[j]) { j } else { posiciones[j + 1] };
+            }
+            posiciones
+        };
+
+        Cierres {
+            c,
+            pareja,
+            codigo: Rachas::new(c),
+            mayor: siguiente(|x| x == '>'),
+            espacio: siguiente(char::is_whitespace),
+            no_espacio: siguiente(|x| !x.is_whitespace()),
+            formula: Memo::default(),
+            enfasis: HashMap::new(),
+        }
+    }
+
+    /// `$x^2$`: sin espacio tras la apertura ni antes del cierre, y el cierre no va
+    /// seguido de un dígito (para no confundir precios como $5 y $10)
+    fn formula(&mut self, i: usize) -> Option<usize> {
+        let c = self.c;
+        let primero = *c.get(i + 1)?;
+        if primero.is_whitespace() || primero == '$' {
+            return None;
+        }
+        self.formula.buscar(i + 1, |j| match c.get(j) {
+            None => Paso::Fin,
+            Some('\\') => Paso::Seguir(j + 2),
+            Some('$')
+                if !c[j - 1].is_whitespace()
+                    && c.get(j + 1).is_none_or(|x| !x.is_ascii_digit()) =>
+            {
+                Paso::Cierre(j)
+            }
+            Some(_) => Paso::Seguir(j + 1),
+        })
+    }
+
+    /// Cierre de un énfasis: una racha del delimitador de exactamente `largo` (o de 3+
+    /// cuando cierra anidados) que no va precedida de espacio
+    fn enfasis(&mut self, desde: usize, delimitador: char, largo: usize) -> Option<usize> {
+        let c = self.c;
+        let codigo = &self.codigo;
+        // Lo que queda de la racha de apertura no cierra
+        let inicio = match c.get(desde) {
+            Some(x) if *x == delimitador => desde + racha(c, desde, delimitador),
+            _ => desde,
+        };
+        let memo = self.enfasis.entry((delimitador, largo)).or_default();
+        memo.buscar(inicio, |j| match c.get(j) {
+            None => Paso::Fin,
+            Some('\\') => Paso::Seguir(j + 2),
+            Some('`') => {
+                let racha = racha(c, j, '`');
+                Paso::Seguir(
+                    codigo
+                        .cierre(j + racha, racha)
+                        .map_or(j + racha, |fin| fin + racha),
+                )
+            }
+            Some(x) if *x == delimitador => {
+                let racha = racha(c, j, delimitador);
+                let despues = c.get(j + racha);
+                let valido = !c[j - 1].is_whitespace()
+                    && (racha == largo || racha >= 3)
+                    && (delimitador != '_' || despues.is_none_or(|x| !x.is_alphanumeric()));
+                if valido {
+                    Paso::Cierre(j + racha - largo)
+                } else {
+                    Paso::Seguir(j + racha)
+                }
+            }
+            Some(_) => Paso::Seguir(j + 1),
+        })
+    }
+
+    /// `[texto](url "título")` empezando en el `[`
+    fn enlace(&self, i: usize) -> Option<Destino> {
+        let c = self.c;
+        let fin_texto = self.pareja[i]?;
+        if c.get(fin_texto + 1) != Some(&'(') {
+            return None;
+        }
+        let fin = self.pareja[fin_texto + 1]?;
+
+        // El interior sin espacios a los lados es [inicio, limite)
+        let inicio = self.no_espacio[fin_texto + 2].min(fin);
+        let mut limite = fin;
+        while limite > inicio && c[limite - 1].is_whitespace() {
+            limite -= 1;
+        }
+        let (url, resto) = if inicio < limite && c[inicio] == '<' {
+            let cierre = self.mayor[inicio];
+            if cierre >= limite {
+                return None;
+            }
+            (&c[inicio + 1..cierre], cierre + 1)
+        } else {
+            let corte = self.espacio[inicio].min(limite);
+            (&c[inicio..corte], corte)
+        };
+        let resto = self.no_espacio[resto].min(limite);
+        let titulo = match c[resto..limite] {
+            [] => None,
+            [comilla @ ('"' | '\''), ref interior @ .., ultima] if ultima == comilla => {
+                Some(interior.iter().collect())
+            }
+            _ => return None,
+        };
+
+        Some(Destino {
+            fin_texto,
+            url: url.iter().collect(),
+            titulo,
+            fin: fin + 1,
+        })
+    }
+}
+
+/// Inicios de las rachas de comillas invertidas, por largo
+struct Rachas(HashMap<usize, Vec<usize>>);
+
+impl Rachas {
+    fn new(c: &[char]) -> Self {
+        let mut inicios: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut j = 0;
+        while j < c.len() {
+            if c[j] == '`' {
+                let largo = racha(c, j, '`');
+                inicios.entry(largo).or_default().push(j);
+                j += largo;
+            } else {
+                j += 1;
+            }
+        }
+        Rachas(inicios)
+    }
+
+    /// Cierre de un span de código con la misma cantidad de comillas invertidas
+    fn cierre(&self, desde: usize, largo: usize) -> Option<usize> {
+        let inicios = self.0.get(&largo)?;
+        inicios
+            .get(inicios.partition_point(|inicio| *inicio < desde))
+            .copied()
+    }
+}
+
+enum Paso {
+    Seguir(usize),
+    Cierre(usize),
+    Fin,
+}
+
+/// Cierre al que llegó un escaneo desde cada posición que recorrió. Los escaneos que
+/// salen de distintas aperturas acaban pasando por las mismas posiciones, así que
+/// cada una se recorre una sola vez
+#[derive(Default)]
+struct Memo(HashMap<usize, Option<usize>>);
+
+impl Memo {
+    fn buscar(&mut self, desde: usize, paso: impl Fn(usize) -> Paso) -> Option<usize> {
+        let mut recorridas = Vec::new();
+        let mut j = desde;
+        let cierre = loop {
+            if let Some(cierre) = self.0.get(&j) {
+                break *cierre;
+            }
+            match paso(j) {
+                Paso::Seguir(siguiente) => {
+                    recorridas.push(j);
+                    j = siguiente;
+                }
+                Paso::Cierre(fin) => break Some(fin),
+                Paso::Fin => break None,
+            }
+        };
+        for j in recorridas {
+            self.0.insert(j, cierre);
+        }
+        cierre
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rechaza_esquemas_peligrosos_aunque_vengan_codificados() {
+        for fuente in [
+            "[a](javascript:alert(1))",
+            "[a](JavaScript:alert(1))",
+            "[a](&#106;avascript:alert(1))",
+            "[a](javascript&colon;alert(1))",
+            "[a](&#x6A;ava&#x09;script:alert(1))",
+            "<javascript:alert(1)>",
+            "![a](data:text/html,x)",
+            "<b href=\"vbscript:x\">a</b>",
+        ] {
+            assert!(renderizar(fuente).is_err(), "{fuente}");
+            assert!(!a_html(fuente).contains("href=\"j"), "{fuente}");
+        }
+        assert!(renderizar("[a](https://ejemplo.com)").is_ok());
+    }
+
+    #[test]
+    fn rechaza_scripts_y_atributos_de_evento() {
+        assert!(renderizar("<script>alert(1)</script>").is_err());
+        assert!(renderizar("<b onclick=\"x()\">a</b>").is_err());
+        assert!(renderizar("<img src=x onerror=alert(1)>").is_err());
+        assert_eq!(
+            a_html("<b onclick=\"x()\">a</b>"),
+            "<p>&lt;b onclick=&quot;x()&quot;&gt;a</p>\n"
+        );
+    }
+
+    #[test]
+    fn escapa_codigo_y_formulas() {
+        assert_eq!(
+            renderizar("`<script>` y $a<b$").unwrap(),
+            "<p><code>&lt;script&gt;</code> y <span class=\"math math-inline\">a&lt;b</span></p>\n"
+        );
+        assert!(renderizar("```html\n<script>alert(1)</script>\n```")
+            .unwrap()
+            .contains("&lt;script&gt;"));
+        assert!(renderizar("$$\n<b onclick=x>\n$$")
+            .unwrap()
+            .contains("&lt;b onclick=x&gt;"));
+    }
+
+    #[test]
+    fn balancea_las_etiquetas_permitidas() {
+        assert_eq!(a_html("<b>sin cerrar"), "<p><b>sin cerrar</b></p>\n");
+        assert_eq!(a_html("huérfano</i>"), "<p>huérfano</p>\n");
+        assert_eq!(a_html("<b><i>a</b>"), "<p><b><i>a</i></b></p>\n");
+        assert_eq!(a_html("*<u>a*</u>"), "<p><em><u>a</u></em></p>\n");
+        assert_eq!(a_html("a<br>b"), "<p>a<br>b</p>\n");
+        assert_eq!(a_html("<b <i>a</i>"), "<p>&lt;b <i>a</i></p>\n");
+    }
+
+    #[test]
+    fn aperturas_sin_cerrar_no_vuelven_a_recorrer_el_texto() {
+        for patron in [
+            "*a ", "**a ", "_a ", "~~a ", "$a ", "`a ", "[a ", "<1 ", "![a](x ",
+        ] {
+            let fuente = patron.repeat(LARGO_MAXIMO / patron.len());
+            let html = renderizar(&fuente).unwrap();
+            assert!(
+                html.starts_with("<p>") && html.len() >= fuente.len(),
+                "{patron}"
+            );
+        }
+        // Destinos anidados que no valen (espacio sin título entre comillas)
+        let n = LARGO_MAXIMO / 5 - 1;
+        let fuente = format!("{} y{}", "[](x".repeat(n), ")".repeat(n));
+        assert!(!renderizar(&fuente).unwrap().contains("<a "));
+    }
+
+    #[test]
+    fn limita_el_anidamiento() {
+        let n = LARGO_MAXIMO / 5 - 1;
+        let enlaces = format!("{}a{}", "[".repeat(n), "](x)".repeat(n));
+        let html = renderizar(&enlaces).unwrap();
+        assert_eq!(html.matches("<a href").count(), PROFUNDIDAD_MAXIMA - 1);
+
+        let etiquetas = a_html(&"<b>".repeat(n));
+        assert_eq!(etiquetas.matches("<b>").count(), PROFUNDIDAD_MAXIMA);
+        assert_eq!(etiquetas.matches("</b>").count(), PROFUNDIDAD_MAXIMA);
+
+        for fuente in [">".repeat(n), "- ".repeat(n), "> - ".repeat(n)] {
+            let html = a_html(&format!("{fuente}a"));
+            assert!(html.contains('a'), "{}", &fuente[..4]);
+        }
+    }
+
+    #[test]
+    fn rechaza_textos_demasiado_largos() {
+        let largo = "a".repeat(LARGO_MAXIMO + 1);
+        assert!(renderizar(&largo).is_err());
+        assert!(renderizar(&largo[1..]).is_ok());
+        assert_eq!(
+            a_html(&format!("<{largo}")),
+            format!("<p>&lt;{largo}</p>\n")
+        );
+    }
+}
@@ -1,2 +1,9 @@
+pub mod client_ip;
 pub mod errors;
+pub mod ical;
 pub mod logger;
+pub mod markdown;
+pub mod oembed;
+pub mod password;
+pub mod plantillas_notificacion;
+pub mod tokens;
@@ -0,0 +1,233 @@
+use std::{sync::Arc, time::Duration};
+
+use serde_json::{Map, Value};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+use tokio_rustls::{
+    rustls::{crypto::ring, pki_types::ServerName, ClientConfig, RootCertStore},
+    TlsConnector,
+};
+
+/// Largo máximo aceptado para una URL de contenido
+const LARGO_MAXIMO_URL: usize = 2048;
+
+/// Tope de la respuesta del proveedor
+const TAMANO_MAXIMO_RESPUESTA: u64 = 256 * 1024;
+
+const TIEMPO_ESPERA: Duration = Duration::from_secs(5);
+
+/// Campos de la respuesta oEmbed que se guardan con el contenido
+const CAMPOS: &[&str] = &[
+    "type",
+    "title",
+    "author_name",
+    "provider_name",
+    "thumbnail_url",
+    "html",
+    "width",
+    "height",
+];
+
+/// Proveedores conocidos: (hosts, endpoint oEmbed que recibe la URL codificada)
+const PROVEEDORES: &[(&[&str], &str)] = &[
+    (
+        &[
+            "youtube.com",
+            "www.youtube.com",
+            "m.youtube.com",
+            "youtu.be",
+        ],
+        "https://www.youtube.com/oembed?format=json&url=",
+    ),
+    (
+        &["vimeo.com", "www.vimeo.com", "player.vimeo.com"],
+        "https://vimeo.com/api/oembed.json?url=",
+    ),
+    (
+        &["dailymotion.com", "www.dailymotion.com", "dai.ly"],
+        "https://www.dailymotion.com/services/oembed?format=json&url=",
+    ),
+    (
+        &["soundcloud.com", "www.soundcloud.com"],
+        "https://soundcloud.com/oembed?format=json&url=",
+    ),
+];
+
+/// URL http(s) separada en sus partes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlHttp {
+    pub segura: bool,
+    pub host: String,
+    pub puerto: u16,
+    /// Ruta con la consulta, empieza por '/'
+    pub ruta: String,
+}
+
+/// Valida una URL absoluta http o https con host
+pub fn validar_url(url: &str) -> Result<UrlHttp, String> {
+    if url.len() > LARGO_MAXIMO_URL {
+        return Err(format!("La URL supera los {} caracteres", LARGO_MAXIMO_URL));
+    }
+    if url.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err("La URL no puede contener espacios".to_string());
+    }
+
+    let (segura, resto) = if let Some(resto) = url.strip_prefix("https://") {
+        (true, resto)
+    } else if let Some(resto) = url.strip_prefix("http://") {
+        (false, resto)
+    } else {
+        return Err("La URL debe empezar por http:// o https://".to_string());
+    };
+
+    let fin_autoridad = resto.find(['/', '?', '#']).unwrap_or(resto.len());
+    let (autoridad, ruta) = resto.split_at(fin_autoridad);
+    if autoridad.contains('@') {
+        return Err("La URL no puede incluir credenciales".to_string());
+    }
+
+    let (host, puerto) = match autoridad.rsplit_once(':') {
+        Some((host, puerto)) => (
+            host,
+            puerto
+                .parse::<u16>()
+                .map_err(|_| "Puerto de la URL inválido".to_string())?,
+        ),
+        None => (autoridad, if segura { 443 } else { 80 }),
+    };
+    let host_valido = !host.is_empty()
+        && host.split('.').all(|parte| {
+            !parte.is_empty() && parte.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    if !host_valido {
+        return Err("El dominio de la URL no es válido".to_string());
+    }
+
+    let ruta = ruta.split('#').next().unwrap_or_default();
+    let ruta = if ruta.starts_with('/') {
+        ruta.to_string()
+    } else {
+        format!("/{ruta}")
+    };
+
+    Ok(UrlHttp {
+        segura,
+        host: host.to_ascii_lowercase(),
+        puerto,
+        ruta,
+    })
+}
+
+/// Endpoint oEmbed del proveedor de la URL, si es uno conocido
+fn endpoint(url: &str) -> Option<String> {
+    let host = validar_url(url).ok()?.host;
+    PROVEEDORES
+        .iter()
+        .find(|(hosts, _)| hosts.contains(&host.as_str()))
+        .map(|(_, endpoint)| format!("{endpoint}{}", codificar(url)))
+}
+
+/// Codificación de componente de URL (RFC 3986)
+fn codificar(valor: &str) -> String {
+    let mut salida = String::with_capacity(valor.len() * 3);
+    for byte in valor.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                salida.push(byte as char)
+            }
+            _ => salida.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    salida
+}
+
+/// Metadatos oEmbed de un video o enlace (título, miniatura, html embebible...).
+/// Devuelve None si el proveedor no es conocido o no responde; no es un error
+/// para quien crea el contenido
+pub async fn obtener(url: &str) -> Option<Value> {
+    let endpoint = endpoint(url)?;
+    match tokio::time::timeout(TIEMPO_ESPERA, consultar(&endpoint)).await {
+        Ok(Ok(datos)) => Some(datos),
+        Ok(Err(e)) => {
+            tracing::warn!(
+                "⚠️  No se pudieron obtener los metadatos oEmbed de {}: {}",
+                url,
+                e
+            );
+            None
+        }
+        Err(_) => {
+            tracing::warn!("⚠️  Tiempo de espera agotado consultando oEmbed de {}", url);
+            None
+        }
+    }
+}
+
+async fn consultar(endpoint: &str) -> Result<Value, String> {
+    let destino = validar_url(endpoint)?;
+    let tcp = TcpStream::connect((destino.host.as_str(), destino.puerto))
+        .await
+        .map_err(|e| e.to_string())?;
+    let nombre = ServerName::try_from(destino.host.clone()).map_err(|e| e.to_string())?;
+    let mut tls = conector_tls()?
+        .connect(nombre, tcp)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // HTTP/1.0: respuesta sin chunked y conexión cerrada al terminar
+    let peticion = format!(
+        "GET {} HTTP/1.0\r\nHost: {}\r\nAccept: application/json\r\nUser-Agent: backend-aula\r\n\r\n",
+        destino.ruta, destino.host
+    );
+    tls.write_all(peticion.as_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut respuesta = Vec::new();
+    (&mut tls)
+        .take(TAMANO_MAXIMO_RESPUESTA)
+        .read_to_end(&mut respuesta)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let separador = respuesta
+        .windows(4)
+        .position(|v| v == b"\r\n\r\n")
+        .ok_or("Respuesta HTTP incompleta")?;
+    let cabecera = String::from_utf8_lossy(&respuesta[..separador]);
+    let estado = cabecera
+        .lines()
+        .next()
+        .and_then(|linea| linea.split_whitespace().nth(1))
+        .unwrap_or_default();
+    if estado != "200" {
+        return Err(format!("El proveedor respondió {estado}"));
+    }
+
+    let cuerpo: Value =
+        serde_json::from_slice(&respuesta[separador + 4..]).map_err(|e| e.to_string())?;
+    let campos: Map<String, Value> = CAMPOS
+        .iter()
+        .filter_map(|campo| {
+            cuerpo
+                .get(*campo)
+                .filter(|v| !v.is_null())
+                .map(|v| (campo.to_string(), v.clone()))
+        })
+        .collect();
+    Ok(Value::Object(campos))
+}
+
+fn conector_tls() -> Result<TlsConnector, String> {
+    let raices = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    let config = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?
+        .with_root_certificates(raices)
+        .with_no_client_auth();
+    Ok(TlsConnector::from(Arc::new(config)))
+}
@@ -0,0 +1,133 @@
+use bcrypt::{hash, verify, DEFAULT_COST};
+use sqlx::PgPool;
+use subtle::ConstantTimeEq;
+
+use crate::utils::{errors::AppError, tokens};
+
+/// Contraseñas demasiado comunes aunque cumplan las demás reglas
+const CONTRASENAS_COMUNES: [&str; 8] = [
+    "password",
+    "contraseña",
+    "contrasena",
+    "123456789",
+    "qwerty",
+    "abc123",
+    "admin",
+    "bienvenido",
+];
+
+/// Política de contraseñas: longitud mínima, minúscula, mayúscula y dígito,
+/// sin datos personales (correo, documento) ni palabras comunes
+pub fn validar_politica(
+    contrasena: &str,
+    longitud_minima: usize,
+    datos_personales: &[&str],
+) -> Result<(), AppError> {
+    let mut faltas = Vec::new();
+
+    if contrasena.chars().count() < longitud_minima {
+        faltas.push(format!("mínimo {} caracteres", longitud_minima));
+    }
+    // bcrypt ignora lo que pase de 72 bytes
+    if contrasena.len() > 72 {
+        faltas.push("máximo 72 bytes".to_string());
+    }
+    if !contrasena.chars().any(char::is_lowercase) {
+        faltas.push("una minúscula".to_string());
+    }
+    if !contrasena.chars().any(char::is_uppercase) {
+        faltas.push("una mayúscula".to_string());
+    }
+    if !contrasena.chars().any(|c| c.is_ascii_digit()) {
+        faltas.push("un dígito".to_string());
+    }
+
+    let minusculas = contrasena.to_lowercase();
+    if CONTRASENAS_COMUNES
+        .iter()
+        .any(|comun| minusculas.contains(comun))
+    {
+        faltas.push("sin contraseñas comunes".to_string());
+    }
+    let incluye_personales = datos_personales
+        .iter()
+        .map(|dato| {
+            dato.split('@')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_lowercase()
+        })
+        .filter(|dato| dato.chars().count() >= 4)
+        .any(|dato| minusculas.contains(&dato));
+    if incluye_personales {
+        faltas.push("sin el correo ni el documento".to_string());
+    }
+
+    if faltas.is_empty() {
+        return Ok(());
+    }
+    Err(AppError::BadRequest(
+        format!("La contraseña no cumple la política: {}", faltas.join("; ")).into(),
+    ))
+}
+
+/// Hash bcrypt en un hilo bloqueante (DEFAULT_COST tarda ~250 ms)
+pub async fn hashear(contrasena: &str) -> Result<String, AppError> {
+    let contrasena = contrasena.to_string();
+    tokio::task::spawn_blocking(move || hash(contrasena, DEFAULT_COST))
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Task join error: {}", e).into()))?
+        .map_err(AppError::from)
+}
+
+/// Hash de una contraseña aleatoria que nadie conoce (cuentas pendientes de activación)
+pub async fn hash_inutilizable() -> Result<String, AppError> {
+    hashear(&tokens::generar_token()).await
+}
+
+/// Cuentas antiguas que guardan la contraseña en claro en lugar del hash bcrypt
+pub fn en_texto_plano(almacenada: &str) -> bool {
+    !almacenada.is_empty() && !almacenada.starts_with("$2")
+}
+
+/// Compara con el hash bcrypt; las contraseñas antiguas en claro se comparan en tiempo
+/// constante y se re-hashean en el primer login correcto
+pub async fn verificar(contrasena: &str, almacenada: &str) -> bool {
+    if almacenada.is_empty() {
+        return false;
+    }
+    if en_texto_plano(almacenada) {
+        return contrasena.as_bytes().ct_eq(almacenada.as_bytes()).into();
+    }
+
+    let contrasena = contrasena.to_string();
+    let almacenada = almacenada.to_string();
+    tokio::task::spawn_blocking(move || verify(contrasena, &almacenada).unwrap_or(false))
+        .await
+        .unwrap_or(false)
+}
+
+/// Guarda con bcrypt la contraseña en claro de una cuenta antigua tras un login correcto.
+/// Si falla queda en el log y se reintenta en el siguiente login
+pub async fn migrar_texto_plano(db: &PgPool, usuario_id: i32, contrasena: &str, almacenada: &str) {
+    if !en_texto_plano(almacenada) {
+        return;
+    }
+    let resultado = match hashear(contrasena).await {
+        Ok(hash) => sqlx::query("UPDATE usuarios SET contrasena = $1 WHERE id = $2")
+            .bind(hash)
+            .bind(usuario_id)
+            .execute(db)
+            .await
+            .map_err(AppError::from),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = resultado {
+        tracing::warn!(
+            "⚠️  No se pudo re-hashear la contraseña del usuario {}: {}",
+            usuario_id,
+            e
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use chrono::{Duration, Utc};
+
+use crate::models::notificacion::Model as Notificacion;
+
+/// Tipos que el usuario no puede silenciar ni aplazar: se envían siempre al momento
+pub const TIPOS_OBLIGATORIOS: [&str; 1] = ["seguridad"];
+
+/// Tipos conocidos y el nombre con el que aparecen en los correos
+pub const TIPOS: [(&str, &str); 7] = [
+    ("seguridad", "Seguridad de tu cuenta"),
+    ("calificacion", "Calificaciones"),
+    ("entrega", "Entregas"),
+    ("evaluacion", "Evaluaciones"),
+    ("curso", "Cursos"),
+    ("matricula", "Matrículas"),
+    ("sistema", "Avisos del sistema"),
+];
+
+/// Página del frontend donde el usuario cambia sus preferencias
+const RUTA_PREFERENCIAS: &str = "/preferencias-notificaciones";
+
+pub fn es_obligatorio(tipo: &str) -> bool {
+    TIPOS_OBLIGATORIOS.contains(&tipo)
+}
+
+fn etiqueta(tipo: &str) -> &str {
+    TIPOS
+        .iter()
+        .find(|(clave, _)| *clave == tipo)
+        .map(|(_, nombre)| *nombre)
+        .unwrap_or("Otras notificaciones")
+}
+
+/// Enlace absoluto: las rutas relativas se resuelven contra el frontend
+fn enlace_absoluto(enlace: &str, frontend_url: &str) -> String {
+    if enlace.starts_with("http://") || enlace.starts_with("https://") {
+        enlace.to_string()
+    } else {
+        format!("{}/{}", frontend_url, enlace.trim_start_matches('/'))
+    }
+}
+
+fn pie(frontend_url: &str) -> String {
+    format!(
+        "--\nAula Virtual. Elige cómo recibir cada tipo de aviso en {}{}",
+        frontend_url, RUTA_PREFERENCIAS
+    )
+}
+
+/// Asunto y cuerpo del correo de una notificación inmediata
+pub fn correo_inmediato(
+    nombre: &str,
+    notificacion: &Notificacion,
+    frontend_url: &str,
+) -> (String, String) {
+    let asunto = format!("{}: {}", etiqueta(&notificacion.tipo), notificacion.titulo);
+
+    let mut texto = format!(
+        "Hola {},\n\n{}\n\n{}\n",
+        nombre, notificacion.titulo, notificacion.mensaje
+    );
+    if let Some(enlace) = notificacion.enlace.as_deref().filter(|e| !e.is_empty()) {
+        texto.push_str(&format!(
+            "\nMás detalles: {}\n",
+            enlace_absoluto(enlace, frontend_url)
+        ));
+    }
+    texto.push('\n');
+    texto.push_str(&pie(frontend_url));
+
+    (asunto, texto)
+}
+
+/// Asunto y cuerpo del resumen diario, agrupado por tipo y en la hora local
+/// (`utc_offset_secs`)
+pub fn correo_resumen(
+    nombre: &str,
+    notificaciones: &[Notificacion],
+    frontend_url: &str,
+    utc_offset_secs: i32,
+) -> (String, String) {
+    let asunto = match notificaciones.len() {
+        1 => "Tu resumen diario del Aula Virtual: 1 notificación".to_string(),
+        total => format!(
+            "Tu resumen diario del Aula Virtual: {} notificaciones",
+            total
+        ),
+    };
+
+    let mut ordenadas: Vec<&Notificacion> = notificaciones.iter().collect();
+    ordenadas.sort_by(|a, b| {
+        etiqueta(&a.tipo)
+            .cmp(etiqueta(&b.tipo))
+            .then(a.created_at.cmp(&b.created_at))
+    });
+
+    let mut texto = format!(
+        "Hola {},\n\nEsto es lo que pasó en el Aula Virtual desde tu último resumen:\n",
+        nombre
+    );
+    let mut grupo_actual = "";
+    for notificacion in ordenadas {
+        let grupo = etiqueta(&notificacion.tipo);
+        if grupo != grupo_actual {
+            texto.push_str(&format!("\n{}\n", grupo));
+            grupo_actual = grupo;
+        }
+        let hora = notificacion
+            .created_at
+            .unwrap_or_else(Utc::now)
+            .checked_add_signed(Duration::seconds(utc_offset_secs.into()))
+            .unwrap_or_else(Utc::now)
+            .format("%d/%m %H:%M");
+        texto.push_str(&format!(
+            "  - [{}] {}: {}\n",
+            hora, notificacion.titulo, notificacion.mensaje
+        ));
+        if let Some(enlace) = notificacion.enlace.as_deref().filter(|e| !e.is_empty()) {
+            texto.push_str(&format!("    {}\n", enlace_absoluto(enlace, frontend_url)));
+        }
+    }
+    texto.push('\n');
+    texto.push_str(&pie(frontend_url));
+
+    (asunto, texto)
+}
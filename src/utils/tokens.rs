@@ -0,0 +1,12 @@
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Token opaco de 64 caracteres hex (dos UUID v4, ~244 bits aleatorios)
+pub fn generar_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// SHA-256 en hex: en la base de datos solo se guarda el hash del token
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}